@@ -3,6 +3,7 @@
 
 use crate::{errors::JsonRpcError, views::AccountView, JsonRpcResponse};
 use anyhow::{ensure, format_err, Error, Result};
+use libra_crypto::HashValue;
 use libra_types::{account_address::AccountAddress, transaction::SignedTransaction};
 use reqwest::{Client, ClientBuilder, Url};
 use serde_json::{json, Value};
@@ -40,6 +41,19 @@ impl JsonRpcBatch {
         Ok(())
     }
 
+    pub fn add_simulate_transaction_request(
+        &mut self,
+        transaction: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Result<()> {
+        let txn_payload = hex::encode(lcs::to_bytes(&transaction)?);
+        self.add_request(
+            "simulate_transaction".to_string(),
+            vec![Value::String(txn_payload), json!(skip_signature_check)],
+        );
+        Ok(())
+    }
+
     pub fn add_get_account_request(&mut self, address: AccountAddress) {
         self.add_request(
             "get_account".to_string(),
@@ -47,6 +61,24 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_account_by_version_request(&mut self, address: AccountAddress, version: u64) {
+        self.add_request(
+            "get_account".to_string(),
+            vec![Value::String(address.to_string()), json!(version)],
+        );
+    }
+
+    pub fn add_get_account_resources_request(
+        &mut self,
+        address: AccountAddress,
+        version: Option<u64>,
+    ) {
+        self.add_request(
+            "get_account_resources".to_string(),
+            vec![Value::String(address.to_string()), json!(version)],
+        );
+    }
+
     pub fn add_get_metadata_request(&mut self, version: Option<u64>) {
         let params = match version {
             Some(version) => vec![json!(version)],
@@ -87,6 +119,13 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_transaction_by_hash_request(&mut self, hash: HashValue, include_events: bool) {
+        self.add_request(
+            "get_transaction_by_hash".to_string(),
+            vec![json!(hash.to_hex()), json!(include_events)],
+        );
+    }
+
     pub fn add_get_account_transactions_request(
         &mut self,
         account: AccountAddress,
@@ -105,6 +144,26 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_account_transactions_page_request(
+        &mut self,
+        account: AccountAddress,
+        limit: u64,
+        include_events: bool,
+        cursor: Option<String>,
+        descending: bool,
+    ) {
+        self.add_request(
+            "get_account_transactions_page".to_string(),
+            vec![
+                json!(account.to_string()),
+                json!(limit),
+                json!(include_events),
+                json!(cursor),
+                json!(descending),
+            ],
+        );
+    }
+
     pub fn add_get_events_request(&mut self, event_key: String, start: u64, limit: u64) {
         self.add_request(
             "get_events".to_string(),
@@ -112,6 +171,24 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_events_page_request(
+        &mut self,
+        event_key: String,
+        limit: u64,
+        cursor: Option<String>,
+        descending: bool,
+    ) {
+        self.add_request(
+            "get_events_page".to_string(),
+            vec![
+                json!(event_key),
+                json!(limit),
+                json!(cursor),
+                json!(descending),
+            ],
+        );
+    }
+
     pub fn add_get_state_proof_request(&mut self, known_version: u64) {
         self.add_request("get_state_proof".to_string(), vec![json!(known_version)]);
     }
@@ -190,8 +267,8 @@ impl std::fmt::Display for JsonRpcAsyncClientError {
 
 #[derive(Clone)]
 pub struct JsonRpcAsyncClient {
-    address: String,
-    client: Client,
+    pub(crate) address: String,
+    pub(crate) client: Client,
 }
 
 impl JsonRpcAsyncClient {
@@ -4,6 +4,7 @@
 mod blocking;
 mod client;
 mod response;
+mod stream;
 
 pub use blocking::JsonRpcClient;
 pub use client::{
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::views::{
-    AccountStateWithProofView, AccountView, BlockMetadata, CurrencyInfoView, EventView,
-    StateProofView, TransactionView,
+    AccountResourceView, AccountStateWithProofView, AccountView, BlockMetadata, CurrencyInfoView,
+    EventView, EventsPageView, StateProofView, TransactionSimulationView, TransactionView,
+    TransactionsPageView,
 };
 use anyhow::{ensure, format_err, Error, Result};
 
@@ -14,11 +15,15 @@ use std::convert::TryFrom;
 #[derive(Clone, PartialEq, Debug)]
 pub enum JsonRpcResponse {
     SubmissionResponse,
+    SimulationResponse(TransactionSimulationView),
     AccountResponse(Option<AccountView>),
+    AccountResourcesResponse(Option<Vec<AccountResourceView>>),
     StateProofResponse(StateProofView),
     AccountTransactionResponse(Option<TransactionView>),
     TransactionsResponse(Vec<TransactionView>),
+    TransactionsPageResponse(TransactionsPageView),
     EventsResponse(Vec<EventView>),
+    EventsPageResponse(EventsPageView),
     BlockMetadataResponse(BlockMetadata),
     CurrenciesResponse(Vec<CurrencyInfoView>),
     AccountStateWithProofResponse(AccountStateWithProofView),
@@ -39,6 +44,10 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 );
                 Ok(JsonRpcResponse::SubmissionResponse)
             }
+            "simulate_transaction" => {
+                let simulation: TransactionSimulationView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::SimulationResponse(simulation))
+            }
             "get_account" => {
                 let account = match value {
                     Value::Null => None,
@@ -49,10 +58,24 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 };
                 Ok(JsonRpcResponse::AccountResponse(account))
             }
+            "get_account_resources" => {
+                let resources = match value {
+                    Value::Null => None,
+                    _ => {
+                        let resources: Vec<AccountResourceView> = serde_json::from_value(value)?;
+                        Some(resources)
+                    }
+                };
+                Ok(JsonRpcResponse::AccountResourcesResponse(resources))
+            }
             "get_events" => {
                 let events: Vec<EventView> = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::EventsResponse(events))
             }
+            "get_events_page" => {
+                let page: EventsPageView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::EventsPageResponse(page))
+            }
             "get_metadata" => {
                 let metadata: BlockMetadata = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::BlockMetadataResponse(metadata))
@@ -71,7 +94,7 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 let state_proof: StateProofView = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::StateProofResponse(state_proof))
             }
-            "get_account_transaction" => {
+            "get_account_transaction" | "get_transaction_by_hash" => {
                 let txn = match value {
                     Value::Null => None,
                     _ => {
@@ -85,6 +108,10 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 let txns: Vec<TransactionView> = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::TransactionsResponse(txns))
             }
+            "get_account_transactions_page" => {
+                let page: TransactionsPageView = serde_json::from_value(value)?;
+                Ok(JsonRpcResponse::TransactionsPageResponse(page))
+            }
             "get_transactions" => {
                 let txns: Vec<TransactionView> = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::TransactionsResponse(txns))
@@ -130,6 +157,26 @@ impl ResponseAsView for AccountView {
     }
 }
 
+impl ResponseAsView for TransactionSimulationView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::SimulationResponse(simulation) = response {
+            Ok(simulation)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+impl ResponseAsView for AccountResourceView {
+    fn vec_from_response(response: JsonRpcResponse) -> Result<Vec<Self>> {
+        if let JsonRpcResponse::AccountResourcesResponse(resources) = response {
+            resources.ok_or_else(|| format_err!("account does not exist"))
+        } else {
+            Self::unexpected_response_error::<Vec<Self>>(response)
+        }
+    }
+}
+
 impl ResponseAsView for EventView {
     fn vec_from_response(response: JsonRpcResponse) -> Result<Vec<Self>> {
         if let JsonRpcResponse::EventsResponse(events) = response {
@@ -140,6 +187,26 @@ impl ResponseAsView for EventView {
     }
 }
 
+impl ResponseAsView for EventsPageView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::EventsPageResponse(page) = response {
+            Ok(page)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
+impl ResponseAsView for TransactionsPageView {
+    fn from_response(response: JsonRpcResponse) -> Result<Self> {
+        if let JsonRpcResponse::TransactionsPageResponse(page) = response {
+            Ok(page)
+        } else {
+            Self::unexpected_response_error::<Self>(response)
+        }
+    }
+}
+
 impl ResponseAsView for BlockMetadata {
     fn from_response(response: JsonRpcResponse) -> Result<Self> {
         if let JsonRpcResponse::BlockMetadataResponse(metadata) = response {
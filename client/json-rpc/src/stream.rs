@@ -0,0 +1,318 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async stream API for the JSON RPC streaming subscriptions
+//!
+//! The server pushes items as Server-Sent Events; every event id is the position of the item in
+//! the subscribed sequence. The stream remembers the last position it received and transparently
+//! reconnects from the next one when the connection drops.
+
+use crate::{
+    errors::JsonRpcError,
+    views::{EventView, TransactionView},
+    JsonRpcAsyncClient, JsonRpcAsyncClientError,
+};
+use anyhow::Result;
+use futures::{stream, Stream};
+use libra_types::account_address::AccountAddress;
+use reqwest::{Client, Response, Url};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// Number of consecutive failed (re)connection attempts before the stream gives up
+const MAX_RECONNECT_COUNT: u64 = 2;
+
+impl JsonRpcAsyncClient {
+    /// Subscribes to all committed transactions, starting at `start_version`
+    pub fn subscribe_to_transactions(
+        &self,
+        start_version: u64,
+        include_events: bool,
+    ) -> impl Stream<Item = Result<TransactionView, JsonRpcAsyncClientError>> {
+        self.subscribe(
+            "transactions",
+            vec![("include_events", include_events.to_string())],
+            start_version,
+        )
+    }
+
+    /// Subscribes to transactions sent by `account`, starting at sequence number `start`
+    pub fn subscribe_to_account_transactions(
+        &self,
+        account: AccountAddress,
+        start: u64,
+        include_events: bool,
+    ) -> impl Stream<Item = Result<TransactionView, JsonRpcAsyncClientError>> {
+        self.subscribe(
+            "account_transactions",
+            vec![
+                ("account", account.to_string()),
+                ("include_events", include_events.to_string()),
+            ],
+            start,
+        )
+    }
+
+    /// Subscribes to events emitted under the hex-encoded `event_key`, starting at sequence
+    /// number `start`
+    pub fn subscribe_to_events(
+        &self,
+        event_key: String,
+        start: u64,
+    ) -> impl Stream<Item = Result<EventView, JsonRpcAsyncClientError>> {
+        self.subscribe("events", vec![("key", event_key)], start)
+    }
+
+    fn subscribe<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: Vec<(&'static str, String)>,
+        start: u64,
+    ) -> impl Stream<Item = Result<T, JsonRpcAsyncClientError>> {
+        let state = stream_url(&self.address, path)
+            .map(|url| Subscription {
+                client: self.client.clone(),
+                url,
+                query,
+                cursor: start,
+                response: None,
+                buffer: Vec::new(),
+                failures: 0,
+                _data: PhantomData,
+            })
+            .map_err(|e| JsonRpcAsyncClientError::InvalidArgument(e.to_string()));
+
+        stream::unfold(Some(state), |state| async move {
+            match state? {
+                Ok(mut subscription) => match subscription.next_item().await {
+                    Ok(item) => Some((Ok(item), Some(Ok(subscription)))),
+                    Err(e) => Some((Err(e), None)),
+                },
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+}
+
+/// Builds the url of a stream endpoint out of the JSON RPC endpoint url, which may or may not
+/// already contain the `/v1` api version
+fn stream_url(address: &str, path: &str) -> Result<Url> {
+    let mut url = Url::parse(address)?;
+    let base = url
+        .path()
+        .trim_end_matches('/')
+        .trim_end_matches("v1")
+        .trim_end_matches('/')
+        .to_string();
+    url.set_path(&format!("{}/v1/stream/{}", base, path));
+    Ok(url)
+}
+
+struct Subscription<T> {
+    client: Client,
+    url: Url,
+    query: Vec<(&'static str, String)>,
+    // position of the next expected item; used to resume after reconnects
+    cursor: u64,
+    response: Option<Response>,
+    // raw bytes received so far; only complete events are decoded, as chunks may split a
+    // multibyte UTF-8 character
+    buffer: Vec<u8>,
+    failures: u64,
+    _data: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Subscription<T> {
+    async fn next_item(&mut self) -> Result<T, JsonRpcAsyncClientError> {
+        loop {
+            if let Some(event) = next_event(&mut self.buffer) {
+                match event.name.as_deref() {
+                    // keep-alive comments carry no data
+                    _ if event.data.is_empty() => continue,
+                    Some("error") => {
+                        let err: JsonRpcError = serde_json::from_str(&event.data).map_err(|e| {
+                            JsonRpcAsyncClientError::InvalidServerResponse(e.to_string())
+                        })?;
+                        return Err(JsonRpcAsyncClientError::JsonRpcError(err));
+                    }
+                    _ => {
+                        let item = serde_json::from_str(&event.data).map_err(|e| {
+                            JsonRpcAsyncClientError::InvalidServerResponse(e.to_string())
+                        })?;
+                        if let Some(id) = event.id {
+                            self.cursor = id + 1;
+                        }
+                        self.failures = 0;
+                        return Ok(item);
+                    }
+                }
+            }
+
+            let mut response = match self.response.take() {
+                Some(response) => response,
+                None => self.connect().await?,
+            };
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    self.buffer.extend_from_slice(&chunk);
+                    self.response = Some(response);
+                }
+                // server closed the stream, resume from the current cursor
+                Ok(None) => self.reset(),
+                // the request timeout of the client also applies to the long-lived response
+                // body, so a timeout only means nothing was committed in the meantime
+                Err(e) if e.is_timeout() => self.reset(),
+                Err(e) => {
+                    self.reset();
+                    self.failed(e)?;
+                }
+            }
+        }
+    }
+
+    async fn connect(&mut self) -> Result<Response, JsonRpcAsyncClientError> {
+        loop {
+            let mut query = self.query.clone();
+            query.push(("start", self.cursor.to_string()));
+            let result = self
+                .client
+                .get(self.url.clone())
+                .query(&query)
+                .send()
+                .await
+                .and_then(Response::error_for_status);
+            match result {
+                Ok(response) => return Ok(response),
+                Err(e) => self.failed(e)?,
+            }
+        }
+    }
+
+    fn failed(&mut self, e: reqwest::Error) -> Result<(), JsonRpcAsyncClientError> {
+        self.failures += 1;
+        if self.failures > MAX_RECONNECT_COUNT {
+            Err(JsonRpcAsyncClientError::ClientError(e))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn reset(&mut self) {
+        self.response = None;
+        self.buffer.clear();
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    id: Option<u64>,
+    name: Option<String>,
+    data: String,
+}
+
+/// Pops the first complete Server-Sent Event off the buffer, if any
+fn next_event(buffer: &mut Vec<u8>) -> Option<SseEvent> {
+    // an event ends with an empty line, i.e. "\n\n" or "\r\n\r\n"
+    let (end, separator_len) = (0..buffer.len()).find_map(|i| {
+        let rest = &buffer[i..];
+        if rest.starts_with(b"\n\n") {
+            Some((i, 2))
+        } else if rest.starts_with(b"\n\r\n") {
+            Some((i, 3))
+        } else {
+            None
+        }
+    })?;
+    let raw: Vec<u8> = buffer.drain(..end + separator_len).collect();
+    // keep the line ending of the last line, so that `lines` strips its "\r" if any
+    let raw = String::from_utf8_lossy(&raw[..=end]);
+
+    let mut event = SseEvent::default();
+    for line in raw.lines() {
+        // lines starting with ':' are comments
+        let mut parts = line.splitn(2, ':');
+        let field = parts.next().unwrap_or_default();
+        let value = parts.next().unwrap_or_default();
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "id" => event.id = value.parse().ok(),
+            "event" => event.name = Some(value.to_string()),
+            "data" => {
+                if !event.data.is_empty() {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+            }
+            _ => {}
+        }
+    }
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_event() {
+        let mut buffer =
+            b":\n\nid:7\nevent:transaction\ndata:{\"a\":1}\n\nid: 8\r\nevent: er".to_vec();
+
+        assert_eq!(next_event(&mut buffer), Some(SseEvent::default()));
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(SseEvent {
+                id: Some(7),
+                name: Some("transaction".to_string()),
+                data: "{\"a\":1}".to_string(),
+            })
+        );
+        // incomplete event stays in the buffer until the rest of it arrives
+        assert_eq!(next_event(&mut buffer), None);
+
+        buffer.extend_from_slice(b"ror\r\ndata: {}\r\n\r\n");
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(SseEvent {
+                id: Some(8),
+                name: Some("error".to_string()),
+                data: "{}".to_string(),
+            })
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_next_event_split_character() {
+        let bytes = "data:\"\u{e9}\"\n\n".as_bytes();
+        // split the two bytes of the character across chunks
+        let split = bytes.iter().position(|b| *b >= 0x80).unwrap() + 1;
+
+        let mut buffer = bytes[..split].to_vec();
+        assert_eq!(next_event(&mut buffer), None);
+        buffer.extend_from_slice(&bytes[split..]);
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(SseEvent {
+                data: "\"\u{e9}\"".to_string(),
+                ..SseEvent::default()
+            })
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_stream_url() {
+        for address in &[
+            "http://localhost:8080",
+            "http://localhost:8080/",
+            "http://localhost:8080/v1",
+            "http://localhost:8080/v1/",
+        ] {
+            assert_eq!(
+                stream_url(address, "events").unwrap().as_str(),
+                "http://localhost:8080/v1/stream/events"
+            );
+        }
+    }
+}
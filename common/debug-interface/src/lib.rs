@@ -1,11 +1,13 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::mempool::{MempoolPeerView, MempoolTransactionView};
 use anyhow::Result;
 use libra_logger::json_log::JsonLogEntry;
 use reqwest::blocking;
 use std::collections::HashMap;
 
+pub mod mempool;
 pub mod node_debug_service;
 
 /// Implement default utility client for NodeDebugInterface
@@ -29,7 +31,11 @@ impl NodeDebugClient {
     }
 
     pub fn get_node_metrics(&mut self) -> Result<HashMap<String, i64>> {
-        let response = self.client.get(&format!("{}/metrics", self.addr)).send()?;
+        let response = self
+            .client
+            .get(&format!("{}/metrics", self.addr))
+            .send()?
+            .error_for_status()?;
 
         response
             .json::<HashMap<String, String>>()?
@@ -46,7 +52,77 @@ impl NodeDebugClient {
     }
 
     pub fn get_events(&mut self) -> Result<Vec<JsonLogEntry>> {
-        let response = self.client.get(&format!("{}/events", self.addr)).send()?;
+        let response = self
+            .client
+            .get(&format!("{}/events", self.addr))
+            .send()?
+            .error_for_status()?;
+
+        Ok(response.json()?)
+    }
+
+    /// Returns pending mempool transactions, only those sent by `account` if it is set, and at
+    /// most `limit` of them if it is set
+    pub fn get_mempool_transactions(
+        &mut self,
+        account: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let mut request = self
+            .client
+            .get(&format!("{}/mempool/transactions", self.addr));
+        if let Some(account) = account {
+            request = request.query(&[("account", account)]);
+        }
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        let response = request.send()?.error_for_status()?;
+
+        Ok(response.json()?)
+    }
+
+    /// Returns mempool transactions of the parking lot, at most `limit` of them if it is set
+    pub fn get_mempool_parking_lot(
+        &mut self,
+        limit: Option<usize>,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let mut request = self
+            .client
+            .get(&format!("{}/mempool/parking_lot", self.addr));
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        let response = request.send()?.error_for_status()?;
+
+        Ok(response.json()?)
+    }
+
+    /// Returns mempool transactions ready for broadcast, ordered by timeline position since
+    /// `timeline_id`, at most `limit` of them if it is set
+    pub fn get_mempool_timeline(
+        &mut self,
+        timeline_id: u64,
+        limit: Option<usize>,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let mut request = self
+            .client
+            .get(&format!("{}/mempool/timeline", self.addr))
+            .query(&[("timeline_id", timeline_id)]);
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        let response = request.send()?.error_for_status()?;
+
+        Ok(response.json()?)
+    }
+
+    pub fn get_mempool_peers(&mut self) -> Result<Vec<MempoolPeerView>> {
+        let response = self
+            .client
+            .get(&format!("{}/mempool/peers", self.addr))
+            .send()?
+            .error_for_status()?;
 
         Ok(response.json()?)
     }
@@ -76,7 +152,8 @@ impl AsyncNodeDebugClient {
             .client
             .get(&format!("{}/metrics", self.addr))
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         response
             .json::<HashMap<String, String>>()
@@ -98,7 +175,75 @@ impl AsyncNodeDebugClient {
             .client
             .get(&format!("{}/events", self.addr))
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Returns pending mempool transactions, only those sent by `account` if it is set, and at
+    /// most `limit` of them if it is set
+    pub async fn get_mempool_transactions(
+        &mut self,
+        account: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let mut request = self
+            .client
+            .get(&format!("{}/mempool/transactions", self.addr));
+        if let Some(account) = account {
+            request = request.query(&[("account", account)]);
+        }
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        let response = request.send().await?.error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Returns mempool transactions of the parking lot, at most `limit` of them if it is set
+    pub async fn get_mempool_parking_lot(
+        &mut self,
+        limit: Option<usize>,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let mut request = self
+            .client
+            .get(&format!("{}/mempool/parking_lot", self.addr));
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        let response = request.send().await?.error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Returns mempool transactions ready for broadcast, ordered by timeline position since
+    /// `timeline_id`, at most `limit` of them if it is set
+    pub async fn get_mempool_timeline(
+        &mut self,
+        timeline_id: u64,
+        limit: Option<usize>,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let mut request = self
+            .client
+            .get(&format!("{}/mempool/timeline", self.addr))
+            .query(&[("timeline_id", timeline_id)]);
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        let response = request.send().await?.error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_mempool_peers(&mut self) -> Result<Vec<MempoolPeerView>> {
+        let response = self
+            .client
+            .get(&format!("{}/mempool/peers", self.addr))
+            .send()
+            .await?
+            .error_for_status()?;
 
         Ok(response.json().await?)
     }
@@ -0,0 +1,57 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only views of the mempool of a node, served by the debug interface.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Access to the mempool state, implemented by the node on top of shared mempool
+pub trait MempoolDebugInterface: Send + Sync {
+    /// Returns up to `limit` pending transactions, only those sent by `account` if it is set
+    fn get_transactions(
+        &self,
+        account: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<MempoolTransactionView>>;
+
+    /// Returns up to `limit` transactions of the parking lot
+    fn get_parking_lot(&self, limit: usize) -> Vec<MempoolTransactionView>;
+
+    /// Returns up to `limit` transactions ready for broadcast, in timeline order since
+    /// `timeline_id`
+    fn get_timeline(&self, timeline_id: u64, limit: usize) -> Vec<MempoolTransactionView>;
+
+    /// Returns the broadcast state of every upstream peer
+    fn get_peers(&self) -> Vec<MempoolPeerView>;
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MempoolTransactionView {
+    pub sender: String,
+    pub sequence_number: u64,
+    pub gas_unit_price: u64,
+    pub ranking_score: u64,
+    /// Position in the log of transactions ready for broadcast, unset for non-ready transactions
+    pub timeline_id: Option<u64>,
+    /// Whether the transaction is in the parking lot, i.e. can't be included in the next block
+    pub parked: bool,
+    /// Client-specified expiration time, in seconds since epoch
+    pub expiration_timestamp_secs: u64,
+    /// Time, in seconds since epoch, at which the transaction is garbage collected by system TTL
+    pub system_ttl_expiration_secs: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MempoolPeerView {
+    pub peer: String,
+    pub is_alive: bool,
+    /// Timeline position the next broadcast to this peer starts from
+    pub timeline_id: u64,
+    /// Timeline IDs of broadcast batches that were not ACK'ed yet, by batch ID
+    pub pending_batches: BTreeMap<String, Vec<u64>>,
+    /// Timeline IDs of transactions to broadcast again
+    pub retry_txns: Vec<u64>,
+    pub backoff_mode: bool,
+}
@@ -3,10 +3,34 @@
 
 //! Debug interface to access information in a specific node.
 
+use crate::mempool::{MempoolDebugInterface, MempoolTransactionView};
+use anyhow::Result;
 use libra_logger::json_log;
-use std::net::SocketAddr;
+use serde::Deserialize;
+use std::{cmp::min, net::SocketAddr, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
-use warp::Filter;
+use warp::{http::StatusCode, reply::Json, Filter};
+
+/// Max number of transactions returned by a mempool view, since mempool is locked while they are
+/// copied
+const MAX_MEMPOOL_VIEW_SIZE: usize = 1000;
+
+#[derive(Deserialize)]
+struct MempoolTransactionsQuery {
+    account: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct MempoolParkingLotQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct MempoolTimelineQuery {
+    timeline_id: Option<u64>,
+    limit: Option<usize>,
+}
 
 #[derive(Debug)]
 pub struct NodeDebugService {
@@ -14,7 +38,7 @@ pub struct NodeDebugService {
 }
 
 impl NodeDebugService {
-    pub fn new(address: SocketAddr) -> Self {
+    pub fn new(address: SocketAddr, mempool: Arc<dyn MempoolDebugInterface>) -> Self {
         let runtime = Builder::new()
             .thread_name("nodedebug-")
             .threaded_scheduler()
@@ -29,7 +53,59 @@ impl NodeDebugService {
         // GET /evnets
         let events = warp::path("events").map(|| warp::reply::json(&json_log::pop_last_entries()));
 
-        let routes = warp::get().and(metrics.or(events));
+        let mempool = warp::any().map(move || mempool.clone());
+
+        // GET /mempool/transactions?account=<address>&limit=<limit>
+        let mempool_transactions =
+            warp::path!("mempool" / "transactions")
+                .and(warp::query::<MempoolTransactionsQuery>())
+                .and(mempool.clone())
+                .map(
+                    |query: MempoolTransactionsQuery, mempool: Arc<dyn MempoolDebugInterface>| {
+                        mempool_reply(mempool.get_transactions(
+                            query.account.as_deref(),
+                            mempool_view_size(query.limit),
+                        ))
+                    },
+                );
+
+        // GET /mempool/parking_lot?limit=<limit>
+        let mempool_parking_lot = warp::path!("mempool" / "parking_lot")
+            .and(warp::query::<MempoolParkingLotQuery>())
+            .and(mempool.clone())
+            .map(
+                |query: MempoolParkingLotQuery, mempool: Arc<dyn MempoolDebugInterface>| {
+                    warp::reply::json(&mempool.get_parking_lot(mempool_view_size(query.limit)))
+                },
+            );
+
+        // GET /mempool/timeline?timeline_id=<timeline ID>&limit=<limit>
+        // The next page starts after the timeline ID of the last transaction returned
+        let mempool_timeline = warp::path!("mempool" / "timeline")
+            .and(warp::query::<MempoolTimelineQuery>())
+            .and(mempool.clone())
+            .map(
+                |query: MempoolTimelineQuery, mempool: Arc<dyn MempoolDebugInterface>| {
+                    warp::reply::json(&mempool.get_timeline(
+                        query.timeline_id.unwrap_or(0),
+                        mempool_view_size(query.limit),
+                    ))
+                },
+            );
+
+        // GET /mempool/peers
+        let mempool_peers = warp::path!("mempool" / "peers")
+            .and(mempool)
+            .map(|mempool: Arc<dyn MempoolDebugInterface>| warp::reply::json(&mempool.get_peers()));
+
+        let routes = warp::get().and(
+            metrics
+                .or(events)
+                .or(mempool_transactions)
+                .or(mempool_parking_lot)
+                .or(mempool_timeline)
+                .or(mempool_peers),
+        );
 
         let server = runtime.enter(move || warp::serve(routes).bind(address));
         runtime.handle().spawn(server);
@@ -37,3 +113,18 @@ impl NodeDebugService {
         Self { runtime }
     }
 }
+
+fn mempool_view_size(limit: Option<usize>) -> usize {
+    limit.map_or(MAX_MEMPOOL_VIEW_SIZE, |limit| {
+        min(limit, MAX_MEMPOOL_VIEW_SIZE)
+    })
+}
+
+fn mempool_reply(txns: Result<Vec<MempoolTransactionView>>) -> warp::reply::WithStatus<Json> {
+    match txns {
+        Ok(txns) => warp::reply::with_status(warp::reply::json(&txns), StatusCode::OK),
+        Err(e) => {
+            warp::reply::with_status(warp::reply::json(&e.to_string()), StatusCode::BAD_REQUEST)
+        }
+    }
+}
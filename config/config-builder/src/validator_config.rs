@@ -164,6 +164,8 @@ impl ValidatorConfig {
             let db_rw = DbReaderWriter::new(LibraDB::open(
                 &path, false, /* readonly */
                 None,  /* pruner */
+                None,  /* ledger pruner */
+                None,  /* write set window */
             )?);
             let waypoint = db_bootstrapper::generate_waypoint::<LibraVM>(&db_rw, &genesis)?;
             (WaypointConfig::FromConfig(waypoint), Some(waypoint))
@@ -199,6 +201,7 @@ impl ValidatorConfig {
         if let Some(server_address) = self.safety_rules_addr {
             safety_rules_config.service = SafetyRulesService::Process(RemoteService {
                 server_address: server_address.into(),
+                noise: None,
             })
         }
 
@@ -39,8 +39,8 @@ impl CreateWaypoint {
         let genesis = genesis_helper.execute()?;
 
         let path = TempPath::new();
-        let libradb =
-            LibraDB::open(&path, false, None).map_err(|e| Error::UnexpectedError(e.to_string()))?;
+        let libradb = LibraDB::open(&path, false, None, None, None)
+            .map_err(|e| Error::UnexpectedError(e.to_string()))?;
         let db_rw = DbReaderWriter::new(libradb);
 
         db_bootstrapper::generate_waypoint::<LibraVM>(&db_rw, &genesis)
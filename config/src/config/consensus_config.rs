@@ -10,6 +10,10 @@ use std::{collections::HashMap, path::PathBuf};
 #[serde(default, deny_unknown_fields)]
 pub struct ConsensusConfig {
     pub contiguous_rounds: u32,
+    // Vote on the ordering of blocks before executing them: execution is taken off the critical
+    // path of voting and its result is certified afterwards by a separate round of commit votes.
+    // SafetyRules only signs the votes of this mode, a SafetyRules process has its own setting.
+    pub decoupled_execution: bool,
     pub max_block_size: u64,
     pub max_pruned_blocks_in_mem: usize,
     pub round_initial_timeout_ms: u64,
@@ -24,13 +28,13 @@ impl Default for ConsensusConfig {
     fn default() -> ConsensusConfig {
         ConsensusConfig {
             contiguous_rounds: 2,
+            decoupled_execution: false,
             max_block_size: 1000,
             max_pruned_blocks_in_mem: 10000,
             round_initial_timeout_ms: 1000,
-            proposer_type: ConsensusProposerType::LeaderReputation(LeaderReputationConfig {
-                active_weights: 99,
-                inactive_weights: 1,
-            }),
+            proposer_type: ConsensusProposerType::LeaderReputation(
+                LeaderReputationConfig::default(),
+            ),
             safety_rules: SafetyRulesConfig::default(),
             sync_only: false,
         }
@@ -58,9 +62,43 @@ pub enum ConsensusProposerType {
     RoundProposer(HashMap<Round, AccountAddress>),
 }
 
+// All the validators must use the same parameters: the proposer elected for a round depends on
+// them, and validators that don't agree on the proposer reject each other's proposals. The
+// parameters of the on-chain consensus config take precedence over these ones once they are set,
+// so that all the validators switch together at an epoch boundary.
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(deny_unknown_fields)]
+#[serde(default, deny_unknown_fields)]
 pub struct LeaderReputationConfig {
     pub active_weights: u64,
     pub inactive_weights: u64,
+    // Weight of the validators that missed too many rounds, only used by the participation
+    // heuristic
+    pub failed_weights: u64,
+    // Share of the rounds in the window a validator may miss before getting failed_weights
+    pub failure_threshold_percent: u64,
+    pub heuristic: ReputationHeuristicType,
+    // Number of committed blocks to look back at, 0 means the size of the validator set
+    pub window_size: usize,
+}
+
+impl Default for LeaderReputationConfig {
+    fn default() -> LeaderReputationConfig {
+        LeaderReputationConfig {
+            active_weights: 99,
+            inactive_weights: 1,
+            failed_weights: 1,
+            failure_threshold_percent: 10,
+            heuristic: ReputationHeuristicType::ActiveInactive,
+            window_size: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReputationHeuristicType {
+    // Validators that proposed or voted in the window are active, others are inactive
+    ActiveInactive,
+    // Accounts for the rounds a validator missed and for its vote participation as well
+    Participation,
 }
@@ -15,6 +15,8 @@ pub enum Error {
     Yaml(String, #[source] serde_yaml::Error),
     #[error("Config is missing expected value: {0}")]
     Missing(&'static str),
+    #[error("Unable to read key {0} from secure storage: {1}")]
+    SecureStorage(String, String),
 }
 
 pub fn invariant(cond: bool, msg: String) -> Result<(), Error> {
@@ -8,7 +8,15 @@ use serde::{Deserialize, Serialize};
 pub struct MempoolConfig {
     pub capacity: usize,
     pub capacity_per_user: usize,
+    /// Optional quota on the total size, in bytes, of the raw transactions of a single account
+    pub capacity_bytes_per_user: Option<usize>,
+    /// Records accepted and removed transactions in an on-disk journal under the storage
+    /// directory, so that pending transactions survive a node restart
+    pub enable_journal: bool,
     pub max_broadcasts_per_peer: usize,
+    /// Minimum gas price increase, in percent, for a transaction to replace the one with the same
+    /// sender and sequence number already in mempool
+    pub replace_by_fee_bump_percent: u64,
     pub shared_mempool_backoff_interval_ms: u64,
     pub shared_mempool_batch_size: usize,
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
@@ -27,6 +35,9 @@ impl Default for MempoolConfig {
             max_broadcasts_per_peer: 25,
             capacity: 1_000_000,
             capacity_per_user: 100,
+            capacity_bytes_per_user: None,
+            enable_journal: false,
+            replace_by_fee_bump_percent: 10,
             system_transaction_timeout_secs: 86400,
             system_transaction_gc_interval_ms: 180_000,
         }
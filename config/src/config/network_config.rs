@@ -56,6 +56,10 @@ pub struct NetworkConfig {
     // in case some peers don't have well defined addresses.
     pub seed_pubkeys: SeedPublicKeys,
     pub max_frame_size: usize,
+    // Limits on the messages received from each peer, unlimited by default.
+    pub inbound_rate_limit: InboundRateLimitConfig,
+    // Scoring of the peers on their misbehavior, as reported by the applications.
+    pub peer_scoring: PeerScoringConfig,
 }
 
 impl Default for NetworkConfig {
@@ -76,6 +80,8 @@ impl NetworkConfig {
             seed_pubkeys: HashMap::default(),
             seed_addrs: HashMap::default(),
             max_frame_size: MAX_FRAME_SIZE,
+            inbound_rate_limit: InboundRateLimitConfig::default(),
+            peer_scoring: PeerScoringConfig::default(),
         };
         config.prepare_identity();
         config
@@ -228,6 +234,56 @@ pub struct GossipConfig {
     pub discovery_interval_ms: u64,
 }
 
+/// Token-bucket limits on the messages received from a peer. Messages going over the limits are
+/// dropped before reaching the upstream application.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InboundRateLimitConfig {
+    // Limits on all the messages received from a peer.
+    pub peer: Option<RateLimitConfig>,
+    // Limits on the messages received from a peer for an application protocol, keyed by the
+    // protocol name, e.g., "MempoolDirectSend".
+    pub protocols: HashMap<String, RateLimitConfig>,
+    // Disconnect from a peer once that many of its messages in a row got dropped. Its messages are
+    // only dropped if unset.
+    pub max_consecutive_dropped_messages: Option<u64>,
+}
+
+/// A message takes one token from the message bucket, and as many tokens as its size in bytes
+/// from the byte bucket. Buckets hold up to their burst size, and get refilled continuously at
+/// their rate per second.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub messages_per_sec: u64,
+    pub burst_messages: u64,
+    pub bytes_per_sec: u64,
+    pub burst_bytes: u64,
+}
+
+/// Every misbehavior reported on a peer, besides timeouts, lowers its score, which decays back
+/// towards zero over time.
+/// A peer whose score drops to the ban threshold gets disconnected, and is neither dialed nor
+/// accepted for the duration of the ban.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerScoringConfig {
+    // Time it takes for the score of a peer to decay halfway back to zero.
+    pub decay_half_life_secs: u64,
+    pub ban_threshold: i64,
+    pub ban_duration_secs: u64,
+}
+
+impl Default for PeerScoringConfig {
+    fn default() -> Self {
+        Self {
+            decay_half_life_secs: 300,
+            ban_threshold: -100,
+            ban_duration_secs: 600,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Identity {
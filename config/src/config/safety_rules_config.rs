@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::{LoggerConfig, SecureBackend},
+    config::{Error, LoggerConfig, SecureBackend},
     keys::ConfigKey,
 };
-use libra_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+use libra_crypto::{ed25519::Ed25519PrivateKey, x25519, Uniform};
 use libra_network_address::NetworkAddress;
+use libra_secure_storage::{CryptoStorage, Storage};
 use libra_types::{waypoint::Waypoint, PeerId};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
@@ -18,7 +19,13 @@ use std::{
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct SafetyRulesConfig {
+    // Records what SafetyRules signed and rejected apart from the secure storage, disabled if
+    // unset.
+    pub audit_log: Option<AuditLogConfig>,
     pub backend: SecureBackend,
+    // Execution mode of a SafetyRules process, which doesn't read the consensus config: it has to
+    // match the `decoupled_execution` of the consensus config. Other services take the latter.
+    pub decoupled_execution: bool,
     pub logger: LoggerConfig,
     pub service: SafetyRulesService,
     pub test: Option<SafetyRulesTestConfig>,
@@ -30,7 +37,9 @@ pub struct SafetyRulesConfig {
 impl Default for SafetyRulesConfig {
     fn default() -> Self {
         Self {
+            audit_log: None,
             backend: SecureBackend::InMemoryStorage,
+            decoupled_execution: false,
             logger: LoggerConfig::default(),
             service: SafetyRulesService::Thread,
             test: None,
@@ -43,12 +52,54 @@ impl Default for SafetyRulesConfig {
 
 impl SafetyRulesConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        if let Some(audit_log) = &mut self.audit_log {
+            audit_log.set_data_dir(data_dir.clone());
+        }
         if let SecureBackend::OnDiskStorage(backend) = &mut self.backend {
             backend.set_data_dir(data_dir);
         }
     }
 }
 
+/// The audit log is an append-only sequence of files within `path`, a new file is started once
+/// the current one holds `max_file_records` records and only the latest `max_files` files are
+/// kept.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditLogConfig {
+    // Directory of the audit log, relative to the data directory unless absolute
+    pub path: PathBuf,
+    pub max_file_records: u64,
+    pub max_files: usize,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("safety_rules_audit_log"),
+            max_file_records: 100_000,
+            max_files: 10,
+            data_dir: PathBuf::from("/opt/libra/data"),
+        }
+    }
+}
+
+impl AuditLogConfig {
+    pub fn path(&self) -> PathBuf {
+        if self.path.is_relative() {
+            self.data_dir.join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
+
 /// Defines how safety rules should be executed
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
@@ -68,6 +119,10 @@ pub enum SafetyRulesService {
 #[serde(deny_unknown_fields)]
 pub struct RemoteService {
     pub server_address: NetworkAddress,
+    // Mutually authenticates consensus and safety rules and encrypts their communications, this
+    // is required as soon as they don't run on the same host.
+    #[serde(default)]
+    pub noise: Option<RemoteServiceNoise>,
 }
 
 impl RemoteService {
@@ -80,6 +135,27 @@ impl RemoteService {
     }
 }
 
+/// The keys of the Noise IK handshake between consensus and safety rules, each end of the
+/// connection configures its own identity key and the public key of the other end.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteServiceNoise {
+    pub backend: SecureBackend,
+    pub key_name: String,
+    pub peer_public_key: x25519::PublicKey,
+}
+
+impl RemoteServiceNoise {
+    pub fn identity_key(&self) -> Result<x25519::PrivateKey, Error> {
+        let storage: Storage = (&self.backend).into();
+        let key = storage
+            .export_private_key(&self.key_name)
+            .map_err(|e| Error::SecureStorage(self.key_name.clone(), e.to_string()))?;
+        x25519::PrivateKey::from_ed25519_private_bytes(&key.to_bytes())
+            .map_err(|e| Error::SecureStorage(self.key_name.clone(), e.to_string()))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SafetyRulesTestConfig {
     pub author: PeerId,
@@ -6,8 +6,17 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
+    // whether a full node should apply the transaction outputs (write sets and events) carried on
+    // the chunks it syncs instead of re-executing the transactions, trusting the quorum-signed
+    // transaction infos. Upstream peers only send outputs they keep (see
+    // `storage.write_set_window`), and send the transactions to re-execute otherwise
+    pub apply_transaction_outputs: bool,
     // Size of chunk to request for state synchronization
+    // (also the number of accounts in a chunk of a state snapshot)
     pub chunk_limit: u64,
+    // whether a node with an empty ledger should restore the state snapshot of a peer at the end
+    // of its previous epoch instead of executing all the transactions since genesis
+    pub enable_fast_sync: bool,
     // default timeout used for long polling to remote peer
     pub long_poll_timeout_ms: u64,
     // valid maximum chunk limit for sanity check
@@ -30,7 +39,9 @@ pub struct StateSyncConfig {
 impl Default for StateSyncConfig {
     fn default() -> Self {
         Self {
+            apply_transaction_outputs: false,
             chunk_limit: 250,
+            enable_fast_sync: false,
             long_poll_timeout_ms: 10_000,
             max_chunk_limit: 1000,
             max_pending_li_limit: 1000,
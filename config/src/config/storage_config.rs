@@ -18,6 +18,13 @@ pub struct StorageConfig {
     /// None disables pruning. The windows is in number of versions, consider system tps
     /// (transaction per second) when calculating proper window.
     pub prune_window: Option<u64>,
+    /// Window for pruning transactions, transaction infos and events, in number of versions.
+    /// None disables ledger pruning, in which case the full history is kept.
+    pub ledger_prune_window: Option<u64>,
+    /// Number of latest versions to keep write sets for, so that full nodes syncing with
+    /// `apply_transaction_outputs` can be served transaction outputs. None disables storing write
+    /// sets.
+    pub write_set_window: Option<u64>,
     #[serde(skip)]
     data_dir: PathBuf,
     /// Read, Write, Connect timeout for network operations in milliseconds
@@ -34,6 +41,8 @@ impl Default for StorageConfig {
             // At 100 tps on avg, we keep 4~5 days of history.
             // n.b. Validators have more aggressive override in the config builder.
             prune_window: Some(40_000_000),
+            ledger_prune_window: None,
+            write_set_window: None,
             data_dir: PathBuf::from("/opt/libra/data"),
             // Default read/write/connection timeout, in milliseconds
             timeout_ms: 30_000,
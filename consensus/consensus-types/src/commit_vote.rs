@@ -0,0 +1,97 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{Author, Round};
+use anyhow::Context;
+use libra_crypto::ed25519::Ed25519Signature;
+use libra_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfo, validator_verifier::ValidatorVerifier,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+
+/// CommitVote is sent by a validator in decoupled execution mode once it executed the blocks
+/// committed by an ordered LedgerInfo. It carries the LedgerInfo with the execution result of the
+/// last of those blocks; a quorum of commit votes certifies the result so it can be persisted.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct CommitVote {
+    /// The identity of the voter.
+    author: Author,
+    /// LedgerInfo of the executed block that is going to be committed in case this vote gathers
+    /// a quorum.
+    ledger_info: LedgerInfo,
+    /// Signature of the LedgerInfo
+    signature: Ed25519Signature,
+}
+
+// this is required by structured log
+impl Debug for CommitVote {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for CommitVote {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "CommitVote: [author: {}, {}]",
+            self.author.short_str(),
+            self.ledger_info
+        )
+    }
+}
+
+impl CommitVote {
+    /// Generates a new CommitVote out of a LedgerInfo signed by SafetyRules.
+    pub fn new_with_signature(
+        author: Author,
+        ledger_info: LedgerInfo,
+        signature: Ed25519Signature,
+    ) -> Self {
+        Self {
+            author,
+            ledger_info,
+            signature,
+        }
+    }
+
+    /// Return the author of the commit vote
+    pub fn author(&self) -> Author {
+        self.author
+    }
+
+    /// Return the LedgerInfo associated with this commit vote
+    pub fn ledger_info(&self) -> &LedgerInfo {
+        &self.ledger_info
+    }
+
+    /// Return the signature of the commit vote
+    pub fn signature(&self) -> &Ed25519Signature {
+        &self.signature
+    }
+
+    /// Return the executed block the commit vote is going to commit
+    pub fn commit_info(&self) -> &BlockInfo {
+        self.ledger_info.commit_info()
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.ledger_info.epoch()
+    }
+
+    pub fn round(&self) -> Round {
+        self.ledger_info.round()
+    }
+
+    /// Verifies that the LedgerInfo carries an execution result and its signature.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.commit_info().is_ordered_only(),
+            "CommitVote doesn't carry an execution result"
+        );
+        validator
+            .verify(self.author(), &self.ledger_info, &self.signature)
+            .context("Failed to verify CommitVote")
+    }
+}
@@ -85,7 +85,15 @@ impl ExecutedBlock {
         )
     }
 
-    pub fn maybe_signed_vote_proposal(&self) -> MaybeSignedVoteProposal {
+    /// In decoupled execution mode the block is not executed before voting, so the vote proposal
+    /// only carries the block to be ordered.
+    pub fn maybe_signed_vote_proposal(&self, decoupled_execution: bool) -> MaybeSignedVoteProposal {
+        if decoupled_execution {
+            return MaybeSignedVoteProposal {
+                vote_proposal: VoteProposal::new_ordered_only(self.block.clone()),
+                signature: None,
+            };
+        }
         MaybeSignedVoteProposal {
             vote_proposal: VoteProposal::new(
                 self.compute_result().extension_proof(),
@@ -93,6 +93,31 @@ impl QuorumCert {
         )
     }
 
+    /// In decoupled execution mode, merges a QC whose LedgerInfo orders a block with the
+    /// LedgerInfo certifying the execution result of the same block. The resulting QC carries the
+    /// same vote data, so it still certifies the same block, but commits the executed state.
+    pub fn create_merged_with_executed_state(
+        &self,
+        executed_ledger_info: LedgerInfoWithSignatures,
+    ) -> anyhow::Result<QuorumCert> {
+        ensure!(
+            self.commit_info()
+                .match_ordered_only(executed_ledger_info.ledger_info().commit_info()),
+            "Executed commit info {} doesn't match ordered commit info {}",
+            executed_ledger_info.ledger_info().commit_info(),
+            self.commit_info()
+        );
+        ensure!(
+            self.ledger_info().ledger_info().consensus_data_hash()
+                == executed_ledger_info.ledger_info().consensus_data_hash(),
+            "Executed LedgerInfo has different consensus data hash than ordered LedgerInfo"
+        );
+        Ok(QuorumCert::new(
+            self.vote_data.clone(),
+            executed_ledger_info,
+        ))
+    }
+
     pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
         let vote_hash = self.vote_data.hash();
         ensure!(
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::vote::Vote;
+use libra_types::ledger_info::LedgerInfo;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -12,6 +13,10 @@ pub struct SafetyData {
     pub last_voted_round: u64,
     pub preferred_round: u64,
     pub last_vote: Option<Vote>,
+    /// The executed LedgerInfo of the last commit vote (decoupled execution), absent from the
+    /// data stored by older versions.
+    #[serde(default)]
+    pub last_commit_vote: Option<LedgerInfo>,
 }
 
 impl SafetyData {
@@ -26,6 +31,7 @@ impl SafetyData {
             last_voted_round,
             preferred_round,
             last_vote,
+            last_commit_vote: None,
         }
     }
 }
@@ -1,10 +1,12 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::block::Block;
+use crate::{block::Block, vote_data::VoteData};
 use libra_crypto::{ed25519::Ed25519Signature, hash::TransactionAccumulatorHasher};
 use libra_crypto_derive::{CryptoHasher, LCSCryptoHash};
-use libra_types::{epoch_state::EpochState, proof::AccumulatorExtensionProof};
+use libra_types::{
+    block_info::BlockInfo, epoch_state::EpochState, proof::AccumulatorExtensionProof,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{Display, Formatter},
@@ -23,6 +25,10 @@ pub struct VoteProposal {
     block: Block,
     /// An optional field containing the next epoch info.
     next_epoch_state: Option<EpochState>,
+    /// In decoupled execution mode the vote only certifies the ordering of the block, its
+    /// execution result is certified later by commit votes. This field changes the serialization
+    /// of the requests to a safety rules process, so both have to be upgraded together.
+    decoupled_execution: bool,
 }
 
 impl VoteProposal {
@@ -35,6 +41,17 @@ impl VoteProposal {
             accumulator_extension_proof,
             block,
             next_epoch_state,
+            decoupled_execution: false,
+        }
+    }
+
+    /// Creates a VoteProposal for a block that has been ordered but not executed yet.
+    pub fn new_ordered_only(block: Block) -> Self {
+        Self {
+            accumulator_extension_proof: AccumulatorExtensionProof::new(vec![], 0, vec![]),
+            block,
+            next_epoch_state: None,
+            decoupled_execution: true,
         }
     }
 
@@ -51,6 +68,24 @@ impl VoteProposal {
     pub fn next_epoch_state(&self) -> Option<&EpochState> {
         self.next_epoch_state.as_ref()
     }
+
+    pub fn decoupled_execution(&self) -> bool {
+        self.decoupled_execution
+    }
+
+    /// The VoteData of a vote on the ordering of the block only, the executed state is left as a
+    /// placeholder.
+    pub fn vote_data_ordering_only(&self) -> VoteData {
+        VoteData::new(
+            BlockInfo::new_ordered_only(
+                self.block.epoch(),
+                self.block.round(),
+                self.block.id(),
+                self.block.timestamp_usecs(),
+            ),
+            self.block.quorum_cert().certified_block().clone(),
+        )
+    }
 }
 
 impl Display for VoteProposal {
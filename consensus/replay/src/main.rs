@@ -0,0 +1,80 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Inspects the consensus state persisted by a stopped node: prints its block tree, or replays
+//! the recorded blocks and quorum certs to find out where the node stalled.
+
+use anyhow::{format_err, Result};
+use consensus::replay::{ConsensusDbData, ConsensusReplay};
+use libra_config::config::{ConsensusConfig, NodeConfig};
+use libradb::LibraDB;
+use std::path::PathBuf;
+use storage_interface::DbReader;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Replays the consensus state persisted by a node")]
+struct Opt {
+    /// Storage directory of the node, holding both its libradb and consensusdb
+    #[structopt(long, parse(from_os_str))]
+    db: PathBuf,
+
+    /// Config of the node, to replay with its consensus config instead of the default one
+    #[structopt(long, parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    cmd: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Prints the fork tree of the blocks with their rounds, quorum certs and commits
+    #[structopt(name = "print-tree")]
+    PrintTree,
+
+    /// Replays the recorded blocks and quorum certs, printing the outcome of every event
+    #[structopt(name = "replay")]
+    Replay {
+        /// Stops after the given round
+        #[structopt(long)]
+        until_round: Option<u64>,
+    },
+}
+
+fn main() -> Result<()> {
+    ::libra_logger::Logger::new().init();
+    let opt = Opt::from_args();
+
+    let config = match &opt.config {
+        Some(path) => NodeConfig::load(path)?.consensus,
+        None => ConsensusConfig::default(),
+    };
+    let libra_db = LibraDB::open(
+        &opt.db, true, /* readonly */
+        None, /* pruner */
+        None, /* ledger pruner */
+        None, /* write set window */
+    )?;
+    let startup_info = libra_db
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("LibraDB is empty, nothing to replay"))?;
+    let replay = ConsensusReplay::new(
+        ConsensusDbData::load(&opt.db)?,
+        startup_info.latest_ledger_info.ledger_info().clone(),
+        startup_info.get_epoch_state().clone(),
+        config,
+    );
+
+    match opt.cmd {
+        Command::PrintTree => print!("{}", replay.render_tree()?),
+        Command::Replay { until_round } => {
+            for step in replay.replay(until_round)? {
+                println!("{}", step);
+            }
+        }
+    }
+    Ok(())
+}
@@ -65,7 +65,7 @@ fn in_memory(n: u64) {
         Ed25519PrivateKey::generate_for_testing(),
         waypoint,
     );
-    let safety_rules_manager = SafetyRulesManager::new_local(storage, false);
+    let safety_rules_manager = SafetyRulesManager::new_local(storage, false, false);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
@@ -80,7 +80,7 @@ fn on_disk(n: u64) {
         Ed25519PrivateKey::generate_for_testing(),
         waypoint,
     );
-    let safety_rules_manager = SafetyRulesManager::new_local(storage, false);
+    let safety_rules_manager = SafetyRulesManager::new_local(storage, false, false);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
@@ -95,7 +95,7 @@ fn serializer(n: u64) {
         Ed25519PrivateKey::generate_for_testing(),
         waypoint,
     );
-    let safety_rules_manager = SafetyRulesManager::new_serializer(storage, false);
+    let safety_rules_manager = SafetyRulesManager::new_serializer(storage, false, false);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
@@ -112,7 +112,7 @@ fn thread(n: u64) {
     );
     // Test value, in milliseconds
     let timeout_ms = 5_000;
-    let safety_rules_manager = SafetyRulesManager::new_thread(storage, false, timeout_ms);
+    let safety_rules_manager = SafetyRulesManager::new_thread(storage, false, false, timeout_ms);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
@@ -138,7 +138,7 @@ fn vault(n: u64) {
     );
     // Test value in milliseconds.
     let timeout_ms = 5_000;
-    let safety_rules_manager = SafetyRulesManager::new_thread(storage, false, timeout_ms);
+    let safety_rules_manager = SafetyRulesManager::new_thread(storage, false, false, timeout_ms);
     lsr(safety_rules_manager.client(), signer, n);
 }
 
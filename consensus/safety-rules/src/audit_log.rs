@@ -0,0 +1,314 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The audit log keeps track of everything SafetyRules signed and of every request it rejected.
+//!
+//! The log lives apart from the secure storage, in a directory of append-only files holding one
+//! JSON record per line. Each file is named after the index of its first record, a new file is
+//! started once the current one is full and the oldest files are removed to bound the size of the
+//! log.
+//!
+//! The log is tamper-evident: every record carries the hash of the previous one, so altering,
+//! reordering or removing a record breaks the chain. The hash of every new record is also sent to
+//! the structured logs, which lets an external log collector detect a log rewritten from scratch.
+
+use crate::Error;
+use anyhow::{ensure, Result};
+use consensus_types::{common::Round, timeout::Timeout, vote::Vote};
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_crypto_derive::{CryptoHasher, LCSCryptoHash};
+use libra_types::ledger_info::LedgerInfo;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// An event recorded in the audit log.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AuditEvent {
+    /// A vote got signed.
+    SignedVote(Vote),
+    /// A proposal got signed, its payload is left out to keep the log small.
+    SignedProposal {
+        epoch: u64,
+        round: Round,
+        block_id: HashValue,
+    },
+    /// A timeout got signed.
+    SignedTimeout(Timeout),
+    /// The execution result of an ordered block got signed.
+    SignedCommitVote(LedgerInfo),
+    /// A request got rejected.
+    Rejected {
+        request: String,
+        round: Option<Round>,
+        error: Error,
+    },
+}
+
+/// A record of the audit log.
+#[derive(Clone, CryptoHasher, Debug, Deserialize, LCSCryptoHash, PartialEq, Serialize)]
+pub struct AuditRecord {
+    /// Position of the record in the log, starting at 0.
+    pub index: u64,
+    /// When the record got appended, in microseconds since the unix epoch.
+    pub timestamp_usecs: u64,
+    /// Hash of the previous record, zero for the first one.
+    pub previous_hash: HashValue,
+    pub event: AuditEvent,
+}
+
+/// Appends records to the audit log held in a directory.
+pub struct AuditLog {
+    dir: PathBuf,
+    max_file_records: u64,
+    max_files: usize,
+    file: File,
+    file_records: u64,
+    next_index: u64,
+    last_hash: HashValue,
+}
+
+impl AuditLog {
+    /// Opens the audit log in `dir`, creating it if needed, and resumes after its last record.
+    pub fn open(dir: &Path, max_file_records: u64, max_files: usize) -> Result<Self> {
+        ensure!(
+            max_file_records > 0 && max_files > 0,
+            "The audit log must keep at least one record"
+        );
+        fs::create_dir_all(dir)?;
+
+        // A crash while starting a new file leaves it without any record, it is then started
+        // again, whereas a crash while appending a record leaves part of it to be overwritten.
+        let mut files = log_files(dir)?;
+        let mut last_file = None;
+        while let Some((_, path)) = files.pop() {
+            let (records, valid_len) = read_file(&path)?;
+            if let Some(record) = records.last() {
+                let file = OpenOptions::new().append(true).open(&path)?;
+                file.set_len(valid_len)?;
+                last_file = Some((file, records.len() as u64, record.index + 1, record.hash()));
+                break;
+            }
+            fs::remove_file(&path)?;
+        }
+
+        let (file, file_records, next_index, last_hash) = match last_file {
+            Some(last_file) => last_file,
+            None => (create_file(dir, 0)?, 0, 0, HashValue::zero()),
+        };
+        let mut audit_log = Self {
+            dir: dir.to_path_buf(),
+            max_file_records,
+            max_files,
+            file,
+            file_records,
+            next_index,
+            last_hash,
+        };
+        audit_log.remove_old_files()?;
+        Ok(audit_log)
+    }
+
+    /// Appends an event to the log and returns the hash of its record.
+    pub fn append(&mut self, event: AuditEvent) -> Result<HashValue> {
+        if self.file_records >= self.max_file_records {
+            self.file = create_file(&self.dir, self.next_index)?;
+            self.file_records = 0;
+            self.remove_old_files()?;
+        }
+
+        let record = AuditRecord {
+            index: self.next_index,
+            timestamp_usecs: libra_time::duration_since_epoch().as_micros() as u64,
+            previous_hash: self.last_hash,
+            event,
+        };
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.sync_data()?;
+
+        self.file_records += 1;
+        self.next_index += 1;
+        self.last_hash = record.hash();
+        Ok(self.last_hash)
+    }
+
+    /// Reads the log kept in `dir`, failing if any record got altered, reordered or removed other
+    /// than by removing the oldest files.
+    pub fn read(dir: &Path) -> Result<Vec<AuditRecord>> {
+        let mut records: Vec<AuditRecord> = vec![];
+        for (first_index, path) in log_files(dir)? {
+            let (file_records, _) = read_file(&path)?;
+            if let Some(record) = file_records.first() {
+                ensure!(
+                    record.index == first_index,
+                    "Audit log file {:?} does not start with record {}",
+                    path,
+                    first_index
+                );
+            }
+            for record in file_records {
+                let (index, previous_hash) = match records.last() {
+                    Some(previous) => (previous.index + 1, previous.hash()),
+                    // The records before the first one kept got removed along with their file.
+                    None if record.index > 0 => (record.index, record.previous_hash),
+                    None => (0, HashValue::zero()),
+                };
+                ensure!(
+                    record.index == index && record.previous_hash == previous_hash,
+                    "Audit record {} does not follow the previous record",
+                    record.index
+                );
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    fn remove_old_files(&mut self) -> Result<()> {
+        let files = log_files(&self.dir)?;
+        let excess = files.len().saturating_sub(self.max_files);
+        for (_, path) in files.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn create_file(dir: &Path, first_index: u64) -> Result<File> {
+    Ok(OpenOptions::new()
+        .append(true)
+        .create_new(true)
+        .open(dir.join(format!("{}.log", first_index)))?)
+}
+
+/// Returns the files of the log, ordered by the index of their first record.
+fn log_files(dir: &Path) -> Result<Vec<(u64, PathBuf)>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "log")
+        {
+            if let Some(first_index) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                files.push((first_index, path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reads the records of a file along with the length they span, leaving out a partially written
+/// last record.
+fn read_file(path: &Path) -> Result<(Vec<AuditRecord>, u64)> {
+    let contents = fs::read(path)?;
+    let mut records = vec![];
+    let mut valid_len = 0;
+    while let Some(end) = contents[valid_len..].iter().position(|byte| *byte == b'\n') {
+        let line = &contents[valid_len..valid_len + end];
+        records.push(serde_json::from_slice(line)?);
+        valid_len += end + 1;
+    }
+    Ok((records, valid_len as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_types::timeout::Timeout;
+    use libra_temppath::TempPath;
+
+    fn timeout_event(round: Round) -> AuditEvent {
+        AuditEvent::SignedTimeout(Timeout::new(1, round))
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let dir = TempPath::new();
+        let mut audit_log = AuditLog::open(dir.path(), 2, 2).unwrap();
+        assert!(AuditLog::read(dir.path()).unwrap().is_empty());
+
+        let events = vec![
+            timeout_event(1),
+            AuditEvent::Rejected {
+                request: "sign_timeout".to_string(),
+                round: Some(1),
+                error: Error::IncorrectLastVotedRound(1, 2),
+            },
+            timeout_event(2),
+        ];
+        for event in &events {
+            audit_log.append(event.clone()).unwrap();
+        }
+        let records = AuditLog::read(dir.path()).unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.event.clone())
+                .collect::<Vec<_>>(),
+            events
+        );
+        assert_eq!(records[1].previous_hash, records[0].hash());
+
+        // Reopening the log carries on the chain
+        drop(audit_log);
+        let mut audit_log = AuditLog::open(dir.path(), 2, 2).unwrap();
+        audit_log.append(timeout_event(3)).unwrap();
+        assert_eq!(AuditLog::read(dir.path()).unwrap().len(), 4);
+
+        // Only the latest files are kept
+        audit_log.append(timeout_event(4)).unwrap();
+        let records = AuditLog::read(dir.path()).unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.index)
+                .collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+
+        // Altering a record breaks the chain
+        let path = dir.path().join("2.log");
+        let contents = fs::read_to_string(&path).unwrap();
+        let mut record: AuditRecord =
+            serde_json::from_str(contents.lines().next().unwrap()).unwrap();
+        record.event = timeout_event(5);
+        let altered = contents.replacen(
+            contents.lines().next().unwrap(),
+            &serde_json::to_string(&record).unwrap(),
+            1,
+        );
+        fs::write(&path, altered).unwrap();
+        AuditLog::read(dir.path()).unwrap_err();
+    }
+
+    #[test]
+    fn test_partial_record() {
+        let dir = TempPath::new();
+        let mut audit_log = AuditLog::open(dir.path(), 10, 1).unwrap();
+        let hash = audit_log.append(timeout_event(1)).unwrap();
+        drop(audit_log);
+
+        // A record cut short by a crash is ignored and overwritten
+        let path = dir.path().join("0.log");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"index\":1").unwrap();
+        assert_eq!(AuditLog::read(dir.path()).unwrap().len(), 1);
+
+        let mut audit_log = AuditLog::open(dir.path(), 10, 1).unwrap();
+        audit_log.append(timeout_event(2)).unwrap();
+        let records = AuditLog::read(dir.path()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].previous_hash, hash);
+    }
+}
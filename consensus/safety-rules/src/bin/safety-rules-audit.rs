@@ -0,0 +1,75 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::{Context, Result};
+use consensus_types::vote::Vote;
+use libra_config::config::{PersistableConfig, SafetyRulesConfig};
+use libra_crypto::{ed25519::Ed25519PublicKey, ValidCryptoMaterialStringExt};
+use safety_rules::{AuditLog, EquivocationEvidence};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "safety-rules-audit",
+    about = "Inspect the audit log of SafetyRules and export equivocation evidence"
+)]
+enum Command {
+    /// Verify the audit log of SafetyRules and print it as JSON lines
+    AuditLog {
+        /// Path to the SafetyRules config
+        #[structopt(parse(from_os_str))]
+        config: PathBuf,
+    },
+    /// Verify that two votes observed on the network prove an equivocation and print the evidence
+    /// as JSON
+    Equivocation {
+        /// Path to the first vote, as JSON
+        #[structopt(long, parse(from_os_str))]
+        first_vote: PathBuf,
+        /// Path to the second vote, as JSON
+        #[structopt(long, parse(from_os_str))]
+        second_vote: PathBuf,
+        /// Hex encoded consensus public key of the author of the votes
+        #[structopt(long)]
+        public_key: String,
+    },
+}
+
+fn main() -> Result<()> {
+    match Command::from_args() {
+        Command::AuditLog { config } => {
+            let config = SafetyRulesConfig::load_config(&config)
+                .with_context(|| format!("Unable to read config {:?}", config))?;
+            let audit_log = config
+                .audit_log
+                .context("The audit log is not enabled in the config")?;
+            for record in AuditLog::read(&audit_log.path())? {
+                println!("{}", serde_json::to_string(&record)?);
+            }
+        }
+        Command::Equivocation {
+            first_vote,
+            second_vote,
+            public_key,
+        } => {
+            let public_key = Ed25519PublicKey::from_encoded_string(&public_key)
+                .context("Invalid consensus public key")?;
+            let evidence = EquivocationEvidence::new(
+                read_vote(first_vote)?,
+                read_vote(second_vote)?,
+                &public_key,
+            )?;
+            println!("{}", serde_json::to_string_pretty(&evidence)?);
+        }
+    }
+    Ok(())
+}
+
+fn read_vote(path: PathBuf) -> Result<Vote> {
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Unable to read {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("Invalid vote in {:?}", path))
+}
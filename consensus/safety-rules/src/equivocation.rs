@@ -0,0 +1,65 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Evidence that a validator equivocated, i.e., signed votes for two different blocks or
+//! execution results in the same round. The two votes are typically the ones reported by the
+//! security log of consensus upon receiving an equivocating vote. The evidence is self-contained
+//! and can be verified by anyone knowing the consensus public key of the validator.
+
+use anyhow::{ensure, Context, Result};
+use consensus_types::vote::Vote;
+use libra_crypto::ed25519::Ed25519PublicKey;
+use libra_types::validator_verifier::ValidatorVerifier;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EquivocationEvidence {
+    pub first_vote: Vote,
+    pub second_vote: Vote,
+}
+
+impl EquivocationEvidence {
+    /// Builds the evidence out of two votes, failing if they don't prove an equivocation of the
+    /// validator holding the given consensus key.
+    pub fn new(first_vote: Vote, second_vote: Vote, public_key: &Ed25519PublicKey) -> Result<Self> {
+        let evidence = Self {
+            first_vote,
+            second_vote,
+        };
+        evidence.verify(public_key)?;
+        Ok(evidence)
+    }
+
+    /// Verifies that both votes are signed by the given consensus key, are for the same epoch and
+    /// round, and certify different ledger infos.
+    pub fn verify(&self, public_key: &Ed25519PublicKey) -> Result<()> {
+        let (first, second) = (&self.first_vote, &self.second_vote);
+        ensure!(
+            first.author() == second.author(),
+            "Votes are from different authors: {} and {}",
+            first.author(),
+            second.author()
+        );
+        ensure!(
+            first.epoch() == second.epoch(),
+            "Votes are for different epochs: {} and {}",
+            first.epoch(),
+            second.epoch()
+        );
+        ensure!(
+            first.vote_data().proposed().round() == second.vote_data().proposed().round(),
+            "Votes are for different rounds: {} and {}",
+            first.vote_data().proposed().round(),
+            second.vote_data().proposed().round()
+        );
+        ensure!(
+            first.ledger_info() != second.ledger_info(),
+            "Votes are for the same ledger info"
+        );
+
+        let verifier = ValidatorVerifier::new_single(first.author(), public_key.clone());
+        first.verify(&verifier).context("Invalid first vote")?;
+        second.verify(&verifier).context("Invalid second vote")?;
+        Ok(())
+    }
+}
@@ -4,13 +4,17 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Deserialize, Error, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Error, PartialEq, Serialize)]
 /// Different reasons for proposal rejection
 pub enum Error {
+    #[error("Commit vote for {0} conflicts with the last commit vote, for {1}")]
+    ConflictingCommitVote(String, String),
     #[error("Provided epoch, {0}, does not match expected epoch, {1}")]
     IncorrectEpoch(u64, u64),
     #[error("block has next round that wraps around: {0}")]
     IncorrectRound(u64),
+    #[error("Request expects decoupled execution to be {0}, but it is {1} in SafetyRules")]
+    IncorrectExecutionMode(bool, bool),
     #[error("Provided round, {0}, is incompatible with last voted round, {1}")]
     IncorrectLastVotedRound(u64, u64),
     #[error("Provided round, {0}, is incompatible with preferred round, {1}")]
@@ -19,10 +23,14 @@ pub enum Error {
     InvalidAccumulatorExtension(String),
     #[error("Invalid EpochChangeProof: {0}")]
     InvalidEpochChangeProof(String),
+    #[error("Executed LedgerInfo {0} is inconsistent with ordered LedgerInfo {1}")]
+    InconsistentExecutionResult(String, String),
     #[error("Internal error: {0}")]
     InternalError(String),
     #[error("No next_epoch_state specified in the provided Ledger Info")]
     InvalidLedgerInfo,
+    #[error("Invalid ordered LedgerInfo: {0}")]
+    InvalidOrderedLedgerInfo(String),
     #[error("Invalid proposal: {}", {0})]
     InvalidProposal(String),
     #[error("Invalid QC: {}", {0})]
@@ -47,6 +55,12 @@ impl From<lcs::Error> for Error {
     }
 }
 
+impl From<libra_config::config::Error> for Error {
+    fn from(error: libra_config::config::Error) -> Self {
+        Self::InternalError(error.to_string())
+    }
+}
+
 impl From<libra_secure_net::Error> for Error {
     fn from(error: libra_secure_net::Error) -> Self {
         Self::InternalError(error.to_string())
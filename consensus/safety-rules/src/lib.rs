@@ -3,8 +3,10 @@
 
 #![forbid(unsafe_code)]
 
+mod audit_log;
 mod consensus_state;
 mod counters;
+mod equivocation;
 mod error;
 mod local_client;
 mod logging;
@@ -18,9 +20,14 @@ mod t_safety_rules;
 mod thread;
 
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
-    persistent_safety_storage::PersistentSafetyStorage, process::Process,
-    safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
+    audit_log::{AuditEvent, AuditLog, AuditRecord},
+    consensus_state::ConsensusState,
+    equivocation::EquivocationEvidence,
+    error::Error,
+    persistent_safety_storage::PersistentSafetyStorage,
+    process::Process,
+    safety_rules::SafetyRules,
+    safety_rules_manager::SafetyRulesManager,
     t_safety_rules::TSafetyRules,
 };
 
@@ -7,7 +7,7 @@ use consensus_types::{
     vote_proposal::MaybeSignedVoteProposal,
 };
 use libra_crypto::ed25519::Ed25519Signature;
-use libra_types::epoch_change::EpochChangeProof;
+use libra_types::{epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures};
 use std::sync::{Arc, RwLock};
 
 /// A local interface into SafetyRules. Constructed in such a way that the container / caller
@@ -49,4 +49,15 @@ impl TSafetyRules for LocalClient {
     fn sign_timeout(&mut self, timeout: &Timeout) -> Result<Ed25519Signature, Error> {
         self.internal.write().unwrap().sign_timeout(timeout)
     }
+
+    fn sign_commit_vote(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        executed_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Ed25519Signature, Error> {
+        self.internal
+            .write()
+            .unwrap()
+            .sign_commit_vote(ledger_info, executed_proposal)
+    }
 }
@@ -10,6 +10,7 @@ pub fn safety_log(entry: LogEntry, event: LogEvent) -> StructuredLogEntry {
 
 #[derive(Clone, Copy)]
 pub enum LogEntry {
+    AuditLog,
     ConsensusState,
     ConstructAndSignVote,
     Epoch,
@@ -17,6 +18,7 @@ pub enum LogEntry {
     KeyReconciliation,
     LastVotedRound,
     PreferredRound,
+    SignCommitVote,
     SignProposal,
     SignTimeout,
     Waypoint,
@@ -25,6 +27,7 @@ pub enum LogEntry {
 impl LogEntry {
     pub fn as_str(&self) -> &'static str {
         match self {
+            LogEntry::AuditLog => "audit_log",
             LogEntry::ConsensusState => "consensus_state",
             LogEntry::ConstructAndSignVote => "construct_and_sign_vote",
             LogEntry::Epoch => "epoch",
@@ -32,6 +35,7 @@ impl LogEntry {
             LogEntry::LastVotedRound => "last_voted_round",
             LogEntry::KeyReconciliation => "key_reconciliation",
             LogEntry::PreferredRound => "preferred_round",
+            LogEntry::SignCommitVote => "sign_commit_vote",
             LogEntry::SignProposal => "sign_proposal",
             LogEntry::SignTimeout => "sign_timeout",
             LogEntry::Waypoint => "waypoint",
@@ -32,6 +32,9 @@ fn main() {
 
     MetricsPusher.start();
 
-    let mut service = Process::new(config);
+    let mut service = Process::new(config).unwrap_or_else(|e| {
+        eprintln!("Unable to start SafetyRules: {}", e);
+        process::exit(1);
+    });
     service.start();
 }
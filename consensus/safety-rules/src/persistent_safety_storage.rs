@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::{AuditEvent, AuditLog},
     counters,
     logging::{self, LogEntry, LogEvent, LogField},
 };
@@ -20,6 +21,8 @@ use libra_types::waypoint::Waypoint;
 /// @TODO add retrieval of private key based upon public key to persistent store
 pub struct PersistentSafetyStorage {
     internal_store: Storage,
+    // Kept apart from the internal store, which is not meant to hold an ever growing log.
+    audit_log: Option<AuditLog>,
 }
 
 impl PersistentSafetyStorage {
@@ -54,7 +57,10 @@ impl PersistentSafetyStorage {
             waypoint,
         )
         .expect("Unable to initialize backend storage");
-        Self { internal_store }
+        Self {
+            internal_store,
+            audit_log: None,
+        }
     }
 
     fn initialize_(
@@ -85,7 +91,16 @@ impl PersistentSafetyStorage {
     /// Use this to instantiate a PersistentStorage with an existing data store. This is intended
     /// for constructed environments.
     pub fn new(internal_store: Storage) -> Self {
-        Self { internal_store }
+        Self {
+            internal_store,
+            audit_log: None,
+        }
+    }
+
+    /// Records the events passed to `append_audit_event` in the given audit log.
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
     }
 
     pub fn author(&self) -> Result<Author> {
@@ -131,6 +146,16 @@ impl PersistentSafetyStorage {
         Ok(())
     }
 
+    /// Appends an event to the audit log if there is one, see the audit_log module.
+    pub fn append_audit_event(&mut self, event: AuditEvent) -> Result<()> {
+        if let Some(audit_log) = &mut self.audit_log {
+            let hash = audit_log.append(event)?;
+            send_struct_log!(logging::safety_log(LogEntry::AuditLog, LogEvent::Update)
+                .data(LogField::Message.as_str(), hash));
+        }
+        Ok(())
+    }
+
     #[cfg(any(test, feature = "testing"))]
     pub fn internal_store(&mut self) -> &mut Storage {
         &mut self.internal_store
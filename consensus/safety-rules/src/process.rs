@@ -3,10 +3,10 @@
 
 use crate::{
     persistent_safety_storage::PersistentSafetyStorage,
-    remote_service::{self, RemoteService},
-    safety_rules_manager,
+    remote_service::{self, NoiseKeys, RemoteService},
+    safety_rules_manager, Error,
 };
-use libra_config::config::{SafetyRulesConfig, SafetyRulesService};
+use libra_config::config::{RemoteServiceNoise, SafetyRulesConfig, SafetyRulesService};
 
 use std::net::SocketAddr;
 
@@ -15,24 +15,32 @@ pub struct Process {
 }
 
 impl Process {
-    pub fn new(config: SafetyRulesConfig) -> Self {
+    pub fn new(config: SafetyRulesConfig) -> Result<Self, Error> {
         let storage = safety_rules_manager::storage(&config);
 
         let verify_vote_proposal_signature = config.verify_vote_proposal_signature;
+        let decoupled_execution = config.decoupled_execution;
         let service = match &config.service {
             SafetyRulesService::Process(service) => service,
             _ => panic!("Unexpected SafetyRules service: {:?}", config.service),
         };
         let server_addr = service.server_address();
+        let noise_keys = service
+            .noise
+            .as_ref()
+            .map(|noise| Ok::<_, Error>((noise.identity_key()?, noise.peer_public_key)))
+            .transpose()?;
 
-        Self {
+        Ok(Self {
             data: Some(ProcessData {
                 server_addr,
                 storage,
                 verify_vote_proposal_signature,
+                decoupled_execution,
                 network_timeout: config.network_timeout_ms,
+                noise_keys,
             }),
-        }
+        })
     }
 
     pub fn start(&mut self) {
@@ -41,7 +49,9 @@ impl Process {
             data.storage,
             data.server_addr,
             data.verify_vote_proposal_signature,
+            data.decoupled_execution,
             data.network_timeout,
+            data.noise_keys,
         );
     }
 }
@@ -50,20 +60,28 @@ struct ProcessData {
     server_addr: SocketAddr,
     storage: PersistentSafetyStorage,
     verify_vote_proposal_signature: bool,
+    decoupled_execution: bool,
     // Timeout in Seconds for network operations
     network_timeout: u64,
+    noise_keys: Option<NoiseKeys>,
 }
 
 pub struct ProcessService {
     server_addr: SocketAddr,
     network_timeout_ms: u64,
+    noise: Option<RemoteServiceNoise>,
 }
 
 impl ProcessService {
-    pub fn new(server_addr: SocketAddr, network_timeout: u64) -> Self {
+    pub fn new(
+        server_addr: SocketAddr,
+        network_timeout: u64,
+        noise: Option<RemoteServiceNoise>,
+    ) -> Self {
         Self {
             server_addr,
             network_timeout_ms: network_timeout,
+            noise,
         }
     }
 }
@@ -76,4 +94,12 @@ impl RemoteService for ProcessService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout_ms
     }
+
+    /// The identity key is read from the secure storage whenever a new client is created.
+    fn noise_keys(&self) -> Result<Option<NoiseKeys>, Error> {
+        self.noise
+            .as_ref()
+            .map(|noise| Ok::<_, Error>((noise.identity_key()?, noise.peer_public_key)))
+            .transpose()
+    }
 }
@@ -6,14 +6,34 @@ use crate::{
     serializer::{SafetyRulesInput, SerializerClient, SerializerService, TSerializerClient},
     Error, SafetyRules,
 };
-use libra_logger::warn;
+use libra_crypto::x25519;
+use libra_logger::{error, warn};
 use libra_secure_net::{NetworkClient, NetworkServer};
 use std::net::SocketAddr;
 
+/// The identity key of this end of the connection and the public key of the other end.
+pub type NoiseKeys = (x25519::PrivateKey, x25519::PublicKey);
+
 pub trait RemoteService {
+    /// If the keys of the connection can't be read, the client fails all the requests.
     fn client(&self) -> SerializerClient {
-        let network_client = NetworkClient::new(self.server_address(), self.network_timeout_ms());
-        let service = Box::new(RemoteClient::new(network_client));
+        let service: Box<dyn TSerializerClient> = match self.noise_keys() {
+            Ok(noise_keys) => {
+                let mut network_client =
+                    NetworkClient::new(self.server_address(), self.network_timeout_ms());
+                if let Some((identity_key, server_public_key)) = noise_keys {
+                    network_client = network_client.with_noise(identity_key, server_public_key);
+                }
+                Box::new(RemoteClient::new(network_client))
+            }
+            Err(err) => {
+                error!(
+                    "Unable to read the keys of the SafetyRules service: {}",
+                    err
+                );
+                Box::new(UnavailableClient::new(err))
+            }
+        };
         SerializerClient::new_client(service)
     }
 
@@ -21,17 +41,28 @@ pub trait RemoteService {
 
     /// Network Timeout in milliseconds.
     fn network_timeout_ms(&self) -> u64;
+
+    /// Keys authenticating the connection to the server, none to communicate in the clear.
+    fn noise_keys(&self) -> Result<Option<NoiseKeys>, Error> {
+        Ok(None)
+    }
 }
 
 pub fn execute(
     storage: PersistentSafetyStorage,
     listen_addr: SocketAddr,
     verify_vote_proposal_signature: bool,
+    decoupled_execution: bool,
     network_timeout_ms: u64,
+    noise_keys: Option<NoiseKeys>,
 ) {
-    let safety_rules = SafetyRules::new(storage, verify_vote_proposal_signature);
+    let safety_rules =
+        SafetyRules::new(storage, verify_vote_proposal_signature, decoupled_execution);
     let mut serializer_service = SerializerService::new(safety_rules);
     let mut network_server = NetworkServer::new(listen_addr, network_timeout_ms);
+    if let Some((identity_key, client_public_key)) = noise_keys {
+        network_server = network_server.with_noise(identity_key, client_public_key);
+    }
 
     loop {
         if let Err(e) = process_one_message(&mut network_server, &mut serializer_service) {
@@ -76,3 +107,21 @@ impl TSerializerClient for RemoteClient {
         }
     }
 }
+
+/// Client of a SafetyRules service that can't be reached, e.g. because the keys of the connection
+/// are missing: all the requests fail with the error that prevented setting up the connection.
+struct UnavailableClient {
+    error: Error,
+}
+
+impl UnavailableClient {
+    pub fn new(error: Error) -> Self {
+        Self { error }
+    }
+}
+
+impl TSerializerClient for UnavailableClient {
+    fn request(&mut self, _input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
+        Err(self.error.clone())
+    }
+}
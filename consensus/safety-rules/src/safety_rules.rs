@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::AuditEvent,
     consensus_state::ConsensusState,
     counters,
     error::Error,
@@ -27,8 +28,12 @@ use libra_crypto::{
 };
 use libra_logger::prelude::*;
 use libra_types::{
-    block_info::BlockInfo, epoch_change::EpochChangeProof, epoch_state::EpochState,
-    ledger_info::LedgerInfo, validator_signer::ValidatorSigner, waypoint::Waypoint,
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    waypoint::Waypoint,
 };
 use std::cmp::Ordering;
 
@@ -36,16 +41,19 @@ use std::cmp::Ordering;
 pub struct SafetyRules {
     persistent_storage: PersistentSafetyStorage,
     execution_public_key: Option<Ed25519PublicKey>,
+    decoupled_execution: bool,
     validator_signer: Option<ValidatorSigner>,
     epoch_state: Option<EpochState>,
 }
 
 impl SafetyRules {
     /// Constructs a new instance of SafetyRules with the given persistent storage and the
-    /// consensus private keys
+    /// consensus private keys. In decoupled execution mode, only votes on the ordering of blocks
+    /// and commit votes are signed.
     pub fn new(
         persistent_storage: PersistentSafetyStorage,
         verify_vote_proposal_signature: bool,
+        decoupled_execution: bool,
     ) -> Self {
         let execution_public_key = if verify_vote_proposal_signature {
             Some(
@@ -59,6 +67,7 @@ impl SafetyRules {
         Self {
             persistent_storage,
             execution_public_key,
+            decoupled_execution,
             validator_signer: None,
             epoch_state: None,
         }
@@ -76,6 +85,34 @@ impl SafetyRules {
             .ok_or_else(|| Error::NotInitialized("epoch_state".into()))
     }
 
+    /// This verifies the request is meant for the execution mode of SafetyRules
+    fn verify_execution_mode(&self, decoupled_execution: bool) -> Result<(), Error> {
+        if decoupled_execution != self.decoupled_execution {
+            Err(Error::IncorrectExecutionMode(
+                decoupled_execution,
+                self.decoupled_execution,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// This verifies the execution signature of a vote proposal, if SafetyRules has an execution
+    /// public key
+    fn verify_execution_signature(
+        &self,
+        maybe_signed_vote_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<(), Error> {
+        if let Some(public_key) = self.execution_public_key.as_ref() {
+            maybe_signed_vote_proposal
+                .signature
+                .as_ref()
+                .ok_or_else(|| Error::VoteProposalSignatureNotFound)?
+                .verify(&maybe_signed_vote_proposal.vote_proposal, public_key)?
+        }
+        Ok(())
+    }
+
     /// Check if the executed result extends the parent result.
     fn extension_check(&self, vote_proposal: &VoteProposal) -> Result<VoteData, Error> {
         let proposed_block = vote_proposal.block();
@@ -303,15 +340,11 @@ impl SafetyRules {
         self.signer()?;
         let mut safety_data = self.persistent_storage.safety_data()?;
 
-        let (vote_proposal, execution_signature) = (
-            &maybe_signed_vote_proposal.vote_proposal,
-            maybe_signed_vote_proposal.signature.as_ref(),
-        );
-
-        if let Some(public_key) = self.execution_public_key.as_ref() {
-            execution_signature
-                .ok_or_else(|| Error::VoteProposalSignatureNotFound)?
-                .verify(vote_proposal, public_key)?
+        let vote_proposal = &maybe_signed_vote_proposal.vote_proposal;
+        self.verify_execution_mode(vote_proposal.decoupled_execution())?;
+        // An ordering vote doesn't carry any execution result to be verified.
+        if !self.decoupled_execution {
+            self.verify_execution_signature(maybe_signed_vote_proposal)?;
         }
 
         let proposed_block = vote_proposal.block();
@@ -333,7 +366,11 @@ impl SafetyRules {
             &mut safety_data,
         )?;
 
-        let vote_data = self.extension_check(vote_proposal)?;
+        let vote_data = if self.decoupled_execution {
+            vote_proposal.vote_data_ordering_only()
+        } else {
+            self.extension_check(vote_proposal)?
+        };
 
         let validator_signer = self.signer()?;
         let vote = Vote::new(
@@ -399,6 +436,108 @@ impl SafetyRules {
 
         Ok(signature)
     }
+
+    fn guarded_sign_commit_vote(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        maybe_signed_executed_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<(LedgerInfo, Ed25519Signature), Error> {
+        self.signer()?;
+        self.verify_execution_mode(true)?;
+        let mut safety_data = self.persistent_storage.safety_data()?;
+
+        let executed_proposal = &maybe_signed_executed_proposal.vote_proposal;
+        if executed_proposal.decoupled_execution() {
+            return Err(Error::InvalidProposal(
+                "Proposal does not carry an execution result".into(),
+            ));
+        }
+        self.verify_execution_signature(maybe_signed_executed_proposal)?;
+
+        let old_ledger_info = ledger_info.ledger_info();
+        if !old_ledger_info.commit_info().is_ordered_only() {
+            return Err(Error::InvalidOrderedLedgerInfo(old_ledger_info.to_string()));
+        }
+        let new_tree = executed_proposal
+            .accumulator_extension_proof()
+            .extend_unverified()
+            .map_err(|e| Error::InvalidAccumulatorExtension(e.to_string()))?;
+        let new_ledger_info = LedgerInfo::new(
+            executed_proposal.block().gen_block_info(
+                new_tree.root_hash(),
+                new_tree.version(),
+                executed_proposal.next_epoch_state().cloned(),
+            ),
+            old_ledger_info.consensus_data_hash(),
+        );
+        if !old_ledger_info
+            .commit_info()
+            .match_ordered_only(new_ledger_info.commit_info())
+        {
+            return Err(Error::InconsistentExecutionResult(
+                new_ledger_info.to_string(),
+                old_ledger_info.to_string(),
+            ));
+        }
+
+        // The ordered LedgerInfo must be certified by a quorum of the current epoch
+        ledger_info
+            .verify_signatures(&self.epoch_state()?.verifier)
+            .map_err(|e| Error::InvalidQuorumCertificate(e.to_string()))?;
+
+        // Never certify two execution results for the same round, nor go back to an older round
+        if let Some(last_commit_vote) = &safety_data.last_commit_vote {
+            let round = new_ledger_info.round();
+            let last_round = last_commit_vote.round();
+            if round < last_round || (round == last_round && last_commit_vote != &new_ledger_info) {
+                return Err(Error::ConflictingCommitVote(
+                    new_ledger_info.to_string(),
+                    last_commit_vote.to_string(),
+                ));
+            }
+        }
+        if safety_data.last_commit_vote.as_ref() != Some(&new_ledger_info) {
+            safety_data.last_commit_vote = Some(new_ledger_info.clone());
+            self.persistent_storage.set_safety_data(safety_data)?;
+        }
+
+        let signature = self.signer()?.sign(&new_ledger_info);
+        Ok((new_ledger_info, signature))
+    }
+
+    /// Records the outcome of a request in the audit log. A signature is only released once it
+    /// is recorded, whereas failing to record a rejection doesn't change the outcome.
+    fn audit<R, S>(
+        &mut self,
+        request: LogEntry,
+        round: Option<Round>,
+        result: Result<R, Error>,
+        signed: S,
+    ) -> Result<R, Error>
+    where
+        S: FnOnce(&R) -> Option<AuditEvent>,
+    {
+        match result {
+            Ok(value) => {
+                if let Some(event) = signed(&value) {
+                    self.persistent_storage.append_audit_event(event)?;
+                }
+                Ok(value)
+            }
+            Err(error) => {
+                let event = AuditEvent::Rejected {
+                    request: request.as_str().into(),
+                    round,
+                    error: error.clone(),
+                };
+                if let Err(e) = self.persistent_storage.append_audit_event(event) {
+                    send_struct_log!(logging::safety_log(LogEntry::AuditLog, LogEvent::Error)
+                        .data(LogField::Message.as_str(), e.to_string()));
+                }
+                Err(error)
+            }
+        }
+    }
 }
 
 impl TSafetyRules for SafetyRules {
@@ -411,7 +550,8 @@ impl TSafetyRules for SafetyRules {
     fn initialize(&mut self, proof: &EpochChangeProof) -> Result<(), Error> {
         let log_cb = |log: StructuredLogEntry| log;
         let cb = || self.guarded_initialize(proof);
-        run_and_log(cb, log_cb, LogEntry::Initialize)
+        let result = run_and_log(cb, log_cb, LogEntry::Initialize);
+        self.audit(LogEntry::Initialize, None, result, |_| None)
     }
 
     fn construct_and_sign_vote(
@@ -421,20 +561,56 @@ impl TSafetyRules for SafetyRules {
         let round = maybe_signed_vote_proposal.vote_proposal.block().round();
         let log_cb = |log: StructuredLogEntry| log.data(LogField::Round.as_str(), round);
         let cb = || self.guarded_construct_and_sign_vote(maybe_signed_vote_proposal);
-        run_and_log(cb, log_cb, LogEntry::ConstructAndSignVote)
+        let result = run_and_log(cb, log_cb, LogEntry::ConstructAndSignVote);
+        self.audit(
+            LogEntry::ConstructAndSignVote,
+            Some(round),
+            result,
+            |vote| Some(AuditEvent::SignedVote(vote.clone())),
+        )
     }
 
     fn sign_proposal(&mut self, block_data: BlockData) -> Result<Block, Error> {
         let round = block_data.round();
         let log_cb = |log: StructuredLogEntry| log.data(LogField::Round.as_str(), round);
         let cb = || self.guarded_sign_proposal(block_data);
-        run_and_log(cb, log_cb, LogEntry::SignProposal)
+        let result = run_and_log(cb, log_cb, LogEntry::SignProposal);
+        self.audit(LogEntry::SignProposal, Some(round), result, |block| {
+            Some(AuditEvent::SignedProposal {
+                epoch: block.epoch(),
+                round: block.round(),
+                block_id: block.id(),
+            })
+        })
     }
 
     fn sign_timeout(&mut self, timeout: &Timeout) -> Result<Ed25519Signature, Error> {
         let log_cb = |log: StructuredLogEntry| log.data(LogField::Round.as_str(), timeout.round());
         let cb = || self.guarded_sign_timeout(timeout);
-        run_and_log(cb, log_cb, LogEntry::SignTimeout)
+        let result = run_and_log(cb, log_cb, LogEntry::SignTimeout);
+        self.audit(LogEntry::SignTimeout, Some(timeout.round()), result, |_| {
+            Some(AuditEvent::SignedTimeout(timeout.clone()))
+        })
+    }
+
+    fn sign_commit_vote(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        executed_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Ed25519Signature, Error> {
+        let round = ledger_info.ledger_info().round();
+        let log_cb = |log: StructuredLogEntry| log.data(LogField::Round.as_str(), round);
+        let cb = || self.guarded_sign_commit_vote(ledger_info, executed_proposal);
+        let result = run_and_log(cb, log_cb, LogEntry::SignCommitVote);
+        self.audit(
+            LogEntry::SignCommitVote,
+            Some(round),
+            result,
+            |(signed_ledger_info, _)| {
+                Some(AuditEvent::SignedCommitVote(signed_ledger_info.clone()))
+            },
+        )
+        .map(|(_, signature)| signature)
     }
 }
 
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit_log::AuditLog,
     local_client::LocalClient,
     persistent_safety_storage::PersistentSafetyStorage,
     process::ProcessService,
@@ -10,7 +11,7 @@ use crate::{
     thread::ThreadService,
     SafetyRules, TSafetyRules,
 };
-use libra_config::config::{SafetyRulesConfig, SafetyRulesService};
+use libra_config::config::{RemoteServiceNoise, SafetyRulesConfig, SafetyRulesService};
 use libra_secure_storage::{KVStorage, Storage};
 use std::{
     convert::TryInto,
@@ -25,7 +26,7 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
         .available()
         .expect("Storage is not available");
 
-    if let Some(test_config) = &config.test {
+    let storage = if let Some(test_config) = &config.test {
         let author = test_config.author;
         let consensus_private_key = test_config
             .consensus_key
@@ -48,6 +49,18 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
         )
     } else {
         PersistentSafetyStorage::new(internal_storage)
+    };
+
+    if let Some(audit_log) = &config.audit_log {
+        let audit_log = AuditLog::open(
+            &audit_log.path(),
+            audit_log.max_file_records,
+            audit_log.max_files,
+        )
+        .expect("Unable to open audit log");
+        storage.with_audit_log(audit_log)
+    } else {
+        storage
     }
 }
 
@@ -63,21 +76,30 @@ pub struct SafetyRulesManager {
 }
 
 impl SafetyRulesManager {
-    pub fn new(config: &SafetyRulesConfig) -> Self {
+    /// The execution mode is that of consensus, a SafetyRules process reads its own from its
+    /// config instead.
+    pub fn new(config: &SafetyRulesConfig, decoupled_execution: bool) -> Self {
         if let SafetyRulesService::Process(conf) = &config.service {
-            return Self::new_process(conf.server_address(), config.network_timeout_ms);
+            return Self::new_process(
+                conf.server_address(),
+                config.network_timeout_ms,
+                conf.noise.clone(),
+            );
         }
 
         let storage = storage(config);
         let verify_vote_proposal_signature = config.verify_vote_proposal_signature;
         match config.service {
-            SafetyRulesService::Local => Self::new_local(storage, verify_vote_proposal_signature),
+            SafetyRulesService::Local => {
+                Self::new_local(storage, verify_vote_proposal_signature, decoupled_execution)
+            }
             SafetyRulesService::Serializer => {
-                Self::new_serializer(storage, verify_vote_proposal_signature)
+                Self::new_serializer(storage, verify_vote_proposal_signature, decoupled_execution)
             }
             SafetyRulesService::Thread => Self::new_thread(
                 storage,
                 verify_vote_proposal_signature,
+                decoupled_execution,
                 config.network_timeout_ms,
             ),
             _ => panic!("Unimplemented SafetyRulesService: {:?}", config.service),
@@ -87,15 +109,21 @@ impl SafetyRulesManager {
     pub fn new_local(
         storage: PersistentSafetyStorage,
         verify_vote_proposal_signature: bool,
+        decoupled_execution: bool,
     ) -> Self {
-        let safety_rules = SafetyRules::new(storage, verify_vote_proposal_signature);
+        let safety_rules =
+            SafetyRules::new(storage, verify_vote_proposal_signature, decoupled_execution);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Local(Arc::new(RwLock::new(safety_rules))),
         }
     }
 
-    pub fn new_process(server_addr: SocketAddr, timeout_ms: u64) -> Self {
-        let process_service = ProcessService::new(server_addr, timeout_ms);
+    pub fn new_process(
+        server_addr: SocketAddr,
+        timeout_ms: u64,
+        noise: Option<RemoteServiceNoise>,
+    ) -> Self {
+        let process_service = ProcessService::new(server_addr, timeout_ms, noise);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Process(process_service),
         }
@@ -104,8 +132,10 @@ impl SafetyRulesManager {
     pub fn new_serializer(
         storage: PersistentSafetyStorage,
         verify_vote_proposal_signature: bool,
+        decoupled_execution: bool,
     ) -> Self {
-        let safety_rules = SafetyRules::new(storage, verify_vote_proposal_signature);
+        let safety_rules =
+            SafetyRules::new(storage, verify_vote_proposal_signature, decoupled_execution);
         let serializer_service = SerializerService::new(safety_rules);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Serializer(Arc::new(RwLock::new(
@@ -117,9 +147,15 @@ impl SafetyRulesManager {
     pub fn new_thread(
         storage: PersistentSafetyStorage,
         verify_vote_proposal_signature: bool,
+        decoupled_execution: bool,
         timeout_ms: u64,
     ) -> Self {
-        let thread = ThreadService::new(storage, verify_vote_proposal_signature, timeout_ms);
+        let thread = ThreadService::new(
+            storage,
+            verify_vote_proposal_signature,
+            decoupled_execution,
+            timeout_ms,
+        );
         Self {
             internal_safety_rules: SafetyRulesWrapper::Thread(thread),
         }
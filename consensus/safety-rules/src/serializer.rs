@@ -7,7 +7,7 @@ use consensus_types::{
     vote_proposal::MaybeSignedVoteProposal,
 };
 use libra_crypto::ed25519::Ed25519Signature;
-use libra_types::epoch_change::EpochChangeProof;
+use libra_types::{epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 
@@ -18,6 +18,7 @@ pub enum SafetyRulesInput {
     ConstructAndSignVote(Box<MaybeSignedVoteProposal>),
     SignProposal(Box<BlockData>),
     SignTimeout(Box<Timeout>),
+    SignCommitVote(Box<LedgerInfoWithSignatures>, Box<MaybeSignedVoteProposal>),
 }
 
 pub struct SerializerService {
@@ -44,6 +45,11 @@ impl SerializerService {
             SafetyRulesInput::SignTimeout(timeout) => {
                 lcs::to_bytes(&self.internal.sign_timeout(&timeout))
             }
+            SafetyRulesInput::SignCommitVote(ledger_info, executed_proposal) => lcs::to_bytes(
+                &self
+                    .internal
+                    .sign_commit_vote(*ledger_info, &executed_proposal),
+            ),
         };
 
         Ok(output?)
@@ -99,6 +105,18 @@ impl TSafetyRules for SerializerClient {
         let response = self.request(SafetyRulesInput::SignTimeout(Box::new(timeout.clone())))?;
         lcs::from_bytes(&response)?
     }
+
+    fn sign_commit_vote(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        executed_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Ed25519Signature, Error> {
+        let response = self.request(SafetyRulesInput::SignCommitVote(
+            Box::new(ledger_info),
+            Box::new(executed_proposal.clone()),
+        ))?;
+        lcs::from_bytes(&response)?
+    }
 }
 
 pub trait TSerializerClient: Send + Sync {
@@ -7,7 +7,7 @@ use consensus_types::{
     vote_proposal::MaybeSignedVoteProposal,
 };
 use libra_crypto::ed25519::Ed25519Signature;
-use libra_types::epoch_change::EpochChangeProof;
+use libra_types::{epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures};
 
 /// Interface for SafetyRules
 pub trait TSafetyRules {
@@ -34,4 +34,15 @@ pub trait TSafetyRules {
     /// As the holder of the private key, SafetyRules also signs what is effectively a
     /// timeout message. This returns the signature for that timeout message.
     fn sign_timeout(&mut self, timeout: &Timeout) -> Result<Ed25519Signature, Error>;
+
+    /// In decoupled execution mode, signs the LedgerInfo carrying the execution result of the
+    /// blocks committed by the given quorum-signed ordered LedgerInfo. The execution result is
+    /// taken from the executed vote proposal of the committed block, which must be signed by
+    /// execution if SafetyRules verifies vote proposal signatures. Only one execution result is
+    /// ever signed for a round, and never for a round older than the last one signed.
+    fn sign_commit_vote(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        executed_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Ed25519Signature, Error>;
 }
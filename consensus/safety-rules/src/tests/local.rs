@@ -7,16 +7,24 @@ use libra_types::validator_signer::ValidatorSigner;
 
 #[test]
 fn test() {
-    suite::run_test_suite(&safety_rules(false));
-    suite::run_test_suite(&safety_rules(true));
+    suite::run_test_suite(&safety_rules(false, false));
+    suite::run_test_suite(&safety_rules(true, false));
+    suite::run_decoupled_test_suite(&safety_rules(false, true));
+    suite::run_decoupled_test_suite(&safety_rules(true, true));
 }
 
-fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
+fn safety_rules(
+    verify_vote_proposal_signature: bool,
+    decoupled_execution: bool,
+) -> suite::Callback {
     Box::new(move || {
         let signer = ValidatorSigner::from_int(0);
         let storage = test_utils::test_storage(&signer);
-        let safety_rules_manager =
-            SafetyRulesManager::new_local(storage, verify_vote_proposal_signature);
+        let safety_rules_manager = SafetyRulesManager::new_local(
+            storage,
+            verify_vote_proposal_signature,
+            decoupled_execution,
+        );
         let safety_rules = safety_rules_manager.client();
         (
             safety_rules,
@@ -1,8 +1,19 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, SafetyRulesManager};
+use crate::{remote_service, test_utils, Error, SafetyRulesManager};
+use libra_config::{
+    config::{OnDiskStorageConfig, RemoteServiceNoise, SecureBackend},
+    utils,
+};
+use libra_crypto::{ed25519::Ed25519PrivateKey, x25519, Uniform};
+use libra_secure_storage::{CryptoStorage, Storage};
+use libra_temppath::TempPath;
 use libra_types::validator_signer::ValidatorSigner;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+};
 
 #[test]
 fn test_reconnect() {
@@ -10,10 +21,94 @@ fn test_reconnect() {
     let storage = test_utils::test_storage(&signer);
     // test value for network timeout, in milliseconds.
     let network_timeout = 5_000;
-    let safety_rules_manager = SafetyRulesManager::new_thread(storage, false, network_timeout);
+    let safety_rules_manager =
+        SafetyRulesManager::new_thread(storage, false, false, network_timeout);
 
     // Verify that after a client has disconnected a new client will connect and resume operations
     let state0 = safety_rules_manager.client().consensus_state().unwrap();
     let state1 = safety_rules_manager.client().consensus_state().unwrap();
     assert_eq!(state0, state1);
 }
+
+#[test]
+fn test_noise() {
+    let signer = ValidatorSigner::from_int(0);
+    let storage = test_utils::test_storage(&signer);
+    // test value for network timeout, in milliseconds.
+    let network_timeout = 5_000;
+
+    // The identity key of consensus is read from its secure storage
+    let storage_path = TempPath::new();
+    storage_path.create_as_file().unwrap();
+    let mut storage_config = OnDiskStorageConfig::default();
+    storage_config.path = storage_path.path().to_path_buf();
+    let backend = SecureBackend::OnDiskStorage(storage_config);
+    let consensus_key = Ed25519PrivateKey::generate_for_testing();
+    let consensus_public_key =
+        x25519::PrivateKey::from_ed25519_private_bytes(&consensus_key.to_bytes())
+            .unwrap()
+            .public_key();
+    let mut consensus_storage: Storage = (&backend).into();
+    consensus_storage
+        .import_private_key("consensus_identity", consensus_key)
+        .unwrap();
+
+    let safety_rules_key = x25519::PrivateKey::generate_for_testing();
+    let safety_rules_public_key = safety_rules_key.public_key();
+    let server_port = utils::get_available_port();
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+    thread::spawn(move || {
+        remote_service::execute(
+            storage,
+            server_addr,
+            false,
+            false,
+            network_timeout,
+            Some((safety_rules_key, consensus_public_key)),
+        )
+    });
+
+    let safety_rules_manager = SafetyRulesManager::new_process(
+        server_addr,
+        network_timeout,
+        Some(RemoteServiceNoise {
+            backend,
+            key_name: "consensus_identity".to_string(),
+            peer_public_key: safety_rules_public_key,
+        }),
+    );
+
+    // Every client performs its own handshake
+    let state0 = safety_rules_manager.client().consensus_state().unwrap();
+    let state1 = safety_rules_manager.client().consensus_state().unwrap();
+    assert_eq!(state0, state1);
+}
+
+#[test]
+fn test_noise_missing_identity_key() {
+    // test value for network timeout, in milliseconds.
+    let network_timeout = 5_000;
+
+    // The secure storage of consensus doesn't hold its identity key
+    let storage_path = TempPath::new();
+    storage_path.create_as_file().unwrap();
+    let mut storage_config = OnDiskStorageConfig::default();
+    storage_config.path = storage_path.path().to_path_buf();
+    let backend = SecureBackend::OnDiskStorage(storage_config);
+
+    let server_port = utils::get_available_port();
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+    let safety_rules_manager = SafetyRulesManager::new_process(
+        server_addr,
+        network_timeout,
+        Some(RemoteServiceNoise {
+            backend,
+            key_name: "consensus_identity".to_string(),
+            peer_public_key: x25519::PrivateKey::generate_for_testing().public_key(),
+        }),
+    );
+
+    // The requests fail instead of the client panicking
+    let err = safety_rules_manager.client().consensus_state().unwrap_err();
+    assert!(matches!(err, Error::InternalError(_)), "{}", err);
+}
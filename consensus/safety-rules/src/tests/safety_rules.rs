@@ -1,21 +1,35 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, tests::suite, SafetyRules};
+use crate::{
+    test_utils, tests::suite, AuditEvent, AuditLog, EquivocationEvidence, Error, SafetyRules,
+    TSafetyRules,
+};
+use consensus_types::{block::block_test_utils::random_payload, timeout::Timeout};
 use libra_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+use libra_temppath::TempPath;
 use libra_types::validator_signer::ValidatorSigner;
 
 #[test]
 fn test() {
-    suite::run_test_suite(&safety_rules(false));
-    suite::run_test_suite(&safety_rules(true));
+    suite::run_test_suite(&safety_rules(false, false));
+    suite::run_test_suite(&safety_rules(true, false));
+    suite::run_decoupled_test_suite(&safety_rules(false, true));
+    suite::run_decoupled_test_suite(&safety_rules(true, true));
 }
 
-fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
+fn safety_rules(
+    verify_vote_proposal_signature: bool,
+    decoupled_execution: bool,
+) -> suite::Callback {
     Box::new(move || {
         let signer = ValidatorSigner::from_int(0);
         let storage = test_utils::test_storage(&signer);
-        let safety_rules = Box::new(SafetyRules::new(storage, verify_vote_proposal_signature));
+        let safety_rules = Box::new(SafetyRules::new(
+            storage,
+            verify_vote_proposal_signature,
+            decoupled_execution,
+        ));
         (
             safety_rules,
             signer,
@@ -27,3 +41,87 @@ fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
         )
     })
 }
+
+#[test]
+fn test_audit_log() {
+    let signer = ValidatorSigner::from_int(0);
+    let audit_log_path = TempPath::new();
+    let audit_log = AuditLog::open(audit_log_path.path(), 100, 1).unwrap();
+    let storage = test_utils::test_storage(&signer).with_audit_log(audit_log);
+    let mut safety_rules = SafetyRules::new(storage, false, false);
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let epoch = genesis_qc.certified_block().epoch();
+    let p0 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer, None);
+
+    safety_rules.initialize(&proof).unwrap();
+    let vote = safety_rules.construct_and_sign_vote(&p0).unwrap();
+    let old_timeout = Timeout::new(epoch, round);
+    let error = safety_rules.sign_timeout(&old_timeout).unwrap_err();
+    assert_eq!(error, Error::IncorrectPreferredRound(round, round));
+
+    // The log holds both the signature and the rejection
+    let events = AuditLog::read(audit_log_path.path())
+        .unwrap()
+        .into_iter()
+        .map(|record| record.event)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            AuditEvent::SignedVote(vote),
+            AuditEvent::Rejected {
+                request: "sign_timeout".into(),
+                round: Some(round),
+                error,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_equivocation_evidence() {
+    let signer = ValidatorSigner::from_int(0);
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc_and_proof(
+        random_payload(1),
+        round + 1,
+        test_utils::empty_proof(),
+        genesis_qc.clone(),
+        &signer,
+        None,
+    );
+    let b1 = test_utils::make_proposal_with_qc_and_proof(
+        random_payload(2),
+        round + 1,
+        test_utils::empty_proof(),
+        genesis_qc,
+        &signer,
+        None,
+    );
+
+    // Two SafetyRules instances sharing the same key are the typical source of equivocation
+    let mut first = SafetyRules::new(test_utils::test_storage(&signer), false, false);
+    let mut second = SafetyRules::new(test_utils::test_storage(&signer), false, false);
+    first.initialize(&proof).unwrap();
+    second.initialize(&proof).unwrap();
+    let first_vote = first.construct_and_sign_vote(&a1).unwrap();
+    let second_vote = second.construct_and_sign_vote(&b1).unwrap();
+
+    let evidence = EquivocationEvidence::new(
+        first_vote.clone(),
+        second_vote.clone(),
+        &signer.public_key(),
+    )
+    .unwrap();
+    evidence.verify(&signer.public_key()).unwrap();
+
+    // The same vote twice is no equivocation
+    EquivocationEvidence::new(first_vote.clone(), first_vote.clone(), &signer.public_key())
+        .unwrap_err();
+    // Nor are votes of different validators
+    let other_signer = ValidatorSigner::from_int(1);
+    EquivocationEvidence::new(first_vote, second_vote, &other_signer.public_key()).unwrap_err();
+}
@@ -3,19 +3,27 @@
 
 use crate::{test_utils, Error, SafetyRules, TSafetyRules};
 use consensus_types::{
-    block::block_test_utils::random_payload, common::Round, quorum_cert::QuorumCert,
-    timeout::Timeout, vote_proposal::MaybeSignedVoteProposal,
+    block::{block_test_utils::random_payload, Block},
+    common::Round,
+    quorum_cert::QuorumCert,
+    timeout::Timeout,
+    vote_proposal::{MaybeSignedVoteProposal, VoteProposal},
 };
 use libra_crypto::{
     ed25519::Ed25519PrivateKey,
     hash::{CryptoHash, HashValue},
+    traits::SigningKey,
 };
 use libra_global_constants::CONSENSUS_KEY;
 use libra_secure_storage::CryptoStorage;
 use libra_types::{
-    epoch_state::EpochState, validator_signer::ValidatorSigner,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
+use std::collections::BTreeMap;
 
 type Proof = test_utils::Proof;
 
@@ -52,6 +60,7 @@ pub fn run_test_suite(safety_rules: &Callback) {
     test_commit_rule_consecutive_rounds(safety_rules);
     test_end_to_end(safety_rules);
     test_initialize(safety_rules);
+    test_decoupled_execution_requests(safety_rules);
     test_preferred_block_rule(safety_rules);
     test_sign_timeout(safety_rules);
     test_voting(safety_rules);
@@ -67,6 +76,14 @@ pub fn run_test_suite(safety_rules: &Callback) {
     test_key_not_in_store(safety_rules);
 }
 
+/// The tests for SafetyRules in decoupled execution mode.
+pub fn run_decoupled_test_suite(safety_rules: &Callback) {
+    test_executed_vote_proposal(safety_rules);
+    test_ordering_only_vote(safety_rules);
+    test_sign_commit_vote(safety_rules);
+    test_commit_vote_equivocation(safety_rules);
+}
+
 fn test_bad_execution_output(safety_rules: &Callback) {
     // build a tree of the following form:
     //                 _____
@@ -225,6 +242,80 @@ fn test_initialize(safety_rules: &Callback) {
     };
 }
 
+/// Without decoupled execution, ordering-only votes and commit votes are rejected.
+fn test_decoupled_execution_requests(safety_rules: &Callback) {
+    let (mut safety_rules, signer, key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer, key.as_ref());
+    let ordered_a1 = MaybeSignedVoteProposal {
+        vote_proposal: VoteProposal::new_ordered_only(a1.block().clone()),
+        signature: None,
+    };
+
+    safety_rules.initialize(&proof).unwrap();
+    assert_eq!(
+        safety_rules
+            .construct_and_sign_vote(&ordered_a1)
+            .unwrap_err(),
+        Error::IncorrectExecutionMode(true, false)
+    );
+    assert_eq!(
+        safety_rules
+            .sign_commit_vote(make_ordered_ledger_info(a1.block(), &signer), &a1)
+            .unwrap_err(),
+        Error::IncorrectExecutionMode(true, false)
+    );
+    // The proposal is still accepted in the mode of SafetyRules
+    safety_rules.construct_and_sign_vote(&a1).unwrap();
+}
+
+/// In decoupled execution mode, votes carrying an execution result are rejected.
+fn test_executed_vote_proposal(safety_rules: &Callback) {
+    let (mut safety_rules, signer, key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer, key.as_ref());
+
+    safety_rules.initialize(&proof).unwrap();
+    assert_eq!(
+        safety_rules.construct_and_sign_vote(&a1).unwrap_err(),
+        Error::IncorrectExecutionMode(false, true)
+    );
+    let state = safety_rules.consensus_state().unwrap();
+    assert_eq!(state.last_voted_round(), round);
+}
+
+fn test_ordering_only_vote(safety_rules: &Callback) {
+    let (mut safety_rules, signer, key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+
+    let a1 =
+        test_utils::make_proposal_with_qc(round + 1, genesis_qc.clone(), &signer, key.as_ref());
+    let ordered_a1 = MaybeSignedVoteProposal {
+        vote_proposal: VoteProposal::new_ordered_only(a1.block().clone()),
+        signature: None,
+    };
+
+    safety_rules.initialize(&proof).unwrap();
+    let vote = safety_rules.construct_and_sign_vote(&ordered_a1).unwrap();
+    assert!(vote.vote_data().proposed().is_ordered_only());
+    assert_eq!(vote.vote_data().proposed().id(), a1.block().id());
+    assert_eq!(vote.vote_data().parent(), genesis_qc.certified_block());
+
+    // Ordering votes are tracked by the voting rules as well
+    assert_eq!(
+        safety_rules.construct_and_sign_vote(&ordered_a1).unwrap(),
+        vote
+    );
+    let state = safety_rules.consensus_state().unwrap();
+    assert_eq!(state.last_voted_round(), round + 1);
+}
+
 fn test_preferred_block_rule(safety_rules: &Callback) {
     // Preferred block is the highest 2-chain head.
     //
@@ -345,6 +436,175 @@ fn test_sign_timeout(safety_rules: &Callback) {
     assert_eq!(actual_err, expected_err);
 }
 
+/// A LedgerInfo committing the ordering of the given block, signed by the given signer
+fn make_ordered_ledger_info(block: &Block, signer: &ValidatorSigner) -> LedgerInfoWithSignatures {
+    let ordered_li = LedgerInfo::new(
+        BlockInfo::new_ordered_only(
+            block.epoch(),
+            block.round(),
+            block.id(),
+            block.timestamp_usecs(),
+        ),
+        HashValue::zero(),
+    );
+    let mut signatures = BTreeMap::new();
+    signatures.insert(signer.author(), signer.sign(&ordered_li));
+    LedgerInfoWithSignatures::new(ordered_li, signatures)
+}
+
+/// The vote proposal of the given block executed with the given proof
+fn make_executed_proposal(
+    block: &Block,
+    proof: Proof,
+    exec_key: Option<&Ed25519PrivateKey>,
+) -> MaybeSignedVoteProposal {
+    let vote_proposal = VoteProposal::new(proof, block.clone(), None);
+    let signature = exec_key.map(|key| key.sign(&vote_proposal));
+    MaybeSignedVoteProposal {
+        vote_proposal,
+        signature,
+    }
+}
+
+/// The LedgerInfo certified by a commit vote on the given ordered LedgerInfo and executed proposal
+fn executed_ledger_info(
+    ordered_li: &LedgerInfoWithSignatures,
+    executed_proposal: &MaybeSignedVoteProposal,
+) -> LedgerInfo {
+    let new_tree = executed_proposal
+        .accumulator_extension_proof()
+        .extend_unverified()
+        .unwrap();
+    LedgerInfo::new(
+        executed_proposal
+            .block()
+            .gen_block_info(new_tree.root_hash(), new_tree.version(), None),
+        ordered_li.ledger_info().consensus_data_hash(),
+    )
+}
+
+fn test_sign_commit_vote(safety_rules: &Callback) {
+    let (mut safety_rules, signer, key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 =
+        test_utils::make_proposal_with_qc(round + 1, genesis_qc.clone(), &signer, key.as_ref());
+    let b1 = test_utils::make_proposal_with_qc(round + 2, genesis_qc, &signer, key.as_ref());
+    let ordered_li_with_sigs = make_ordered_ledger_info(a1.block(), &signer);
+    let ordered_li = ordered_li_with_sigs.ledger_info().clone();
+    let executed_li = executed_ledger_info(&ordered_li_with_sigs, &a1);
+
+    safety_rules.initialize(&proof).unwrap();
+    let signature = safety_rules
+        .sign_commit_vote(ordered_li_with_sigs.clone(), &a1)
+        .unwrap();
+    ValidatorVerifier::new_single(signer.author(), signer.public_key())
+        .verify(signer.author(), &executed_li, &signature)
+        .unwrap();
+    // Signing the same execution result again is fine
+    safety_rules
+        .sign_commit_vote(ordered_li_with_sigs.clone(), &a1)
+        .unwrap();
+
+    // The executed proposal must be for the ordered block
+    let other_li = executed_ledger_info(&ordered_li_with_sigs, &b1);
+    let actual_err = safety_rules
+        .sign_commit_vote(ordered_li_with_sigs.clone(), &b1)
+        .unwrap_err();
+    let expected_err =
+        Error::InconsistentExecutionResult(other_li.to_string(), ordered_li.to_string());
+    assert_eq!(actual_err, expected_err);
+
+    // The executed proposal must carry an execution result
+    let ordered_a1 = MaybeSignedVoteProposal {
+        vote_proposal: VoteProposal::new_ordered_only(a1.block().clone()),
+        signature: None,
+    };
+    assert!(matches!(
+        safety_rules.sign_commit_vote(ordered_li_with_sigs.clone(), &ordered_a1),
+        Err(Error::InvalidProposal(_))
+    ));
+
+    // The executed proposal must be signed by execution when SafetyRules verifies it
+    if key.is_some() {
+        let unsigned_a1 = MaybeSignedVoteProposal {
+            vote_proposal: a1.vote_proposal.clone(),
+            signature: None,
+        };
+        assert_eq!(
+            safety_rules
+                .sign_commit_vote(ordered_li_with_sigs.clone(), &unsigned_a1)
+                .unwrap_err(),
+            Error::VoteProposalSignatureNotFound
+        );
+        // signed by the consensus key rather than the execution one
+        let wrongly_signed_a1 = make_executed_proposal(
+            a1.block(),
+            test_utils::empty_proof(),
+            Some(signer.private_key()),
+        );
+        assert!(matches!(
+            safety_rules.sign_commit_vote(ordered_li_with_sigs.clone(), &wrongly_signed_a1),
+            Err(Error::InternalError(_))
+        ));
+    }
+
+    // Only ordered LedgerInfos can be certified by a commit vote
+    let executed_li_with_sigs = LedgerInfoWithSignatures::new(executed_li.clone(), BTreeMap::new());
+    let actual_err = safety_rules
+        .sign_commit_vote(executed_li_with_sigs, &a1)
+        .unwrap_err();
+    let expected_err = Error::InvalidOrderedLedgerInfo(executed_li.to_string());
+    assert_eq!(actual_err, expected_err);
+
+    // The ordered LedgerInfo must carry a quorum of signatures
+    let unsigned_li = LedgerInfoWithSignatures::new(ordered_li, BTreeMap::new());
+    assert!(matches!(
+        safety_rules.sign_commit_vote(unsigned_li, &a1),
+        Err(Error::InvalidQuorumCertificate(_))
+    ));
+}
+
+/// A commit vote is never signed for a second execution result of a round, nor for an older round.
+fn test_commit_vote_equivocation(safety_rules: &Callback) {
+    let (mut safety_rules, signer, key) = safety_rules();
+
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer, key.as_ref());
+    let a2 = make_proposal_with_parent(round + 2, &a1, None, &signer, key.as_ref());
+    let ordered_a1 = make_ordered_ledger_info(a1.block(), &signer);
+    let ordered_a2 = make_ordered_ledger_info(a2.block(), &signer);
+    let a1_li = executed_ledger_info(&ordered_a1, &a1);
+
+    // Another execution result for a1
+    let evil_a1 = make_executed_proposal(
+        a1.block(),
+        Proof::new(vec![], 0, vec![HashValue::random()]),
+        key.as_ref(),
+    );
+    let evil_a1_li = executed_ledger_info(&ordered_a1, &evil_a1);
+
+    safety_rules.initialize(&proof).unwrap();
+    safety_rules
+        .sign_commit_vote(ordered_a1.clone(), &a1)
+        .unwrap();
+    assert_eq!(
+        safety_rules
+            .sign_commit_vote(ordered_a1.clone(), &evil_a1)
+            .unwrap_err(),
+        Error::ConflictingCommitVote(evil_a1_li.to_string(), a1_li.to_string())
+    );
+
+    let a2_li = executed_ledger_info(&ordered_a2, &a2);
+    safety_rules.sign_commit_vote(ordered_a2, &a2).unwrap();
+    assert_eq!(
+        safety_rules.sign_commit_vote(ordered_a1, &a1).unwrap_err(),
+        Error::ConflictingCommitVote(a1_li.to_string(), a2_li.to_string())
+    );
+}
+
 fn test_voting(safety_rules: &Callback) {
     // build a tree of the following form:
     //             _____    __________
@@ -681,7 +941,7 @@ fn test_reconcile_key(_safety_rules: &Callback) {
     let mut storage = test_utils::test_storage(&signer);
 
     let new_pub_key = storage.internal_store().rotate_key(CONSENSUS_KEY).unwrap();
-    let mut safety_rules = Box::new(SafetyRules::new(storage, false));
+    let mut safety_rules = Box::new(SafetyRules::new(storage, false, false));
 
     let (mut proof, genesis_qc) = test_utils::make_genesis(&signer);
     let round = genesis_qc.certified_block().round();
@@ -7,11 +7,16 @@ use libra_types::validator_signer::ValidatorSigner;
 
 #[test]
 fn test() {
-    suite::run_test_suite(&safety_rules(false));
-    suite::run_test_suite(&safety_rules(true));
+    suite::run_test_suite(&safety_rules(false, false));
+    suite::run_test_suite(&safety_rules(true, false));
+    suite::run_decoupled_test_suite(&safety_rules(false, true));
+    suite::run_decoupled_test_suite(&safety_rules(true, true));
 }
 
-fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
+fn safety_rules(
+    verify_vote_proposal_signature: bool,
+    decoupled_execution: bool,
+) -> suite::Callback {
     Box::new(move || {
         let signer = ValidatorSigner::from_int(0);
         let storage = test_utils::test_storage(&signer);
@@ -20,6 +25,7 @@ fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
         let safety_rules_manager = SafetyRulesManager::new_thread(
             storage,
             verify_vote_proposal_signature,
+            decoupled_execution,
             network_timeout,
         );
         let safety_rules = safety_rules_manager.client();
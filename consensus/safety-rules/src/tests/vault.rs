@@ -16,11 +16,16 @@ fn test() {
         return;
     }
 
-    suite::run_test_suite(&safety_rules(false));
-    suite::run_test_suite(&safety_rules(true));
+    suite::run_test_suite(&safety_rules(false, false));
+    suite::run_test_suite(&safety_rules(true, false));
+    suite::run_decoupled_test_suite(&safety_rules(false, true));
+    suite::run_decoupled_test_suite(&safety_rules(true, true));
 }
 
-fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
+fn safety_rules(
+    verify_vote_proposal_signature: bool,
+    decoupled_execution: bool,
+) -> suite::Callback {
     Box::new(move || {
         let signer = ValidatorSigner::from_int(0);
         let mut storage = Storage::from(VaultStorage::new(
@@ -40,8 +45,11 @@ fn safety_rules(verify_vote_proposal_signature: bool) -> suite::Callback {
             Ed25519PrivateKey::generate_for_testing(),
             waypoint,
         );
-        let safety_rules_manager =
-            SafetyRulesManager::new_local(storage, verify_vote_proposal_signature);
+        let safety_rules_manager = SafetyRulesManager::new_local(
+            storage,
+            verify_vote_proposal_signature,
+            decoupled_execution,
+        );
         let safety_rules = safety_rules_manager.client();
         (
             safety_rules,
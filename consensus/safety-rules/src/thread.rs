@@ -29,6 +29,7 @@ impl ThreadService {
     pub fn new(
         storage: PersistentSafetyStorage,
         verify_vote_proposal_signature: bool,
+        decoupled_execution: bool,
         timeout: u64,
     ) -> Self {
         let listen_port = utils::get_available_port();
@@ -40,7 +41,9 @@ impl ThreadService {
                 storage,
                 listen_addr,
                 verify_vote_proposal_signature,
+                decoupled_execution,
                 timeout,
+                None,
             )
         });
 
@@ -16,11 +16,13 @@ use consensus_types::{
     timeout_certificate::TimeoutCertificate,
 };
 use executor_types::{Error, StateComputeResult};
-use libra_crypto::HashValue;
+use libra_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
 use libra_logger::prelude::*;
 use libra_time::duration_since_epoch;
 use libra_trace::prelude::*;
-use libra_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionStatus};
+use libra_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures, transaction::TransactionStatus,
+};
 use std::{
     collections::vec_deque::VecDeque,
     sync::{Arc, RwLock},
@@ -98,6 +100,8 @@ pub struct BlockStore {
     storage: Arc<dyn PersistentLivenessStorage>,
     /// Used to ensure that any block stored will have a timestamp < the local time
     time_service: Arc<dyn TimeService>,
+    /// Whether blocks are inserted ordered-only and executed once a quorum cert orders them.
+    decoupled_execution: bool,
 }
 
 impl BlockStore {
@@ -107,8 +111,10 @@ impl BlockStore {
         state_computer: Arc<dyn StateComputer>,
         max_pruned_blocks_in_mem: usize,
         time_service: Arc<dyn TimeService>,
+        decoupled_execution: bool,
     ) -> Self {
         let highest_tc = initial_data.highest_timeout_certificate();
+        let highest_commit_cert = initial_data.highest_commit_cert();
         let (root, root_metadata, blocks, quorum_certs) = initial_data.take();
         Self::build(
             root,
@@ -116,10 +122,12 @@ impl BlockStore {
             blocks,
             quorum_certs,
             highest_tc,
+            highest_commit_cert,
             state_computer,
             storage,
             max_pruned_blocks_in_mem,
             time_service,
+            decoupled_execution,
         )
     }
 
@@ -129,27 +137,38 @@ impl BlockStore {
         blocks: Vec<Block>,
         quorum_certs: Vec<QuorumCert>,
         highest_timeout_cert: Option<TimeoutCertificate>,
+        highest_commit_cert: Option<QuorumCert>,
         state_computer: Arc<dyn StateComputer>,
         storage: Arc<dyn PersistentLivenessStorage>,
         max_pruned_blocks_in_mem: usize,
         time_service: Arc<dyn TimeService>,
+        decoupled_execution: bool,
     ) -> Self {
         let RootInfo(root_block, root_qc, root_li) = root;
+        // In decoupled execution mode the root qc only orders the root, its executed state is
+        // carried by the ledger info (unless we recovered without the commit cert).
+        let executed_root_info = if decoupled_execution {
+            root_li.commit_info().clone()
+        } else {
+            root_qc.certified_block().clone()
+        };
         //verify root is correct
-        assert_eq!(
-            root_qc.certified_block().version(),
-            root_metadata.version(),
-            "root qc version {} doesn't match committed trees {}",
-            root_qc.certified_block().version(),
-            root_metadata.version(),
-        );
-        assert_eq!(
-            root_qc.certified_block().executed_state_id(),
-            root_metadata.accu_hash,
-            "root qc state id {} doesn't match committed trees {}",
-            root_qc.certified_block().executed_state_id(),
-            root_metadata.accu_hash,
-        );
+        if !(decoupled_execution && executed_root_info.is_ordered_only()) {
+            assert_eq!(
+                executed_root_info.version(),
+                root_metadata.version(),
+                "root qc version {} doesn't match committed trees {}",
+                executed_root_info.version(),
+                root_metadata.version(),
+            );
+            assert_eq!(
+                executed_root_info.executed_state_id(),
+                root_metadata.accu_hash,
+                "root qc state id {} doesn't match committed trees {}",
+                executed_root_info.executed_state_id(),
+                root_metadata.accu_hash,
+            );
+        }
 
         let result = StateComputeResult::new(
             root_metadata.accu_hash,
@@ -174,12 +193,14 @@ impl BlockStore {
             root_li,
             max_pruned_blocks_in_mem,
             highest_timeout_cert.map(Arc::new),
+            decoupled_execution,
         );
         let block_store = Self {
             inner: Arc::new(RwLock::new(tree)),
             state_computer,
             storage,
             time_service,
+            decoupled_execution,
         };
         for block in blocks {
             block_store
@@ -195,6 +216,18 @@ impl BlockStore {
                     panic!("[BlockStore] failed to insert quorum during build{:?}", e)
                 });
         }
+        if let Some(commit_cert) = highest_commit_cert {
+            if commit_cert.commit_info().round() > block_store.root().round() {
+                block_store
+                    .insert_single_quorum_cert(commit_cert)
+                    .unwrap_or_else(|e| {
+                        panic!(
+                            "[BlockStore] failed to insert commit cert during build {:?}",
+                            e
+                        )
+                    });
+            }
+        }
         counters::LAST_COMMITTED_ROUND.set(block_store.root().round() as i64);
         counters::LAST_COMMITTED_VERSION
             .set(block_store.root().compute_result().num_leaves() as i64);
@@ -214,9 +247,24 @@ impl BlockStore {
             "Committed block round lower than root"
         );
 
-        let blocks_to_commit = self
-            .path_from_root(block_id_to_commit)
-            .unwrap_or_else(Vec::new);
+        let blocks_to_commit = if self.decoupled_execution {
+            // The blocks in the tree are only ordered, commit their actual execution output.
+            let executed_blocks = self.execute_ordered_blocks(block_id_to_commit)?;
+            ensure!(
+                executed_blocks
+                    .last()
+                    .map(|block| block.block_info())
+                    .as_ref()
+                    == Some(finality_proof.ledger_info().commit_info()),
+                "Execution result of block {} doesn't match the commit info {}",
+                block_id_to_commit,
+                finality_proof.ledger_info().commit_info(),
+            );
+            executed_blocks
+        } else {
+            self.path_from_root(block_id_to_commit)
+                .unwrap_or_else(Vec::new)
+        };
         for block in &blocks_to_commit {
             end_trace!("commit", {"block", block.id()});
         }
@@ -255,10 +303,12 @@ impl BlockStore {
             blocks,
             quorum_certs,
             prev_htc,
+            None,
             Arc::clone(&self.state_computer),
             Arc::clone(&self.storage),
             max_pruned_blocks_in_mem,
             Arc::clone(&self.time_service),
+            self.decoupled_execution,
         );
         let to_remove = self.inner.read().unwrap().get_all_block_id();
         if let Err(e) = self.storage.prune_tree(to_remove) {
@@ -286,6 +336,9 @@ impl BlockStore {
     /// Execute and insert a block if it passes all validation tests.
     /// Returns the Arc to the block kept in the block store after persisting it to storage
     ///
+    /// In decoupled execution mode the block is inserted with an ordered-only placeholder output
+    /// instead, see `execute_ordered_blocks`.
+    ///
     /// This function assumes that the ancestors are present (returns MissingParent otherwise).
     ///
     /// Duplicate inserts will return the previously inserted block (
@@ -300,21 +353,37 @@ impl BlockStore {
             "Block with old round"
         );
 
-        let executed_block = match self.execute_block(block.clone()) {
-            Ok(res) => Ok(res),
-            Err(Error::BlockNotFound(parent_block_id)) => {
-                // recover the block tree in executor
-                let blocks_to_reexecute = self
-                    .path_from_root(parent_block_id)
-                    .unwrap_or_else(Vec::new);
-
-                for block in blocks_to_reexecute {
-                    self.execute_block(block.block().clone())?;
+        let executed_block = if self.decoupled_execution {
+            ExecutedBlock::new(
+                block,
+                StateComputeResult::new(
+                    *ACCUMULATOR_PLACEHOLDER_HASH,
+                    vec![], /* frozen_subtree_roots */
+                    0,      /* num_leaves */
+                    vec![], /* parent_frozen_subtree_roots */
+                    0,      /* parent_num_leaves */
+                    None,   /* epoch_state */
+                    vec![], /* compute_status */
+                    vec![], /* txn_infos */
+                ),
+            )
+        } else {
+            match self.execute_block(block.clone()) {
+                Ok(res) => Ok(res),
+                Err(Error::BlockNotFound(parent_block_id)) => {
+                    // recover the block tree in executor
+                    let blocks_to_reexecute = self
+                        .path_from_root(parent_block_id)
+                        .unwrap_or_else(Vec::new);
+
+                    for block in blocks_to_reexecute {
+                        self.execute_block(block.block().clone())?;
+                    }
+                    self.execute_block(block)
                 }
-                self.execute_block(block)
-            }
-            err => err,
-        }?;
+                err => err,
+            }?
+        };
 
         // ensure local time past the block time
         let block_time = Duration::from_micros(executed_block.timestamp_usecs());
@@ -325,6 +394,35 @@ impl BlockStore {
         self.inner.write().unwrap().insert_block(executed_block)
     }
 
+    /// Executes the ordered blocks from the root up to the given block (decoupled execution mode).
+    /// Returns the blocks carrying their actual execution output, the output of the blocks that
+    /// were already executed is reused.
+    pub fn execute_ordered_blocks(
+        &self,
+        block_id: HashValue,
+    ) -> anyhow::Result<Vec<Arc<ExecutedBlock>>> {
+        let blocks = self
+            .path_from_root(block_id)
+            .ok_or_else(|| format_err!("Block {} is not a descendant of the root", block_id))?;
+        let mut executed_blocks = vec![];
+        for block in blocks {
+            let execution_result = self.inner.read().unwrap().get_execution_result(&block.id());
+            let executed_block = match execution_result {
+                Some(executed_block) => executed_block,
+                None => {
+                    let executed_block = Arc::new(self.execute_block(block.block().clone())?);
+                    self.inner
+                        .write()
+                        .unwrap()
+                        .insert_execution_result(Arc::clone(&executed_block));
+                    executed_block
+                }
+            };
+            executed_blocks.push(executed_block);
+        }
+        Ok(executed_blocks)
+    }
+
     fn execute_block(&self, block: Block) -> anyhow::Result<ExecutedBlock, Error> {
         trace_code_block!("block_store::execute_block", {"block", block.id()});
 
@@ -355,12 +453,49 @@ impl BlockStore {
             None => bail!("Insert {} without having the block in store first", qc),
         }
 
-        self.storage
-            .save_tree(vec![], vec![qc.clone()])
-            .context("Insert block failed when saving quorum")?;
+        if self.is_commit_cert(&qc) {
+            // The commit cert certifies the same block as its ordering quorum cert, so it's not
+            // persisted along with the quorum certs.
+            if qc.commit_info().round() > self.highest_commit_cert().commit_info().round() {
+                self.storage
+                    .save_highest_commit_cert(qc.clone())
+                    .context("Insert block failed when saving commit cert")?;
+            }
+        } else {
+            self.storage
+                .save_tree(vec![], vec![qc.clone()])
+                .context("Insert block failed when saving quorum")?;
+        }
         self.inner.write().unwrap().insert_quorum_cert(qc)
     }
 
+    /// Whether the given quorum cert carries the executed state of the block it commits, which
+    /// only happens for the merged commit certs in decoupled execution mode.
+    fn is_commit_cert(&self, qc: &QuorumCert) -> bool {
+        self.decoupled_execution
+            && qc.certified_block().round() > 0
+            && *qc.commit_info() != BlockInfo::empty()
+            && !qc.commit_info().is_ordered_only()
+    }
+
+    /// Return the quorum certificate that carries ordered-only ledger info with the highest round
+    /// (decoupled execution mode only).
+    pub fn highest_ordered_cert(&self) -> Arc<QuorumCert> {
+        self.inner.read().unwrap().highest_ordered_cert()
+    }
+
+    /// Return the quorum certificate that orders the given block, if any.
+    pub fn get_ordered_cert_for_block(&self, block_id: HashValue) -> Option<Arc<QuorumCert>> {
+        self.inner
+            .read()
+            .unwrap()
+            .get_ordered_cert_for_block(&block_id)
+    }
+
+    pub fn decoupled_execution(&self) -> bool {
+        self.decoupled_execution
+    }
+
     /// Replace the highest timeout certificate in case the given one has a higher round.
     /// In case a timeout certificate is updated, persist it to storage.
     pub fn insert_timeout_certificate(&self, tc: Arc<TimeoutCertificate>) -> anyhow::Result<()> {
@@ -458,7 +593,7 @@ impl BlockReader for BlockStore {
     }
 }
 
-#[cfg(any(test, feature = "fuzzing"))]
+#[cfg(any(test, feature = "fuzzing", feature = "replay"))]
 impl BlockStore {
     /// Returns the number of blocks in the tree
     pub(crate) fn len(&self) -> usize {
@@ -53,6 +53,7 @@ fn get_initial_data_and_qc(db: &dyn DbReader) -> (RecoveryData, QuorumCert) {
             ),
             vec![],
             None,
+            None,
         )
         .unwrap(),
         qc,
@@ -82,6 +83,7 @@ fn build_inserter(
             state_computer,
             10, // max pruned blocks in mem
             Arc::new(SimulatedTimeService::new()),
+            false, // decoupled execution
         )),
     )
 }
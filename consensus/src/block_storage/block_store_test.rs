@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::{block_store::sync_manager::NeedFetchResult, BlockReader},
+    block_storage::{block_store::sync_manager::NeedFetchResult, BlockReader, BlockStore},
     pending_votes::{PendingVotes, VoteReceptionResult},
-    test_utils::{build_empty_tree, build_simple_tree, TreeInserter},
+    test_utils::{
+        build_empty_tree, build_simple_tree, EmptyStateComputer, EmptyStorage, TreeInserter,
+    },
+    util::mock_time_service::SimulatedTimeService,
 };
 use consensus_types::{
     block::{
@@ -23,7 +26,7 @@ use libra_types::{
     validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
 };
 use proptest::prelude::*;
-use std::{cmp::min, collections::HashSet};
+use std::{cmp::min, collections::HashSet, sync::Arc};
 
 #[test]
 fn test_highest_block_and_quorum_cert() {
@@ -395,3 +398,45 @@ fn test_need_fetch_for_qc() {
         NeedFetchResult::QCAlreadyExist,
     );
 }
+
+#[test]
+fn test_decoupled_execution_ordered_cert() {
+    let (initial_data, storage) = EmptyStorage::start_for_testing();
+    let block_store = Arc::new(BlockStore::new(
+        storage,
+        initial_data,
+        Arc::new(EmptyStateComputer),
+        10, // max pruned blocks in mem
+        Arc::new(SimulatedTimeService::new()),
+        true, // decoupled execution
+    ));
+    let mut inserter =
+        TreeInserter::new_with_store(ValidatorSigner::random(None), block_store.clone());
+
+    // build a tree of the following form
+    // genesis <- a1 <- a2 <- a3
+    // where the QC of a2 orders a1
+    let genesis = block_store.root();
+    let a1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    // blocks are inserted without being executed
+    assert!(a1.block_info().is_ordered_only());
+    let a2 = inserter.insert_block(&a1, 2, None);
+    let _a3 = inserter.insert_block(&a2, 3, Some(a1.block_info()));
+
+    // a1 is ordered but not committed until its execution result is certified
+    assert_eq!(
+        block_store.highest_ordered_cert().commit_info(),
+        &a1.block_info()
+    );
+    assert_eq!(block_store.highest_commit_cert().commit_info().round(), 0);
+    assert_eq!(block_store.root(), genesis);
+    assert_eq!(
+        block_store
+            .get_ordered_cert_for_block(a1.id())
+            .expect("a1 should be ordered")
+            .certified_block()
+            .id(),
+        a2.id()
+    );
+    assert!(block_store.get_ordered_cert_for_block(a2.id()).is_none());
+}
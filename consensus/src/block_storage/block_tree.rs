@@ -71,6 +71,13 @@ pub struct BlockTree {
     highest_timeout_cert: Option<Arc<TimeoutCertificate>>,
     /// The quorum certificate that has highest commit info.
     highest_commit_cert: Arc<QuorumCert>,
+    /// The quorum certificate that has highest ordered-only commit info (decoupled execution only).
+    highest_ordered_cert: Arc<QuorumCert>,
+    /// Map of block id to the block carrying its actual execution output, blocks in the tree
+    /// are only ordered but not executed in decoupled execution mode.
+    id_to_execution_result: HashMap<HashValue, Arc<ExecutedBlock>>,
+    /// Whether blocks are ordered and executed separately.
+    decoupled_execution: bool,
     /// Map of block id to its completed quorum certificate (2f + 1 votes)
     id_to_quorum_cert: HashMap<HashValue, Arc<QuorumCert>>,
     /// To keep the IDs of the elements that have been pruned from the tree but not cleaned up yet.
@@ -86,6 +93,7 @@ impl BlockTree {
         root_ledger_info: QuorumCert,
         max_pruned_blocks_in_mem: usize,
         highest_timeout_cert: Option<Arc<TimeoutCertificate>>,
+        decoupled_execution: bool,
     ) -> Self {
         assert_eq!(
             root.id(),
@@ -106,6 +114,7 @@ impl BlockTree {
         );
 
         let pruned_block_ids = VecDeque::with_capacity(max_pruned_blocks_in_mem);
+        let root_ledger_info = Arc::new(root_ledger_info);
 
         BlockTree {
             id_to_block,
@@ -113,7 +122,10 @@ impl BlockTree {
             highest_certified_block_id: root_id,
             highest_quorum_cert: Arc::clone(&root_quorum_cert),
             highest_timeout_cert,
-            highest_commit_cert: Arc::new(root_ledger_info),
+            highest_commit_cert: Arc::clone(&root_ledger_info),
+            highest_ordered_cert: root_ledger_info,
+            id_to_execution_result: HashMap::new(),
+            decoupled_execution,
             id_to_quorum_cert,
             pruned_block_ids,
             max_pruned_blocks_in_mem,
@@ -140,6 +152,7 @@ impl BlockTree {
         // Remove the block from the store
         self.id_to_block.remove(&block_id);
         self.id_to_quorum_cert.remove(&block_id);
+        self.id_to_execution_result.remove(&block_id);
     }
 
     pub(super) fn block_exists(&self, block_id: &HashValue) -> bool {
@@ -177,6 +190,30 @@ impl BlockTree {
         Arc::clone(&self.highest_commit_cert)
     }
 
+    pub(super) fn highest_ordered_cert(&self) -> Arc<QuorumCert> {
+        Arc::clone(&self.highest_ordered_cert)
+    }
+
+    pub(super) fn get_execution_result(&self, block_id: &HashValue) -> Option<Arc<ExecutedBlock>> {
+        self.id_to_execution_result.get(block_id).cloned()
+    }
+
+    pub(super) fn insert_execution_result(&mut self, block: Arc<ExecutedBlock>) {
+        self.id_to_execution_result.insert(block.id(), block);
+    }
+
+    /// Returns the quorum certificate that orders the given block (i.e. carries its ordered-only
+    /// commit info), if any.
+    pub(super) fn get_ordered_cert_for_block(
+        &self,
+        block_id: &HashValue,
+    ) -> Option<Arc<QuorumCert>> {
+        self.id_to_quorum_cert
+            .values()
+            .find(|qc| qc.commit_info().id() == *block_id && qc.commit_info().is_ordered_only())
+            .cloned()
+    }
+
     pub(super) fn get_quorum_cert_for_block(
         &self,
         block_id: &HashValue,
@@ -240,7 +277,13 @@ impl BlockTree {
             .entry(block_id)
             .or_insert_with(|| Arc::clone(&qc));
 
-        if self.highest_commit_cert.commit_info().round() < qc.commit_info().round() {
+        // In decoupled execution mode only the commit certs merged with the executed state
+        // commit blocks, the quorum certs carrying ordered-only commit info just order them.
+        if self.decoupled_execution && qc.commit_info().is_ordered_only() {
+            if self.highest_ordered_cert.commit_info().round() < qc.commit_info().round() {
+                self.highest_ordered_cert = qc;
+            }
+        } else if self.highest_commit_cert.commit_info().round() < qc.commit_info().round() {
             self.highest_commit_cert = qc;
         }
 
@@ -353,7 +396,7 @@ impl BlockTree {
     }
 }
 
-#[cfg(any(test, feature = "fuzzing"))]
+#[cfg(any(test, feature = "fuzzing", feature = "replay"))]
 impl BlockTree {
     /// Returns the number of blocks in the tree
     pub(super) fn len(&self) -> usize {
@@ -41,6 +41,12 @@ impl BlockStore {
         // does not result in an addition overflow.
         checked_precondition!(self.root().round() < std::u64::MAX - 1);
 
+        // In decoupled execution mode an ordered-only ledger info doesn't carry any state to
+        // sync to.
+        if self.decoupled_execution && qc.commit_info().is_ordered_only() {
+            return false;
+        }
+
         // If we have the block locally, we're not far from this QC thus don't need to sync.
         // In case root().round() is greater than that the committed
         // block carried by LI is older than my current commit.
@@ -97,8 +103,17 @@ impl BlockStore {
         match self.need_fetch_for_quorum_cert(&qc) {
             NeedFetchResult::NeedFetch => self.fetch_quorum_cert(qc.clone(), retriever).await?,
             NeedFetchResult::QCBlockExist => self.insert_single_quorum_cert(qc.clone())?,
+            // The commit cert certifies a block that already has its ordering quorum cert.
+            NeedFetchResult::QCAlreadyExist if self.is_commit_cert(qc) => {
+                self.insert_single_quorum_cert(qc.clone())?
+            }
             _ => (),
         }
+        // In decoupled execution mode the blocks are only committed once their execution result
+        // is certified by the commit votes.
+        if self.decoupled_execution && qc.commit_info().is_ordered_only() {
+            return Ok(());
+        }
         if self.root().round() < qc.commit_info().round() {
             let finality_proof = qc.ledger_info();
             self.commit(finality_proof.clone()).await?;
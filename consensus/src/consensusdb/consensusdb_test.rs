@@ -48,3 +48,18 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_highest_commit_cert() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    assert_eq!(db.get_highest_commit_cert().unwrap(), None);
+
+    let commit_cert = lcs::to_bytes(&certificate_for_genesis()).unwrap();
+    db.save_highest_commit_cert(commit_cert.clone()).unwrap();
+    assert_eq!(db.get_data().unwrap().4, Some(commit_cert));
+
+    db.delete_highest_commit_cert().unwrap();
+    assert_eq!(db.get_highest_commit_cert().unwrap(), None);
+}
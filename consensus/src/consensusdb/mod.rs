@@ -45,6 +45,22 @@ impl ConsensusDB {
         Self { db }
     }
 
+    /// Opens the ConsensusDB of a node in read-only mode, e.g. to inspect it offline.
+    #[cfg(any(test, feature = "fuzzing", feature = "replay"))]
+    pub fn new_readonly<P: AsRef<Path> + Clone>(db_root_path: P) -> Result<Self> {
+        let column_families = vec![
+            /* UNUSED CF = */ DEFAULT_CF_NAME,
+            BLOCK_CF_NAME,
+            QC_CF_NAME,
+            SINGLE_ENTRY_CF_NAME,
+        ];
+
+        let path = db_root_path.as_ref().join("consensusdb");
+        let db = DB::open_readonly(path, "consensus", column_families)?;
+
+        Ok(Self { db })
+    }
+
     pub fn get_data(
         &self,
     ) -> Result<(
@@ -52,9 +68,11 @@ impl ConsensusDB {
         Option<Vec<u8>>,
         Vec<Block>,
         Vec<QuorumCert>,
+        Option<Vec<u8>>,
     )> {
         let last_vote = self.get_last_vote()?;
         let highest_timeout_certificate = self.get_highest_timeout_certificate()?;
+        let highest_commit_cert = self.get_highest_commit_cert()?;
         let consensus_blocks = self
             .get_blocks()?
             .into_iter()
@@ -70,6 +88,7 @@ impl ConsensusDB {
             highest_timeout_certificate,
             consensus_blocks,
             consensus_qcs,
+            highest_commit_cert,
         ))
     }
 
@@ -85,6 +104,12 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    pub fn save_highest_commit_cert(&self, highest_commit_cert: Vec<u8>) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<SingleEntrySchema>(&SingleEntryKey::HighestCommitCert, &highest_commit_cert)?;
+        self.commit(batch)
+    }
+
     pub fn save_vote(&self, last_vote: Vec<u8>) -> Result<()> {
         let mut batch = SchemaBatch::new();
         batch.put::<SingleEntrySchema>(&SingleEntryKey::LastVoteMsg, &last_vote)?;
@@ -146,6 +171,19 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Get the serialized highest commit cert (only stored in decoupled execution mode).
+    fn get_highest_commit_cert(&self) -> Result<Option<Vec<u8>>> {
+        self.db
+            .get::<SingleEntrySchema>(&SingleEntryKey::HighestCommitCert)
+    }
+
+    /// Delete the highest commit cert
+    pub fn delete_highest_commit_cert(&self) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.delete::<SingleEntrySchema>(&SingleEntryKey::HighestCommitCert)?;
+        self.commit(batch)
+    }
+
     /// Get serialized latest vote (if available)
     fn get_last_vote(&self) -> Result<Option<Vec<u8>>> {
         self.db
@@ -35,6 +35,9 @@ pub enum SingleEntryKey {
     HighestTimeoutCertificate = 0,
     // Used to store the last vote
     LastVoteMsg = 1,
+    // Used to store the highest commit cert carrying an executed LedgerInfo in decoupled
+    // execution mode
+    HighestCommitCert = 2,
 }
 
 impl KeyCodec<SingleEntrySchema> for SingleEntryKey {
@@ -79,6 +79,15 @@ pub static VOTE_NIL_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the commit votes sent by this validator since last restart (decoupled execution).
+pub static COMMIT_VOTES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "libra_consensus_commit_votes_count",
+        "Count of the commit votes sent by this validator since last restart (decoupled execution)."
+    )
+    .unwrap()
+});
+
 //////////////////////
 // RoundState COUNTERS
 //////////////////////
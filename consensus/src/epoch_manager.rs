@@ -5,7 +5,10 @@ use crate::{
     block_storage::BlockStore,
     counters,
     liveness::{
-        leader_reputation::{ActiveInactiveHeuristic, LeaderReputation, LibraDBBackend},
+        leader_reputation::{
+            ActiveInactiveHeuristic, LeaderReputation, LibraDBBackend, ParticipationHeuristic,
+            ReputationHeuristic,
+        },
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
         rotating_proposer_election::{choose_leader, RotatingProposer},
@@ -27,16 +30,18 @@ use consensus_types::{
     epoch_retrieval::EpochRetrievalRequest,
 };
 use futures::{select, StreamExt};
-use libra_config::config::{ConsensusConfig, ConsensusProposerType, NodeConfig};
+use libra_config::config::{
+    ConsensusConfig, ConsensusProposerType, NodeConfig, ReputationHeuristicType,
+};
 use libra_logger::prelude::*;
 use libra_metrics::monitor;
 use libra_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
-    on_chain_config::{OnChainConfigPayload, ValidatorSet},
+    on_chain_config::{OnChainConfigPayload, OnChainConsensusConfig, ValidatorSet},
 };
-use network::protocols::network::Event;
+use network::{peer_manager::Misbehavior, protocols::network::Event};
 use safety_rules::SafetyRulesManager;
 use std::{cmp::Ordering, sync::Arc, time::Duration};
 
@@ -78,6 +83,8 @@ pub struct EpochManager {
     storage: Arc<dyn PersistentLivenessStorage>,
     safety_rules_manager: SafetyRulesManager,
     processor: Option<RoundProcessor>,
+    // The on-chain consensus config as of the reconfiguration that started the current epoch
+    on_chain_config: OnChainConsensusConfig,
 }
 
 impl EpochManager {
@@ -94,7 +101,8 @@ impl EpochManager {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
         let sr_config = &node_config.consensus.safety_rules;
-        let safety_rules_manager = SafetyRulesManager::new(sr_config);
+        let safety_rules_manager =
+            SafetyRulesManager::new(sr_config, node_config.consensus.decoupled_execution);
         Self {
             author,
             config,
@@ -107,6 +115,7 @@ impl EpochManager {
             storage,
             safety_rules_manager,
             processor: None,
+            on_chain_config: OnChainConsensusConfig::default(),
         }
     }
 
@@ -163,14 +172,40 @@ impl EpochManager {
                 ))
             }
             ConsensusProposerType::LeaderReputation(heuristic_config) => {
-                let backend = Box::new(LibraDBBackend::new(
-                    proposers.len(),
-                    self.storage.libra_db(),
-                ));
-                let heuristic = Box::new(ActiveInactiveHeuristic::new(
-                    heuristic_config.active_weights,
-                    heuristic_config.inactive_weights,
-                ));
+                // The on-chain parameters take precedence over the local ones once set
+                let on_chain_params = self.on_chain_config.leader_reputation;
+                let window_size = on_chain_params
+                    .map(|params| params.window_size as usize)
+                    .unwrap_or(heuristic_config.window_size);
+                let window_size = if window_size == 0 {
+                    proposers.len()
+                } else {
+                    window_size
+                };
+                let heuristic: Box<dyn ReputationHeuristic> =
+                    match (on_chain_params, heuristic_config.heuristic) {
+                        (Some(params), _) => Box::new(ParticipationHeuristic::new(
+                            params.active_weight,
+                            params.inactive_weight,
+                            params.failed_weight,
+                            params.failure_threshold_percent,
+                        )),
+                        (None, ReputationHeuristicType::ActiveInactive) => {
+                            Box::new(ActiveInactiveHeuristic::new(
+                                heuristic_config.active_weights,
+                                heuristic_config.inactive_weights,
+                            ))
+                        }
+                        (None, ReputationHeuristicType::Participation) => {
+                            Box::new(ParticipationHeuristic::new(
+                                heuristic_config.active_weights,
+                                heuristic_config.inactive_weights,
+                                heuristic_config.failed_weights,
+                                heuristic_config.failure_threshold_percent,
+                            ))
+                        }
+                    };
+                let backend = Box::new(LibraDBBackend::new(window_size, self.storage.libra_db()));
                 Box::new(LeaderReputation::new(proposers, backend, heuristic))
             }
             ConsensusProposerType::RoundProposer(round_proposers) => {
@@ -286,6 +321,7 @@ impl EpochManager {
             Arc::clone(&self.state_computer),
             self.config.max_pruned_blocks_in_mem,
             Arc::clone(&self.time_service),
+            self.config.decoupled_execution,
         ));
 
         info!("Update SafetyRules");
@@ -370,6 +406,13 @@ impl EpochManager {
             epoch: payload.epoch(),
             verifier: (&validator_set).into(),
         };
+        self.on_chain_config = payload.get().unwrap_or_else(|e| {
+            warn!(
+                "[EpochManager] Failed to get the on-chain consensus config: {:?}",
+                e
+            );
+            OnChainConsensusConfig::default()
+        });
 
         match self.storage.start() {
             LivenessStorageData::RecoveryData(initial_data) => {
@@ -401,6 +444,12 @@ impl EpochManager {
                         .data("from_peer", &peer_id)
                         .data_display("error", &err)
                         .data("event", &unverified_event));
+                    if let Err(e) = self
+                        .network_sender
+                        .report_misbehavior(peer_id, Misbehavior::InvalidSignature)
+                    {
+                        error!("Failed to report misbehavior of {}: {:?}", peer_id, e);
+                    }
                     err
                 })?;
 
@@ -427,6 +476,13 @@ impl EpochManager {
                     );
                 }
             }
+            ConsensusMsg::CommitVoteMsg(_) => {
+                let event: UnverifiedEvent = msg.into();
+                // Commit votes are only relevant to the blocks ordered in the current epoch.
+                if event.epoch() == self.epoch() {
+                    return Ok(Some(event));
+                }
+            }
             ConsensusMsg::EpochChangeProof(proof) => {
                 let msg_epoch = proof.epoch()?;
                 if msg_epoch == self.epoch() {
@@ -467,6 +523,9 @@ impl EpochManager {
                     VerifiedEvent::ProposalMsg(proposal) => p.process_proposal_msg(*proposal).await,
                     VerifiedEvent::VoteMsg(vote) => p.process_vote_msg(*vote).await,
                     VerifiedEvent::SyncInfo(sync_info) => p.sync_up(&sync_info, peer_id).await,
+                    VerifiedEvent::CommitVote(_) => {
+                        bail!("[EpochManager] Ignore commit vote during recovery")
+                    }
                 }?;
                 let epoch_state = p.epoch_state().clone();
                 info!("Recovered from SyncProcessor");
@@ -484,6 +543,10 @@ impl EpochManager {
                     "process_sync_info",
                     p.process_sync_info_msg(*sync_info, peer_id).await
                 ),
+                VerifiedEvent::CommitVote(commit_vote) => monitor!(
+                    "process_commit_vote",
+                    p.process_commit_vote_msg(*commit_vote).await
+                ),
             },
         }
     }
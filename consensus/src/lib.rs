@@ -9,7 +9,7 @@
 //! [HotStuff](https://arxiv.org/pdf/1803.05069.pdf)).
 
 #![cfg_attr(not(feature = "fuzzing"), deny(missing_docs))]
-#![cfg_attr(feature = "fuzzing", allow(dead_code))]
+#![cfg_attr(any(feature = "fuzzing", feature = "replay"), allow(dead_code))]
 #![recursion_limit = "512"]
 
 mod block_storage;
@@ -21,12 +21,13 @@ mod metrics_safety_rules;
 mod network;
 #[cfg(test)]
 mod network_tests;
+mod pending_commit_votes;
 mod pending_votes;
 mod persistent_liveness_storage;
 mod round_manager;
 mod state_computer;
 mod state_replication;
-#[cfg(any(test, feature = "fuzzing"))]
+#[cfg(any(test, feature = "fuzzing", feature = "replay"))]
 mod test_utils;
 #[cfg(test)]
 mod twins_test;
@@ -37,6 +38,9 @@ mod util;
 pub mod consensus_provider;
 /// LibraNet interface.
 pub mod network_interface;
+/// Offline replay of the consensus data of a node.
+#[cfg(any(test, feature = "fuzzing", feature = "replay"))]
+pub mod replay;
 
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
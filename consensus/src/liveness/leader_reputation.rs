@@ -6,8 +6,8 @@ use consensus_types::common::{Author, Round};
 use libra_logger::prelude::*;
 use libra_types::block_metadata::{new_block_event_key, NewBlockEvent};
 use std::{
-    cmp::Ordering,
-    collections::HashSet,
+    cmp::{max, Ordering},
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 use storage_interface::{DbReader, Order};
@@ -124,6 +124,91 @@ impl ReputationHeuristic for ActiveInactiveHeuristic {
     }
 }
 
+/// Weights candidates by how they took part in the committed history:
+/// 1. a candidate that neither proposed nor voted is assigned inactive_weight,
+/// 2. a candidate that failed more than failure_threshold_percent of the rounds covered by the
+/// history is assigned failed_weight,
+/// 3. others are assigned active_weight scaled by the share of blocks they voted for.
+///
+/// NewBlockEvent doesn't tell who was expected to propose a round without committed block, so the
+/// skipped rounds preceding a block (failed proposals or timeouts) are charged to the candidates
+/// missing from its votes: a quorum couldn't be formed in time without them.
+pub struct ParticipationHeuristic {
+    active_weight: u64,
+    inactive_weight: u64,
+    failed_weight: u64,
+    failure_threshold_percent: u64,
+}
+
+impl ParticipationHeuristic {
+    pub fn new(
+        active_weight: u64,
+        inactive_weight: u64,
+        failed_weight: u64,
+        failure_threshold_percent: u64,
+    ) -> Self {
+        Self {
+            active_weight,
+            inactive_weight,
+            failed_weight,
+            failure_threshold_percent,
+        }
+    }
+}
+
+impl ReputationHeuristic for ParticipationHeuristic {
+    fn get_weights(&self, candidates: &[Author], history: &[NewBlockEvent]) -> Vec<u64> {
+        // the backends don't agree on the order of the history, rounds restart at every epoch
+        // while timestamps only increase (nil blocks reuse the one of their parent)
+        let mut history = history.iter().collect::<Vec<_>>();
+        history.sort_by_key(|meta| (meta.timestamp(), meta.round()));
+
+        let mut proposals = HashMap::new();
+        let mut votes = HashMap::new();
+        let mut failed_rounds = HashMap::new();
+        let mut covered_rounds = history.len() as u64;
+        for (i, meta) in history.iter().enumerate() {
+            *proposals.entry(meta.proposer()).or_insert(0u64) += 1;
+            let voters = meta.votes().into_iter().collect::<HashSet<_>>();
+            for voter in &voters {
+                *votes.entry(*voter).or_insert(0u64) += 1;
+            }
+            let skipped_rounds = match i.checked_sub(1).map(|prev| history[prev]) {
+                Some(prev) => meta.round().saturating_sub(prev.round() + 1),
+                None => 0,
+            };
+            if skipped_rounds > 0 {
+                covered_rounds += skipped_rounds;
+                for author in candidates.iter().filter(|a| !voters.contains(a)) {
+                    *failed_rounds.entry(*author).or_insert(0u64) += skipped_rounds;
+                }
+            }
+        }
+
+        candidates
+            .iter()
+            .map(|author| {
+                let proposed = proposals.get(author).copied().unwrap_or(0);
+                let voted = votes.get(author).copied().unwrap_or(0);
+                let failed = failed_rounds.get(author).copied().unwrap_or(0);
+                if proposed == 0 && voted == 0 {
+                    self.inactive_weight
+                } else if failed.saturating_mul(100)
+                    > covered_rounds.saturating_mul(self.failure_threshold_percent)
+                {
+                    self.failed_weight
+                } else {
+                    // computed in u128 so that it can't overflow, the result is at most
+                    // active_weight as a candidate votes at most once per block
+                    let weight =
+                        u128::from(self.active_weight) * u128::from(voted) / history.len() as u128;
+                    max(weight as u64, self.inactive_weight)
+                }
+            })
+            .collect()
+    }
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
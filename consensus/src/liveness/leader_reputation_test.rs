@@ -3,7 +3,8 @@
 
 use crate::liveness::{
     leader_reputation::{
-        ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, ReputationHeuristic,
+        ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, ParticipationHeuristic,
+        ReputationHeuristic,
     },
     proposer_election::{next, ProposerElection},
 };
@@ -76,6 +77,81 @@ fn test_simple_heuristic() {
     }
 }
 
+fn create_block_at(
+    round: Round,
+    timestamp: u64,
+    proposer: Author,
+    voters: Vec<&ValidatorSigner>,
+) -> NewBlockEvent {
+    NewBlockEvent::new(
+        round,
+        proposer,
+        voters.iter().map(|v| v.author()).collect(),
+        timestamp,
+    )
+}
+
+#[test]
+fn test_participation_heuristic() {
+    let active_weight = 90;
+    let inactive_weight = 1;
+    let failed_weight = 5;
+    let mut proposers = vec![];
+    let mut signers = vec![];
+    for i in 0..4 {
+        let signer = ValidatorSigner::random([i; 32]);
+        proposers.push(signer.author());
+        signers.push(signer);
+    }
+    let heuristic = ParticipationHeuristic::new(active_weight, inactive_weight, failed_weight, 30);
+    // 1. Window size not enough
+    let weights = heuristic.get_weights(&proposers, &[]);
+    assert_eq!(weights, vec![inactive_weight; proposers.len()]);
+    // 2. Sliding window (latest first) with [round 1, voters 0, 2] of the next epoch,
+    // [round 5, voters 0, 2], [round 2, voters 0, 1], [round 1, voters 0, 1]
+    // all proposed by proposer 0: rounds 3 and 4 failed, which is charged to proposers 1 and 3
+    let history = vec![
+        create_block_at(1, 10, proposers[0], vec![&signers[0], &signers[2]]),
+        create_block_at(5, 5, proposers[0], vec![&signers[0], &signers[2]]),
+        create_block_at(2, 2, proposers[0], vec![&signers[0], &signers[1]]),
+        create_block_at(1, 1, proposers[0], vec![&signers[0], &signers[1]]),
+    ];
+    let weights = heuristic.get_weights(&proposers, &history);
+    assert_eq!(
+        weights,
+        vec![
+            // voted for all the blocks
+            active_weight,
+            // failed 2 out of 6 rounds
+            failed_weight,
+            // voted for half of the blocks
+            active_weight / 2,
+            // never participated
+            inactive_weight,
+        ]
+    );
+    // 3. Same history in chronological order
+    let mut chronological = history.clone();
+    chronological.reverse();
+    assert_eq!(heuristic.get_weights(&proposers, &chronological), weights);
+    // 4. Large weights and threshold don't overflow
+    let heuristic = ParticipationHeuristic::new(
+        u64::max_value(),
+        inactive_weight,
+        failed_weight,
+        u64::max_value(),
+    );
+    assert_eq!(
+        heuristic.get_weights(&proposers, &history),
+        vec![
+            u64::max_value(),
+            u64::max_value() / 2,
+            u64::max_value() / 2,
+            inactive_weight,
+        ]
+    );
+}
+
 #[test]
 fn test_api() {
     let active_weight = 9;
@@ -8,7 +8,7 @@ use consensus_types::{
 };
 use libra_crypto::ed25519::Ed25519Signature;
 use libra_metrics::monitor;
-use libra_types::epoch_change::EpochChangeProof;
+use libra_types::{epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures};
 use safety_rules::{ConsensusState, Error, TSafetyRules};
 use std::sync::Arc;
 
@@ -82,4 +82,24 @@ impl TSafetyRules for MetricsSafetyRules {
         }
         result
     }
+
+    fn sign_commit_vote(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        executed_proposal: &MaybeSignedVoteProposal,
+    ) -> Result<Ed25519Signature, Error> {
+        let mut result = monitor!(
+            "safety_rules",
+            self.inner
+                .sign_commit_vote(ledger_info.clone(), executed_proposal)
+        );
+        if let Err(Error::NotInitialized(_res)) = result {
+            self.perform_initialize()?;
+            result = monitor!(
+                "safety_rules",
+                self.inner.sign_commit_vote(ledger_info, executed_proposal)
+            );
+        }
+        result
+    }
 }
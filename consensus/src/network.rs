@@ -22,7 +22,10 @@ use libra_types::{
     account_address::AccountAddress, epoch_change::EpochChangeProof,
     validator_verifier::ValidatorVerifier,
 };
-use network::protocols::{network::Event, rpc::error::RpcError};
+use network::{
+    peer_manager::Misbehavior,
+    protocols::{network::Event, rpc::error::RpcError},
+};
 use std::{
     mem::{discriminant, Discriminant},
     num::NonZeroUsize,
@@ -86,26 +89,35 @@ impl NetworkSender {
     ) -> anyhow::Result<BlockRetrievalResponse> {
         ensure!(from != self.author, "Retrieve block from self");
         let msg = ConsensusMsg::BlockRetrievalRequest(Box::new(retrieval_request.clone()));
-        let response_msg = monitor!(
+        let response_msg = match monitor!(
             "block_retrieval",
-            self.network_sender.send_rpc(from, msg, timeout).await?
-        );
+            self.network_sender.send_rpc(from, msg, timeout).await
+        ) {
+            Ok(response_msg) => response_msg,
+            Err(RpcError::TimedOut) => {
+                self.report_misbehavior(from, Misbehavior::RequestTimeout);
+                return Err(RpcError::TimedOut.into());
+            }
+            Err(e) => return Err(e.into()),
+        };
         let response = match response_msg {
             ConsensusMsg::BlockRetrievalResponse(resp) => *resp,
-            _ => return Err(anyhow!("Invalid response to request")),
+            _ => {
+                self.report_misbehavior(from, Misbehavior::InvalidMessage);
+                return Err(anyhow!("Invalid response to request"));
+            }
         };
-        response
-            .verify(
-                retrieval_request.block_id(),
-                retrieval_request.num_blocks(),
-                &self.validators,
-            )
-            .map_err(|e| {
-                send_struct_log!(security_log(security_events::INVALID_RETRIEVED_BLOCK)
-                    .data("request_block_reponse", &response)
-                    .data_display("error", &e));
-                e
-            })?;
+        if let Err(e) = response.verify(
+            retrieval_request.block_id(),
+            retrieval_request.num_blocks(),
+            &self.validators,
+        ) {
+            send_struct_log!(security_log(security_events::INVALID_RETRIEVED_BLOCK)
+                .data("request_block_reponse", &response)
+                .data_display("error", &e));
+            self.report_misbehavior(from, Misbehavior::InvalidMessage);
+            return Err(e);
+        }
 
         Ok(response)
     }
@@ -184,6 +196,12 @@ impl NetworkSender {
             warn!("Failed to notify to self an epoch change {:?}", e);
         }
     }
+
+    fn report_misbehavior(&mut self, peer: Author, misbehavior: Misbehavior) {
+        if let Err(e) = self.network_sender.report_misbehavior(peer, misbehavior) {
+            warn!("Failed to report misbehavior of peer {:?}: {:?}", peer, e);
+        }
+    }
 }
 
 pub struct NetworkTask {
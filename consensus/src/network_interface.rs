@@ -7,6 +7,7 @@ use crate::counters;
 use channel::message_queues::QueueStyle;
 use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse},
+    commit_vote::CommitVote,
     epoch_retrieval::EpochRetrievalRequest,
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
@@ -18,7 +19,7 @@ use libra_types::{epoch_change::EpochChangeProof, PeerId};
 use network::{
     constants::NETWORK_CHANNEL_SIZE,
     error::NetworkError,
-    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    peer_manager::{ConnectionRequestSender, Misbehavior, PeerManagerRequestSender},
     protocols::{
         network::{NetworkEvents, NetworkSender, NewNetworkSender},
         rpc::error::RpcError,
@@ -48,6 +49,10 @@ pub enum ConsensusMsg {
     /// VoteMsg is the struct that is ultimately sent by the voter in response for receiving a
     /// proposal.
     VoteMsg(Box<VoteMsg>),
+    /// CommitVote is sent in decoupled execution mode to certify the execution result of the
+    /// ordered blocks. Validators that don't know this variant can't deserialize it, so the mode
+    /// must only be enabled once all the validators of the epoch support it.
+    CommitVoteMsg(Box<CommitVote>),
 }
 
 /// The interface from Network to Consensus layer.
@@ -140,4 +145,13 @@ impl ConsensusNetworkSender {
             .send_rpc(recipient, protocol, message, timeout)
             .await
     }
+
+    /// Report the misbehavior of a peer to the network.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.network_sender.report_misbehavior(peer, misbehavior)
+    }
 }
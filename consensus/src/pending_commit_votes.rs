@@ -0,0 +1,215 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! PendingCommitVotes store the commit votes observed in decoupled execution mode.
+//! Unlike PendingVotes it isn't bound to a round: commit votes arrive after the blocks got
+//! ordered, so the votes of all the uncommitted rounds are kept until a commit prunes them.
+//! The module takes care of aggregating the signatures of an executed LedgerInfo once
+//! enough commit votes have been observed.
+
+use consensus_types::{
+    commit_vote::CommitVote,
+    common::{Author, Round},
+};
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_logger::prelude::*;
+use libra_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    validator_verifier::{ValidatorVerifier, VerifyError},
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Result of the commit vote processing.
+#[derive(Debug, PartialEq)]
+pub enum CommitVoteReceptionResult {
+    /// The vote has been added but the LedgerInfo has not gathered a quorum yet. Return the
+    /// amount of voting power of the given LedgerInfo.
+    VoteAdded(u64),
+    /// The very same vote message has been processed in past.
+    DuplicateVote,
+    /// The very same author has already voted for another execution result in this round.
+    EquivocateVote,
+    /// The LedgerInfo gathered a quorum of signatures after adding the vote.
+    NewLedgerInfoWithSignatures(LedgerInfoWithSignatures),
+    /// There might be some issues adding a vote
+    ErrorAddingVote(VerifyError),
+}
+
+/// A PendingCommitVotes structure keep track of commit votes
+pub struct PendingCommitVotes {
+    /// Maps LedgerInfo digest to associated signatures (contained in a partial
+    /// LedgerInfoWithSignatures).
+    li_digest_to_votes: HashMap<HashValue /* LedgerInfo digest */, LedgerInfoWithSignatures>,
+    /// Map of (Author, Round) to the LedgerInfo digest voted for. This is useful to discard
+    /// multiple votes.
+    author_to_vote: HashMap<(Author, Round), HashValue>,
+}
+
+impl PendingCommitVotes {
+    /// Creates an empty PendingCommitVotes structure
+    pub fn new() -> Self {
+        PendingCommitVotes {
+            li_digest_to_votes: HashMap::new(),
+            author_to_vote: HashMap::new(),
+        }
+    }
+
+    /// Insert a commit vote and if the vote is valid, return the LedgerInfoWithSignatures if
+    /// it gathers a quorum. Every new vote for an already certified LedgerInfo returns it again.
+    pub fn insert_vote(
+        &mut self,
+        commit_vote: &CommitVote,
+        validator_verifier: &ValidatorVerifier,
+    ) -> CommitVoteReceptionResult {
+        let li_digest = commit_vote.ledger_info().hash();
+
+        // 1. Has the author already voted for this round?
+        match self
+            .author_to_vote
+            .get(&(commit_vote.author(), commit_vote.round()))
+        {
+            Some(previous_digest) if *previous_digest == li_digest => {
+                return CommitVoteReceptionResult::DuplicateVote;
+            }
+            Some(_) => {
+                sl_error!(security_log(security_events::CONSENSUS_EQUIVOCATING_VOTE)
+                    .data("from_peer", commit_vote.author())
+                    .data("commit_vote", &commit_vote));
+                return CommitVoteReceptionResult::EquivocateVote;
+            }
+            None => (),
+        }
+
+        // 2. Store the new vote
+        self.author_to_vote
+            .insert((commit_vote.author(), commit_vote.round()), li_digest);
+        let li_with_sig = self.li_digest_to_votes.entry(li_digest).or_insert_with(|| {
+            LedgerInfoWithSignatures::new(commit_vote.ledger_info().clone(), BTreeMap::new())
+        });
+        li_with_sig.add_signature(commit_vote.author(), commit_vote.signature().clone());
+
+        // 3. Check if we have enough signatures to certify the execution result
+        match validator_verifier.check_voting_power(li_with_sig.signatures().keys()) {
+            Ok(_) => CommitVoteReceptionResult::NewLedgerInfoWithSignatures(li_with_sig.clone()),
+            Err(VerifyError::TooLittleVotingPower { voting_power, .. }) => {
+                CommitVoteReceptionResult::VoteAdded(voting_power)
+            }
+            Err(error) => {
+                error!(
+                    "MUST_FIX: commit vote received could not be added: {}, vote: {}",
+                    error, commit_vote
+                );
+                CommitVoteReceptionResult::ErrorAddingVote(error)
+            }
+        }
+    }
+
+    /// Drops the votes for the rounds that are already committed.
+    pub fn prune(&mut self, committed_round: Round) {
+        self.li_digest_to_votes
+            .retain(|_, li| li.ledger_info().round() > committed_round);
+        self.author_to_vote
+            .retain(|(_, round), _| *round > committed_round);
+    }
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::{CommitVoteReceptionResult, PendingCommitVotes};
+    use consensus_types::commit_vote::CommitVote;
+    use libra_crypto::HashValue;
+    use libra_types::{
+        block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+        validator_verifier::random_validator_verifier,
+    };
+
+    /// Creates a random executed ledger info for epoch 1 and the given round.
+    fn random_ledger_info(round: u64) -> LedgerInfo {
+        LedgerInfo::new(
+            BlockInfo::new(
+                1,
+                round,
+                HashValue::random(),
+                HashValue::random(),
+                0,
+                0,
+                None,
+            ),
+            HashValue::random(),
+        )
+    }
+
+    fn commit_vote(signer: &ValidatorSigner, ledger_info: &LedgerInfo) -> CommitVote {
+        CommitVote::new_with_signature(
+            signer.author(),
+            ledger_info.clone(),
+            signer.sign(ledger_info),
+        )
+    }
+
+    #[test]
+    /// Verify that commit votes are properly aggregated based on their LedgerInfo digest
+    fn test_commit_vote_aggregation() {
+        ::libra_logger::Logger::new().environment_only(true).init();
+
+        // set up 4 validators
+        let (signers, validator) = random_validator_verifier(4, Some(2), false);
+        let mut pending_commit_votes = PendingCommitVotes::new();
+
+        let li1 = random_ledger_info(1);
+        assert_eq!(
+            pending_commit_votes.insert_vote(&commit_vote(&signers[0], &li1), &validator),
+            CommitVoteReceptionResult::VoteAdded(1)
+        );
+
+        // same author voting for the same thing -> DuplicateVote
+        assert_eq!(
+            pending_commit_votes.insert_vote(&commit_vote(&signers[0], &li1), &validator),
+            CommitVoteReceptionResult::DuplicateVote
+        );
+
+        // same author voting for a different result in the same round -> EquivocateVote
+        let li1_prime = random_ledger_info(1);
+        assert_eq!(
+            pending_commit_votes.insert_vote(&commit_vote(&signers[0], &li1_prime), &validator),
+            CommitVoteReceptionResult::EquivocateVote
+        );
+
+        // votes for a later round are kept apart
+        let li2 = random_ledger_info(2);
+        assert_eq!(
+            pending_commit_votes.insert_vote(&commit_vote(&signers[0], &li2), &validator),
+            CommitVoteReceptionResult::VoteAdded(1)
+        );
+
+        // two votes for the ledger info -> NewLedgerInfoWithSignatures
+        match pending_commit_votes.insert_vote(&commit_vote(&signers[1], &li1), &validator) {
+            CommitVoteReceptionResult::NewLedgerInfoWithSignatures(li_with_sig) => {
+                assert_eq!(li_with_sig.ledger_info(), &li1);
+                assert!(li_with_sig.verify_signatures(&validator).is_ok());
+            }
+            _ => {
+                panic!("No quorum formed.");
+            }
+        };
+
+        // once the round is committed its votes are dropped
+        pending_commit_votes.prune(1);
+        assert_eq!(
+            pending_commit_votes.insert_vote(&commit_vote(&signers[2], &li1), &validator),
+            CommitVoteReceptionResult::VoteAdded(1)
+        );
+        match pending_commit_votes.insert_vote(&commit_vote(&signers[1], &li2), &validator) {
+            CommitVoteReceptionResult::NewLedgerInfoWithSignatures(li_with_sig) => {
+                assert_eq!(li_with_sig.ledger_info(), &li2);
+            }
+            _ => {
+                panic!("No quorum formed.");
+            }
+        };
+    }
+}
@@ -43,6 +43,11 @@ pub trait PersistentLivenessStorage: Send + Sync {
     /// to jump to this round
     fn save_highest_timeout_cert(&self, highest_timeout_cert: TimeoutCertificate) -> Result<()>;
 
+    /// Persist the highest commit cert carrying an executed LedgerInfo in decoupled execution
+    /// mode, it's kept apart from the quorum certs because it certifies the same block as the
+    /// ordering quorum cert.
+    fn save_highest_commit_cert(&self, highest_commit_cert: QuorumCert) -> Result<()>;
+
     /// Retrieve a epoch change proof for SafetyRules so it can instantiate its
     /// ValidatorVerifier.
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof>;
@@ -73,10 +78,14 @@ impl LedgerRecoveryData {
     /// and the ledger info for the root block, return an error if it can not be found.
     ///
     /// We guarantee that the block corresponding to the storage's latest ledger info always exists.
+    ///
+    /// In decoupled execution mode the quorum certs only carry ordered LedgerInfos, the executed
+    /// one for the root is taken from the highest commit cert if it commits the root.
     fn find_root(
         &self,
         blocks: &mut Vec<Block>,
         quorum_certs: &mut Vec<QuorumCert>,
+        highest_commit_cert: Option<&QuorumCert>,
     ) -> Result<RootInfo> {
         info!(
             "The last committed block id as recorded in storage: {}",
@@ -112,8 +121,9 @@ impl LedgerRecoveryData {
             .find(|qc| qc.certified_block().id() == root_block.id())
             .ok_or_else(|| format_err!("No QC found for root: {}", root_id))?
             .clone();
-        let root_ledger_info = quorum_certs
-            .iter()
+        let root_ledger_info = highest_commit_cert
+            .into_iter()
+            .chain(quorum_certs.iter())
             .find(|qc| qc.commit_info().id() == root_block.id())
             .ok_or_else(|| format_err!("No LI found for root: {}", root_id))?
             .clone();
@@ -143,7 +153,7 @@ impl RootMetadata {
         max(self.num_leaves, 1) - 1
     }
 
-    #[cfg(any(test, feature = "fuzzing"))]
+    #[cfg(any(test, feature = "fuzzing", feature = "replay"))]
     pub fn new_empty() -> Self {
         Self::new(0, *libra_crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH, vec![])
     }
@@ -161,6 +171,8 @@ pub struct RecoveryData {
     blocks: Vec<Block>,
     quorum_certs: Vec<QuorumCert>,
     blocks_to_prune: Option<Vec<HashValue>>,
+    // The highest commit cert carrying an executed LedgerInfo (decoupled execution mode only).
+    highest_commit_cert: Option<QuorumCert>,
 
     // Liveness data
     highest_timeout_certificate: Option<TimeoutCertificate>,
@@ -174,9 +186,10 @@ impl RecoveryData {
         root_metadata: RootMetadata,
         mut quorum_certs: Vec<QuorumCert>,
         highest_timeout_certificate: Option<TimeoutCertificate>,
+        highest_commit_cert: Option<QuorumCert>,
     ) -> Result<Self> {
         let root = ledger_recovery_data
-            .find_root(&mut blocks, &mut quorum_certs)
+            .find_root(&mut blocks, &mut quorum_certs, highest_commit_cert.as_ref())
             .with_context(|| {
                 // for better readability
                 quorum_certs.sort_by_key(|qc| qc.certified_block().round());
@@ -202,6 +215,7 @@ impl RecoveryData {
             &mut quorum_certs,
         ));
         let epoch = root.0.epoch();
+        let root_round = root.0.round();
         Ok(RecoveryData {
             last_vote: match last_vote {
                 Some(v) if v.epoch() == epoch => Some(v),
@@ -216,6 +230,17 @@ impl RecoveryData {
                 Some(tc) if tc.epoch() == epoch => Some(tc),
                 _ => None,
             },
+            // A commit cert above the root means we crashed before committing it, the block
+            // store commits it again once the tree is built.
+            highest_commit_cert: match highest_commit_cert {
+                Some(qc)
+                    if qc.certified_block().epoch() == epoch
+                        && qc.commit_info().round() >= root_round =>
+                {
+                    Some(qc)
+                }
+                _ => None,
+            },
         })
     }
 
@@ -246,6 +271,10 @@ impl RecoveryData {
         self.highest_timeout_certificate.clone()
     }
 
+    pub fn highest_commit_cert(&self) -> Option<QuorumCert> {
+        self.highest_commit_cert.clone()
+    }
+
     fn find_blocks_to_prune(
         root_id: HashValue,
         blocks: &mut Vec<Block>,
@@ -331,6 +360,9 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         });
         let blocks = raw_data.2;
         let quorum_certs: Vec<_> = raw_data.3;
+        let highest_commit_cert = raw_data
+            .4
+            .map(|qc| lcs::from_bytes(&qc[..]).expect("unable to deserialize highest commit cert"));
         let blocks_repr: Vec<String> = blocks.iter().map(|b| format!("\n\t{}", b)).collect();
         info!(
             "The following blocks were restored from ConsensusDB : {}",
@@ -369,6 +401,7 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             ),
             quorum_certs,
             highest_timeout_certificate,
+            highest_commit_cert,
         ) {
             Ok(mut initial_data) => {
                 (self as &dyn PersistentLivenessStorage)
@@ -384,6 +417,11 @@ impl PersistentLivenessStorage for StorageWriteProxy {
                         .delete_highest_timeout_certificate()
                         .expect("unable to cleanup highest timeout cert");
                 }
+                if initial_data.highest_commit_cert.is_none() {
+                    self.db
+                        .delete_highest_commit_cert()
+                        .expect("unable to cleanup highest commit cert");
+                }
                 info!(
                     "Starting up the consensus state machine with recovery data - [last_vote {}], [highest timeout certificate: {}]",
                     initial_data.last_vote.as_ref().map_or("None".to_string(), |v| v.to_string()),
@@ -404,6 +442,11 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .save_highest_timeout_certificate(lcs::to_bytes(&highest_timeout_cert)?)
     }
 
+    fn save_highest_commit_cert(&self, highest_commit_cert: QuorumCert) -> Result<()> {
+        self.db
+            .save_highest_commit_cert(lcs::to_bytes(&highest_commit_cert)?)
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let (_, proofs, _) = self.libra_db.get_state_proof(version)?;
         Ok(proofs)
@@ -0,0 +1,615 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline replay of the consensus state persisted by a node, to investigate stalls.
+//!
+//! The blocks and quorum certs found in the consensusdb are fed in round order to a RoundManager
+//! running on mocks: a MockStorage instead of the consensusdb, a MockStateComputer returning the
+//! execution results certified by the recorded quorum certs, a simulated clock driven by the
+//! block timestamps and a network whose messages are dropped. The replaying node is not part of
+//! the validator set and runs in sync only mode, so it never votes nor proposes and replaying the
+//! same data always goes through the same steps.
+
+use crate::{
+    block_storage::{BlockReader, BlockStore},
+    consensusdb::ConsensusDB,
+    liveness::{
+        proposal_generator::ProposalGenerator,
+        round_proposer_election::RoundProposer,
+        round_state::{ExponentialTimeInterval, RoundState},
+    },
+    metrics_safety_rules::MetricsSafetyRules,
+    network::NetworkSender,
+    network_interface::ConsensusNetworkSender,
+    persistent_liveness_storage::{
+        LedgerRecoveryData, PersistentLivenessStorage, RecoveryData, RootInfo, RootMetadata,
+    },
+    round_manager::RoundManager,
+    test_utils::{MockSharedStorage, MockStateComputer, MockStorage, MockTransactionManager},
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use anyhow::Result;
+use channel::{libra_channel, message_queues::QueueStyle};
+use consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    proposal_msg::ProposalMsg,
+    quorum_cert::QuorumCert,
+    sync_info::SyncInfo,
+    timeout_certificate::TimeoutCertificate,
+    vote::Vote,
+};
+use executor_types::StateComputeResult;
+use futures::channel::mpsc;
+use libra_config::config::ConsensusConfig;
+use libra_crypto::HashValue;
+use libra_types::{
+    account_address::AccountAddress,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::ValidatorSet,
+    validator_signer::ValidatorSigner,
+};
+use network::{
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::NewNetworkSender,
+};
+use safety_rules::{test_utils, SafetyRules};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+    num::NonZeroUsize,
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::Runtime;
+
+#[cfg(test)]
+#[path = "replay_test.rs"]
+mod replay_test;
+
+/// The data persisted in the consensusdb of a node.
+pub struct ConsensusDbData {
+    /// The last vote sent by the node.
+    pub last_vote: Option<Vote>,
+    /// The highest timeout certificate known by the node.
+    pub highest_timeout_certificate: Option<TimeoutCertificate>,
+    /// The blocks of the block tree.
+    pub blocks: Vec<Block>,
+    /// The quorum certs of the block tree.
+    pub quorum_certs: Vec<QuorumCert>,
+    /// The highest commit cert (decoupled execution mode only).
+    pub highest_commit_cert: Option<QuorumCert>,
+}
+
+impl ConsensusDbData {
+    /// Reads the consensusdb found in the given storage directory of a node.
+    pub fn load<P: AsRef<Path> + Clone>(db_root_path: P) -> Result<Self> {
+        let db = ConsensusDB::new_readonly(db_root_path)?;
+        let (last_vote, highest_timeout_certificate, blocks, quorum_certs, highest_commit_cert) =
+            db.get_data()?;
+        Ok(Self {
+            last_vote: last_vote.map(|bytes| lcs::from_bytes(&bytes)).transpose()?,
+            highest_timeout_certificate: highest_timeout_certificate
+                .map(|bytes| lcs::from_bytes(&bytes))
+                .transpose()?,
+            blocks,
+            quorum_certs,
+            highest_commit_cert: highest_commit_cert
+                .map(|bytes| lcs::from_bytes(&bytes))
+                .transpose()?,
+        })
+    }
+}
+
+/// Outcome of a replayed event.
+pub struct ReplayStep {
+    /// The replayed event.
+    pub event: String,
+    /// The error returned by the RoundManager if the event got rejected.
+    pub error: Option<String>,
+    /// The round of the RoundManager after the event.
+    pub current_round: Round,
+    /// The round of the highest quorum cert after the event.
+    pub certified_round: Round,
+    /// The round of the root (last committed block) after the event.
+    pub committed_round: Round,
+}
+
+impl Display for ReplayStep {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[round {}, certified {}, committed {}] {}",
+            self.current_round, self.certified_round, self.committed_round, self.event
+        )?;
+        if let Some(error) = &self.error {
+            write!(f, "\n\trejected: {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replays the consensus data of a node, see the module documentation.
+pub struct ConsensusReplay {
+    data: ConsensusDbData,
+    /// The latest ledger info committed to the ledger storage of the node.
+    storage_ledger: LedgerInfo,
+    /// The epoch of the recorded blocks, used to verify the certificates.
+    epoch_state: EpochState,
+    config: ConsensusConfig,
+}
+
+impl ConsensusReplay {
+    /// Creates a replay of the given consensus data on top of the ledger of the node.
+    pub fn new(
+        data: ConsensusDbData,
+        storage_ledger: LedgerInfo,
+        epoch_state: EpochState,
+        config: ConsensusConfig,
+    ) -> Self {
+        Self {
+            data,
+            storage_ledger,
+            epoch_state,
+            config,
+        }
+    }
+
+    /// Finds the root like the node does on restart: the blocks that don't descend from the root
+    /// are returned separately as they would get pruned.
+    fn recover(&self) -> Result<(RootInfo, Vec<Block>, Vec<QuorumCert>, Vec<HashValue>)> {
+        let mut recovery_data = RecoveryData::new(
+            self.data.last_vote.clone(),
+            LedgerRecoveryData::new(self.storage_ledger.clone()),
+            self.data.blocks.clone(),
+            RootMetadata::new_empty(),
+            self.data.quorum_certs.clone(),
+            None,
+            self.data.highest_commit_cert.clone(),
+        )?;
+        let blocks_to_prune = recovery_data.take_blocks_to_prune();
+        let (root, _, blocks, quorum_certs) = recovery_data.take();
+        Ok((root, blocks, quorum_certs, blocks_to_prune))
+    }
+
+    /// Renders the block tree rooted at the last committed block, followed by the highest timeout
+    /// certificate and the last vote of the node.
+    pub fn render_tree(&self) -> Result<String> {
+        let (RootInfo(root, _, _), blocks, quorum_certs, blocks_to_prune) = self.recover()?;
+        let mut output = render_tree(
+            &root,
+            &blocks,
+            quorum_certs
+                .iter()
+                .chain(self.data.highest_commit_cert.iter()),
+        );
+        if !blocks_to_prune.is_empty() {
+            output.push_str(&format!(
+                "{} blocks don't descend from the root\n",
+                blocks_to_prune.len()
+            ));
+        }
+        if let Some(tc) = &self.data.highest_timeout_certificate {
+            output.push_str(&format!(
+                "highest timeout certificate: epoch {}, round {}\n",
+                tc.epoch(),
+                tc.round()
+            ));
+        }
+        // like on restart, a vote of an older epoch is ignored
+        if let Some(vote) = self
+            .data
+            .last_vote
+            .as_ref()
+            .filter(|vote| vote.epoch() == root.epoch())
+        {
+            output.push_str(&format!(
+                "last vote: round {}, block {}{}\n",
+                vote.vote_data().proposed().round(),
+                vote.vote_data().proposed().id().short_str(),
+                if vote.is_timeout() { " (timeout)" } else { "" }
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Replays the recorded proposals up to the given round (all of them by default), then the
+    /// quorum certs and timeout certificate that no proposal carried.
+    pub fn replay(&self, until_round: Option<Round>) -> Result<Vec<ReplayStep>> {
+        let (RootInfo(root, root_qc, root_li), blocks, quorum_certs, _) = self.recover()?;
+        let blocks = blocks
+            .into_iter()
+            .filter(|block| until_round.map_or(true, |round| block.round() <= round))
+            .collect::<Vec<_>>();
+        let proposers = blocks
+            .iter()
+            .filter_map(|block| block.author().map(|author| (block.round(), author)))
+            .collect::<Vec<_>>();
+        // rounds without recorded proposal are left to the first recorded proposer
+        let default_proposer = proposers
+            .first()
+            .map_or(AccountAddress::ZERO, |(_, author)| *author);
+
+        let root_timestamp_usecs = root.timestamp_usecs();
+
+        let mut runtime = Runtime::new()?;
+        let mut node = ReplayNode::new(
+            &self.storage_ledger,
+            &self.epoch_state,
+            &self.config,
+            RoundProposer::new(proposers.into_iter().collect(), default_proposer),
+            (root, root_qc, root_li),
+            compute_results(
+                self.data
+                    .quorum_certs
+                    .iter()
+                    .chain(self.data.highest_commit_cert.iter())
+                    .chain(self.data.blocks.iter().map(|block| block.quorum_cert())),
+            ),
+        )?;
+        let mut steps = vec![];
+        runtime.block_on(async {
+            // the first round starts when the root got committed
+            node.advance_time_to(root_timestamp_usecs);
+            node.round_manager.start(None).await;
+            for block in blocks {
+                steps.push(node.replay_proposal(block).await);
+            }
+            let mut quorum_certs = quorum_certs
+                .into_iter()
+                .chain(self.data.highest_commit_cert.clone())
+                .filter(|qc| {
+                    until_round.map_or(true, |round| qc.certified_block().round() <= round)
+                })
+                .collect::<Vec<_>>();
+            quorum_certs.sort_by_key(|qc| qc.certified_block().round());
+            for qc in quorum_certs {
+                if qc.certified_block().round() > node.block_store.sync_info().highest_round() {
+                    steps.push(node.replay_sync_info(qc, None).await);
+                }
+            }
+            if let Some(tc) = self.data.highest_timeout_certificate.clone() {
+                if tc.epoch() == self.epoch_state.epoch
+                    && tc.round() > node.block_store.sync_info().highest_round()
+                    && until_round.map_or(true, |round| tc.round() <= round)
+                {
+                    let highest_quorum_cert = node.block_store.highest_quorum_cert();
+                    steps.push(
+                        node.replay_sync_info(highest_quorum_cert.as_ref().clone(), Some(tc))
+                            .await,
+                    );
+                }
+            }
+        });
+        Ok(steps)
+    }
+}
+
+/// A RoundManager and its block store running on mocks.
+struct ReplayNode {
+    round_manager: RoundManager,
+    block_store: Arc<BlockStore>,
+    time_service: Arc<SimulatedTimeService>,
+    // The receivers must outlive the node, otherwise the mocks fail to notify the commits.
+    _state_sync_receiver: mpsc::UnboundedReceiver<Payload>,
+    _commit_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+}
+
+impl ReplayNode {
+    fn new(
+        storage_ledger: &LedgerInfo,
+        epoch_state: &EpochState,
+        config: &ConsensusConfig,
+        proposer_election: RoundProposer,
+        (root, root_qc, root_li): (Block, QuorumCert, QuorumCert),
+        compute_results: HashMap<HashValue, StateComputeResult>,
+    ) -> Result<Self> {
+        // The nil address is never a validator, so the replaying node never proposes.
+        let signer = ValidatorSigner::from_int(0);
+        let storage = Arc::new(MockStorage::new_with_ledger_info(
+            Arc::new(MockSharedStorage::new(ValidatorSet::empty())),
+            storage_ledger.clone(),
+        ));
+        // The root of a new epoch is a virtual genesis block that is never persisted.
+        let (blocks, quorum_certs) = if storage_ledger.ends_epoch() {
+            (vec![], vec![])
+        } else {
+            (vec![root], vec![root_qc, root_li])
+        };
+        let committed_info = storage_ledger.commit_info();
+        let recovery_data = RecoveryData::new(
+            None,
+            LedgerRecoveryData::new(storage_ledger.clone()),
+            blocks.clone(),
+            RootMetadata::new(
+                committed_info.version() + 1,
+                committed_info.executed_state_id(),
+                vec![],
+            ),
+            quorum_certs.clone(),
+            None,
+            None,
+        )?;
+        if !blocks.is_empty() {
+            storage.save_tree(blocks, quorum_certs)?;
+        }
+
+        let (state_sync_sender, state_sync_receiver) = mpsc::unbounded();
+        let (commit_sender, commit_receiver) = mpsc::unbounded();
+        let state_computer = Arc::new(
+            MockStateComputer::new(state_sync_sender, commit_sender, storage.clone())
+                .with_compute_results(compute_results),
+        );
+        let time_service = Arc::new(SimulatedTimeService::new());
+        let block_store = Arc::new(BlockStore::new(
+            storage.clone(),
+            recovery_data,
+            state_computer,
+            config.max_pruned_blocks_in_mem,
+            time_service.clone(),
+            config.decoupled_execution,
+        ));
+
+        // Nobody listens to the network, whatever the node sends is dropped.
+        let (network_reqs_tx, _) =
+            libra_channel::new(QueueStyle::FIFO, NonZeroUsize::new(8).unwrap(), None);
+        let (connection_reqs_tx, _) =
+            libra_channel::new(QueueStyle::FIFO, NonZeroUsize::new(8).unwrap(), None);
+        let (self_sender, _) = channel::new_test(8);
+        let network = NetworkSender::new(
+            signer.author(),
+            ConsensusNetworkSender::new(
+                PeerManagerRequestSender::new(network_reqs_tx),
+                ConnectionRequestSender::new(connection_reqs_tx),
+            ),
+            self_sender,
+            epoch_state.verifier.clone(),
+        );
+
+        let (timeout_sender, _) = channel::new_test(1_024);
+        let round_state = RoundState::new(
+            Box::new(ExponentialTimeInterval::new(
+                Duration::from_millis(config.round_initial_timeout_ms),
+                1.2,
+                6,
+            )),
+            time_service.clone(),
+            timeout_sender,
+        );
+        let txn_manager = Arc::new(MockTransactionManager::new(None));
+        let proposal_generator = ProposalGenerator::new(
+            signer.author(),
+            block_store.clone(),
+            txn_manager.clone(),
+            time_service.clone(),
+            config.max_block_size,
+        );
+        let safety_rules = MetricsSafetyRules::new(
+            Box::new(SafetyRules::new(
+                test_utils::test_storage(&signer),
+                false,
+                false,
+            )),
+            storage.clone(),
+        );
+
+        let round_manager = RoundManager::new(
+            epoch_state.clone(),
+            block_store.clone(),
+            round_state,
+            Box::new(proposer_election),
+            proposal_generator,
+            safety_rules,
+            network,
+            txn_manager,
+            storage,
+            true, // sync only
+        );
+        Ok(Self {
+            round_manager,
+            block_store,
+            time_service,
+            _state_sync_receiver: state_sync_receiver,
+            _commit_receiver: commit_receiver,
+        })
+    }
+
+    /// Moves the simulated clock forward to the given time, it never goes backward.
+    fn advance_time_to(&self, timestamp_usecs: u64) {
+        let now = self.time_service.get_current_timestamp();
+        let timestamp = Duration::from_micros(timestamp_usecs);
+        if timestamp > now {
+            self.time_service.sleep(timestamp - now);
+        }
+    }
+
+    /// Processes the block as a proposal carrying its quorum cert. A rejected block is inserted
+    /// anyway so that the following ones can be replayed.
+    async fn replay_proposal(&mut self, block: Block) -> ReplayStep {
+        self.advance_time_to(block.timestamp_usecs());
+        let event = format!("proposal {}", block);
+        let sync_info = self.sync_info(block.quorum_cert().clone(), None);
+        let result = self
+            .round_manager
+            .process_proposal_msg(ProposalMsg::new(block.clone(), sync_info))
+            .await;
+        // In sync only mode processing a proposal always fails once the block is inserted,
+        // instead of voting for it.
+        let error = if self.block_store.block_exists(block.id()) {
+            None
+        } else {
+            let error = result.err().map(|e| format!("{:?}", e));
+            if let Err(e) = self.block_store.execute_and_insert_block(block) {
+                Some(format!(
+                    "{}\n\tfailed to insert the block: {:?}",
+                    error.unwrap_or_default(),
+                    e
+                ))
+            } else {
+                error
+            }
+        };
+        self.step(event, error)
+    }
+
+    /// Processes a SyncInfo carrying the given certificates.
+    async fn replay_sync_info(
+        &mut self,
+        quorum_cert: QuorumCert,
+        timeout_cert: Option<TimeoutCertificate>,
+    ) -> ReplayStep {
+        let event = match &timeout_cert {
+            Some(tc) => format!("timeout certificate for round {}", tc.round()),
+            None => format!("quorum cert {}", quorum_cert),
+        };
+        let sync_info = self.sync_info(quorum_cert, timeout_cert);
+        let error = self
+            .round_manager
+            .process_sync_info_msg(sync_info, AccountAddress::ZERO)
+            .await
+            .err()
+            .map(|e| format!("{:?}", e));
+        self.step(event, error)
+    }
+
+    /// The SyncInfo a peer would send along with the given certificates.
+    fn sync_info(
+        &self,
+        quorum_cert: QuorumCert,
+        timeout_cert: Option<TimeoutCertificate>,
+    ) -> SyncInfo {
+        let highest_commit_cert = if *quorum_cert.commit_info() != BlockInfo::empty() {
+            quorum_cert.clone()
+        } else {
+            self.block_store.highest_commit_cert().as_ref().clone()
+        };
+        SyncInfo::new(quorum_cert, highest_commit_cert, timeout_cert)
+    }
+
+    fn step(&self, event: String, error: Option<String>) -> ReplayStep {
+        ReplayStep {
+            event,
+            error,
+            current_round: self.round_manager.round_state().current_round(),
+            certified_round: self
+                .block_store
+                .highest_quorum_cert()
+                .certified_block()
+                .round(),
+            committed_round: self.block_store.root().round(),
+        }
+    }
+}
+
+/// The execution results certified by the quorum certs, so that executing the blocks again
+/// yields the same block infos.
+fn compute_results<'a>(
+    quorum_certs: impl Iterator<Item = &'a QuorumCert>,
+) -> HashMap<HashValue, StateComputeResult> {
+    quorum_certs
+        .flat_map(|qc| vec![qc.certified_block(), qc.commit_info()])
+        .filter(|info| !info.is_ordered_only() && *info != &BlockInfo::empty())
+        .map(|info| {
+            (
+                info.id(),
+                StateComputeResult::new(
+                    info.executed_state_id(),
+                    vec![],
+                    info.version() + 1,
+                    vec![],
+                    0,
+                    info.next_epoch_state().cloned(),
+                    vec![],
+                    vec![],
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Renders the fork tree of the blocks rooted at the given one, every block is annotated with
+/// its round, author and whether a quorum cert certifies or commits it.
+pub(crate) fn render_tree<'a>(
+    root: &Block,
+    blocks: &[Block],
+    quorum_certs: impl Iterator<Item = &'a QuorumCert>,
+) -> String {
+    let quorum_certs = quorum_certs
+        .chain(blocks.iter().map(|block| block.quorum_cert()))
+        .collect::<Vec<_>>();
+    let certified = quorum_certs
+        .iter()
+        .map(|qc| qc.certified_block().id())
+        .collect::<HashSet<_>>();
+    let committed = quorum_certs
+        .iter()
+        .map(|qc| qc.commit_info().id())
+        .collect::<HashSet<_>>();
+    let mut blocks = blocks
+        .iter()
+        .filter(|block| block.id() != root.id())
+        .collect::<Vec<_>>();
+    blocks.sort_by_key(|block| block.round());
+    let mut children = HashMap::new();
+    for block in blocks {
+        children
+            .entry(block.parent_id())
+            .or_insert_with(Vec::new)
+            .push(block);
+    }
+
+    let describe = |block: &Block| {
+        format!(
+            "round {} {} by {}{}{}",
+            block.round(),
+            block.id().short_str(),
+            block
+                .author()
+                .map_or("NIL".to_string(), |author| author.short_str()),
+            if certified.contains(&block.id()) {
+                " [QC]"
+            } else {
+                ""
+            },
+            if committed.contains(&block.id()) {
+                " [committed]"
+            } else {
+                ""
+            },
+        )
+    };
+    let mut output = format!("{} [root]\n", describe(root));
+    output.push_str(&render_subtree(root.id(), "", &children, &describe));
+    output
+}
+
+/// Renders the descendants of the given block depth first, siblings in round order.
+fn render_subtree(
+    parent_id: HashValue,
+    prefix: &str,
+    children: &HashMap<HashValue, Vec<&Block>>,
+    describe: &dyn Fn(&Block) -> String,
+) -> String {
+    let mut output = String::new();
+    if let Some(siblings) = children.get(&parent_id) {
+        for (i, block) in siblings.iter().enumerate() {
+            let last = i + 1 == siblings.len();
+            output.push_str(&format!(
+                "{}{}{}\n",
+                prefix,
+                if last { "╰-- " } else { "├-- " },
+                describe(block)
+            ));
+            output.push_str(&render_subtree(
+                block.id(),
+                &format!("{}{}", prefix, if last { "    " } else { "│   " }),
+                children,
+                describe,
+            ));
+        }
+    }
+    output
+}
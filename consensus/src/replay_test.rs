@@ -0,0 +1,150 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    replay::{render_tree, ConsensusDbData, ConsensusReplay},
+    test_utils::{build_simple_tree, TreeInserter},
+};
+use consensus_types::{
+    block::{block_test_utils::certificate_for_genesis, Block},
+    executed_block::ExecutedBlock,
+    vote::Vote,
+    vote_data::VoteData,
+};
+use libra_config::config::ConsensusConfig;
+use libra_crypto::HashValue;
+use libra_types::{
+    block_info::BlockInfo, epoch_state::EpochState, ledger_info::LedgerInfo,
+    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier,
+};
+use std::iter;
+
+fn describe(block: &ExecutedBlock, annotations: &str) -> String {
+    format!(
+        "round {} {} by {}{}",
+        block.round(),
+        block.id().short_str(),
+        block
+            .block()
+            .author()
+            .map_or("NIL".to_string(), |author| author.short_str()),
+        annotations
+    )
+}
+
+#[test]
+fn test_render_tree() {
+    let (executed_blocks, _) = build_simple_tree();
+    let blocks = executed_blocks
+        .iter()
+        .map(|block| block.block().clone())
+        .collect::<Vec<_>>();
+    let (genesis, a1, a2, a3, b1, b2, c1) = (
+        &executed_blocks[0],
+        &executed_blocks[1],
+        &executed_blocks[2],
+        &executed_blocks[3],
+        &executed_blocks[4],
+        &executed_blocks[5],
+        &executed_blocks[6],
+    );
+
+    let expected = vec![
+        format!("{} [root]", describe(genesis, " [QC] [committed]")),
+        format!("├-- {}", describe(a1, " [QC]")),
+        format!("│   ╰-- {}", describe(a2, " [QC]")),
+        format!("│       ╰-- {}", describe(a3, "")),
+        format!("╰-- {}", describe(b1, " [QC]")),
+        format!("    ├-- {}", describe(b2, "")),
+        format!("    ╰-- {}", describe(c1, "")),
+    ];
+    assert_eq!(
+        render_tree(genesis.block(), &blocks[1..], iter::empty()),
+        format!("{}\n", expected.join("\n"))
+    );
+}
+
+#[test]
+fn test_replay() {
+    let signer = ValidatorSigner::from_int(1);
+    let mut inserter = TreeInserter::new(signer.clone());
+    let genesis = inserter.block_store().root();
+
+    // Genesis--> A1--> A2--> A3--> A4
+    let a1 = inserter.insert_block_with_qc(certificate_for_genesis(), &genesis, 1);
+    let a2 = inserter.insert_block(&a1, 2, None);
+    let a3 = inserter.insert_block(&a2, 3, None);
+    let a4 = inserter.insert_block(&a3, 4, Some(a1.block_info()));
+    let tip_qc = inserter.create_qc_for_block(&a4, Some(a2.block_info()));
+
+    let blocks: Vec<Block> = vec![&a1, &a2, &a3, &a4]
+        .into_iter()
+        .map(|block| block.block().clone())
+        .collect();
+    let quorum_certs = blocks
+        .iter()
+        .map(|block| block.quorum_cert().clone())
+        .chain(iter::once(tip_qc))
+        .collect();
+    let last_vote = Vote::new(
+        VoteData::new(a4.block_info(), a3.block_info()),
+        signer.author(),
+        LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+        &signer,
+    );
+    let data = ConsensusDbData {
+        last_vote: Some(last_vote),
+        highest_timeout_certificate: None,
+        blocks,
+        quorum_certs,
+        highest_commit_cert: None,
+    };
+    let epoch_state = EpochState {
+        epoch: genesis.block().epoch(),
+        verifier: ValidatorVerifier::new_single(signer.author(), signer.public_key()),
+    };
+    let replay = ConsensusReplay::new(
+        data,
+        LedgerInfo::mock_genesis(None),
+        epoch_state,
+        ConsensusConfig::default(),
+    );
+
+    let tree = replay.render_tree().unwrap();
+    let lines = tree.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[4].ends_with(&describe(&a4, " [QC]")));
+    assert_eq!(
+        lines[5],
+        format!("last vote: round 4, block {}", a4.id().short_str())
+    );
+
+    let steps = replay.replay(None).unwrap();
+    // one step per proposal, plus the quorum cert of A4 that no proposal carried
+    assert_eq!(steps.len(), 5);
+    for step in &steps {
+        assert!(step.error.is_none(), "{}", step);
+    }
+    let last_step = steps.last().unwrap();
+    assert_eq!(last_step.current_round, 5);
+    assert_eq!(last_step.certified_round, 4);
+    assert_eq!(last_step.committed_round, 2);
+
+    // replaying is deterministic
+    let replayed_again = replay.replay(None).unwrap();
+    assert_eq!(
+        steps.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        replayed_again
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+
+    // replaying up to a round skips the later proposals, but not the quorum cert of that round
+    let steps = replay.replay(Some(2)).unwrap();
+    assert_eq!(steps.len(), 3);
+    let last_step = steps.last().unwrap();
+    assert_eq!(last_step.current_round, 3);
+    assert_eq!(last_step.certified_round, 2);
+    assert_eq!(last_step.committed_round, 0);
+}
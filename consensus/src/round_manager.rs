@@ -12,6 +12,7 @@ use crate::{
     metrics_safety_rules::MetricsSafetyRules,
     network::{IncomingBlockRetrievalRequest, NetworkSender},
     network_interface::ConsensusMsg,
+    pending_commit_votes::{CommitVoteReceptionResult, PendingCommitVotes},
     pending_votes::VoteReceptionResult,
     persistent_liveness_storage::{PersistentLivenessStorage, RecoveryData},
     state_replication::{StateComputer, TxnManager},
@@ -20,6 +21,7 @@ use anyhow::{bail, ensure, Context, Result};
 use consensus_types::{
     block::Block,
     block_retrieval::{BlockRetrievalResponse, BlockRetrievalStatus},
+    commit_vote::CommitVote,
     common::{Author, Round},
     proposal_msg::ProposalMsg,
     quorum_cert::QuorumCert,
@@ -32,7 +34,11 @@ use inject_error::inject_error;
 use libra_logger::prelude::*;
 use libra_time::duration_since_epoch;
 use libra_trace::prelude::*;
-use libra_types::{epoch_state::EpochState, validator_verifier::ValidatorVerifier};
+use libra_types::{
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_verifier::ValidatorVerifier,
+};
 #[cfg(test)]
 use safety_rules::ConsensusState;
 use safety_rules::TSafetyRules;
@@ -45,6 +51,7 @@ pub enum UnverifiedEvent {
     ProposalMsg(Box<ProposalMsg>),
     VoteMsg(Box<VoteMsg>),
     SyncInfo(Box<SyncInfo>),
+    CommitVote(Box<CommitVote>),
 }
 
 impl UnverifiedEvent {
@@ -62,6 +69,10 @@ impl UnverifiedEvent {
                 s.verify(validator)?;
                 VerifiedEvent::SyncInfo(s)
             }
+            UnverifiedEvent::CommitVote(v) => {
+                v.verify(validator)?;
+                VerifiedEvent::CommitVote(v)
+            }
         })
     }
 
@@ -70,6 +81,7 @@ impl UnverifiedEvent {
             UnverifiedEvent::ProposalMsg(p) => p.epoch(),
             UnverifiedEvent::VoteMsg(v) => v.epoch(),
             UnverifiedEvent::SyncInfo(s) => s.epoch(),
+            UnverifiedEvent::CommitVote(v) => v.epoch(),
         }
     }
 }
@@ -80,6 +92,7 @@ impl From<ConsensusMsg> for UnverifiedEvent {
             ConsensusMsg::ProposalMsg(m) => UnverifiedEvent::ProposalMsg(m),
             ConsensusMsg::VoteMsg(m) => UnverifiedEvent::VoteMsg(m),
             ConsensusMsg::SyncInfo(m) => UnverifiedEvent::SyncInfo(m),
+            ConsensusMsg::CommitVoteMsg(m) => UnverifiedEvent::CommitVote(m),
             _ => unreachable!("Unexpected conversion"),
         }
     }
@@ -89,6 +102,7 @@ pub enum VerifiedEvent {
     ProposalMsg(Box<ProposalMsg>),
     VoteMsg(Box<VoteMsg>),
     SyncInfo(Box<SyncInfo>),
+    CommitVote(Box<CommitVote>),
 }
 
 #[cfg(test)]
@@ -184,6 +198,10 @@ pub struct RoundManager {
     txn_manager: Arc<dyn TxnManager>,
     storage: Arc<dyn PersistentLivenessStorage>,
     sync_only: bool,
+    /// The commit votes for the execution results of the ordered blocks (decoupled execution).
+    pending_commit_votes: PendingCommitVotes,
+    /// The round of the last block this validator sent a commit vote for.
+    last_commit_vote_round: Round,
 }
 
 impl RoundManager {
@@ -199,6 +217,7 @@ impl RoundManager {
         storage: Arc<dyn PersistentLivenessStorage>,
         sync_only: bool,
     ) -> Self {
+        let last_commit_vote_round = block_store.root().round();
         Self {
             epoch_state,
             block_store,
@@ -210,6 +229,8 @@ impl RoundManager {
             network,
             storage,
             sync_only,
+            pending_commit_votes: PendingCommitVotes::new(),
+            last_commit_vote_round,
         }
     }
 
@@ -456,6 +477,82 @@ impl RoundManager {
         if let Some(new_round_event) = self.round_state.process_certificates(sync_info) {
             self.process_new_round_event(new_round_event).await?;
         }
+        if self.block_store.decoupled_execution() {
+            // Executing the ordered blocks must not prevent the ordering from making progress.
+            if let Err(e) = self.process_ordered_cert().await {
+                error!("[RoundManager] Error processing ordered cert: {:?}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// In decoupled execution mode, executes the blocks committed by the highest ordered cert
+    /// and broadcasts a commit vote for the execution result.
+    async fn process_ordered_cert(&mut self) -> anyhow::Result<()> {
+        let ordered_cert = self.block_store.highest_ordered_cert();
+        let ordered_info = ordered_cert.commit_info();
+        let last_commit_vote_round = self.last_commit_vote_round;
+        if self.sync_only
+            || !ordered_info.is_ordered_only()
+            || ordered_info.round() <= last_commit_vote_round
+            || ordered_info.round() <= self.block_store.root().round()
+        {
+            return Ok(());
+        }
+        let executed_blocks = self
+            .block_store
+            .execute_ordered_blocks(ordered_info.id())
+            .context("[RoundManager] Failed to execute the ordered blocks")?;
+        // notify mempool about failed txn
+        for executed_block in executed_blocks
+            .iter()
+            .filter(|block| block.round() > last_commit_vote_round)
+        {
+            if let Err(e) = self
+                .txn_manager
+                .notify(executed_block.block(), executed_block.compute_result())
+                .await
+            {
+                error!(
+                    "[RoundManager] Failed to notify mempool of rejected txns: {:?}",
+                    e
+                );
+            }
+        }
+
+        let executed_block = executed_blocks
+            .last()
+            .expect("Ordered block must be a descendant of the root");
+        let ledger_info = LedgerInfo::new(
+            executed_block.block_info(),
+            ordered_cert
+                .ledger_info()
+                .ledger_info()
+                .consensus_data_hash(),
+        );
+        let signature = self
+            .safety_rules
+            .sign_commit_vote(
+                ordered_cert.ledger_info().clone(),
+                &executed_block.maybe_signed_vote_proposal(false),
+            )
+            .context(format!(
+                "[RoundManager] SafetyRules {}Rejected{} commit vote for {}",
+                Fg(Red),
+                Fg(Reset),
+                executed_block.block()
+            ))?;
+        self.last_commit_vote_round = ordered_info.round();
+        let commit_vote = CommitVote::new_with_signature(
+            self.proposal_generator.author(),
+            ledger_info,
+            signature,
+        );
+        debug!("{}Commit voted:{} {}", Fg(Green), Fg(Reset), commit_vote);
+        counters::COMMIT_VOTES_COUNT.inc();
+        self.network
+            .broadcast(ConsensusMsg::CommitVoteMsg(Box::new(commit_vote)))
+            .await;
         Ok(())
     }
 
@@ -520,17 +617,20 @@ impl RoundManager {
             .block_store
             .execute_and_insert_block(proposed_block)
             .context("[RoundManager] Failed to execute_and_insert the block")?;
-        // notify mempool about failed txn
-        let compute_result = executed_block.compute_result();
-        if let Err(e) = self
-            .txn_manager
-            .notify(executed_block.block(), compute_result)
-            .await
-        {
-            error!(
-                "[RoundManager] Failed to notify mempool of rejected txns: {:?}",
-                e
-            );
+        // notify mempool about failed txn, in decoupled execution mode the block is not executed
+        // yet so mempool is notified once it gets executed.
+        if !self.block_store.decoupled_execution() {
+            let compute_result = executed_block.compute_result();
+            if let Err(e) = self
+                .txn_manager
+                .notify(executed_block.block(), compute_result)
+                .await
+            {
+                error!(
+                    "[RoundManager] Failed to notify mempool of rejected txns: {:?}",
+                    e
+                );
+            }
         }
 
         // Short circuit if already voted.
@@ -545,7 +645,8 @@ impl RoundManager {
             "[RoundManager] sync_only flag is set, stop voting"
         );
 
-        let maybe_signed_vote_proposal = executed_block.maybe_signed_vote_proposal();
+        let maybe_signed_vote_proposal =
+            executed_block.maybe_signed_vote_proposal(self.block_store.decoupled_execution());
         let vote = self
             .safety_rules
             .construct_and_sign_vote(&maybe_signed_vote_proposal)
@@ -652,6 +753,63 @@ impl RoundManager {
         result
     }
 
+    /// Upon new commit vote (decoupled execution mode):
+    /// 1. Filter out the votes for already committed rounds.
+    /// 2. Add the vote to the pending commit votes and check whether the execution result
+    /// gathers a quorum.
+    /// 3. Once it does, merge it with the ordering quorum cert and commit.
+    pub async fn process_commit_vote_msg(&mut self, commit_vote: CommitVote) -> anyhow::Result<()> {
+        ensure!(
+            self.block_store.decoupled_execution(),
+            "[RoundManager] Received {} while decoupled execution is disabled",
+            commit_vote
+        );
+        if commit_vote.round() <= self.block_store.root().round() {
+            return Ok(());
+        }
+        debug!("Add commit vote: {}", commit_vote);
+        match self
+            .pending_commit_votes
+            .insert_vote(&commit_vote, &self.epoch_state.verifier)
+        {
+            CommitVoteReceptionResult::NewLedgerInfoWithSignatures(ledger_info) => {
+                self.new_commit_ledger_info_aggregated(ledger_info, commit_vote.author())
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn new_commit_ledger_info_aggregated(
+        &mut self,
+        ledger_info: LedgerInfoWithSignatures,
+        preferred_peer: Author,
+    ) -> anyhow::Result<()> {
+        // If the block is not ordered locally yet, our own commit vote for it completes the
+        // ledger info again once it is.
+        let ordered_cert = match self
+            .block_store
+            .get_ordered_cert_for_block(ledger_info.ledger_info().consensus_block_id())
+        {
+            Some(ordered_cert) => ordered_cert,
+            None => return Ok(()),
+        };
+        let commit_cert = ordered_cert
+            .create_merged_with_executed_state(ledger_info)
+            .context("[RoundManager] Failed to merge the executed state to the ordering QC")?;
+        let result = self
+            .block_store
+            .insert_quorum_cert(
+                &commit_cert,
+                &mut self.create_block_retriever(preferred_peer),
+            )
+            .await
+            .context("[RoundManager] Failed to process a newly aggregated commit cert");
+        self.pending_commit_votes
+            .prune(self.block_store.root().round());
+        result
+    }
+
     async fn new_tc_aggregated(&mut self, tc: Arc<TimeoutCertificate>) -> anyhow::Result<()> {
         let result = self
             .block_store
@@ -712,6 +870,11 @@ impl RoundManager {
         if let Err(e) = self.process_new_round_event(new_round_event).await {
             error!("[RoundManager] Error during start: {:?}", e);
         }
+        if self.block_store.decoupled_execution() {
+            if let Err(e) = self.process_ordered_cert().await {
+                error!("[RoundManager] Error during start: {:?}", e);
+            }
+        }
     }
 
     /// Inspect the current consensus state.
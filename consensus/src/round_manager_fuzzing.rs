@@ -70,6 +70,7 @@ fn build_empty_store(
         Arc::new(EmptyStateComputer),
         10, // max pruned blocks in mem
         Arc::new(SimulatedTimeService::new()),
+        false, // decoupled execution
     ))
 }
 
@@ -106,7 +107,7 @@ fn create_node_for_fuzzing() -> RoundManager {
 
     // TODO: remove
     let proof = make_initial_epoch_change_proof(&signer);
-    let mut safety_rules = SafetyRules::new(test_utils::test_storage(&signer), false);
+    let mut safety_rules = SafetyRules::new(test_utils::test_storage(&signer), false, false);
     safety_rules.initialize(&proof).unwrap();
 
     // TODO: mock channels
@@ -106,7 +106,7 @@ impl NodeSetup {
                 Ed25519PrivateKey::generate_for_testing(),
                 waypoint,
             );
-            let safety_rules_manager = SafetyRulesManager::new_local(safety_storage, false);
+            let safety_rules_manager = SafetyRulesManager::new_local(safety_storage, false, false);
 
             nodes.push(Self::new(
                 playground,
@@ -178,6 +178,7 @@ impl NodeSetup {
             state_computer,
             10, // max pruned blocks in mem
             time_service.clone(),
+            false, // decoupled execution
         ));
 
         let proposal_generator = ProposalGenerator::new(
@@ -870,7 +871,7 @@ fn safety_rules_crash() {
             node.round_manager.consensus_state().waypoint(),
         );
 
-        node.safety_rules_manager = SafetyRulesManager::new_local(safety_storage, false);
+        node.safety_rules_manager = SafetyRulesManager::new_local(safety_storage, false, false);
         let safety_rules =
             MetricsSafetyRules::new(node.safety_rules_manager.client(), node.storage.clone());
         node.round_manager.set_safety_rules(safety_rules);
@@ -20,6 +20,8 @@ pub struct MockStateComputer {
     commit_callback: mpsc::UnboundedSender<LedgerInfoWithSignatures>,
     consensus_db: Arc<MockStorage>,
     block_cache: Mutex<HashMap<HashValue, Payload>>,
+    // Known execution results of blocks, the other blocks get a placeholder result
+    compute_results: HashMap<HashValue, StateComputeResult>,
 }
 
 impl MockStateComputer {
@@ -33,8 +35,19 @@ impl MockStateComputer {
             commit_callback,
             consensus_db,
             block_cache: Mutex::new(HashMap::new()),
+            compute_results: HashMap::new(),
         }
     }
+
+    /// Returns the given results when computing the corresponding blocks, e.g. to reproduce the
+    /// execution results certified by recorded quorum certs.
+    pub fn with_compute_results(
+        mut self,
+        compute_results: HashMap<HashValue, StateComputeResult>,
+    ) -> Self {
+        self.compute_results = compute_results;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -48,6 +61,9 @@ impl StateComputer for MockStateComputer {
             .lock()
             .unwrap()
             .insert(block.id(), block.payload().unwrap_or(&vec![]).clone());
+        if let Some(result) = self.compute_results.get(&block.id()) {
+            return Ok(result.clone());
+        }
         let result = StateComputeResult::new(
             *ACCUMULATOR_PLACEHOLDER_HASH,
             vec![],
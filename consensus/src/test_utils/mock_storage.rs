@@ -32,6 +32,7 @@ pub struct MockSharedStorage {
 
     // Liveness state
     pub highest_timeout_certificate: Mutex<Option<TimeoutCertificate>>,
+    pub highest_commit_cert: Mutex<Option<QuorumCert>>,
     pub validator_set: ValidatorSet,
 }
 
@@ -43,6 +44,7 @@ impl MockSharedStorage {
             lis: Mutex::new(HashMap::new()),
             last_vote: Mutex::new(None),
             highest_timeout_certificate: Mutex::new(None),
+            highest_commit_cert: Mutex::new(None),
             validator_set,
         }
     }
@@ -130,6 +132,11 @@ impl MockStorage {
                 .lock()
                 .unwrap()
                 .clone(),
+            self.shared_storage
+                .highest_commit_cert
+                .lock()
+                .unwrap()
+                .clone(),
         )
     }
 
@@ -222,6 +229,15 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_highest_commit_cert(&self, highest_commit_cert: QuorumCert) -> Result<()> {
+        self.shared_storage
+            .highest_commit_cert
+            .lock()
+            .unwrap()
+            .replace(highest_commit_cert);
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let lis = self
             .shared_storage
@@ -281,6 +297,7 @@ impl PersistentLivenessStorage for EmptyStorage {
             RootMetadata::new_empty(),
             vec![],
             None,
+            None,
         ) {
             Ok(recovery_data) => LivenessStorageData::RecoveryData(recovery_data),
             Err(e) => {
@@ -294,6 +311,10 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn save_highest_commit_cert(&self, _: QuorumCert) -> Result<()> {
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
         unimplemented!()
     }
@@ -17,7 +17,7 @@ use tokio::{runtime, time::timeout};
 
 mod mock_state_computer;
 mod mock_storage;
-#[cfg(any(test, feature = "fuzzing"))]
+#[cfg(any(test, feature = "fuzzing", feature = "replay"))]
 mod mock_txn_manager;
 
 use crate::util::mock_time_service::SimulatedTimeService;
@@ -65,6 +65,7 @@ pub fn build_empty_tree() -> Arc<BlockStore> {
         Arc::new(EmptyStateComputer),
         10, // max pruned blocks in mem
         Arc::new(SimulatedTimeService::new()),
+        false, // decoupled execution
     ))
 }
 
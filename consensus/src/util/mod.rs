@@ -2,6 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod config_subscription;
-#[cfg(any(test, feature = "fuzzing"))]
+#[cfg(any(test, feature = "fuzzing", feature = "replay"))]
 pub mod mock_time_service;
 pub mod time_service;
@@ -615,6 +615,10 @@ pub static SPARSE_MERKLE_PLACEHOLDER_HASH: Lazy<HashValue> =
 pub static PRE_GENESIS_BLOCK_ID: Lazy<HashValue> =
     Lazy::new(|| create_literal_hash("PRE_GENESIS_BLOCK_ID"));
 
+/// Executed state id reserved for the `BlockInfo` of a block that is ordered but not executed yet.
+pub static ORDERED_ONLY_STATE_ID: Lazy<HashValue> =
+    Lazy::new(|| create_literal_hash("ORDERED_ONLY_STATE_ID"));
+
 /// Genesis block id is used as a parent of the very first block executed by the executor.
 pub static GENESIS_BLOCK_ID: Lazy<HashValue> = Lazy::new(|| {
     // This maintains the invariant that block.id() == block.hash(), for
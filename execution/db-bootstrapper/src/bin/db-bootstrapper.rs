@@ -42,7 +42,13 @@ fn main() -> Result<()> {
 
     let tmpdir;
     let db = if opt.commit {
-        LibraDB::open(&opt.db_dir, false, None /* pruner */)
+        LibraDB::open(
+            &opt.db_dir,
+            false, /* readonly */
+            None,  /* pruner */
+            None,  /* ledger pruner */
+            None,  /* write set window */
+        )
     } else {
         // When not committing, we open the DB as secondary so the tool is usable along side a
         // running node on the same DB. Using a TempPath since it won't run for long.
@@ -309,6 +309,8 @@ fn create_storage_service_and_executor(
             &config.storage.dir(),
             false, /* readonly */
             None,  /* pruner */
+            None,  /* ledger pruner */
+            None,  /* write set window */
         )
         .expect("DB should open."),
     );
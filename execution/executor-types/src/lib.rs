@@ -13,12 +13,17 @@ use libra_crypto::{
     HashValue,
 };
 use libra_types::{
+    account_state_blob::AccountStateBlob,
     contract_event::ContractEvent,
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
-    proof::{accumulator::InMemoryAccumulator, AccumulatorExtensionProof, SparseMerkleProof},
+    proof::{
+        accumulator::InMemoryAccumulator, AccumulatorExtensionProof, SparseMerkleProof,
+        SparseMerkleRangeProof,
+    },
     transaction::{
-        Transaction, TransactionInfo, TransactionListWithProof, TransactionStatus, Version,
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        TransactionStatus, Version,
     },
 };
 use scratchpad::{ProofRead, SparseMerkleTree};
@@ -39,6 +44,43 @@ pub trait ChunkExecutor: Send {
         // carrying any epoch change LI.
         epoch_change_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<Vec<ContractEvent>>;
+
+    /// Verifies the transactions and their outputs based on the provided proofs and ledger info.
+    /// If they are valid, applies the outputs without re-executing the transactions and commits
+    /// immediately if the resulting state matches the proofs.
+    /// Returns a vector of reconfiguration events in the chunk
+    fn apply_and_commit_chunk(
+        &mut self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        // Target LI that has been verified independently: the proofs are relative to this version.
+        verified_target_li: LedgerInfoWithSignatures,
+        // An optional end of epoch LedgerInfo. We do not allow chunks that end epoch without
+        // carrying any epoch change LI.
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<Vec<ContractEvent>>;
+
+    /// Verifies the transaction at the version of the verified target LI and starts restoring the
+    /// state snapshot at that version, skipping all the transactions before it.
+    /// Returns the key of the last account already restored by a previous, interrupted attempt
+    /// to restore the same snapshot, if any.
+    fn start_state_snapshot(
+        &mut self,
+        // Contains only the transaction at the version of `verified_target_li`, with its events.
+        txn_list_with_proof: TransactionListWithProof,
+        // Target LI that has been verified independently: the snapshot is taken at its version.
+        verified_target_li: LedgerInfoWithSignatures,
+    ) -> Result<Option<HashValue>>;
+
+    /// Verifies a chunk of accounts of the state snapshot being restored and persists it.
+    fn save_account_chunk(
+        &mut self,
+        accounts: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()>;
+
+    /// Once all accounts are received, finishes restoring the state snapshot and commits its
+    /// ledger info, after which chunks of transactions can be executed on top of it.
+    fn finalize_state_snapshot(&mut self) -> Result<()>;
 }
 
 pub trait BlockExecutor: Send {
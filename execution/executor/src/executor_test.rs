@@ -16,7 +16,11 @@ use libra_types::{
     account_address::AccountAddress,
     block_info::BlockInfo,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::{Transaction, TransactionListWithProof, Version},
+    transaction::{
+        Transaction, TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
+        Version,
+    },
+    write_set::WriteSet,
 };
 use libradb::LibraDB;
 use proptest::prelude::*;
@@ -260,6 +264,51 @@ fn test_executor_execute_same_block_multiple_times() {
 fn create_transaction_chunks(
     chunk_ranges: Vec<std::ops::Range<Version>>,
 ) -> (Vec<TransactionListWithProof>, LedgerInfoWithSignatures) {
+    let (db, ledger_info) = create_chunks_source_db(&chunk_ranges);
+    let ledger_version = ledger_info.ledger_info().version();
+
+    let batches: Vec<_> = chunk_ranges
+        .into_iter()
+        .map(|range| {
+            db.reader
+                .get_transactions(
+                    range.start,
+                    range.end - range.start,
+                    ledger_version,
+                    false, /* fetch_events */
+                )
+                .unwrap()
+        })
+        .collect();
+
+    (batches, ledger_info)
+}
+
+/// Generates a list of `TransactionOutputListWithProof`s according to the given ranges.
+fn create_transaction_output_chunks(
+    chunk_ranges: Vec<std::ops::Range<Version>>,
+) -> (
+    Vec<TransactionOutputListWithProof>,
+    LedgerInfoWithSignatures,
+) {
+    let (db, ledger_info) = create_chunks_source_db(&chunk_ranges);
+    let ledger_version = ledger_info.ledger_info().version();
+
+    let batches: Vec<_> = chunk_ranges
+        .into_iter()
+        .map(|range| {
+            db.reader
+                .get_transaction_outputs(range.start, range.end - range.start, ledger_version)
+                .unwrap()
+        })
+        .collect();
+
+    (batches, ledger_info)
+}
+
+fn create_chunks_source_db(
+    chunk_ranges: &[std::ops::Range<Version>],
+) -> (DbReaderWriter, LedgerInfoWithSignatures) {
     assert_eq!(chunk_ranges.first().unwrap().start, 1);
     for i in 1..chunk_ranges.len() {
         let previous_range = &chunk_ranges[i - 1];
@@ -271,7 +320,7 @@ fn create_transaction_chunks(
     }
 
     // To obtain the batches of transactions, we first execute and save all these transactions in a
-    // separate DB. Then we call get_transactions to retrieve them.
+    // separate DB. Then we call get_transactions (or get_transaction_outputs) to retrieve them.
     let (config, _) = build_test_config();
     let db = create_storage(&config);
     let mut executor = Executor::<MockVM>::new(db.clone());
@@ -292,21 +341,7 @@ fn create_transaction_chunks(
         .commit_blocks(vec![id], ledger_info.clone())
         .unwrap();
 
-    let batches: Vec<_> = chunk_ranges
-        .into_iter()
-        .map(|range| {
-            db.reader
-                .get_transactions(
-                    range.start,
-                    range.end - range.start,
-                    ledger_version,
-                    false, /* fetch_events */
-                )
-                .unwrap()
-        })
-        .collect();
-
-    (batches, ledger_info)
+    (db, ledger_info)
 }
 
 #[test]
@@ -376,6 +411,55 @@ fn test_executor_execute_and_commit_chunk() {
     assert_eq!(li, ledger_info);
 }
 
+#[test]
+fn test_executor_apply_and_commit_chunk() {
+    let first_batch_size = 30;
+    let second_batch_size = 40;
+    let overlapping_size = 5;
+
+    let (chunks, ledger_info) = {
+        let first_batch_start = 1;
+        let second_batch_start = first_batch_start + first_batch_size - overlapping_size;
+        create_transaction_output_chunks(vec![
+            first_batch_start..first_batch_start + first_batch_size,
+            second_batch_start..second_batch_start + second_batch_size,
+        ])
+    };
+
+    let (config, _) = build_test_config();
+    let db = create_storage(&config);
+    let mut executor = Executor::<MockVM>::new(db.clone());
+
+    // Apply the first chunk. After that we should still get the genesis ledger info from DB.
+    executor
+        .apply_and_commit_chunk(chunks[0].clone(), ledger_info.clone(), None)
+        .unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li.ledger_info().version(), 0);
+    assert_eq!(li.ledger_info().consensus_block_id(), HashValue::zero());
+
+    // Applying an output that doesn't match the transaction info should fail.
+    let mut bad_chunk = chunks[1].clone();
+    let (_, output) = &mut bad_chunk.transactions_and_outputs[overlapping_size as usize];
+    *output = TransactionOutput::new(
+        WriteSet::default(),
+        output.events().to_vec(),
+        output.gas_used(),
+        output.status().clone(),
+    );
+    assert!(executor
+        .apply_and_commit_chunk(bad_chunk, ledger_info.clone(), None)
+        .is_err());
+
+    // Apply the second chunk, overlapping the first one. After that we should get the new ledger
+    // info.
+    executor
+        .apply_and_commit_chunk(chunks[1].clone(), ledger_info.clone(), None)
+        .unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
 #[test]
 fn test_executor_execute_and_commit_chunk_restart() {
     let first_batch_size = 30;
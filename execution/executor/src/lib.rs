@@ -22,7 +22,7 @@ use crate::{
         LIBRA_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS,
     },
     speculation_cache::SpeculationCache,
-    types::{ProcessedVMOutput, TransactionData},
+    types::{ProcessedVMOutput, StateSnapshot, TransactionData},
 };
 use anyhow::{anyhow, bail, ensure, format_err, Result};
 use executor_types::{
@@ -34,7 +34,7 @@ use libra_crypto::{
     HashValue,
 };
 use libra_logger::prelude::*;
-use libra_state_view::StateViewId;
+use libra_state_view::{StateView, StateViewId};
 use libra_trace::prelude::*;
 use libra_types::{
     account_address::AccountAddress,
@@ -44,10 +44,14 @@ use libra_types::{
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config,
-    proof::{accumulator::InMemoryAccumulator, SparseMerkleProof},
+    proof::{
+        accumulator::InMemoryAccumulator, SparseMerkleProof, SparseMerkleRangeProof,
+        TransactionListProof,
+    },
     transaction::{
         Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
-        TransactionPayload, TransactionStatus, TransactionToCommit, Version,
+        TransactionOutputListWithProof, TransactionPayload, TransactionStatus, TransactionToCommit,
+        Version,
     },
     write_set::{WriteOp, WriteSet},
 };
@@ -69,6 +73,8 @@ static OP_COUNTERS: Lazy<libra_metrics::OpMetrics> =
 pub struct Executor<V> {
     db: DbReaderWriter,
     cache: SpeculationCache,
+    /// The state snapshot being restored by state synchronization, if any.
+    state_snapshot: Option<StateSnapshot>,
     phantom: PhantomData<V>,
 }
 
@@ -91,6 +97,7 @@ where
         Self {
             db,
             cache: SpeculationCache::new_with_startup_info(startup_info),
+            state_snapshot: None,
             phantom: PhantomData,
         }
     }
@@ -109,6 +116,7 @@ where
         Self {
             db,
             cache: SpeculationCache::new_for_db_bootstrapping(tree_state),
+            state_snapshot: None,
             phantom: PhantomData,
         }
     }
@@ -177,11 +185,64 @@ where
             txn_list_with_proof.first_transaction_version,
         )?;
 
+        // 2. Verify that skipped transactions match what's already persisted (no fork):
+        let num_txns_to_skip = self.num_txns_to_skip(
+            txn_list_with_proof.first_transaction_version,
+            &txn_list_with_proof.proof,
+        )?;
+
+        // 3. Return verified transactions to be applied.
+        let mut txns: Vec<_> = txn_list_with_proof.transactions;
+        txns.drain(0..num_txns_to_skip);
+        let (_, mut txn_infos) = txn_list_with_proof.proof.unpack();
+        txn_infos.drain(0..num_txns_to_skip);
+
+        Ok((txns, txn_infos))
+    }
+
+    /// Same as `verify_chunk`, for a chunk carrying transaction outputs to be applied instead of
+    /// transactions to be executed.
+    fn verify_output_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<(Vec<(Transaction, TransactionOutput)>, Vec<TransactionInfo>)> {
+        // 1. Verify that input transactions and outputs belongs to the ledger represented by the
+        // ledger info.
+        txn_output_list_with_proof.verify(
+            verified_target_li.ledger_info(),
+            txn_output_list_with_proof.first_transaction_output_version,
+        )?;
+
+        // 2. Verify that skipped transactions match what's already persisted (no fork):
+        let num_txns_to_skip = self.num_txns_to_skip(
+            txn_output_list_with_proof.first_transaction_output_version,
+            &txn_output_list_with_proof.proof,
+        )?;
+
+        // 3. Return verified transactions and outputs to be applied.
+        let mut txns_and_outputs: Vec<_> = txn_output_list_with_proof.transactions_and_outputs;
+        txns_and_outputs.drain(0..num_txns_to_skip);
+        let (_, mut txn_infos) = txn_output_list_with_proof.proof.unpack();
+        txn_infos.drain(0..num_txns_to_skip);
+
+        Ok((txns_and_outputs, txn_infos))
+    }
+
+    /// Returns the number of transactions at the beginning of a verified chunk that are already
+    /// persisted, after verifying that they match what's in DB (no fork). All of them are to be
+    /// skipped if the whole chunk is already persisted.
+    fn num_txns_to_skip(
+        &self,
+        first_transaction_version: Option<Version>,
+        proof: &TransactionListProof,
+    ) -> Result<usize> {
         // Return empty if there's no work to do.
-        if txn_list_with_proof.transactions.is_empty() {
-            return Ok((Vec::new(), Vec::new()));
+        let num_txns = proof.transaction_infos().len();
+        if num_txns == 0 {
+            return Ok(0);
         }
-        let first_txn_version = match txn_list_with_proof.first_transaction_version {
+        let first_txn_version = match first_transaction_version {
             Some(tx) => tx as Version,
             None => {
                 sl_error!(StructuredLogEntry::new_named("MUST_FIX", "assertion")
@@ -198,25 +259,22 @@ where
             first_txn_version
         );
         let versions_between_first_and_committed = num_committed_txns - first_txn_version;
-        if txn_list_with_proof.transactions.len() <= versions_between_first_and_committed as usize {
+        if num_txns <= versions_between_first_and_committed as usize {
             // All already in DB, nothing to do.
-            return Ok((Vec::new(), Vec::new()));
+            return Ok(num_txns);
         }
 
-        // 2. Verify that skipped transactions match what's already persisted (no fork):
         let num_txns_to_skip = num_committed_txns - first_txn_version;
         if num_txns_to_skip > 0 {
             info!("Skipping the first {} transactions.", num_txns_to_skip);
         }
 
         // If the proof is verified, then the length of txn_infos and txns must be the same.
-        let skipped_transaction_infos =
-            &txn_list_with_proof.proof.transaction_infos()[..num_txns_to_skip as usize];
+        let skipped_transaction_infos = &proof.transaction_infos()[..num_txns_to_skip as usize];
 
         // Left side of the proof happens to be the frozen subtree roots of the accumulator
         // right before the list of txns are applied.
-        let frozen_subtree_roots_from_proof = txn_list_with_proof
-            .proof
+        let frozen_subtree_roots_from_proof = proof
             .left_siblings()
             .iter()
             .rev()
@@ -238,13 +296,7 @@ where
             "Fork happens because the current synced_trees doesn't match the txn list provided."
         );
 
-        // 3. Return verified transactions to be applied.
-        let mut txns: Vec<_> = txn_list_with_proof.transactions;
-        txns.drain(0..num_txns_to_skip as usize);
-        let (_, mut txn_infos) = txn_list_with_proof.proof.unpack();
-        txn_infos.drain(0..num_txns_to_skip as usize);
-
-        Ok((txns, txn_infos))
+        Ok(num_txns_to_skip as usize)
     }
 
     /// Post-processing of what the VM outputs. Returns the entire block's output.
@@ -272,6 +324,7 @@ where
             if next_epoch_state.is_some() {
                 txn_data.push(TransactionData::new(
                     HashMap::new(),
+                    WriteSet::default(),
                     vec![],
                     TransactionStatus::Retry,
                     Arc::clone(&current_state_tree),
@@ -331,6 +384,7 @@ where
 
             txn_data.push(TransactionData::new(
                 blobs,
+                vm_output.write_set().clone(),
                 vm_output.events().to_vec(),
                 vm_output.status().clone(),
                 Arc::clone(&state_tree),
@@ -432,13 +486,7 @@ where
         Vec<ContractEvent>,
     )> {
         // Construct a StateView and pass the transactions to VM.
-        let state_view = VerifiedStateView::new(
-            StateViewId::ChunkExecution { first_version },
-            Arc::clone(&self.db.reader),
-            self.cache.synced_trees().version(),
-            self.cache.synced_trees().state_root(),
-            self.cache.synced_trees().state_tree(),
-        );
+        let state_view = self.get_chunk_state_view(first_version);
         let vm_outputs = {
             let __timer = OP_COUNTERS.timer("vm_execute_chunk_time_s");
             let _timer = LIBRA_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
@@ -453,6 +501,117 @@ where
             }
         }
 
+        Self::process_chunk_outputs(
+            state_view,
+            transactions,
+            vm_outputs,
+            transaction_infos,
+            self.cache.synced_trees(),
+        )
+    }
+
+    fn apply_chunk(
+        &self,
+        first_version: u64,
+        txns_and_outputs: Vec<(Transaction, TransactionOutput)>,
+        transaction_infos: Vec<TransactionInfo>,
+    ) -> Result<(
+        ProcessedVMOutput,
+        Vec<TransactionToCommit>,
+        Vec<ContractEvent>,
+    )> {
+        // Without the VM reading them, the accounts touched by the write sets have to be loaded
+        // into the StateView explicitly so that the outputs can be applied on top of them.
+        let state_view = self.get_chunk_state_view(first_version);
+        for (_, output) in &txns_and_outputs {
+            for (access_path, _) in output.write_set() {
+                state_view.get(access_path)?;
+            }
+        }
+        let (transactions, outputs) = txns_and_outputs.into_iter().unzip();
+
+        Self::process_chunk_outputs(
+            state_view,
+            transactions,
+            outputs,
+            transaction_infos,
+            self.cache.synced_trees(),
+        )
+    }
+
+    /// Commits a processed chunk along with the ledger info it ends at, if any, and moves the
+    /// synced trees forward.
+    fn commit_chunk(
+        &mut self,
+        first_version: u64,
+        output: ProcessedVMOutput,
+        txns_to_commit: Vec<TransactionToCommit>,
+        verified_target_li: LedgerInfoWithSignatures,
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        let ledger_info_to_commit =
+            Self::find_chunk_li(verified_target_li, epoch_change_li, &output)?;
+        if ledger_info_to_commit.is_none() && txns_to_commit.is_empty() {
+            return Ok(());
+        }
+        self.db.writer.save_transactions(
+            &txns_to_commit,
+            first_version,
+            ledger_info_to_commit.as_ref(),
+        )?;
+
+        // Cache maintenance.
+        let output_trees = output.executed_trees().clone();
+        if let Some(ledger_info_with_sigs) = &ledger_info_to_commit {
+            self.cache.update_block_tree_root(
+                output_trees,
+                ledger_info_with_sigs.ledger_info(),
+                vec![],
+                vec![],
+            );
+        } else {
+            self.cache.update_synced_trees(output_trees);
+        }
+        self.cache.reset();
+
+        info!(
+            "Synced to version {}, the corresponding LedgerInfo is {}.",
+            self.cache
+                .synced_trees()
+                .version()
+                .expect("version must exist"),
+            if ledger_info_to_commit.is_some() {
+                "committed"
+            } else {
+                "not committed"
+            },
+        );
+        Ok(())
+    }
+
+    fn get_chunk_state_view(&self, first_version: u64) -> VerifiedStateView<'_> {
+        VerifiedStateView::new(
+            StateViewId::ChunkExecution { first_version },
+            Arc::clone(&self.db.reader),
+            self.cache.synced_trees().version(),
+            self.cache.synced_trees().state_root(),
+            self.cache.synced_trees().state_tree(),
+        )
+    }
+
+    /// Computes the new state out of the outputs of a chunk of transactions and verifies it
+    /// against the transaction infos carried on the chunk.
+    fn process_chunk_outputs(
+        state_view: VerifiedStateView<'_>,
+        transactions: Vec<Transaction>,
+        vm_outputs: Vec<TransactionOutput>,
+        transaction_infos: Vec<TransactionInfo>,
+        parent_trees: &ExecutedTrees,
+    ) -> Result<(
+        ProcessedVMOutput,
+        Vec<TransactionToCommit>,
+        Vec<ContractEvent>,
+    )> {
         let (account_to_state, account_to_proof) = state_view.into();
 
         let output = Self::process_vm_outputs(
@@ -460,7 +619,7 @@ where
             account_to_proof,
             &transactions,
             vm_outputs,
-            self.cache.synced_trees(),
+            parent_trees,
         )?;
 
         // Since we have verified the proofs, we just need to verify that each TransactionInfo
@@ -497,6 +656,7 @@ where
             txns_to_commit.push(TransactionToCommit::new(
                 txn,
                 txn_data.account_blobs().clone(),
+                txn_data.write_set().clone(),
                 txn_data.events().to_vec(),
                 txn_data.gas_used(),
                 recorded_status,
@@ -540,45 +700,164 @@ impl<V: VMExecutor> ChunkExecutor for Executor<V> {
             self.execute_chunk(first_version, transactions, transaction_infos)?;
 
         // 4. Commit to DB.
-        let ledger_info_to_commit =
-            Self::find_chunk_li(verified_target_li, epoch_change_li, &output)?;
-        if ledger_info_to_commit.is_none() && txns_to_commit.is_empty() {
-            return Ok(reconfig_events);
-        }
-        self.db.writer.save_transactions(
-            &txns_to_commit,
+        self.commit_chunk(
             first_version,
-            ledger_info_to_commit.as_ref(),
+            output,
+            txns_to_commit,
+            verified_target_li,
+            epoch_change_li,
         )?;
+        Ok(reconfig_events)
+    }
 
-        // 5. Cache maintenance.
-        let output_trees = output.executed_trees().clone();
-        if let Some(ledger_info_with_sigs) = &ledger_info_to_commit {
-            self.cache.update_block_tree_root(
-                output_trees,
-                ledger_info_with_sigs.ledger_info(),
-                vec![],
-                vec![],
-            );
-        } else {
-            self.cache.update_synced_trees(output_trees);
-        }
-        self.cache.reset();
+    fn apply_and_commit_chunk(
+        &mut self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        epoch_change_li: Option<LedgerInfoWithSignatures>,
+    ) -> Result<Vec<ContractEvent>> {
+        // 1. Update the cache in executor to be consistent with latest synced state.
+        self.reset_cache()?;
 
         info!(
-            "Synced to version {}, the corresponding LedgerInfo is {}.",
-            self.cache
-                .synced_trees()
-                .version()
-                .expect("version must exist"),
-            if ledger_info_to_commit.is_some() {
-                "committed"
-            } else {
-                "not committed"
-            },
+            "Local synced version: {}. First transaction output version in request: {:?}. \
+             Number of transaction outputs in request: {}.",
+            self.cache.synced_trees().txn_accumulator().num_leaves() - 1,
+            txn_output_list_with_proof.first_transaction_output_version,
+            txn_output_list_with_proof.len(),
         );
+
+        // 2. Verify input transaction output list.
+        let (txns_and_outputs, transaction_infos) =
+            self.verify_output_chunk(txn_output_list_with_proof, &verified_target_li)?;
+
+        // 3. Apply transaction outputs.
+        let first_version = self.cache.synced_trees().txn_accumulator().num_leaves();
+        let (output, txns_to_commit, reconfig_events) =
+            self.apply_chunk(first_version, txns_and_outputs, transaction_infos)?;
+
+        // 4. Commit to DB.
+        self.commit_chunk(
+            first_version,
+            output,
+            txns_to_commit,
+            verified_target_li,
+            epoch_change_li,
+        )?;
         Ok(reconfig_events)
     }
+
+    fn start_state_snapshot(
+        &mut self,
+        mut txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+    ) -> Result<Option<HashValue>> {
+        self.reset_cache()?;
+
+        // 1. Verify the transaction at the snapshot version.
+        let version = verified_target_li.ledger_info().version();
+        let num_committed_txns = self.cache.synced_trees().txn_accumulator().num_leaves();
+        ensure!(
+            version >= num_committed_txns,
+            "State snapshot too old. Expected version: {}. Snapshot version: {}.",
+            num_committed_txns,
+            version,
+        );
+        txn_list_with_proof.verify(verified_target_li.ledger_info(), Some(version))?;
+        ensure!(
+            txn_list_with_proof.transactions.len() == 1,
+            "Expecting exactly one transaction at the snapshot version, got {}.",
+            txn_list_with_proof.transactions.len(),
+        );
+        // The events are verified against the transaction info above if they are present.
+        let events = txn_list_with_proof
+            .events
+            .take()
+            .and_then(|mut events| events.pop())
+            .ok_or_else(|| format_err!("Missing events of the transaction at snapshot version."))?;
+
+        // 2. Left side of the proof happens to be the frozen subtree roots of the accumulator
+        // right before the transaction, which makes the accumulator at the snapshot version.
+        let txn_info = txn_list_with_proof.proof.transaction_infos()[0].clone();
+        let frozen_subtree_roots_from_proof = txn_list_with_proof
+            .proof
+            .left_siblings()
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>();
+        let accumulator = InMemoryAccumulator::<TransactionAccumulatorHasher>::new(
+            frozen_subtree_roots_from_proof,
+            version,
+        )?
+        .append(&[txn_info.hash()]);
+        ensure!(
+            accumulator.root_hash()
+                == verified_target_li
+                    .ledger_info()
+                    .transaction_accumulator_hash(),
+            "Root hash in target ledger info does not match local computation."
+        );
+
+        // 3. Start restoring the state tree, resuming a previous attempt if there is one.
+        let receiver = self
+            .db
+            .writer
+            .get_state_snapshot_receiver(version, txn_info.state_root_hash())?;
+        let previous_key = receiver.previous_key();
+        info!(
+            "Restoring state snapshot at version {}, resuming after account {:?}.",
+            version, previous_key,
+        );
+        self.state_snapshot = Some(StateSnapshot::new(
+            receiver,
+            txn_list_with_proof
+                .transactions
+                .pop()
+                .expect("Transaction must exist."),
+            events,
+            txn_info,
+            accumulator.frozen_subtree_roots().clone(),
+            verified_target_li,
+        ));
+        Ok(previous_key)
+    }
+
+    fn save_account_chunk(
+        &mut self,
+        accounts: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        self.state_snapshot
+            .as_mut()
+            .ok_or_else(|| format_err!("No state snapshot being restored."))?
+            .receiver_mut()
+            .add_chunk(accounts, proof)
+    }
+
+    fn finalize_state_snapshot(&mut self) -> Result<()> {
+        let state_snapshot = self
+            .state_snapshot
+            .take()
+            .ok_or_else(|| format_err!("No state snapshot being restored."))?;
+        let (receiver, txn, events, txn_info, frozen_subtrees, ledger_info_with_sigs) =
+            state_snapshot.into_inner();
+        receiver.finish()?;
+        self.db.writer.finalize_state_snapshot(
+            &txn,
+            &events,
+            &txn_info,
+            &frozen_subtrees,
+            &ledger_info_with_sigs,
+        )?;
+        self.reset_cache()?;
+
+        info!(
+            "Restored state snapshot at version {}.",
+            ledger_info_with_sigs.ledger_info().version()
+        );
+        Ok(())
+    }
 }
 
 impl<V: VMExecutor> TransactionReplayer for Executor<V> {
@@ -759,6 +1038,7 @@ impl<V: VMExecutor> BlockExecutor for Executor<V> {
                 txns_to_keep.push(TransactionToCommit::new(
                     txn.clone(),
                     txn_data.account_blobs().clone(),
+                    txn_data.write_set().clone(),
                     txn_data.events().to_vec(),
                     txn_data.gas_used(),
                     recorded_status.clone(),
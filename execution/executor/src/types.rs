@@ -10,11 +10,14 @@ use libra_types::{
     account_state_blob::AccountStateBlob,
     contract_event::ContractEvent,
     epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
     proof::accumulator::InMemoryAccumulator,
-    transaction::{TransactionStatus, Version},
+    transaction::{Transaction, TransactionInfo, TransactionStatus, Version},
+    write_set::WriteSet,
 };
 use scratchpad::SparseMerkleTree;
 use std::{collections::HashMap, sync::Arc};
+use storage_interface::StateSnapshotReceiver;
 
 /// The entire set of data associated with a transaction. In addition to the output generated by VM
 /// which includes the write set and events, this also has the in-memory trees.
@@ -26,6 +29,9 @@ pub struct TransactionData {
     /// new blob.
     account_blobs: HashMap<AccountAddress, AccountStateBlob>,
 
+    /// The write set generated by the VM.
+    write_set: WriteSet,
+
     /// The list of events emitted during this transaction.
     events: Vec<ContractEvent>,
 
@@ -50,6 +56,7 @@ pub struct TransactionData {
 impl TransactionData {
     pub fn new(
         account_blobs: HashMap<AccountAddress, AccountStateBlob>,
+        write_set: WriteSet,
         events: Vec<ContractEvent>,
         status: TransactionStatus,
         state_tree: Arc<SparseMerkleTree>,
@@ -59,6 +66,7 @@ impl TransactionData {
     ) -> Self {
         TransactionData {
             account_blobs,
+            write_set,
             events,
             status,
             state_tree,
@@ -72,6 +80,10 @@ impl TransactionData {
         &self.account_blobs
     }
 
+    pub fn write_set(&self) -> &WriteSet {
+        &self.write_set
+    }
+
     pub fn events(&self) -> &[ContractEvent] {
         &self.events
     }
@@ -182,3 +194,59 @@ impl ProcessedVMOutput {
         )
     }
 }
+
+/// A state snapshot being restored, along with what is needed to commit it once all the accounts
+/// are received: the transaction at the snapshot version, its events, its info, the frozen subtree
+/// roots of the transaction accumulator at that version and the ledger info.
+pub struct StateSnapshot {
+    receiver: Box<dyn StateSnapshotReceiver>,
+    txn: Transaction,
+    events: Vec<ContractEvent>,
+    txn_info: TransactionInfo,
+    frozen_subtree_roots: Vec<HashValue>,
+    ledger_info_with_sigs: LedgerInfoWithSignatures,
+}
+
+impl StateSnapshot {
+    pub fn new(
+        receiver: Box<dyn StateSnapshotReceiver>,
+        txn: Transaction,
+        events: Vec<ContractEvent>,
+        txn_info: TransactionInfo,
+        frozen_subtree_roots: Vec<HashValue>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+    ) -> Self {
+        Self {
+            receiver,
+            txn,
+            events,
+            txn_info,
+            frozen_subtree_roots,
+            ledger_info_with_sigs,
+        }
+    }
+
+    pub fn receiver_mut(&mut self) -> &mut dyn StateSnapshotReceiver {
+        self.receiver.as_mut()
+    }
+
+    pub fn into_inner(
+        self,
+    ) -> (
+        Box<dyn StateSnapshotReceiver>,
+        Transaction,
+        Vec<ContractEvent>,
+        TransactionInfo,
+        Vec<HashValue>,
+        LedgerInfoWithSignatures,
+    ) {
+        (
+            self.receiver,
+            self.txn,
+            self.events,
+            self.txn_info,
+            self.frozen_subtree_roots,
+            self.ledger_info_with_sigs,
+        )
+    }
+}
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use libra_metrics::{
-    register_histogram_vec, register_int_counter, register_int_counter_vec, HistogramVec,
-    IntCounter, IntCounterVec,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge_vec,
+    HistogramVec, IntCounter, IntCounterVec, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -61,3 +61,23 @@ pub static REQUEST_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Number of currently open streaming subscriptions
+pub static ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "libra_client_service_active_subscriptions",
+        "Number of currently open JSON RPC streaming subscriptions",
+        &["type"] // transactions / account_transactions / events
+    )
+    .unwrap()
+});
+
+/// Cumulative number of streaming subscriptions terminated by a server side error
+pub static SUBSCRIPTION_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_client_service_subscription_error_count",
+        "Cumulative number of JSON RPC streaming subscriptions terminated by an error",
+        &["type"] // transactions / account_transactions / events
+    )
+    .unwrap()
+});
@@ -11,6 +11,7 @@
 //! Module organization:
 //! ├── methods.rs        # contains all available JSON RPC method handlers
 //! ├── runtime.rs        # implementation of JSON RPC protocol over HTTP
+//! ├── stream.rs         # streaming subscriptions (Server-Sent Events) for new transactions and events
 //! ├── tests.rs          # tests
 
 #[macro_use]
@@ -19,6 +20,7 @@ mod util;
 mod counters;
 mod methods;
 mod runtime;
+mod stream;
 
 pub use libra_json_rpc_types::{errors, response, views};
 
@@ -5,18 +5,22 @@
 use crate::{
     errors::JsonRpcError,
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, CurrencyInfoView, EventView,
-        StateProofView, TransactionView,
+        AccountResourceView, AccountStateWithProofView, AccountView, BlockMetadata, BytesView,
+        CurrencyInfoView, EventView, EventsPageView, MoveFieldView, MoveStructView, MoveValueView,
+        StateProofView, TransactionSimulationView, TransactionView, TransactionsPageView,
+        VMStatusView, WriteOpView,
     },
 };
-use anyhow::{ensure, format_err, Error, Result};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use core::future::Future;
 use futures::{channel::oneshot, SinkExt};
 use libra_config::config::RoleType;
-use libra_crypto::hash::CryptoHash;
+use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_mempool::MempoolClientSender;
+use libra_state_view::StateViewId;
 use libra_trace::prelude::*;
 use libra_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     account_config::{from_currency_code_string, libra_root_address, AccountResource},
     account_state::AccountState,
@@ -24,13 +28,15 @@ use libra_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::MempoolStatusCode,
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, TransactionStatus, Version},
 };
+use libra_vm::LibraVM;
 use network::counters;
-use serde::de::DeserializeOwned;
+use resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValueAnnotator};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::{cmp::min, collections::HashMap, convert::TryFrom, pin::Pin, sync::Arc};
-use storage_interface::{DbReader, Order};
+use storage_interface::{state_view::DbStateView, DbReader, Order};
 
 #[derive(Clone)]
 pub(crate) struct JsonRpcService {
@@ -69,6 +75,10 @@ impl JsonRpcService {
         self.chain_id
     }
 
+    pub fn page_size_limit(&self) -> u16 {
+        self.page_size_limit
+    }
+
     pub fn validate_batch_size_limit(&self, size: usize) -> Result<(), JsonRpcError> {
         self.validate_size_limit("batch size", self.batch_size_limit, size)
     }
@@ -119,11 +129,36 @@ impl JsonRpcRequest {
         self.ledger_info.ledger_info().version()
     }
 
+    /// Returns the version given by the optional params[index], defaulting to the request ledger
+    /// version when the param is omitted or null.
+    /// Fails if the version is beyond the request ledger version.
+    fn parse_version_param(&self, index: usize, name: &str) -> Result<Version> {
+        if self.get_param(index).is_null() {
+            return Ok(self.version());
+        }
+        let version: Version = self.parse_param(index, name)?;
+        if version > self.version() {
+            bail!(JsonRpcError::invalid_request_with_msg(format!(
+                "{} {} is greater than latest ledger version {}",
+                name,
+                version,
+                self.version()
+            )));
+        }
+        Ok(version)
+    }
+
     /// Return AccountAddress by try parse from params[index]
     fn parse_account_address(&self, index: usize) -> Result<AccountAddress> {
         self.try_parse_param(index, "account address")
     }
 
+    /// Return HashValue by try parse hex-encoded params[index]
+    fn parse_transaction_hash(&self, index: usize) -> Result<HashValue> {
+        let raw: String = self.parse_param(index, "transaction hash")?;
+        Ok(HashValue::from_hex(&raw).map_err(|_| invalid_param(index, "transaction hash"))?)
+    }
+
     /// Return type T instance with 2 steps parsing:
     ///   1. deserialize params[index] into String type.
     ///   2. call TryFrom<String> to create target T instance.
@@ -171,6 +206,101 @@ impl JsonRpcRequest {
             ._parse_event_key(self.get_param(index))
             .map_err(|_| invalid_param(index, name))?)
     }
+
+    /// Returns the optional continuation token at params[index], which must have been issued for
+    /// the same account address or event key
+    fn parse_page_cursor(&self, index: usize, key: &[u8]) -> Result<Option<PageCursor>> {
+        if self.get_param(index).is_null() {
+            return Ok(None);
+        }
+        let cursor = self
+            ._parse_page_cursor(self.get_param(index))
+            .map_err(|_| invalid_param(index, "cursor"))?;
+        if cursor.key != key {
+            bail!(invalid_param(index, "cursor"));
+        }
+        Ok(Some(cursor))
+    }
+
+    fn _parse_page_cursor(&self, val: Value) -> Result<PageCursor> {
+        let raw: String = serde_json::from_value(val)?;
+        Ok(lcs::from_bytes(&hex::decode(raw)?)?)
+    }
+
+    /// Returns the optional params[index] boolean, false when omitted or null
+    fn parse_optional_bool(&self, index: usize, name: &str) -> Result<bool> {
+        if self.get_param(index).is_null() {
+            return Ok(false);
+        }
+        self.parse_param(index, name)
+    }
+}
+
+/// Continuation token of the paginated methods. Clients receive it as an opaque hex-encoded
+/// string and pass it back unchanged to fetch the next page.
+#[derive(Debug, Deserialize, Serialize)]
+struct PageCursor {
+    // account address or event key the cursor was issued for
+    key: Vec<u8>,
+    // sequence number of the next item to return
+    next: u64,
+    descending: bool,
+}
+
+impl PageCursor {
+    fn new(key: &[u8], next: u64, order: Order) -> Self {
+        Self {
+            key: key.to_vec(),
+            next,
+            descending: order == Order::Descending,
+        }
+    }
+
+    fn order(&self) -> Order {
+        if self.descending {
+            Order::Descending
+        } else {
+            Order::Ascending
+        }
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(hex::encode(lcs::to_bytes(self)?))
+    }
+
+    /// Returns the start sequence number and order of a page given the optional cursor of the
+    /// request. Without a cursor, walking starts at the first item, or the latest item when
+    /// `descending` is set.
+    fn start(cursor: Option<PageCursor>, descending: bool) -> (u64, Order) {
+        match cursor {
+            Some(cursor) => (cursor.next, cursor.order()),
+            None if descending => (u64::max_value(), Order::Descending),
+            None => (0, Order::Ascending),
+        }
+    }
+
+    /// Returns the cursor of the page following the one made of `limit` items read starting at
+    /// `start`, given the sequence number of its last item. Ascending cursors are always returned,
+    /// so clients can keep following new items; descending ones stop at the first item.
+    fn next_page(
+        key: &[u8],
+        start: u64,
+        order: Order,
+        limit: u64,
+        num_read: u64,
+        last_seq_num: Option<u64>,
+    ) -> Result<Option<String>> {
+        let next = match (order, last_seq_num) {
+            (Order::Ascending, Some(seq_num)) => Some(seq_num + 1),
+            (Order::Ascending, None) => Some(start),
+            (Order::Descending, Some(seq_num)) if num_read == limit && seq_num > 0 => {
+                Some(seq_num - 1)
+            }
+            (Order::Descending, _) => None,
+        };
+        next.map(|next| PageCursor::new(key, next, order).encode())
+            .transpose()
+    }
 }
 
 /// Submits transaction to full node
@@ -195,15 +325,72 @@ async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<
     }
 }
 
-/// Returns account state (AccountView) by given address
+/// Executes the given transaction against the state at the request ledger version without
+/// committing it, and returns its vm status, gas usage, events and write set
+async fn simulate_transaction(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<TransactionSimulationView> {
+    let transaction = request.parse_signed_transaction(0, "data")?;
+    let skip_signature_check: bool = if request.get_param(1).is_null() {
+        false
+    } else {
+        request.parse_param(1, "skip_signature_check")?
+    };
+
+    trace_code_block!("json-rpc::simulate_transaction", {"txn", transaction.sender(), transaction.sequence_number()});
+
+    // the state is read as of the ledger version the response is returned with, not the latest
+    // one, which may have moved on since
+    let version = request.version();
+    let state_view = DbStateView::new(
+        StateViewId::TransactionSimulation {
+            base_version: version,
+        },
+        Arc::clone(&service.db),
+        version,
+    );
+    let (_, output) = tokio::task::spawn_blocking(move || {
+        LibraVM::simulate_signed_transaction(transaction, &state_view, skip_signature_check)
+    })
+    .await?;
+
+    let vm_status = match output.status() {
+        TransactionStatus::Keep(status) => VMStatusView::from(status),
+        TransactionStatus::Discard(status_code) => bail!(JsonRpcError::vm_status(*status_code)),
+        TransactionStatus::Retry => bail!(JsonRpcError::internal_error(
+            "transaction simulation was not completed".to_string()
+        )),
+    };
+    // events carry the version the transaction would be committed at
+    let events = output
+        .events()
+        .iter()
+        .map(|event| EventView::from((version + 1, event.clone())))
+        .collect();
+    let write_set = output
+        .write_set()
+        .iter()
+        .map(|(access_path, write_op)| WriteOpView::from((access_path, write_op)))
+        .collect();
+    Ok(TransactionSimulationView {
+        vm_status,
+        gas_used: output.gas_used(),
+        events,
+        write_set,
+    })
+}
+
+/// Returns account state (AccountView) by given address, as of the optional given version
 async fn get_account(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Option<AccountView>> {
     let account_address: AccountAddress = request.parse_account_address(0)?;
+    let version = request.parse_version_param(1, "version")?;
     let account_state_blob = service
         .db
-        .get_account_state_with_proof_by_version(account_address, request.version())?
+        .get_account_state_with_proof_by_version(account_address, version)?
         .0;
 
     let blob = match account_state_blob {
@@ -219,7 +406,7 @@ async fn get_account(
         .get_freezing_bit()?
         .ok_or_else(|| format_err!("invalid account data: no freezing bit"))?;
 
-    let currency_info = get_currencies(service, request).await?;
+    let currency_info = get_currencies_by_version(service.db.as_ref(), version)?;
     let currencies: Vec<_> = currency_info
         .into_iter()
         .map(|info| from_currency_code_string(&info.code))
@@ -239,6 +426,43 @@ async fn get_account(
     )))
 }
 
+/// Returns every resource stored under given address, decoded, as of the optional given version
+async fn get_account_resources(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<Vec<AccountResourceView>>> {
+    let account_address: AccountAddress = request.parse_account_address(0)?;
+    let version = request.parse_version_param(1, "version")?;
+    let blob = match service
+        .db
+        .get_account_state_with_proof_by_version(account_address, version)?
+        .0
+    {
+        Some(blob) => blob,
+        None => return Ok(None),
+    };
+
+    // modules are resolved from the state as of the same version, so that resources are decoded
+    // with the layouts that were in effect at that time
+    let state_view = DbStateView::new(StateViewId::Miscellaneous, Arc::clone(&service.db), version);
+    let annotator = MoveValueAnnotator::new_no_stdlib(&state_view);
+
+    let account_state = AccountState::try_from(&blob)?;
+    let resources = account_state
+        .iter()
+        .filter(|(path, _)| path.first() == Some(&AccessPath::RESOURCE_TAG))
+        .map(|(path, bytes)| AccountResourceView {
+            access_path: BytesView::from(path),
+            bytes: BytesView::from(bytes),
+            value: annotator
+                .view_access_path(AccessPath::new(account_address, path.clone()), bytes)
+                .ok()
+                .map(|resource| move_struct_view(&resource)),
+        })
+        .collect();
+    Ok(Some(resources))
+}
+
 /// Returns the blockchain metadata for a specified version. If no version is specified, default to
 /// returning the current blockchain metadata
 /// Can be used to verify that target Full Node is up-to-date
@@ -259,7 +483,7 @@ async fn get_metadata(service: JsonRpcService, request: JsonRpcRequest) -> Resul
 }
 
 /// Returns transactions by range
-async fn get_transactions(
+pub(crate) async fn get_transactions(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Vec<TransactionView>> {
@@ -357,8 +581,53 @@ async fn get_account_transaction(
     }
 }
 
+/// Returns the transaction with the given hash
+async fn get_transaction_by_hash(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<TransactionView>> {
+    let hash = request.parse_transaction_hash(0)?;
+    let include_events: bool = request.parse_param(1, "include_events")?;
+
+    let tx = service
+        .db
+        .get_transaction_by_hash(hash, request.version(), include_events)?;
+
+    if let Some(tx) = tx {
+        if include_events {
+            ensure!(
+                tx.events.is_some(),
+                "Storage layer didn't return events when requested!"
+            );
+        }
+        let tx_version = tx.version;
+
+        let events = tx
+            .events
+            .unwrap_or_default()
+            .into_iter()
+            .map(|x| ((tx_version, x).into()))
+            .collect();
+
+        Ok(Some(TransactionView {
+            version: tx_version,
+            hash: tx.transaction.hash().to_hex(),
+            bytes: lcs::to_bytes(&tx.transaction)?.into(),
+            transaction: tx.transaction.into(),
+            events,
+            vm_status: tx.proof.transaction_info().status().into(),
+            gas_used: tx.proof.transaction_info().gas_used(),
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Returns events by given access path
-async fn get_events(service: JsonRpcService, request: JsonRpcRequest) -> Result<Vec<EventView>> {
+pub(crate) async fn get_events(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<EventView>> {
     let event_key = request.parse_event_key(0, "event key")?;
 
     let start: u64 = request.parse_param(1, "start")?;
@@ -379,14 +648,66 @@ async fn get_events(service: JsonRpcService, request: JsonRpcRequest) -> Result<
     Ok(events)
 }
 
+/// Returns one page of events by given access path, walking forwards or backwards from the
+/// optional continuation token
+async fn get_events_page(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<EventsPageView> {
+    let event_key = request.parse_event_key(0, "event key")?;
+    let limit: u64 = request.parse_param(1, "limit")?;
+    let cursor = request.parse_page_cursor(2, event_key.as_bytes())?;
+    let descending = request.parse_optional_bool(3, "descending")?;
+
+    service.validate_page_size_limit(limit as usize)?;
+
+    let (start, order) = PageCursor::start(cursor, descending);
+    let events = if limit == 0 {
+        vec![]
+    } else {
+        service.db.get_events(&event_key, start, order, limit)?
+    };
+    let num_read = events.len() as u64;
+    let last_read = events.last().map(|(_, event)| event.sequence_number());
+
+    // the DB may have committed events after the ledger version of the request
+    let req_version = request.version();
+    let events: Vec<_> = events
+        .into_iter()
+        .filter(|(version, _event)| version <= &req_version)
+        .collect();
+    // ascending pages resume right after the last event returned, so that the events filtered
+    // out are returned by the next page; descending pages only filter out their newest events
+    let last_seq_num = match order {
+        Order::Ascending => events.last().map(|(_, event)| event.sequence_number()),
+        Order::Descending => last_read,
+    };
+    let next_cursor = PageCursor::next_page(
+        event_key.as_bytes(),
+        start,
+        order,
+        limit,
+        num_read,
+        last_seq_num,
+    )?;
+
+    Ok(EventsPageView {
+        events: events.into_iter().map(|event| event.into()).collect(),
+        next_cursor,
+    })
+}
+
 /// Returns meta information about supported currencies
 async fn get_currencies(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Vec<CurrencyInfoView>> {
-    if let Some(blob) = service
-        .db
-        .get_account_state_with_proof_by_version(libra_root_address(), request.version())?
+    get_currencies_by_version(service.db.as_ref(), request.version())
+}
+
+fn get_currencies_by_version(db: &dyn DbReader, version: Version) -> Result<Vec<CurrencyInfoView>> {
+    if let Some(blob) = db
+        .get_account_state_with_proof_by_version(libra_root_address(), version)?
         .0
     {
         let account_state = AccountState::try_from(&blob)?;
@@ -401,7 +722,7 @@ async fn get_currencies(
 }
 
 /// Returns all account transactions
-async fn get_account_transactions(
+pub(crate) async fn get_account_transactions(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Vec<TransactionView>> {
@@ -503,6 +824,75 @@ async fn get_account_state_with_proof(
     )?)
 }
 
+/// Returns one page of the transactions sent by an account, walking forwards or backwards from the
+/// optional continuation token
+async fn get_account_transactions_page(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<TransactionsPageView> {
+    let account = request.parse_account_address(0)?;
+    let limit: u64 = request.parse_param(1, "limit")?;
+    let include_events: bool = request.parse_param(2, "include_events")?;
+    let cursor = request.parse_page_cursor(3, account.as_ref())?;
+    let descending = request.parse_optional_bool(4, "descending")?;
+
+    service.validate_page_size_limit(limit as usize)?;
+
+    let (start, order) = PageCursor::start(cursor, descending);
+    let txs = service.db.get_account_transactions(
+        account,
+        start,
+        order,
+        limit,
+        request.version(),
+        include_events,
+    )?;
+    let last_seq_num = match txs.last() {
+        Some(tx) => Some(tx.transaction.as_signed_user_txn()?.sequence_number()),
+        None => None,
+    };
+    let next_cursor = PageCursor::next_page(
+        account.as_ref(),
+        start,
+        order,
+        limit,
+        txs.len() as u64,
+        last_seq_num,
+    )?;
+
+    let mut transactions = vec![];
+    for tx in txs {
+        let tx_version = tx.version;
+        let events = if include_events {
+            ensure!(
+                tx.events.is_some(),
+                "Storage layer didn't return events when requested!"
+            );
+            tx.events
+                .unwrap_or_default()
+                .into_iter()
+                .map(|x| ((tx_version, x).into()))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        transactions.push(TransactionView {
+            version: tx.version,
+            hash: tx.transaction.hash().to_hex(),
+            bytes: lcs::to_bytes(&tx.transaction)?.into(),
+            transaction: tx.transaction.into(),
+            events,
+            vm_status: tx.proof.transaction_info().status().into(),
+            gas_used: tx.proof.transaction_info().gas_used(),
+        });
+    }
+    Ok(TransactionsPageView {
+        transactions,
+        next_cursor,
+    })
+}
+
 /// Returns the number of peers this node is connected to
 async fn get_network_status(service: JsonRpcService, _request: JsonRpcRequest) -> Result<u64> {
     let peers = counters::LIBRA_NETWORK_PEERS
@@ -517,9 +907,24 @@ async fn get_network_status(service: JsonRpcService, _request: JsonRpcRequest) -
 pub(crate) fn build_registry() -> RpcRegistry {
     let mut registry = RpcRegistry::new();
     register_rpc_method!(registry, "submit", submit, 1, 0);
+    register_rpc_method!(registry, "simulate_transaction", simulate_transaction, 1, 1);
     register_rpc_method!(registry, "get_metadata", get_metadata, 0, 1);
-    register_rpc_method!(registry, "get_account", get_account, 1, 0);
+    register_rpc_method!(registry, "get_account", get_account, 1, 1);
+    register_rpc_method!(
+        registry,
+        "get_account_resources",
+        get_account_resources,
+        1,
+        1
+    );
     register_rpc_method!(registry, "get_transactions", get_transactions, 3, 0);
+    register_rpc_method!(
+        registry,
+        "get_transaction_by_hash",
+        get_transaction_by_hash,
+        2,
+        0
+    );
     register_rpc_method!(
         registry,
         "get_account_transaction",
@@ -534,7 +939,15 @@ pub(crate) fn build_registry() -> RpcRegistry {
         4,
         0
     );
+    register_rpc_method!(
+        registry,
+        "get_account_transactions_page",
+        get_account_transactions_page,
+        3,
+        2
+    );
     register_rpc_method!(registry, "get_events", get_events, 3, 0);
+    register_rpc_method!(registry, "get_events_page", get_events_page, 2, 2);
     register_rpc_method!(registry, "get_currencies", get_currencies, 0, 0);
 
     register_rpc_method!(registry, "get_state_proof", get_state_proof, 1, 0);
@@ -558,11 +971,45 @@ fn invalid_param(index: usize, name: &str) -> JsonRpcError {
         "limit" => "unsigned int64",
         "account sequence number" => "unsigned int64",
         "include_events" => "boolean",
+        "skip_signature_check" => "boolean",
+        "descending" => "boolean",
+        "cursor" => "continuation token returned by a previous page",
         "account address" => "hex-encoded string",
         "event key" => "hex-encoded string",
+        "transaction hash" => "hex-encoded string",
         "known version" => "unsigned int64",
+        "version" => "unsigned int64",
         "data" => "hex-encoded string of LCS serialized Libra SignedTransaction type",
         _ => "unknown",
     };
     JsonRpcError::invalid_param(index, name, type_info)
 }
+
+fn move_struct_view(value: &AnnotatedMoveStruct) -> MoveStructView {
+    MoveStructView {
+        type_tag: value.type_.to_string(),
+        fields: value
+            .value
+            .iter()
+            .map(|(name, value)| MoveFieldView {
+                name: name.to_string(),
+                value: move_value_view(value),
+            })
+            .collect(),
+    }
+}
+
+fn move_value_view(value: &AnnotatedMoveValue) -> MoveValueView {
+    match value {
+        AnnotatedMoveValue::U8(v) => MoveValueView::U8(*v),
+        AnnotatedMoveValue::U64(v) => MoveValueView::U64(*v),
+        AnnotatedMoveValue::U128(v) => MoveValueView::U128(v.to_string()),
+        AnnotatedMoveValue::Bool(v) => MoveValueView::Bool(*v),
+        AnnotatedMoveValue::Address(v) => MoveValueView::Address(v.to_string()),
+        AnnotatedMoveValue::Vector(v) => {
+            MoveValueView::Vector(v.iter().map(move_value_view).collect())
+        }
+        AnnotatedMoveValue::Bytes(v) => MoveValueView::Bytes(BytesView::from(v)),
+        AnnotatedMoveValue::Struct(v) => MoveValueView::Struct(move_struct_view(v)),
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     counters,
     errors::JsonRpcError,
     methods::{build_registry, JsonRpcRequest, JsonRpcService, RpcRegistry},
+    stream,
 };
 use futures::future::join_all;
 use libra_config::config::{NodeConfig, RoleType};
@@ -57,12 +58,14 @@ pub fn bootstrap(
         page_size_limit,
     );
 
+    let service_filter = warp::any().map(move || service.clone());
+
     let base_route = warp::any()
         .and(warp::post())
         .and(warp::header::exact("content-type", "application/json"))
         .and(warp::body::content_length_limit(content_len_limit as u64))
         .and(warp::body::json())
-        .and(warp::any().map(move || service.clone()))
+        .and(service_filter.clone())
         .and(warp::any().map(move || Arc::clone(&registry)))
         .and_then(rpc_endpoint);
 
@@ -77,7 +80,33 @@ pub fn bootstrap(
         .and(warp::path::end())
         .map(|| "libra-node:ok");
 
-    let full_route = health_route.or(route_v1.or(route_root));
+    // Streaming subscriptions, served as Server-Sent Events
+    let stream_transactions_route = warp::path!("v1" / "stream" / "transactions")
+        .and(warp::get())
+        .and(warp::query::<stream::TransactionsQuery>())
+        .and(warp::sse::last_event_id::<u64>())
+        .and(service_filter.clone())
+        .map(stream::stream_transactions);
+
+    let stream_account_transactions_route = warp::path!("v1" / "stream" / "account_transactions")
+        .and(warp::get())
+        .and(warp::query::<stream::AccountTransactionsQuery>())
+        .and(warp::sse::last_event_id::<u64>())
+        .and(service_filter.clone())
+        .map(stream::stream_account_transactions);
+
+    let stream_events_route = warp::path!("v1" / "stream" / "events")
+        .and(warp::get())
+        .and(warp::query::<stream::EventsQuery>())
+        .and(warp::sse::last_event_id::<u64>())
+        .and(service_filter)
+        .map(stream::stream_events);
+
+    let stream_route = stream_transactions_route
+        .or(stream_account_transactions_route)
+        .or(stream_events_route);
+
+    let full_route = health_route.or(stream_route.or(route_v1.or(route_root)));
 
     // Ensure that we actually bind to the socket first before spawning the
     // server tasks. This helps in tests to prevent races where a client attempts
@@ -0,0 +1,332 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module contains streaming subscription handlers for Full Node JSON-RPC interface
+//!
+//! Subscriptions are served as Server-Sent Events (a long-lived chunked HTTP response) under
+//! `/v1/stream/...`. Every pushed item carries an SSE `id` that is its position in the subscribed
+//! sequence (transaction version, account sequence number or event sequence number), so a client
+//! can resume after a reconnect either by passing the next position as the `start` query
+//! parameter, or by sending back the last received id in the standard `Last-Event-ID` header.
+//! Error events end the stream and carry the id of the position before the one that failed (an
+//! empty id at position 0), so that resuming from them doesn't skip the item that failed.
+use crate::{
+    counters,
+    errors::JsonRpcError,
+    methods::{
+        get_account_transactions, get_events, get_transactions, JsonRpcRequest, JsonRpcService,
+    },
+};
+use anyhow::Result;
+use futures::{stream, Stream};
+use libra_types::{account_address::AccountAddress, event::EventKey};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    cmp::min,
+    collections::VecDeque,
+    convert::{Infallible, TryFrom},
+    time::Duration,
+};
+use tokio::time::delay_for;
+use warp::sse::ServerSentEvent;
+
+/// Maximum number of items fetched from storage in one go for a single subscription
+const STREAM_PAGE_SIZE: u16 = 100;
+/// How long a subscription waits before polling storage again once it caught up with the ledger
+const STREAM_POLL_INTERVAL_MS: u64 = 500;
+
+// SSE event names
+const EVENT_TRANSACTION: &str = "transaction";
+const EVENT_EVENT: &str = "event";
+const EVENT_ERROR: &str = "error";
+
+#[derive(Deserialize)]
+pub(crate) struct TransactionsQuery {
+    #[serde(default)]
+    start: u64,
+    #[serde(default)]
+    include_events: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AccountTransactionsQuery {
+    account: String,
+    #[serde(default)]
+    start: u64,
+    #[serde(default)]
+    include_events: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EventsQuery {
+    key: String,
+    #[serde(default)]
+    start: u64,
+}
+
+/// Kind of data a subscription is following
+enum Subscription {
+    Transactions {
+        include_events: bool,
+    },
+    AccountTransactions {
+        account: AccountAddress,
+        include_events: bool,
+    },
+    Events {
+        key: EventKey,
+    },
+}
+
+impl Subscription {
+    fn label(&self) -> &'static str {
+        match self {
+            Subscription::Transactions { .. } => "transactions",
+            Subscription::AccountTransactions { .. } => "account_transactions",
+            Subscription::Events { .. } => "events",
+        }
+    }
+
+    fn event_name(&self) -> &'static str {
+        match self {
+            Subscription::Events { .. } => EVENT_EVENT,
+            _ => EVENT_TRANSACTION,
+        }
+    }
+}
+
+/// Keeps the active subscriptions gauge in sync with the lifetime of a subscription stream
+struct SubscriptionGuard(&'static str);
+
+impl SubscriptionGuard {
+    fn new(label: &'static str) -> Self {
+        counters::ACTIVE_SUBSCRIPTIONS
+            .with_label_values(&[label])
+            .inc();
+        Self(label)
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        counters::ACTIVE_SUBSCRIPTIONS
+            .with_label_values(&[self.0])
+            .dec();
+    }
+}
+
+struct SubscriptionState {
+    service: JsonRpcService,
+    subscription: Subscription,
+    // position of the next item to be pushed to the client
+    cursor: u64,
+    pending: VecDeque<Value>,
+    _guard: SubscriptionGuard,
+}
+
+impl SubscriptionState {
+    /// Fetches the next page of items at or after `cursor`, using a fresh ledger info snapshot.
+    /// Returns an empty page once the subscription caught up with the latest committed version.
+    async fn next_page(&self) -> Result<Vec<Value>> {
+        let limit = min(STREAM_PAGE_SIZE, self.service.page_size_limit());
+        let ledger_info = self.service.get_latest_ledger_info()?;
+        let service = self.service.clone();
+        let page = match &self.subscription {
+            Subscription::Transactions { include_events } => {
+                let request = JsonRpcRequest {
+                    params: vec![json!(self.cursor), json!(limit), json!(include_events)],
+                    ledger_info,
+                };
+                to_values(get_transactions(service, request).await?)?
+            }
+            Subscription::AccountTransactions {
+                account,
+                include_events,
+            } => {
+                let request = JsonRpcRequest {
+                    params: vec![
+                        json!(account.to_string()),
+                        json!(self.cursor),
+                        json!(limit),
+                        json!(include_events),
+                    ],
+                    ledger_info,
+                };
+                to_values(get_account_transactions(service, request).await?)?
+            }
+            Subscription::Events { key } => {
+                let request = JsonRpcRequest {
+                    params: vec![
+                        json!(hex::encode(key.as_bytes())),
+                        json!(self.cursor),
+                        json!(limit),
+                    ],
+                    ledger_info,
+                };
+                to_values(get_events(service, request).await?)?
+            }
+        };
+        Ok(page)
+    }
+}
+
+/// Streams committed transactions starting at the given version
+pub(crate) fn stream_transactions(
+    query: TransactionsQuery,
+    last_event_id: Option<u64>,
+    service: JsonRpcService,
+) -> impl warp::Reply {
+    let subscription = Ok(Subscription::Transactions {
+        include_events: query.include_events,
+    });
+    subscription_reply(
+        service,
+        subscription,
+        resume_from(query.start, last_event_id),
+    )
+}
+
+/// Streams transactions sent by the given account starting at the given sequence number
+pub(crate) fn stream_account_transactions(
+    query: AccountTransactionsQuery,
+    last_event_id: Option<u64>,
+    service: JsonRpcService,
+) -> impl warp::Reply {
+    let subscription = AccountAddress::try_from(query.account)
+        .map(|account| Subscription::AccountTransactions {
+            account,
+            include_events: query.include_events,
+        })
+        .map_err(|_| JsonRpcError::invalid_request_with_msg("invalid account".to_string()));
+    subscription_reply(
+        service,
+        subscription,
+        resume_from(query.start, last_event_id),
+    )
+}
+
+/// Streams events emitted under the given event key starting at the given sequence number
+pub(crate) fn stream_events(
+    query: EventsQuery,
+    last_event_id: Option<u64>,
+    service: JsonRpcService,
+) -> impl warp::Reply {
+    let subscription = hex::decode(query.key)
+        .ok()
+        .and_then(|bytes| EventKey::try_from(&bytes[..]).ok())
+        .map(|key| Subscription::Events { key })
+        .ok_or_else(|| JsonRpcError::invalid_request_with_msg("invalid event key".to_string()));
+    subscription_reply(
+        service,
+        subscription,
+        resume_from(query.start, last_event_id),
+    )
+}
+
+/// `Last-Event-ID` is the position of the last item the client received, so it takes
+/// precedence over the `start` query parameter when a client reconnects
+fn resume_from(start: u64, last_event_id: Option<u64>) -> u64 {
+    match last_event_id {
+        Some(id) => id.saturating_add(1),
+        None => start,
+    }
+}
+
+fn subscription_reply(
+    service: JsonRpcService,
+    subscription: Result<Subscription, JsonRpcError>,
+    cursor: u64,
+) -> impl warp::Reply {
+    warp::sse::reply(warp::sse::keep_alive().stream(subscription_stream(
+        service,
+        subscription,
+        cursor,
+    )))
+}
+
+fn subscription_stream(
+    service: JsonRpcService,
+    subscription: Result<Subscription, JsonRpcError>,
+    cursor: u64,
+) -> impl Stream<Item = Result<impl ServerSentEvent, Infallible>> + Send + 'static {
+    let state = subscription.map(|subscription| SubscriptionState {
+        service,
+        _guard: SubscriptionGuard::new(subscription.label()),
+        subscription,
+        cursor,
+        pending: VecDeque::new(),
+    });
+
+    stream::unfold(Some(state), move |state| async move {
+        let mut state = match state? {
+            Ok(state) => state,
+            Err(err) => {
+                return Some((
+                    Ok(sse_event(
+                        last_delivered_id(cursor),
+                        EVENT_ERROR,
+                        err.serialize(),
+                    )),
+                    None,
+                ))
+            }
+        };
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                let event = sse_event(
+                    state.cursor.to_string(),
+                    state.subscription.event_name(),
+                    item,
+                );
+                state.cursor += 1;
+                return Some((Ok(event), Some(Ok(state))));
+            }
+            match state.next_page().await {
+                Ok(page) if page.is_empty() => {
+                    delay_for(Duration::from_millis(STREAM_POLL_INTERVAL_MS)).await
+                }
+                Ok(page) => state.pending.extend(page),
+                Err(err) => {
+                    counters::SUBSCRIPTION_ERRORS
+                        .with_label_values(&[state.subscription.label()])
+                        .inc();
+                    let error = match err.downcast_ref::<JsonRpcError>() {
+                        Some(custom_error) => custom_error.clone(),
+                        None => JsonRpcError::internal_error(err.to_string()),
+                    };
+                    return Some((
+                        Ok(sse_event(
+                            last_delivered_id(state.cursor),
+                            EVENT_ERROR,
+                            error.serialize(),
+                        )),
+                        None,
+                    ));
+                }
+            }
+        }
+    })
+}
+
+/// The id of the item before `cursor`, or an empty id if `cursor` is the first position
+fn last_delivered_id(cursor: u64) -> String {
+    cursor
+        .checked_sub(1)
+        .map_or_else(String::new, |id| id.to_string())
+}
+
+fn sse_event(id: String, name: &'static str, data: Value) -> impl ServerSentEvent {
+    (
+        warp::sse::id(id),
+        warp::sse::event(name),
+        warp::sse::data(data.to_string()),
+    )
+}
+
+fn to_values<T: serde::Serialize>(items: Vec<T>) -> Result<Vec<Value>> {
+    Ok(items
+        .into_iter()
+        .map(serde_json::to_value)
+        .collect::<Result<_, _>>()?)
+}
@@ -19,10 +19,11 @@ use libra_config::{config::DEFAULT_CONTENT_LENGTH_LIMIT, utils};
 use libra_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, HashValue, PrivateKey, Uniform};
 use libra_json_rpc_client::{
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, BytesView, EventView,
-        StateProofView, TransactionDataView, TransactionView, VMStatusView,
+        AccountResourceView, AccountStateWithProofView, AccountView, BlockMetadata, BytesView,
+        EventView, EventsPageView, MoveValueView, StateProofView, TransactionDataView,
+        TransactionView, TransactionsPageView, VMStatusView,
     },
-    JsonRpcAsyncClient, JsonRpcBatch, JsonRpcResponse, ResponseAsView,
+    JsonRpcAsyncClient, JsonRpcAsyncClientError, JsonRpcBatch, JsonRpcResponse, ResponseAsView,
 };
 use libra_mempool::SubmissionStatus;
 use libra_metrics::get_all_metrics;
@@ -53,7 +54,7 @@ use move_core_types::{
 };
 use move_vm_types::values::{Struct, Value};
 use proptest::prelude::*;
-use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use rand::{distributions::Alphanumeric, rngs::OsRng, thread_rng, Rng};
 use std::{
     cmp::{max, min},
     collections::HashMap,
@@ -869,6 +870,48 @@ fn test_transaction_submission() {
     }
 }
 
+#[test]
+fn test_simulate_transaction() {
+    let (_, client, mut runtime) = create_database_client_and_runtime();
+
+    // signature does not match the public key of the transaction
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let other_key = Ed25519PrivateKey::generate(&mut OsRng);
+    let sender = AccountAddress::new([9; AccountAddress::LENGTH]);
+    let txn = get_test_signed_txn(sender, 0, &privkey, other_key.public_key(), None);
+
+    let mut batch = JsonRpcBatch::default();
+    batch
+        .add_simulate_transaction_request(txn.clone(), false)
+        .unwrap();
+    let err = runtime
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, ServerCode::VmValidationError as i16);
+    let status_code: StatusCode = err.as_status_code().unwrap();
+    assert_eq!(status_code, StatusCode::INVALID_SIGNATURE);
+
+    // skip_signature_check must be a boolean
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "simulate_transaction".to_string(),
+        vec![
+            json!(hex::encode(lcs::to_bytes(&txn).unwrap())),
+            json!("true"),
+        ],
+    );
+    let err = runtime
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, -32602);
+}
+
 #[test]
 fn test_get_account() {
     let (mock_db, client, mut runtime) = create_database_client_and_runtime();
@@ -938,6 +981,70 @@ fn test_get_account() {
     }
 }
 
+#[test]
+fn test_get_account_by_version() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+    let account = get_first_account_from_mock_db(&mock_db);
+    let version = mock_db.get_latest_version().unwrap();
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_by_version_request(account, version - 1);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let view = AccountView::optional_from_response(result)
+        .unwrap()
+        .expect("account does not exist");
+    let expected_resource = AccountState::try_from(&mock_db.all_accounts[&account])
+        .unwrap()
+        .get_account_resource()
+        .unwrap()
+        .unwrap();
+    assert_eq!(view.sequence_number, expected_resource.sequence_number());
+
+    // versions beyond the latest ledger version are rejected
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_by_version_request(account, version + 1);
+    let err = runtime
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, -32600);
+}
+
+#[test]
+fn test_get_account_resources() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_resources_request(libra_root_address(), None);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let resources = AccountResourceView::vec_from_response(result).unwrap();
+
+    let expected_state = AccountState::try_from(&mock_db.genesis[&libra_root_address()]).unwrap();
+    let account_resource = expected_state.get_account_resource().unwrap().unwrap();
+    let account_view = resources
+        .iter()
+        .filter_map(|resource| resource.value.as_ref())
+        .find(|value| value.type_tag.ends_with("::LibraAccount::LibraAccount"))
+        .expect("LibraAccount resource is not decoded");
+    let sequence_number = account_view
+        .fields
+        .iter()
+        .find(|field| field.name == "sequence_number")
+        .map(|field| field.value.clone());
+    assert_eq!(
+        sequence_number,
+        Some(MoveValueView::U64(account_resource.sequence_number()))
+    );
+
+    // non existing account
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_resources_request(AccountAddress::random(), Some(1));
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    assert_eq!(result, JsonRpcResponse::AccountResourcesResponse(None));
+}
+
 #[test]
 fn test_get_metadata_latest() {
     let (mock_db, client, mut runtime) = create_database_client_and_runtime();
@@ -983,6 +1090,109 @@ fn test_limit_batch_size() {
     assert_eq!(ret.unwrap_err().to_string(), expected)
 }
 
+#[test]
+fn test_get_events_page() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+
+    let (_, first_event) = mock_db.events[0].clone();
+    let event_key = hex::encode(first_event.key().as_bytes());
+    let mut expected: Vec<u64> = mock_db
+        .events
+        .iter()
+        .filter(|(_, e)| e.key() == first_event.key())
+        .map(|(_, e)| e.sequence_number())
+        .collect();
+    expected.sort_unstable();
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_events_page_request(event_key.clone(), 1, None, true);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let page = EventsPageView::from_response(result).unwrap();
+    assert_eq!(
+        page.events
+            .iter()
+            .map(|e| e.sequence_number)
+            .collect::<Vec<_>>(),
+        vec![*expected.last().unwrap()]
+    );
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_events_page_request(event_key, 1, None, false);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let page = EventsPageView::from_response(result).unwrap();
+    assert_eq!(
+        page.events
+            .iter()
+            .map(|e| e.sequence_number)
+            .collect::<Vec<_>>(),
+        expected
+            .iter()
+            .cloned()
+            .filter(|seq_num| *seq_num == 0)
+            .collect::<Vec<_>>()
+    );
+    // ascending pages always return a cursor to follow new events with
+    assert!(page.next_cursor.is_some());
+}
+
+#[test]
+fn test_get_events_page_committed_after_request_version() {
+    let mut mock_db = mock_db();
+    let (_, first_event) = mock_db.events[0].clone();
+    let event_key = hex::encode(first_event.key().as_bytes());
+    let mut expected: Vec<u64> = mock_db
+        .events
+        .iter()
+        .filter(|(_, e)| e.key() == first_event.key())
+        .map(|(_, e)| e.sequence_number())
+        .collect();
+    expected.sort_unstable();
+
+    // events the DB commits after the ledger version the request is served at
+    let next_seq_num = expected.last().unwrap() + 1;
+    let new_seq_nums = vec![next_seq_num, next_seq_num + 1];
+    for seq_num in &new_seq_nums {
+        mock_db.events.push((
+            mock_db.version + 1,
+            ContractEvent::new(
+                *first_event.key(),
+                *seq_num,
+                first_event.type_tag().clone(),
+                first_event.event_data().to_vec(),
+            ),
+        ));
+    }
+    let limit = (expected.len() + new_seq_nums.len()) as u64;
+    let get_page = |mock_db: MockLibraDB, cursor: Option<String>| {
+        let port = utils::get_available_port();
+        let address = format!("0.0.0.0:{}", port);
+        let (mp_sender, _) = channel(1);
+        let mut runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+        let client = JsonRpcAsyncClient::new(
+            reqwest::Url::from_str(format!("http://{}:{}/v1", "127.0.0.1", port).as_str())
+                .expect("invalid url"),
+        );
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_events_page_request(event_key.clone(), limit, cursor, false);
+        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+        EventsPageView::from_response(result).unwrap()
+    };
+    let seq_nums = |page: &EventsPageView| {
+        page.events
+            .iter()
+            .map(|e| e.sequence_number)
+            .collect::<Vec<_>>()
+    };
+
+    let page = get_page(mock_db.clone(), None);
+    assert_eq!(seq_nums(&page), expected);
+
+    // once the ledger version includes them, the next page starts with them
+    mock_db.version += 1;
+    let page = get_page(mock_db, page.next_cursor);
+    assert_eq!(seq_nums(&page), new_seq_nums);
+}
+
 #[test]
 fn test_get_events_page_limit() {
     let (_, client, mut runtime) = create_database_client_and_runtime();
@@ -1122,6 +1332,164 @@ fn test_get_transactions() {
     }
 }
 
+#[test]
+fn test_stream_transactions() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+
+    // crosses the subscription page boundary for most generated databases
+    let count = min(mock_db.all_txns.len(), 150);
+    let txns = runtime.block_on(
+        client
+            .subscribe_to_transactions(0, true)
+            .take(count)
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(txns.len(), count);
+    for (version, txn) in txns.into_iter().enumerate() {
+        let txn = txn.unwrap();
+        assert_eq!(txn.version, version as u64);
+        assert_eq!(txn.hash, mock_db.all_txns[version].0.hash().to_hex());
+    }
+
+    // resume from a later version
+    let start = count as u64 / 2;
+    let txn = runtime
+        .block_on(Box::pin(client.subscribe_to_transactions(start, false)).next())
+        .unwrap()
+        .unwrap();
+    assert_eq!(txn.version, start);
+    assert!(txn.events.is_empty());
+}
+
+#[test]
+fn test_stream_events() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+
+    let (first_event_version, first_event) = mock_db.events[0].clone();
+    let event_key = hex::encode(first_event.key().as_bytes());
+
+    let event = runtime
+        .block_on(
+            Box::pin(client.subscribe_to_events(event_key, first_event.sequence_number())).next(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.sequence_number, first_event.sequence_number());
+    assert_eq!(event.transaction_version, first_event_version);
+
+    // invalid subscription parameters are reported as a JSON RPC error on the stream
+    let err = runtime
+        .block_on(Box::pin(client.subscribe_to_events("invalid".to_string(), 0)).next())
+        .unwrap()
+        .unwrap_err();
+    match err {
+        JsonRpcAsyncClientError::JsonRpcError(err) => assert_eq!(err.code, -32600),
+        _ => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn test_stream_resumes_from_error_event() {
+    let mock_db = mock_db();
+    let (account, seq) = mock_db
+        .all_accounts
+        .iter()
+        .map(|(acc, blob)| {
+            (
+                *acc,
+                AccountResource::try_from(blob).unwrap().sequence_number(),
+            )
+        })
+        .find(|(acc, seq)| *seq > 1 && !mock_db.genesis.contains_key(acc))
+        .expect("mock DB missing account with transactions");
+    let start = seq - 1;
+
+    // the first server doesn't know the account, so fetching the item at `start` fails
+    let mut failing_db = mock_db.clone();
+    failing_db.all_accounts.remove(&account);
+    let failing_address = format!("0.0.0.0:{}", utils::get_available_port());
+    let (mp_sender, _) = channel(1);
+    let _failing_runtime = test_bootstrap(
+        failing_address.parse().unwrap(),
+        Arc::new(failing_db),
+        mp_sender,
+    );
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let (mp_sender, _) = channel(1);
+    let mut runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+
+    let path = format!(
+        "/v1/stream/account_transactions?account={}&start={}",
+        account, start
+    );
+    let client = reqwest::Client::new();
+    let (id, name, _) = runtime.block_on(async {
+        let response = client
+            .get(&format!("http://{}{}", failing_address, path))
+            .send()
+            .await
+            .unwrap();
+        next_sse_event(response).await
+    });
+    assert_eq!(name, "error");
+    assert_eq!(id, (start - 1).to_string());
+
+    // reconnecting with the id of the error event yields the item that failed
+    let (id, name, data) = runtime.block_on(async {
+        let response = client
+            .get(&format!("http://{}{}", address, path))
+            .header("Last-Event-ID", id)
+            .send()
+            .await
+            .unwrap();
+        next_sse_event(response).await
+    });
+    assert_eq!(name, "transaction");
+    assert_eq!(id, start.to_string());
+    let txn: TransactionView = serde_json::from_str(&data).unwrap();
+    match txn.transaction {
+        TransactionDataView::UserTransaction {
+            sender,
+            sequence_number,
+            ..
+        } => {
+            assert_eq!(sender, account.to_string());
+            assert_eq!(sequence_number, start);
+        }
+        _ => panic!("unexpected transaction: {:?}", txn.transaction),
+    }
+}
+
+/// Reads the id, name and data of the first event of a Server-Sent Events response
+async fn next_sse_event(mut response: reqwest::Response) -> (String, String, String) {
+    let mut buffer = vec![];
+    loop {
+        if let Some(end) = buffer.windows(2).position(|bytes| bytes == b"\n\n") {
+            let (mut id, mut name, mut data) = (String::new(), String::new(), String::new());
+            for line in std::str::from_utf8(&buffer[..end]).unwrap().lines() {
+                let (field, value) = match line.find(':') {
+                    Some(colon) => (&line[..colon], &line[colon + 1..]),
+                    None => (line, ""),
+                };
+                match field {
+                    "id" => id = value.trim_start().to_string(),
+                    "event" => name = value.trim_start().to_string(),
+                    "data" => data = value.trim_start().to_string(),
+                    _ => (),
+                }
+            }
+            if !name.is_empty() {
+                return (id, name, data);
+            }
+            // keep-alive comment
+            buffer.drain(..end + 2);
+            continue;
+        }
+        let chunk = response.chunk().await.unwrap().expect("stream ended");
+        buffer.extend_from_slice(&chunk);
+    }
+}
+
 #[test]
 fn test_get_account_transaction() {
     let (mock_db, client, mut runtime) = create_database_client_and_runtime();
@@ -1213,6 +1581,89 @@ fn test_get_account_transactions() {
         assert_eq!(tx_views.len() as u64, total);
     }
 }
+#[test]
+fn test_get_transaction_by_hash() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+
+    for (version, (txn, status)) in mock_db.all_txns.iter().enumerate().take(10) {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_transaction_by_hash_request(txn.hash(), true);
+
+        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+        let tx_view = TransactionView::optional_from_response(result)
+            .unwrap()
+            .expect("Transaction didn't exists!");
+        assert_eq!(tx_view.version, version as u64);
+        assert_eq!(tx_view.hash, txn.hash().to_hex());
+        assert_eq!(tx_view.vm_status, VMStatusView::from(status));
+    }
+
+    // unknown hash
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_transaction_by_hash_request(HashValue::random(), false);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    assert_eq!(result, JsonRpcResponse::AccountTransactionResponse(None));
+}
+
+#[test]
+fn test_get_account_transactions_page() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime();
+
+    for (acc, blob) in mock_db.all_accounts.iter() {
+        let total = AccountResource::try_from(blob).unwrap().sequence_number();
+
+        for &descending in &[false, true] {
+            let mut seq_nums = vec![];
+            let mut cursor = None;
+            loop {
+                let mut batch = JsonRpcBatch::default();
+                batch.add_get_account_transactions_page_request(*acc, 2, false, cursor, descending);
+                let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+                let page = TransactionsPageView::from_response(result).unwrap();
+                assert!(page.transactions.len() <= 2);
+                if page.transactions.is_empty() {
+                    break;
+                }
+                seq_nums.extend(page.transactions.iter().map(|tx| match tx.transaction {
+                    TransactionDataView::UserTransaction {
+                        sequence_number, ..
+                    } => sequence_number,
+                    _ => panic!("expected user transaction"),
+                }));
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            if descending {
+                seq_nums.reverse();
+            }
+            assert_eq!(seq_nums, (0..total).collect::<Vec<_>>());
+        }
+    }
+
+    // cursors are bound to the account they were issued for
+    let mut accounts = mock_db.all_accounts.keys();
+    let (first, second) = (*accounts.next().unwrap(), *accounts.next().unwrap());
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_transactions_page_request(first, 1, false, None, false);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let cursor = TransactionsPageView::from_response(result)
+        .unwrap()
+        .next_cursor;
+    assert!(cursor.is_some());
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_transactions_page_request(second, 1, false, cursor, false);
+    let err = runtime
+        .block_on(client.execute(batch))
+        .unwrap()
+        .remove(0)
+        .unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, -32602);
+}
+
 #[test]
 // Check that if version and ledger_version parameters are None, then the server returns the latest
 // known state.
@@ -5,7 +5,7 @@ use anyhow::{Error, Result};
 use libra_config::config::{
     RoleType, DEFAULT_BATCH_SIZE_LIMIT, DEFAULT_CONTENT_LENGTH_LIMIT, DEFAULT_PAGE_SIZE_LIMIT,
 };
-use libra_crypto::HashValue;
+use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_mempool::MempoolClientSender;
 use libra_types::{
     account_address::AccountAddress,
@@ -64,6 +64,38 @@ pub(crate) struct MockLibraDB {
     pub timestamps: Vec<u64>,
 }
 
+impl MockLibraDB {
+    fn txn_with_proof(&self, version: u64, fetch_events: bool) -> TransactionWithProof {
+        let (transaction, status) = &self.all_txns[version as usize];
+        TransactionWithProof {
+            version,
+            transaction: transaction.clone(),
+            events: if fetch_events {
+                Some(
+                    self.events
+                        .iter()
+                        .filter(|(ev, _)| *ev == version)
+                        .map(|(_, e)| e)
+                        .cloned()
+                        .collect(),
+                )
+            } else {
+                None
+            },
+            proof: TransactionInfoWithProof::new(
+                TransactionAccumulatorProof::new(vec![]),
+                TransactionInfo::new(
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    0,
+                    status.clone(),
+                ),
+            ),
+        }
+    }
+}
+
 impl DbReader for MockLibraDB {
     fn get_latest_account_state(
         &self,
@@ -114,32 +146,55 @@ impl DbReader for MockLibraDB {
                     false
                 }
             })
-            .map(|(v, (x, status))| TransactionWithProof {
-                version: v as u64,
-                transaction: x.clone(),
-                events: if fetch_events {
-                    Some(
-                        self.events
-                            .iter()
-                            .filter(|(ev, _)| *ev == v as u64)
-                            .map(|(_, e)| e)
-                            .cloned()
-                            .collect(),
-                    )
-                } else {
-                    None
-                },
-                proof: TransactionInfoWithProof::new(
-                    TransactionAccumulatorProof::new(vec![]),
-                    TransactionInfo::new(
-                        Default::default(),
-                        Default::default(),
-                        Default::default(),
-                        0,
-                        status.clone(),
-                    ),
-                ),
-            }))
+            .map(|(v, _)| self.txn_with_proof(v as u64, fetch_events)))
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        _ledger_version: u64,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>, Error> {
+        Ok(self
+            .all_txns
+            .iter()
+            .position(|(x, _)| x.hash() == hash)
+            .map(|v| self.txn_with_proof(v as u64, fetch_events)))
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start_seq_num: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: u64,
+        fetch_events: bool,
+    ) -> Result<Vec<TransactionWithProof>, Error> {
+        let mut seq_nums: Vec<u64> = self
+            .all_txns
+            .iter()
+            .filter_map(|(x, _)| x.as_signed_user_txn().ok())
+            .filter(|t| t.sender() == address)
+            .map(|t| t.sequence_number())
+            .collect();
+        seq_nums.sort_unstable();
+        if order == Order::Descending {
+            seq_nums.reverse();
+        }
+        seq_nums
+            .into_iter()
+            .filter(|seq_num| match order {
+                Order::Ascending => *seq_num >= start_seq_num,
+                Order::Descending => *seq_num <= start_seq_num,
+            })
+            .take(limit as usize)
+            .map(|seq_num| {
+                Ok(self
+                    .get_txn_by_account(address, seq_num, ledger_version, fetch_events)?
+                    .expect("transaction exists"))
+            })
+            .collect()
     }
 
     fn get_transactions(
@@ -195,20 +250,40 @@ impl DbReader for MockLibraDB {
         &self,
         key: &EventKey,
         start: u64,
-        _order: Order,
+        order: Order,
         limit: u64,
     ) -> Result<Vec<(u64, ContractEvent)>> {
+        let start = if order == Order::Descending && start == u64::max_value() {
+            self.events
+                .iter()
+                .filter(|(_, e)| e.key() == key)
+                .map(|(_, e)| e.sequence_number())
+                .max()
+                .unwrap_or(0)
+        } else {
+            start
+        };
         let events = self
             .events
             .iter()
             .filter(|(_, e)| {
                 e.key() == key
-                    && start <= e.sequence_number()
-                    && e.sequence_number() < start + limit
+                    && match order {
+                        Order::Ascending => {
+                            start <= e.sequence_number()
+                                && e.sequence_number() < start.saturating_add(limit)
+                        }
+                        Order::Descending => {
+                            e.sequence_number() <= start
+                                && start.saturating_sub(e.sequence_number()) < limit
+                        }
+                    }
             })
-            .cloned()
-            .collect();
-        Ok(events)
+            .cloned();
+        Ok(match order {
+            Order::Ascending => events.collect(),
+            Order::Descending => events.rev().collect(),
+        })
     }
 
     fn get_state_proof(
@@ -264,7 +339,7 @@ impl DbReader for MockLibraDB {
     }
 
     fn get_latest_state_root(&self) -> Result<(u64, HashValue)> {
-        unimplemented!()
+        Ok((self.version, HashValue::zero()))
     }
 
     fn get_latest_tree_state(&self) -> Result<TreeState> {
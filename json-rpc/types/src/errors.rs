@@ -29,6 +29,8 @@ pub enum ServerCode {
     MempoolInvalidUpdate = -32010,
     MempoolVmError = -32011,
     MempoolUnknownError = -32012,
+    MempoolEvicted = -32013,
+    MempoolTooManyBytes = -32014,
 }
 
 /// JSON RPC server error codes for invalid request
@@ -157,6 +159,8 @@ impl JsonRpcError {
             MempoolStatusCode::InvalidUpdate => ServerCode::MempoolInvalidUpdate,
             MempoolStatusCode::VmError => ServerCode::MempoolVmError,
             MempoolStatusCode::UnknownStatus => ServerCode::MempoolUnknownError,
+            MempoolStatusCode::Evicted => ServerCode::MempoolEvicted,
+            MempoolStatusCode::TooManyBytes => ServerCode::MempoolTooManyBytes,
             MempoolStatusCode::Accepted => {
                 return Err(anyhow::format_err!(
                     "[JSON RPC] cannot create mempool error for mempool accepted status"
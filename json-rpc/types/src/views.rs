@@ -4,6 +4,7 @@
 use anyhow::{format_err, Error, Result};
 use libra_crypto::HashValue;
 use libra_types::{
+    access_path::AccessPath,
     account_config::{
         AccountResource, AccountRole, BalanceResource, BurnEvent, CancelBurnEvent,
         CurrencyInfoResource, FreezingBit, MintEvent, NewBlockEvent, NewEpochEvent, PreburnEvent,
@@ -17,6 +18,7 @@ use libra_types::{
     proof::{AccountStateProof, AccumulatorConsistencyProof},
     transaction::{Transaction, TransactionArgument, TransactionPayload},
     vm_status::KeptVMStatus,
+    write_set::WriteOp,
 };
 use move_core_types::{
     identifier::Identifier,
@@ -111,6 +113,49 @@ impl AccountView {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountResourceView {
+    pub access_path: BytesView,
+    pub bytes: BytesView,
+    /// Decoded resource, null if the resource type could not be resolved
+    pub value: Option<MoveStructView>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MoveStructView {
+    #[serde(rename = "type")]
+    pub type_tag: String,
+    pub fields: Vec<MoveFieldView>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MoveFieldView {
+    pub name: String,
+    pub value: MoveValueView,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "value")]
+pub enum MoveValueView {
+    #[serde(rename = "u8")]
+    U8(u8),
+    #[serde(rename = "u64")]
+    U64(u64),
+    /// u128 values are encoded as decimal strings, as they do not fit into JSON numbers
+    #[serde(rename = "u128")]
+    U128(String),
+    #[serde(rename = "bool")]
+    Bool(bool),
+    #[serde(rename = "address")]
+    Address(String),
+    #[serde(rename = "vector")]
+    Vector(Vec<MoveValueView>),
+    #[serde(rename = "bytes")]
+    Bytes(BytesView),
+    #[serde(rename = "struct")]
+    Struct(MoveStructView),
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct EventView {
     pub key: BytesView,
@@ -119,6 +164,13 @@ pub struct EventView {
     pub data: EventDataView,
 }
 
+/// One page of events, `next_cursor` is the opaque token to pass back to fetch the next page
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EventsPageView {
+    pub events: Vec<EventView>,
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum EventDataView {
@@ -414,6 +466,50 @@ pub struct TransactionView {
     pub gas_used: u64,
 }
 
+/// One page of transactions, `next_cursor` is the opaque token to pass back to fetch the next page
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TransactionsPageView {
+    pub transactions: Vec<TransactionView>,
+    pub next_cursor: Option<String>,
+}
+
+/// Output of a transaction executed against the latest state without being committed
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TransactionSimulationView {
+    pub vm_status: VMStatusView,
+    pub gas_used: u64,
+    pub events: Vec<EventView>,
+    pub write_set: Vec<WriteOpView>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum WriteOpView {
+    #[serde(rename = "value")]
+    Value {
+        address: String,
+        path: BytesView,
+        value: BytesView,
+    },
+    #[serde(rename = "deletion")]
+    Deletion { address: String, path: BytesView },
+}
+
+impl From<(&AccessPath, &WriteOp)> for WriteOpView {
+    fn from((access_path, write_op): (&AccessPath, &WriteOp)) -> Self {
+        let address = access_path.address.to_string();
+        let path = BytesView::from(&access_path.path);
+        match write_op {
+            WriteOp::Value(value) => WriteOpView::Value {
+                address,
+                path,
+                value: BytesView::from(value),
+            },
+            WriteOp::Deletion => WriteOpView::Deletion { address, path },
+        }
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(tag = "type")]
@@ -0,0 +1,58 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use language_e2e_tests::{
+    account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor,
+};
+use libra_types::{transaction::TransactionStatus, vm_status::KeptVMStatus};
+use move_vm_types::gas_profiler::TOP_LEVEL_FRAME;
+
+#[test]
+fn peer_to_peer_gas_profile() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let (outputs, gas_profile) = executor
+        .execute_block_with_gas_profile(vec![txn.clone()])
+        .expect("The VM should not fail to startup");
+    assert_eq!(
+        outputs[0].status(),
+        &TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    // Profiling does not change the outcome of the transaction.
+    assert_eq!(outputs[0], executor.execute_transaction(txn));
+    assert!(gas_profile.total_gas() > 0);
+
+    let report = gas_profile.report();
+    assert_eq!(report.total_gas, gas_profile.total_gas());
+    let script = report
+        .functions
+        .get("Script::main")
+        .expect("the script must be profiled");
+    assert_eq!(script.calls, 1);
+    let (_, pay_from) = report
+        .functions
+        .iter()
+        .find(|(name, _)| name.ends_with("::LibraAccount::pay_from"))
+        .expect("the functions called by the script must be profiled");
+    assert!(pay_from.total_gas > 0);
+    assert!(pay_from.total_gas <= script.total_gas);
+    assert!(!report.instructions.is_empty());
+    assert!(!report.natives.is_empty());
+
+    // Every charge is attributed to exactly one folded stack.
+    let folded = gas_profile.folded_stacks();
+    let folded_total: u64 = folded
+        .lines()
+        .map(|line| {
+            let (stack, gas) = line.split_at(line.rfind(' ').expect("gas must follow stack"));
+            assert!(stack == TOP_LEVEL_FRAME || stack.starts_with("Script::main"));
+            gas.trim().parse::<u64>().expect("gas must be a number")
+        })
+        .sum();
+    assert_eq!(folded_total, gas_profile.total_gas());
+}
@@ -16,6 +16,7 @@ mod create_account;
 mod data_store;
 mod execution_strategies;
 mod failed_transaction_tests;
+mod gas_profiler;
 mod genesis;
 mod mint;
 mod module_publishing;
@@ -23,6 +24,7 @@ mod on_chain_configs;
 mod peer_to_peer;
 mod rotate_key;
 mod scripts;
+mod simulation;
 mod transaction_builder;
 mod transaction_fees;
 mod transaction_fuzzer;
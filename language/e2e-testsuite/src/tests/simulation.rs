@@ -0,0 +1,64 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use language_e2e_tests::{
+    account::{self, AccountData},
+    common_transactions::peer_to_peer_txn,
+    executor::FakeExecutor,
+};
+use libra_crypto::ed25519::Ed25519Signature;
+use libra_types::{
+    transaction::{SignedTransaction, TransactionStatus},
+    vm_status::{KeptVMStatus, StatusCode, VMStatus},
+};
+use libra_vm::LibraVM;
+
+#[test]
+fn simulate_peer_to_peer() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let (status, output) =
+        LibraVM::simulate_signed_transaction(txn.clone(), executor.get_state_view(), false);
+    assert_eq!(status, VMStatus::Executed);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    assert!(output.gas_used() > 0);
+    let sender_path = sender.account().make_account_access_path();
+    let receiver_balance_path = receiver
+        .account()
+        .make_balance_access_path(account::lbr_currency_code());
+    assert!(output
+        .write_set()
+        .iter()
+        .any(|(path, _)| path == &sender_path));
+    assert!(output
+        .write_set()
+        .iter()
+        .any(|(path, _)| path == &receiver_balance_path));
+    // The simulation charges the same gas and writes the same values as the execution.
+    assert_eq!(output, executor.execute_transaction(txn.clone()));
+
+    // A transaction which is not signed yet is only simulated when skipping the signature check.
+    let unsigned_txn = SignedTransaction::new(
+        txn.into_raw_transaction(),
+        sender.account().pubkey.clone(),
+        Ed25519Signature::dummy_signature(),
+    );
+    let (status, _) = LibraVM::simulate_signed_transaction(
+        unsigned_txn.clone(),
+        executor.get_state_view(),
+        false,
+    );
+    assert_eq!(status, VMStatus::Error(StatusCode::INVALID_SIGNATURE));
+    let (status, unchecked_output) =
+        LibraVM::simulate_signed_transaction(unsigned_txn, executor.get_state_view(), true);
+    assert_eq!(status, VMStatus::Executed);
+    assert_eq!(unchecked_output, output);
+}
@@ -20,8 +20,9 @@ use libra_types::{
     account_config,
     block_metadata::BlockMetadata,
     transaction::{
-        ChangeSet, Module, Script, SignatureCheckedTransaction, Transaction, TransactionArgument,
-        TransactionOutput, TransactionPayload, TransactionStatus, WriteSetPayload,
+        ChangeSet, Module, Script, SignatureCheckedTransaction, SignedTransaction, Transaction,
+        TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
+        WriteSetPayload,
     },
     vm_status::{KeptVMStatus, StatusCode, VMStatus},
     write_set::{WriteSet, WriteSetMut},
@@ -31,9 +32,10 @@ use move_core_types::{
     gas_schedule::{CostTable, GasAlgebra, GasCarrier, GasUnits},
     identifier::IdentStr,
 };
-use move_vm_runtime::{data_cache::RemoteCache, session::Session};
+use move_vm_runtime::{data_cache::RemoteCache, debugger::DebugHook, session::Session};
 
 use move_vm_types::{
+    gas_profiler::GasProfile,
     gas_schedule::{zero_cost_schedule, CostStrategy},
     values::Value,
 };
@@ -41,6 +43,7 @@ use rayon::prelude::*;
 use std::{
     collections::HashSet,
     convert::{AsMut, AsRef, TryFrom},
+    sync::Arc,
 };
 
 pub struct LibraVM(LibraVMImpl);
@@ -240,6 +243,16 @@ impl LibraVM {
         &mut self,
         remote_cache: &StateViewCache<'_>,
         txn: &SignatureCheckedTransaction,
+    ) -> (VMStatus, TransactionOutput) {
+        self.execute_unchecked_user_transaction(remote_cache, txn)
+    }
+
+    /// Executes a user transaction whose signature may not have been checked. Only transactions
+    /// whose output is never committed, i.e. simulated ones, may skip the signature check.
+    fn execute_unchecked_user_transaction(
+        &mut self,
+        remote_cache: &StateViewCache<'_>,
+        txn: &SignedTransaction,
     ) -> (VMStatus, TransactionOutput) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
@@ -253,6 +266,9 @@ impl LibraVM {
         let gas_schedule = unwrap_or_discard!(self.0.get_gas_schedule());
         let txn_data = TransactionMetadata::new(txn);
         let mut cost_strategy = CostStrategy::system(gas_schedule, txn_data.max_gas_amount());
+        if self.0.gas_profile.is_some() {
+            cost_strategy.enable_profiling();
+        }
         let account_currency_symbol = unwrap_or_discard!(
             account_config::from_currency_code_string(txn.gas_currency_code())
                 .map_err(|_| VMStatus::Error(StatusCode::INVALID_GAS_SPECIFIER))
@@ -282,6 +298,11 @@ impl LibraVM {
             .sub(cost_strategy.remaining_gas())
             .get();
         TXN_GAS_USAGE.observe(gas_usage as f64);
+        if let (Some(block_profile), Some(txn_profile)) =
+            (&mut self.0.gas_profile, cost_strategy.take_gas_profile())
+        {
+            block_profile.merge(&txn_profile);
+        }
 
         match result {
             Ok(output) => output,
@@ -634,6 +655,59 @@ impl LibraVM {
         let mut vm = LibraVM::new(&state_view_cache);
         vm.execute_block_impl(transactions, &mut state_view_cache)
     }
+
+    /// Alternate form of 'execute_block_and_keep_vm_status' that also profiles the gas charged
+    /// to the user transactions of the block. See `move_vm_types::gas_profiler`.
+    pub fn execute_block_with_gas_profile(
+        transactions: Vec<Transaction>,
+        state_view: &dyn StateView,
+    ) -> Result<(Vec<(VMStatus, TransactionOutput)>, GasProfile), VMStatus> {
+        let mut state_view_cache = StateViewCache::new(state_view);
+        let mut vm = LibraVM::new(&state_view_cache);
+        vm.0.gas_profile = Some(GasProfile::default());
+        let output = vm.execute_block_impl(transactions, &mut state_view_cache)?;
+        Ok((output, vm.0.gas_profile.take().unwrap_or_default()))
+    }
+
+    /// Alternate form of 'execute_block_and_keep_vm_status' that debugs the executions of the
+    /// block with `hook`. See `move_vm_runtime::debugger`.
+    pub fn execute_block_with_debug_hook(
+        transactions: Vec<Transaction>,
+        state_view: &dyn StateView,
+        hook: Arc<dyn DebugHook>,
+    ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
+        let mut state_view_cache = StateViewCache::new(state_view);
+        let mut vm = LibraVM::new(&state_view_cache);
+        vm.0.debug_hook = Some(hook);
+        vm.execute_block_impl(transactions, &mut state_view_cache)
+    }
+
+    /// Executes a single user transaction against `state_view` without applying its output, so
+    /// that clients can learn its gas usage, events and write set before submitting it. When
+    /// `skip_signature_check` is set the transaction signature is not verified, the rest of the
+    /// prologue still runs.
+    pub fn simulate_signed_transaction(
+        txn: SignedTransaction,
+        state_view: &dyn StateView,
+        skip_signature_check: bool,
+    ) -> (VMStatus, TransactionOutput) {
+        let txn = if skip_signature_check {
+            txn
+        } else {
+            match txn.check_signature() {
+                Ok(checked_txn) => checked_txn.into_inner(),
+                Err(_) => {
+                    return discard_error_vm_status(VMStatus::Error(StatusCode::INVALID_SIGNATURE))
+                }
+            }
+        };
+        if let TransactionPayload::WriteSet(_) = txn.payload() {
+            return discard_error_vm_status(VMStatus::Error(StatusCode::REJECTED_WRITE_SET));
+        }
+        let state_view_cache = StateViewCache::new(state_view);
+        let mut vm = LibraVM::new(&state_view_cache);
+        vm.execute_unchecked_user_transaction(&state_view_cache, &txn)
+    }
 }
 
 fn preprocess_transaction(txn: Transaction) -> Result<PreprocessedTransaction, VMStatus> {
@@ -31,10 +31,12 @@ use move_core_types::{
 
 use move_vm_runtime::{
     data_cache::{RemoteCache, TransactionEffects},
+    debugger::DebugHook,
     move_vm::MoveVM,
     session::Session,
 };
 use move_vm_types::{
+    gas_profiler::GasProfile,
     gas_schedule::{calculate_intrinsic_gas, zero_cost_schedule, CostStrategy},
     values::Value,
 };
@@ -48,6 +50,10 @@ pub struct LibraVMImpl {
     on_chain_config: Option<VMConfig>,
     version: Option<LibraVersion>,
     publishing_option: Option<VMPublishingOption>,
+    /// The gas charged to the user transactions executed so far, when profiling is enabled.
+    pub(crate) gas_profile: Option<GasProfile>,
+    /// The hook debugging the executions of the sessions, when debugging is enabled.
+    pub(crate) debug_hook: Option<Arc<dyn DebugHook>>,
 }
 
 impl LibraVMImpl {
@@ -59,6 +65,8 @@ impl LibraVMImpl {
             on_chain_config: None,
             version: None,
             publishing_option: None,
+            gas_profile: None,
+            debug_hook: None,
         };
         vm.load_configs_impl(&RemoteStorage::new(state));
         vm
@@ -75,6 +83,8 @@ impl LibraVMImpl {
             on_chain_config: Some(on_chain_config),
             version: Some(version),
             publishing_option: Some(publishing_option),
+            gas_profile: None,
+            debug_hook: None,
         }
     }
 
@@ -376,7 +386,11 @@ impl LibraVMImpl {
     }
 
     pub fn new_session<'r, R: RemoteCache>(&self, r: &'r R) -> Session<'r, '_, R> {
-        self.move_vm.new_session(r)
+        let mut session = self.move_vm.new_session(r);
+        if let Some(hook) = &self.debug_hook {
+            session.set_debug_hook(Arc::clone(hook));
+        }
+        session
     }
 }
 
@@ -38,6 +38,22 @@ pub struct Script {
     pub specs: Vec<SpecBlock>,
 }
 
+//**************************************************************************************************
+// Attributes
+//**************************************************************************************************
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Attribute_ {
+    // #[test]
+    Test,
+    // #[test_only]
+    TestOnly,
+    // #[expected_failure]
+    // #[expected_failure(abort_code = <u64>)]
+    ExpectedFailure(Option<u64>),
+}
+pub type Attribute = Spanned<Attribute_>;
+
 //**************************************************************************************************
 // Modules
 //**************************************************************************************************
@@ -45,6 +61,7 @@ pub struct Script {
 #[derive(Debug)]
 pub struct ModuleDefinition {
     pub loc: Loc,
+    pub attributes: Vec<Attribute>,
     pub is_source_module: bool,
     pub structs: UniqueMap<StructName, StructDefinition>,
     pub functions: UniqueMap<FunctionName, Function>,
@@ -97,6 +114,7 @@ pub struct SpecId(usize);
 #[derive(PartialEq, Debug)]
 pub struct Function {
     pub loc: Loc,
+    pub attributes: Vec<Attribute>,
     pub visibility: FunctionVisibility,
     pub signature: FunctionSignature,
     pub acquires: Vec<ModuleAccess>,
@@ -293,6 +311,26 @@ pub type SequenceItem = Spanned<SequenceItem_>;
 // impls
 //**************************************************************************************************
 
+impl Attribute_ {
+    pub const TEST: &'static str = "test";
+    pub const TEST_ONLY: &'static str = "test_only";
+    pub const EXPECTED_FAILURE: &'static str = "expected_failure";
+    pub const ABORT_CODE: &'static str = "abort_code";
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute_::Test => Attribute_::TEST,
+            Attribute_::TestOnly => Attribute_::TEST_ONLY,
+            Attribute_::ExpectedFailure(_) => Attribute_::EXPECTED_FAILURE,
+        }
+    }
+
+    /// Returns true if the annotated item is only compiled for unit tests
+    pub fn is_test_only(&self) -> bool {
+        matches!(self, Attribute_::Test | Attribute_::TestOnly)
+    }
+}
+
 impl SpecId {
     pub fn new(u: usize) -> Self {
         SpecId(u)
@@ -391,16 +429,38 @@ impl AstDebug for Script {
     }
 }
 
+impl AstDebug for Vec<Attribute> {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        if self.is_empty() {
+            return;
+        }
+        w.write("#[");
+        w.comma(self, |w, attr| attr.ast_debug(w));
+        w.writeln("]");
+    }
+}
+
+impl AstDebug for Attribute_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        w.write(self.name());
+        if let Attribute_::ExpectedFailure(Some(code)) = self {
+            w.write(&format!("({} = {})", Attribute_::ABORT_CODE, code))
+        }
+    }
+}
+
 impl AstDebug for ModuleDefinition {
     fn ast_debug(&self, w: &mut AstWriter) {
         let ModuleDefinition {
             loc: _loc,
+            attributes,
             is_source_module,
             structs,
             functions,
             constants,
             specs,
         } = self;
+        attributes.ast_debug(w);
         w.writeln(if *is_source_module {
             "source module"
         } else {
@@ -572,6 +632,7 @@ impl AstDebug for (FunctionName, &Function) {
             name,
             Function {
                 loc: _loc,
+                attributes,
                 visibility,
                 signature,
                 acquires,
@@ -579,6 +640,7 @@ impl AstDebug for (FunctionName, &Function) {
                 ..
             },
         ) = self;
+        attributes.ast_debug(w);
         visibility.ast_debug(w);
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
@@ -220,8 +220,14 @@ fn set_sender_address(context: &mut Context, loc: Loc, sender: Option<Address>)
 }
 
 fn module_(context: &mut Context, mdef: P::ModuleDefinition) -> (ModuleIdent, E::ModuleDefinition) {
-    let P::ModuleDefinition { loc, name, members } = mdef;
+    let P::ModuleDefinition {
+        loc,
+        attributes: pattributes,
+        name,
+        members,
+    } = mdef;
     let _ = check_restricted_self_name(context, "module", &name.0);
+    let attributes = attributes(context, AttributePosition::Module, pattributes);
 
     let name_loc = name.loc();
     let mident_ = ModuleIdent_ {
@@ -269,6 +275,7 @@ fn module_(context: &mut Context, mdef: P::ModuleDefinition) -> (ModuleIdent, E:
 
     let def = E::ModuleDefinition {
         loc,
+        attributes,
         is_source_module: context.is_source_module,
         structs,
         constants,
@@ -309,6 +316,10 @@ fn script_(context: &mut Context, pscript: P::Script) -> E::Script {
     }
 
     let (function_name, function) = function_(context, pfunction);
+    if let Some(attribute) = function.attributes.first() {
+        let msg = "Invalid attribute. Attributes are not supported on script functions";
+        context.error(vec![(attribute.loc, msg)]);
+    }
     if let FunctionVisibility::Public(loc) = &function.visibility {
         let msg = "Extraneous 'public' modifier. Script functions are always public";
         context.error(vec![(*loc, msg)]);
@@ -695,6 +706,7 @@ fn function(
 fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E::Function) {
     let P::Function {
         loc,
+        attributes: pattributes,
         name,
         visibility,
         signature: psignature,
@@ -702,8 +714,10 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         acquires,
     } = pfunction;
     assert!(context.exp_specs.is_empty());
+    let attributes = attributes(context, AttributePosition::Function, pattributes);
     let old_aliases = context.new_alias_scope(AliasMap::new());
     let signature = function_signature(context, psignature);
+    check_test_function(context, &attributes, &signature);
     let acquires = acquires
         .into_iter()
         .flat_map(|a| module_access(context, Access::Type, a))
@@ -712,6 +726,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
     let specs = context.extract_exp_specs();
     let fdef = E::Function {
         loc,
+        attributes,
         visibility,
         signature,
         acquires,
@@ -758,6 +773,170 @@ fn function_body(context: &mut Context, sp!(loc, pbody_): P::FunctionBody) -> E:
     sp(loc, body_)
 }
 
+// Test functions are called directly by the unit test runner, with no arguments
+fn check_test_function(
+    context: &mut Context,
+    attributes: &[E::Attribute],
+    signature: &E::FunctionSignature,
+) {
+    use E::Attribute_ as EA;
+    let find = |f: fn(&EA) -> bool| attributes.iter().find(|attr| f(&attr.value));
+    let test_opt = find(|attr| matches!(attr, EA::Test));
+    if let Some(sp!(loc, EA::TestOnly)) = find(|attr| matches!(attr, EA::TestOnly)) {
+        if test_opt.is_some() {
+            let msg = format!(
+                "Invalid '#[{}]' attribute. Test functions are already only compiled for testing",
+                EA::TEST_ONLY
+            );
+            context.error(vec![(*loc, msg)]);
+        }
+    }
+    if let Some(sp!(loc, _)) = find(|attr| matches!(attr, EA::ExpectedFailure(_))) {
+        if test_opt.is_none() {
+            let msg = format!(
+                "Invalid '#[{}]' attribute. Only test functions, annotated with '#[{}]', can be \
+                 expected to fail",
+                EA::EXPECTED_FAILURE,
+                EA::TEST
+            );
+            context.error(vec![(*loc, msg)]);
+        }
+    }
+    let test_loc = match test_opt {
+        Some(attr) => attr.loc,
+        None => return,
+    };
+    if let Some((tp, _)) = signature.type_parameters.first() {
+        context.error(vec![
+            (
+                tp.loc,
+                "Invalid test function. Test functions cannot have type parameters",
+            ),
+            (test_loc, "Declared as a test function here"),
+        ]);
+    }
+    if let Some((v, _)) = signature.parameters.first() {
+        context.error(vec![
+            (
+                v.loc(),
+                "Invalid test function. Test functions cannot have parameters",
+            ),
+            (test_loc, "Declared as a test function here"),
+        ]);
+    }
+}
+
+//**************************************************************************************************
+// Attributes
+//**************************************************************************************************
+
+#[derive(Clone, Copy)]
+enum AttributePosition {
+    Module,
+    Function,
+}
+
+fn attributes(
+    context: &mut Context,
+    position: AttributePosition,
+    pattributes: Vec<P::Attribute>,
+) -> Vec<E::Attribute> {
+    let mut attributes: Vec<E::Attribute> = vec![];
+    for pattribute in pattributes {
+        let attribute = match attribute(context, pattribute) {
+            Some(attribute) => attribute,
+            None => continue,
+        };
+        let name = attribute.value.name();
+        if let AttributePosition::Module = position {
+            if attribute.value != E::Attribute_::TestOnly {
+                let msg = format!(
+                    "Invalid attribute '{}'. Only '{}' can be given to a module",
+                    name,
+                    E::Attribute_::TEST_ONLY
+                );
+                context.error(vec![(attribute.loc, msg)]);
+                continue;
+            }
+        }
+        if let Some(prev) = attributes.iter().find(|prev| prev.value.name() == name) {
+            context.error(vec![
+                (attribute.loc, format!("Duplicate attribute '{}'", name)),
+                (prev.loc, "Previously given here".into()),
+            ]);
+            continue;
+        }
+        attributes.push(attribute)
+    }
+    attributes
+}
+
+fn attribute(context: &mut Context, sp!(loc, pattribute_): P::Attribute) -> Option<E::Attribute> {
+    use E::Attribute_ as EA;
+    use P::Attribute_ as PA;
+    let attribute_ = match pattribute_ {
+        PA::Name(n) => match n.value.as_str() {
+            EA::TEST => EA::Test,
+            EA::TEST_ONLY => EA::TestOnly,
+            EA::EXPECTED_FAILURE => EA::ExpectedFailure(None),
+            _ => {
+                unknown_attribute(context, &n);
+                return None;
+            }
+        },
+        PA::Parameterized(n, args) if n.value == EA::EXPECTED_FAILURE => {
+            EA::ExpectedFailure(Some(abort_code(context, loc, args)?))
+        }
+        PA::Assigned(n, _) | PA::Parameterized(n, _) => match n.value.as_str() {
+            EA::TEST | EA::TEST_ONLY | EA::EXPECTED_FAILURE => {
+                let msg = format!("Invalid attribute. '{}' does not take arguments", n);
+                context.error(vec![(loc, msg)]);
+                return None;
+            }
+            _ => {
+                unknown_attribute(context, &n);
+                return None;
+            }
+        },
+    };
+    Some(sp(loc, attribute_))
+}
+
+fn unknown_attribute(context: &mut Context, n: &Name) {
+    let msg = format!(
+        "Unknown attribute '{}'. Expected one of: '{}', '{}', or '{}'",
+        n,
+        E::Attribute_::TEST,
+        E::Attribute_::TEST_ONLY,
+        E::Attribute_::EXPECTED_FAILURE
+    );
+    context.error(vec![(n.loc, msg)])
+}
+
+fn abort_code(context: &mut Context, loc: Loc, args: Vec<P::Attribute>) -> Option<u64> {
+    use P::{AttributeValue_ as PV, Attribute_ as PA, Value_ as V};
+    let mut args = args.into_iter();
+    let code_opt = match (args.next(), args.next()) {
+        (Some(sp!(_, PA::Assigned(n, v))), None) if n.value == E::Attribute_::ABORT_CODE => {
+            match v.value {
+                PV::InferredNum(u) if u <= (std::u64::MAX as u128) => Some(u as u64),
+                PV::Value(sp!(_, V::U64(u))) => Some(u),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    if code_opt.is_none() {
+        let msg = format!(
+            "Invalid attribute. Expected '{}({} = <u64>)'",
+            E::Attribute_::EXPECTED_FAILURE,
+            E::Attribute_::ABORT_CODE
+        );
+        context.error(vec![(loc, msg)]);
+    }
+    code_opt
+}
+
 //**************************************************************************************************
 // Specification Blocks
 //**************************************************************************************************
@@ -19,6 +19,7 @@ pub mod shared;
 pub mod test_utils;
 mod to_bytecode;
 pub mod typing;
+pub mod unit_test;
 
 use anyhow::anyhow;
 use codespan::{ByteIndex, Span};
@@ -120,13 +121,75 @@ pub fn move_compile_to_expansion_no_report(
 )> {
     let (files, pprog_and_comments_res) = parse_program(targets, deps)?;
     let res = pprog_and_comments_res.and_then(|(pprog, comment_map)| {
-        let (eprog, errors) = expansion::translate::program(pprog, sender_opt);
+        let (mut eprog, errors) = expansion::translate::program(pprog, sender_opt);
         check_errors(errors)?;
+        unit_test::filter_test_members::program(&mut eprog);
         Ok((eprog, comment_map))
     });
     Ok((files, res))
 }
 
+/// Move compile for unit testing, returning errors instead of reporting them to stderr
+/// - Unlike the other entry points, keeps the `#[test]` and `#[test_only]` modules and functions
+/// - Also returns the test plan, i.e. the `#[test]` functions of the targets
+pub fn move_compile_for_testing_no_report(
+    targets: &[String],
+    deps: &[String],
+    sender_opt: Option<Address>,
+) -> anyhow::Result<(
+    FilesSourceText,
+    Result<(Vec<CompiledUnit>, unit_test::TestPlan), Errors>,
+)> {
+    let (files, pprog_and_comments_res) = parse_program(targets, deps)?;
+    let pprog_res = pprog_and_comments_res.map(|(pprog, _)| pprog);
+    Ok((files, compile_program_for_testing(pprog_res, sender_opt)))
+}
+
+/// Move check up to the typing phase the targets and dependencies whose source text is given in
+/// `files`, e.g. the contents of files being edited, instead of reading them from disk
+/// - Unlike the other entry points, returns the typed program even when naming or typing fail,
+///   along with the errors, so that tools can make sense of programs being edited
+/// - Only parsing errors prevent the typed program from being built
+/// The phases following typing are run with `move_check_typed_program`
+pub fn move_check_sources_to_typing_no_report(
+    targets: &[&'static str],
+    deps: &[&'static str],
+    files: &FilesSourceText,
+    sender_opt: Option<Address>,
+) -> Result<(typing::ast::Program, Errors), Errors> {
+    let mut source_definitions = Vec::new();
+    let mut lib_definitions = Vec::new();
+    let mut errors: Errors = Vec::new();
+    for fname in targets {
+        let (defs, _, mut es) = parse_source(fname, &files[fname]);
+        source_definitions.extend(defs);
+        errors.append(&mut es);
+    }
+    for fname in deps {
+        let (defs, _, mut es) = parse_source(fname, &files[fname]);
+        lib_definitions.extend(defs);
+        errors.append(&mut es);
+    }
+    check_errors(errors)?;
+
+    let pprog = parser::ast::Program {
+        source_definitions,
+        lib_definitions,
+    };
+    let (mut eprog, errors) = expansion::translate::program(pprog, sender_opt);
+    unit_test::filter_test_members::program(&mut eprog);
+    let (nprog, errors) = naming::translate::program(eprog, errors);
+    Ok(typing::translate::program(nprog, errors))
+}
+
+/// Runs the phases following typing, e.g. borrow checking, on a typed program without errors
+/// Returns the errors found, does not compile to Move bytecode
+pub fn move_check_typed_program(tprog: typing::ast::Program) -> Errors {
+    let (hprog, errors) = hlir::translate::program(tprog);
+    let (_, errors) = cfgir::translate::program(errors, hprog);
+    errors
+}
+
 //**************************************************************************************************
 // Utils
 //**************************************************************************************************
@@ -205,7 +268,15 @@ fn check_program(
     prog: Result<parser::ast::Program, Errors>,
     sender_opt: Option<Address>,
 ) -> Result<cfgir::ast::Program, Errors> {
-    let (eprog, errors) = expansion::translate::program(prog?, sender_opt);
+    let (mut eprog, errors) = expansion::translate::program(prog?, sender_opt);
+    unit_test::filter_test_members::program(&mut eprog);
+    check_expanded_program(eprog, errors)
+}
+
+fn check_expanded_program(
+    eprog: expansion::ast::Program,
+    errors: Errors,
+) -> Result<cfgir::ast::Program, Errors> {
     let (nprog, errors) = naming::translate::program(eprog, errors);
     let (tprog, errors) = typing::translate::program(nprog, errors);
     check_errors(errors)?;
@@ -223,6 +294,17 @@ fn compile_program(
     to_bytecode::translate::program(cprog)
 }
 
+fn compile_program_for_testing(
+    prog: Result<parser::ast::Program, Errors>,
+    sender_opt: Option<Address>,
+) -> Result<(Vec<CompiledUnit>, unit_test::TestPlan), Errors> {
+    let (eprog, errors) = expansion::translate::program(prog?, sender_opt);
+    let test_plan = unit_test::plan_builder::construct_test_plan(&eprog);
+    let cprog = check_expanded_program(eprog, errors)?;
+    let compiled_units = to_bytecode::translate::program(cprog)?;
+    Ok((compiled_units, test_plan))
+}
+
 //**************************************************************************************************
 // Parsing
 //**************************************************************************************************
@@ -320,16 +402,24 @@ fn parse_file(
     files: &mut FilesSourceText,
     fname: &'static str,
 ) -> anyhow::Result<(Vec<parser::ast::Definition>, MatchedFileCommentMap, Errors)> {
-    let mut errors: Errors = Vec::new();
     let mut f = File::open(fname)
         .map_err(|err| std::io::Error::new(err.kind(), format!("{}: {}", err, fname)))?;
     let mut source_buffer = String::new();
     f.read_to_string(&mut source_buffer)?;
-    let (no_comments_buffer, comment_map) = match strip_comments_and_verify(fname, &source_buffer) {
+    let (defs, comments, errors) = parse_source(fname, &source_buffer);
+    files.insert(fname, source_buffer);
+    Ok((defs, comments, errors))
+}
+
+fn parse_source(
+    fname: &'static str,
+    source_buffer: &str,
+) -> (Vec<parser::ast::Definition>, MatchedFileCommentMap, Errors) {
+    let mut errors: Errors = Vec::new();
+    let (no_comments_buffer, comment_map) = match strip_comments_and_verify(fname, source_buffer) {
         Err(errs) => {
             errors.extend(errs.into_iter());
-            files.insert(fname, source_buffer);
-            return Ok((vec![], MatchedFileCommentMap::new(), errors));
+            return (vec![], MatchedFileCommentMap::new(), errors);
         }
         Ok(result) => result,
     };
@@ -340,8 +430,7 @@ fn parse_file(
             (vec![], MatchedFileCommentMap::new())
         }
     };
-    files.insert(fname, source_buffer);
-    Ok((defs, comments, errors))
+    (defs, comments, errors)
 }
 
 //**************************************************************************************************
@@ -77,6 +77,29 @@ pub enum Use {
     Members(ModuleIdent, Vec<(Name, Option<Name>)>),
 }
 
+//**************************************************************************************************
+// Attributes
+//**************************************************************************************************
+
+#[derive(Debug, PartialEq)]
+pub enum AttributeValue_ {
+    Value(Value),
+    // <num>
+    InferredNum(u128),
+}
+pub type AttributeValue = Spanned<AttributeValue_>;
+
+#[derive(Debug, PartialEq)]
+pub enum Attribute_ {
+    // n
+    Name(Name),
+    // n = v
+    Assigned(Name, AttributeValue),
+    // n(a1, ..., an)
+    Parameterized(Name, Vec<Attribute>),
+}
+pub type Attribute = Spanned<Attribute_>;
+
 //**************************************************************************************************
 // Modules
 //**************************************************************************************************
@@ -94,6 +117,7 @@ pub struct ModuleIdent(pub Spanned<ModuleIdent_>);
 #[derive(Debug)]
 pub struct ModuleDefinition {
     pub loc: Loc,
+    pub attributes: Vec<Attribute>,
     pub name: ModuleName,
     pub members: Vec<ModuleMember>,
 }
@@ -158,12 +182,14 @@ pub enum FunctionBody_ {
 pub type FunctionBody = Spanned<FunctionBody_>;
 
 #[derive(PartialEq, Debug)]
+// #[a1, ..., an]?
 // (public?) foo<T1(: copyable?), ..., TN(: copyable?)>(x1: t1, ..., xn: tn): t1 * ... * tn {
 //    body
 //  }
 // (public?) native foo<T1(: copyable?), ..., TN(: copyable?)>(x1: t1, ..., xn: tn): t1 * ... * tn;
 pub struct Function {
     pub loc: Loc,
+    pub attributes: Vec<Attribute>,
     pub visibility: FunctionVisibility,
     pub signature: FunctionSignature,
     pub acquires: Vec<ModuleAccess>,
@@ -786,9 +812,11 @@ impl AstDebug for ModuleDefinition {
     fn ast_debug(&self, w: &mut AstWriter) {
         let ModuleDefinition {
             loc: _loc,
+            attributes,
             name,
             members,
         } = self;
+        attributes.ast_debug(w);
         w.write(&format!("module {}", name));
         w.block(|w| {
             for mem in members {
@@ -810,6 +838,43 @@ impl AstDebug for ModuleMember {
     }
 }
 
+impl AstDebug for Vec<Attribute> {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        if self.is_empty() {
+            return;
+        }
+        w.write("#[");
+        w.comma(self, |w, attr| attr.ast_debug(w));
+        w.writeln("]");
+    }
+}
+
+impl AstDebug for Attribute_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            Attribute_::Name(n) => w.write(&format!("{}", n)),
+            Attribute_::Assigned(n, v) => {
+                w.write(&format!("{} = ", n));
+                v.ast_debug(w)
+            }
+            Attribute_::Parameterized(n, attrs) => {
+                w.write(&format!("{}(", n));
+                w.comma(attrs, |w, attr| attr.ast_debug(w));
+                w.write(")")
+            }
+        }
+    }
+}
+
+impl AstDebug for AttributeValue_ {
+    fn ast_debug(&self, w: &mut AstWriter) {
+        match self {
+            AttributeValue_::Value(v) => v.ast_debug(w),
+            AttributeValue_::InferredNum(u) => w.write(&format!("{}", u)),
+        }
+    }
+}
+
 impl AstDebug for Use {
     fn ast_debug(&self, w: &mut AstWriter) {
         match self {
@@ -1045,12 +1110,14 @@ impl AstDebug for Function {
     fn ast_debug(&self, w: &mut AstWriter) {
         let Function {
             loc: _loc,
+            attributes,
             visibility,
             signature,
             acquires,
             name,
             body,
         } = self;
+        attributes.ast_debug(w);
         visibility.ast_debug(w);
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
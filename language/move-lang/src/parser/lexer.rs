@@ -18,6 +18,7 @@ pub enum Tok {
     IdentifierValue,
     Exclaim,
     ExclaimEqual,
+    NumSign,
     Percent,
     Amp,
     AmpAmp,
@@ -94,6 +95,7 @@ impl fmt::Display for Tok {
             IdentifierValue => "[Identifier]",
             Exclaim => "!",
             ExclaimEqual => "!=",
+            NumSign => "#",
             Percent => "%",
             Amp => "&",
             AmpAmp => "&&",
@@ -392,6 +394,7 @@ fn find_token(file: &'static str, text: &str, start_offset: usize) -> Result<(To
                 (Tok::Colon, 1)
             }
         }
+        '#' => (Tok::NumSign, 1),
         '%' => (Tok::Percent, 1),
         '(' => (Tok::LParen, 1),
         ')' => (Tok::RParen, 1),
@@ -1154,6 +1154,74 @@ fn make_builtin_call(loc: Loc, name: &str, type_args: Option<Vec<Type>>, args: V
     sp(loc, Exp_::Call(maccess, type_args, sp(loc, args)))
 }
 
+//**************************************************************************************************
+// Attributes
+//**************************************************************************************************
+
+// Parse an attribute value:
+//      AttributeValue = <Value> | <Num>
+fn parse_attribute_value<'input>(tokens: &mut Lexer<'input>) -> Result<AttributeValue, Error> {
+    let start_loc = tokens.start_loc();
+    let val = match tokens.peek() {
+        Tok::AddressValue
+        | Tok::True
+        | Tok::False
+        | Tok::U8Value
+        | Tok::U64Value
+        | Tok::U128Value
+        | Tok::ByteStringValue => AttributeValue_::Value(parse_value(tokens)?),
+        Tok::NumValue => AttributeValue_::InferredNum(parse_num(tokens)?),
+        _ => return Err(unexpected_token_error(tokens, "an attribute value")),
+    };
+    let end_loc = tokens.previous_end_loc();
+    Ok(spanned(tokens.file_name(), start_loc, end_loc, val))
+}
+
+// Parse an attribute:
+//      Attribute =
+//          <Identifier>
+//          | <Identifier> "=" <AttributeValue>
+//          | <Identifier> "(" Comma<Attribute> ")"
+fn parse_attribute<'input>(tokens: &mut Lexer<'input>) -> Result<Attribute, Error> {
+    let start_loc = tokens.start_loc();
+    let n = parse_identifier(tokens)?;
+    let attr = match tokens.peek() {
+        Tok::Equal => {
+            tokens.advance()?;
+            Attribute_::Assigned(n, parse_attribute_value(tokens)?)
+        }
+        Tok::LParen => {
+            let args = parse_comma_list(
+                tokens,
+                Tok::LParen,
+                Tok::RParen,
+                parse_attribute,
+                "an attribute",
+            )?;
+            Attribute_::Parameterized(n, args)
+        }
+        _ => Attribute_::Name(n),
+    };
+    let end_loc = tokens.previous_end_loc();
+    Ok(spanned(tokens.file_name(), start_loc, end_loc, attr))
+}
+
+// Parse the attributes of a module or of a function:
+//      Attributes = ("#" "[" Comma<Attribute> "]")*
+fn parse_attributes<'input>(tokens: &mut Lexer<'input>) -> Result<Vec<Attribute>, Error> {
+    let mut attributes = vec![];
+    while match_token(tokens, Tok::NumSign)? {
+        attributes.extend(parse_comma_list(
+            tokens,
+            Tok::LBracket,
+            Tok::RBracket,
+            parse_attribute,
+            "an attribute",
+        )?);
+    }
+    Ok(attributes)
+}
+
 //**************************************************************************************************
 // Types
 //**************************************************************************************************
@@ -1283,13 +1351,13 @@ fn parse_optional_type_parameters<'input>(
 //          <NativeFunctionDecl>
 //          | <MoveFunctionDecl>
 //      NativeFunctionDecl =
-//          <DocComments> "native" ( "public" )? "fun"
+//          <DocComments> <Attributes> "native" ( "public" )? "fun"
 //          <FunctionDefName> "(" Comma<Parameter> ")"
 //          (":" <Type>)?
 //          ("acquires" <ModuleAccess> ("," <ModuleAccess>)*)?
 //          ";"
 //      MoveFunctionDecl =
-//          <DocComments> ( "public" )? "fun"
+//          <DocComments> <Attributes> ( "public" )? "fun"
 //          <FunctionDefName> "(" Comma<Parameter> ")"
 //          (":" <Type>)?
 //          ("acquires" <ModuleAccess> ("," <ModuleAccess>)*)?
@@ -1305,6 +1373,7 @@ fn parse_function_decl<'input>(
 ) -> Result<Function, Error> {
     tokens.match_doc_comments();
     let start_loc = tokens.start_loc();
+    let attributes = parse_attributes(tokens)?;
     // Record the source location of the "native" keyword (if there is one).
     let native_opt = if allow_native {
         consume_optional_token_with_loc(tokens, Tok::Native)?
@@ -1392,6 +1461,7 @@ fn parse_function_decl<'input>(
     let loc = make_loc(tokens.file_name(), start_loc, tokens.previous_end_loc());
     Ok(Function {
         loc,
+        attributes,
         visibility,
         signature,
         acquires,
@@ -1608,13 +1678,14 @@ fn is_struct_definition<'input>(tokens: &mut Lexer<'input>) -> Result<bool, Erro
 
 // Parse a module:
 //      Module =
-//          <DocComments> "module" <ModuleName> "{"
+//          <DocComments> <Attributes> "module" <ModuleName> "{"
 //              <UseDecl>*
 //              ( <ConstantDecl> | <StructDefinition> | <FunctionDecl> | <Spec> )*
 //          "}"
 fn parse_module<'input>(tokens: &mut Lexer<'input>) -> Result<ModuleDefinition, Error> {
     tokens.match_doc_comments();
     let start_loc = tokens.start_loc();
+    let attributes = parse_attributes(tokens)?;
 
     consume_token(tokens, Tok::Module)?;
     let name = parse_module_name(tokens)?;
@@ -1636,7 +1707,12 @@ fn parse_module<'input>(tokens: &mut Lexer<'input>) -> Result<ModuleDefinition,
     consume_token(tokens, Tok::RBrace)?;
 
     let loc = make_loc(tokens.file_name(), start_loc, tokens.previous_end_loc());
-    Ok(ModuleDefinition { loc, name, members })
+    Ok(ModuleDefinition {
+        loc,
+        attributes,
+        name,
+        members,
+    })
 }
 
 //**************************************************************************************************
@@ -2256,7 +2332,7 @@ fn parse_file<'input>(tokens: &mut Lexer<'input>) -> Result<Vec<Definition>, Err
     let mut defs = vec![];
     while tokens.peek() != Tok::EOF {
         defs.push(match tokens.peek() {
-            Tok::Module => Definition::Module(parse_module(tokens)?),
+            Tok::Module | Tok::NumSign => Definition::Module(parse_module(tokens)?),
             Tok::Script => Definition::Script(parse_script(tokens)?),
             _ => {
                 let (loc, addr, modules) = parse_address_block(tokens)?;
@@ -0,0 +1,34 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::expansion::ast as E;
+
+/// Removes the modules and functions annotated with `#[test]` or `#[test_only]`, which are only
+/// compiled for unit tests
+pub fn program(prog: &mut E::Program) {
+    let test_only_modules = prog
+        .modules
+        .iter()
+        .filter(|(_, mdef)| is_test_only(&mdef.attributes))
+        .map(|(mident, _)| mident)
+        .collect::<Vec<_>>();
+    for mident in test_only_modules {
+        prog.modules.remove(&mident);
+    }
+
+    for (_, mdef) in prog.modules.iter_mut() {
+        let test_only_functions = mdef
+            .functions
+            .iter()
+            .filter(|(_, fdef)| is_test_only(&fdef.attributes))
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        for name in test_only_functions {
+            mdef.functions.remove(&name);
+        }
+    }
+}
+
+fn is_test_only(attributes: &[E::Attribute]) -> bool {
+    attributes.iter().any(|attr| attr.value.is_test_only())
+}
@@ -0,0 +1,32 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+pub(crate) mod filter_test_members;
+pub(crate) mod plan_builder;
+
+use crate::parser::ast::ModuleIdent;
+use move_ir_types::location::Loc;
+use std::collections::BTreeMap;
+
+/// The `#[test]` functions of the source modules of a program, grouped by module
+pub type TestPlan = Vec<ModuleTestPlan>;
+
+#[derive(Debug)]
+pub struct ModuleTestPlan {
+    pub module: ModuleIdent,
+    pub tests: BTreeMap<String, TestCase>,
+}
+
+#[derive(Debug)]
+pub struct TestCase {
+    pub loc: Loc,
+    pub expected_failure: Option<ExpectedFailure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedFailure {
+    // #[expected_failure], met by any abort or arithmetic error
+    Any,
+    // #[expected_failure(abort_code = <u64>)]
+    AbortCode(u64),
+}
@@ -0,0 +1,55 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    expansion::ast as E,
+    shared::Identifier,
+    unit_test::{ExpectedFailure, ModuleTestPlan, TestCase, TestPlan},
+};
+use std::collections::BTreeMap;
+
+/// Collects the `#[test]` functions of the source modules, along with their expected failure
+pub fn construct_test_plan(prog: &E::Program) -> TestPlan {
+    prog.modules
+        .iter()
+        .filter(|(_, mdef)| mdef.is_source_module)
+        .filter_map(|(mident, mdef)| {
+            let tests = mdef
+                .functions
+                .iter()
+                .filter_map(|(name, fdef)| Some((name.value().to_owned(), test_case(fdef)?)))
+                .collect::<BTreeMap<_, _>>();
+            if tests.is_empty() {
+                None
+            } else {
+                Some(ModuleTestPlan {
+                    module: mident,
+                    tests,
+                })
+            }
+        })
+        .collect()
+}
+
+fn test_case(fdef: &E::Function) -> Option<TestCase> {
+    use E::Attribute_ as EA;
+    let mut is_test = false;
+    let mut expected_failure = None;
+    for attr in &fdef.attributes {
+        match &attr.value {
+            EA::Test => is_test = true,
+            EA::TestOnly => (),
+            EA::ExpectedFailure(None) => expected_failure = Some(ExpectedFailure::Any),
+            EA::ExpectedFailure(Some(code)) => {
+                expected_failure = Some(ExpectedFailure::AbortCode(*code))
+            }
+        }
+    }
+    if !is_test {
+        return None;
+    }
+    Some(TestCase {
+        loc: fdef.loc,
+        expected_failure,
+    })
+}
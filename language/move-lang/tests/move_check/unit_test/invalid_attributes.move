@@ -0,0 +1,11 @@
+#[test]
+module M {
+    #[test, test_only]
+    fun a() {}
+    #[expected_failure]
+    fun b() {}
+    #[test, expected_failure(abort_code = true)]
+    fun c(x: u64) {}
+    #[tset]
+    fun d() {}
+}
@@ -0,0 +1,32 @@
+address 0x2 {
+#[test_only]
+module Helpers {
+    public fun expected_sum(): u64 {
+        3
+    }
+}
+
+module M {
+    use 0x2::Helpers;
+
+    public fun sum(a: u64, b: u64): u64 {
+        a + b
+    }
+
+    #[test_only]
+    fun check_sum(a: u64, b: u64) {
+        assert(sum(a, b) == Helpers::expected_sum(), 0)
+    }
+
+    #[test]
+    fun sum_is_correct() {
+        check_sum(1, 2)
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 0)]
+    fun sum_is_wrong() {
+        check_sum(1, 1)
+    }
+}
+}
@@ -183,6 +183,7 @@ fn run_spec_checker(
                     function_infos.add(function_name, function_info).unwrap();
                     let expanded_module = ModuleDefinition {
                         loc,
+                        attributes: vec![],
                         is_source_module: true,
                         structs: UniqueMap::new(),
                         constants,
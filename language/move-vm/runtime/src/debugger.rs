@@ -0,0 +1,180 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for debuggers of Move code.
+//!
+//! A debugger installs a `DebugHook` on a session with `Session::set_debug_hook`. The hook is
+//! called before each instruction of the executions of the session, and the execution is
+//! suspended until the hook returns. The hook can inspect the state of the execution through the
+//! `DebugState` it is given: the call stack, the locals of each frame and the resources of global
+//! storage accessed so far by the instructions of the execution. Values are given serialized with
+//! LCS along with their type, so that they can be annotated with the field names of their
+//! structs, e.g. with `resource-viewer`.
+//!
+//! When no hook is installed, checking for one costs a single branch per instruction.
+
+use crate::{
+    interpreter::{Frame, Interpreter},
+    loader::Loader,
+    tracing::TracedFunction,
+};
+use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+use move_vm_types::{
+    data_store::DataStore,
+    loaded_data::runtime_types::Type,
+    values::{Reference, Value},
+};
+use vm::{errors::PartialVMResult, file_format::FunctionDefinitionIndex};
+
+/// A debugger of the execution of Move code.
+pub trait DebugHook: Send + Sync {
+    /// Called before the execution of each instruction. The execution resumes when the call
+    /// returns.
+    fn on_instruction(&self, state: &mut DebugState);
+}
+
+/// A frame of the call stack of a suspended execution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebugFrame {
+    pub function: TracedFunction,
+    /// The index of the function in its module, 0 for scripts. Together with `pc`, it locates
+    /// the source code executed by the frame in the source map of the module or script.
+    pub function_index: FunctionDefinitionIndex,
+    /// The offset of the instruction about to be executed for the innermost frame, and of the
+    /// call in progress for the other frames.
+    pub pc: u16,
+}
+
+/// A value of a suspended execution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebugValue {
+    /// The type of the value, or of the referenced value for references.
+    pub type_: TypeTag,
+    pub is_reference: bool,
+    /// The value, or the referenced value for references, serialized with LCS.
+    pub blob: Vec<u8>,
+}
+
+/// A resource of global storage accessed by a suspended execution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GlobalResource {
+    pub address: AccountAddress,
+    pub type_: TypeTag,
+    /// The current value of the resource, `None` if it does not exist.
+    pub value: Option<DebugValue>,
+}
+
+/// The state of an execution suspended by a `DebugHook`.
+pub struct DebugState<'a> {
+    interpreter: &'a Interpreter,
+    current_frame: &'a Frame,
+    loader: &'a Loader,
+    data_store: &'a mut dyn DataStore,
+}
+
+impl<'a> DebugState<'a> {
+    pub(crate) fn new(
+        interpreter: &'a Interpreter,
+        current_frame: &'a Frame,
+        loader: &'a Loader,
+        data_store: &'a mut dyn DataStore,
+    ) -> Self {
+        Self {
+            interpreter,
+            current_frame,
+            loader,
+            data_store,
+        }
+    }
+
+    /// The call stack, from the outermost frame to the frame about to execute an instruction.
+    pub fn call_stack(&self) -> Vec<DebugFrame> {
+        let callers = self.interpreter.call_stack_frames();
+        let mut call_stack: Vec<_> = callers
+            .iter()
+            .map(|frame| DebugFrame {
+                function: TracedFunction::new(frame.function()),
+                function_index: frame.function().index(),
+                // The program counter of a caller is already past the call.
+                pc: frame.pc().saturating_sub(1),
+            })
+            .collect();
+        call_stack.push(DebugFrame {
+            function: TracedFunction::new(self.current_frame.function()),
+            function_index: self.current_frame.function().index(),
+            pc: self.current_frame.pc(),
+        });
+        call_stack
+    }
+
+    /// The locals of the frame at position `depth` in `call_stack`, parameters first. A local is
+    /// `None` if it holds no value, e.g. because it was moved, or if it cannot be inspected.
+    pub fn locals(&self, depth: usize) -> Vec<Option<DebugValue>> {
+        let callers = self.interpreter.call_stack_frames();
+        let frame = match depth {
+            depth if depth < callers.len() => &callers[depth],
+            depth if depth == callers.len() => self.current_frame,
+            _ => return vec![],
+        };
+        let local_types = match self.loader.local_types(frame.function(), frame.ty_args()) {
+            Ok(local_types) => local_types,
+            Err(_) => return vec![],
+        };
+        local_types
+            .iter()
+            .enumerate()
+            .map(|(idx, ty)| {
+                let value = frame.locals().copy_loc(idx).ok()?;
+                self.debug_value(value, ty).ok()?
+            })
+            .collect()
+    }
+
+    /// The resources of global storage accessed by the execution so far, with their current
+    /// value. Only the accesses of the `exists`, `borrow_global`, `move_from` and `move_to`
+    /// instructions are recorded, which are the only ones to access resources: natives don't.
+    /// Resources changed by earlier executions of the session are not included, unless the
+    /// execution accessed them as well.
+    pub fn global_resources(&mut self) -> Vec<GlobalResource> {
+        let mut resources = vec![];
+        for (address, ty) in self.interpreter.accessed_resources() {
+            let type_ = match self.loader.type_to_type_tag(ty) {
+                Ok(type_) => type_,
+                Err(_) => continue,
+            };
+            let value = match self.data_store.load_resource(*address, ty) {
+                Ok(global_value) => match global_value.exists() {
+                    Ok(true) => global_value.borrow_global().ok(),
+                    _ => None,
+                },
+                Err(_) => continue,
+            };
+            let value = value.and_then(|value| {
+                let ty = Type::Reference(Box::new(ty.clone()));
+                self.debug_value(value, &ty).ok().flatten()
+            });
+            resources.push(GlobalResource {
+                address: *address,
+                type_,
+                value,
+            });
+        }
+        resources
+    }
+
+    fn debug_value(&self, value: Value, ty: &Type) -> PartialVMResult<Option<DebugValue>> {
+        let (value, ty, is_reference) = match ty {
+            Type::Reference(ty) | Type::MutableReference(ty) => {
+                (value.value_as::<Reference>()?.read_ref()?, &**ty, true)
+            }
+            ty => (value, ty, false),
+        };
+        let type_ = self.loader.type_to_type_tag(ty)?;
+        let layout = self.loader.type_to_type_layout(ty)?;
+        Ok(value.simple_serialize(&layout).map(|blob| DebugValue {
+            type_,
+            is_reference,
+            blob,
+        }))
+    }
+}
@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    debugger::{DebugHook, DebugState},
     loader::{Function, Loader, Resolver},
     native_functions::FunctionContext,
     trace,
+    tracing::{self, GlobalAccessKind, TraceEvent, TraceSink, TracedFunction},
 };
 use libra_logger::prelude::*;
 use move_core_types::{
@@ -54,6 +56,13 @@ pub(crate) struct Interpreter {
     operand_stack: Stack,
     /// The stack of active functions.
     call_stack: CallStack,
+    /// Where the execution is traced to, if tracing is enabled.
+    trace_sink: Option<Arc<dyn TraceSink>>,
+    /// The debugger of the execution, if any.
+    debug_hook: Option<Arc<dyn DebugHook>>,
+    /// The resources of global storage accessed so far by the instructions of the execution,
+    /// only recorded for debuggers.
+    accessed_resources: Vec<(AccountAddress, Type)>,
 }
 
 impl Interpreter {
@@ -66,19 +75,32 @@ impl Interpreter {
         data_store: &mut impl DataStore,
         cost_strategy: &mut CostStrategy,
         loader: &Loader,
+        trace_sink: Option<Arc<dyn TraceSink>>,
+        debug_hook: Option<Arc<dyn DebugHook>>,
     ) -> VMResult<()> {
         // We count the intrinsic cost of the transaction here, since that needs to also cover the
         // setup of the function.
-        let mut interp = Self::new();
-        interp.execute(loader, data_store, cost_strategy, function, ty_args, args)
+        let mut interp = Self::new(trace_sink, debug_hook);
+        let profiled_depth = cost_strategy
+            .gas_profiler()
+            .map(|profiler| profiler.stack_depth());
+        let result = interp.execute(loader, data_store, cost_strategy, function, ty_args, args);
+        // Errors stop the execution without returning from the functions being executed.
+        if let (Some(depth), Some(profiler)) = (profiled_depth, cost_strategy.gas_profiler()) {
+            profiler.unwind(depth);
+        }
+        result
     }
 
     /// Create a new instance of an `Interpreter` in the context of a transaction with a
     /// given module cache and gas schedule.
-    fn new() -> Self {
+    fn new(trace_sink: Option<Arc<dyn TraceSink>>, debug_hook: Option<Arc<dyn DebugHook>>) -> Self {
         Interpreter {
             operand_stack: Stack::new(),
             call_stack: CallStack::new(),
+            trace_sink,
+            debug_hook,
+            accessed_resources: vec![],
         }
     }
 
@@ -122,6 +144,10 @@ impl Interpreter {
                 .map_err(|e| self.set_location(e))?;
         }
 
+        if let Some(profiler) = cost_strategy.gas_profiler() {
+            profiler.enter_function(function.pretty_string());
+        }
+        self.trace_function_enter(loader, &function, &ty_args);
         let mut current_frame = Frame::new(function, ty_args, locals);
         loop {
             macro_rules! set_err_info {
@@ -141,6 +167,12 @@ impl Interpreter {
                         .locals
                         .check_resources_for_return()
                         .map_err(|e| set_err_info!(current_frame, e))?;
+                    if let Some(profiler) = cost_strategy.gas_profiler() {
+                        profiler.exit_function();
+                    }
+                    self.trace_event(|| TraceEvent::FunctionExit {
+                        function: TracedFunction::new(&current_frame.function),
+                    });
                     if let Some(frame) = self.call_stack.pop() {
                         current_frame = frame;
                     } else {
@@ -159,6 +191,10 @@ impl Interpreter {
                         self.call_native(&resolver, data_store, cost_strategy, func, vec![])?;
                         continue;
                     }
+                    if let Some(profiler) = cost_strategy.gas_profiler() {
+                        profiler.enter_function(func.pretty_string());
+                    }
+                    self.trace_function_enter(loader, &func, &[]);
                     let frame = self
                         .make_call_frame(func, vec![])
                         .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
@@ -187,6 +223,10 @@ impl Interpreter {
                         self.call_native(&resolver, data_store, cost_strategy, func, ty_args)?;
                         continue;
                     }
+                    if let Some(profiler) = cost_strategy.gas_profiler() {
+                        profiler.enter_function(func.pretty_string());
+                    }
+                    self.trace_function_enter(loader, &func, &ty_args);
                     let frame = self
                         .make_call_frame(func, ty_args)
                         .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
@@ -262,10 +302,26 @@ impl Interpreter {
         for _ in 0..expected_args {
             arguments.push_front(self.operand_stack.pop()?);
         }
+        if let Some(profiler) = cost_strategy.gas_profiler() {
+            profiler.enter_native(function.pretty_string());
+        }
+        self.trace_event(|| TraceEvent::NativeCall {
+            function: TracedFunction::new(&function),
+            ty_args: tracing::type_tags(resolver.loader(), &ty_args),
+        });
         let mut native_context = FunctionContext::new(self, data_store, cost_strategy, resolver);
         let native_function = function.get_native()?;
         let result = native_function.dispatch(&mut native_context, ty_args, arguments)?;
         cost_strategy.deduct_gas(result.cost)?;
+        if let Some(profiler) = cost_strategy.gas_profiler() {
+            profiler.exit_function();
+        }
+        if let Err(code) = result.result {
+            self.trace_event(|| TraceEvent::Abort {
+                function: TracedFunction::new(&function),
+                code,
+            });
+        }
         let values = result
             .result
             .map_err(|code| PartialVMError::new(StatusCode::ABORTED).with_sub_status(code))?;
@@ -383,6 +439,68 @@ impl Interpreter {
         Ok(size)
     }
 
+    //
+    // Tracing helpers.
+    //
+
+    /// Record an event in the trace, if tracing is enabled.
+    pub(crate) fn trace_event(&self, event: impl FnOnce() -> TraceEvent) {
+        if let Some(sink) = &self.trace_sink {
+            sink.record(&event());
+        }
+    }
+
+    fn trace_function_enter(&self, loader: &Loader, function: &Function, ty_args: &[Type]) {
+        self.trace_event(|| TraceEvent::FunctionEnter {
+            function: TracedFunction::new(function),
+            ty_args: tracing::type_tags(loader, ty_args),
+        });
+    }
+
+    /// Trace an access to global storage, and record it for debuggers.
+    fn trace_global_access(
+        &mut self,
+        loader: &Loader,
+        kind: GlobalAccessKind,
+        address: AccountAddress,
+        ty: &Type,
+    ) {
+        if let Some(sink) = &self.trace_sink {
+            if let Ok(resource) = loader.type_to_type_tag(ty) {
+                sink.record(&TraceEvent::GlobalAccess {
+                    kind,
+                    address,
+                    resource,
+                });
+            }
+        }
+        if self.debug_hook.is_some()
+            && !self
+                .accessed_resources
+                .iter()
+                .any(|(accessed_address, accessed_ty)| {
+                    *accessed_address == address && accessed_ty == ty
+                })
+        {
+            self.accessed_resources.push((address, ty.clone()));
+        }
+    }
+
+    /// Suspend the execution in the debugger, if any, before `frame` executes an instruction.
+    fn debug_step(&self, frame: &Frame, loader: &Loader, data_store: &mut dyn DataStore) {
+        if let Some(hook) = &self.debug_hook {
+            hook.on_instruction(&mut DebugState::new(self, frame, loader, data_store));
+        }
+    }
+
+    pub(crate) fn call_stack_frames(&self) -> &[Frame] {
+        &self.call_stack.0
+    }
+
+    pub(crate) fn accessed_resources(&self) -> &[(AccountAddress, Type)] {
+        &self.accessed_resources
+    }
+
     //
     // Debugging and logging helpers.
     //
@@ -632,7 +750,7 @@ impl CallStack {
 /// A `Frame` is the execution context for a function. It holds the locals of the function and
 /// the function itself.
 #[derive(Debug)]
-struct Frame {
+pub(crate) struct Frame {
     pc: u16,
     locals: Locals,
     function: Arc<Function>,
@@ -693,6 +811,7 @@ impl Frame {
                     &resolver,
                     &interpreter
                 );
+                interpreter.debug_step(self, resolver.loader(), data_store);
                 self.pc += 1;
 
                 match instruction {
@@ -984,6 +1103,10 @@ impl Frame {
                     Bytecode::Abort => {
                         cost_strategy.charge_instr(Opcodes::ABORT)?;
                         let error_code = interpreter.operand_stack.pop_as::<u64>()?;
+                        interpreter.trace_event(|| TraceEvent::Abort {
+                            function: TracedFunction::new(&self.function),
+                            code: error_code,
+                        });
                         return Err(PartialVMError::new(StatusCode::ABORTED)
                             .with_sub_status(error_code)
                             .with_message(format!(
@@ -1013,6 +1136,12 @@ impl Frame {
                     Bytecode::MutBorrowGlobal(sd_idx) | Bytecode::ImmBorrowGlobal(sd_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::BorrowGlobal,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.borrow_global(data_store, addr, &ty)?;
                         cost_strategy.charge_instr_with_size(Opcodes::MUT_BORROW_GLOBAL, size)?;
                     }
@@ -1020,6 +1149,12 @@ impl Frame {
                     | Bytecode::ImmBorrowGlobalGeneric(si_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::BorrowGlobal,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.borrow_global(data_store, addr, &ty)?;
                         cost_strategy
                             .charge_instr_with_size(Opcodes::MUT_BORROW_GLOBAL_GENERIC, size)?;
@@ -1027,18 +1162,36 @@ impl Frame {
                     Bytecode::Exists(sd_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::Exists,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.exists(data_store, addr, &ty)?;
                         cost_strategy.charge_instr_with_size(Opcodes::EXISTS, size)?;
                     }
                     Bytecode::ExistsGeneric(si_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::Exists,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.exists(data_store, addr, &ty)?;
                         cost_strategy.charge_instr_with_size(Opcodes::EXISTS_GENERIC, size)?;
                     }
                     Bytecode::MoveFrom(sd_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::MoveFrom,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.move_from(data_store, addr, &ty)?;
                         // TODO: Have this calculate before pulling in the data based upon
                         // the size of the data that we are about to read in.
@@ -1047,6 +1200,12 @@ impl Frame {
                     Bytecode::MoveFromGeneric(si_idx) => {
                         let addr = interpreter.operand_stack.pop_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::MoveFrom,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.move_from(data_store, addr, &ty)?;
                         // TODO: Have this calculate before pulling in the data based upon
                         // the size of the data that we are about to read in.
@@ -1062,6 +1221,12 @@ impl Frame {
                             .value_as::<AccountAddress>()?;
                         let ty = resolver.get_struct_type(*sd_idx);
                         // REVIEW: Can we simplify Interpreter::move_to?
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::MoveTo,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.move_to(data_store, addr, &ty, resource)?;
                         cost_strategy.charge_instr_with_size(Opcodes::MOVE_TO, size)?;
                     }
@@ -1074,6 +1239,12 @@ impl Frame {
                             .read_ref()?
                             .value_as::<AccountAddress>()?;
                         let ty = resolver.instantiate_generic_type(*si_idx, self.ty_args())?;
+                        interpreter.trace_global_access(
+                            resolver.loader(),
+                            GlobalAccessKind::MoveTo,
+                            addr,
+                            &ty,
+                        );
                         let size = interpreter.move_to(data_store, addr, &ty, resource)?;
                         cost_strategy.charge_instr_with_size(Opcodes::MOVE_TO_GENERIC, size)?;
                     }
@@ -1107,10 +1278,22 @@ impl Frame {
         }
     }
 
-    fn ty_args(&self) -> &[Type] {
+    pub(crate) fn ty_args(&self) -> &[Type] {
         &self.ty_args
     }
 
+    pub(crate) fn function(&self) -> &Function {
+        &self.function
+    }
+
+    pub(crate) fn pc(&self) -> u16 {
+        self.pc
+    }
+
+    pub(crate) fn locals(&self) -> &Locals {
+        &self.locals
+    }
+
     fn resolver<'a>(&self, loader: &'a Loader) -> Resolver<'a> {
         self.function.get_resolver(loader)
     }
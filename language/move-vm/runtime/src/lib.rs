@@ -13,6 +13,7 @@
 extern crate mirai_annotations;
 
 pub mod data_cache;
+pub mod debugger;
 mod interpreter;
 mod loader;
 pub mod move_vm;
@@ -20,7 +21,7 @@ mod native_functions;
 mod runtime;
 pub mod session;
 #[macro_use]
-mod tracing;
+pub mod tracing;
 
 // Only include debugging functionality in debug builds
 #[cfg(debug_assertions)]
@@ -1930,4 +1930,28 @@ impl Loader {
     pub(crate) fn type_to_kind_info(&self, ty: &Type) -> PartialVMResult<MoveKindInfo> {
         self.type_to_kind_info_impl(ty, 1)
     }
+
+    /// The types of the locals of `function`, parameters included, instantiated with `ty_args`.
+    pub(crate) fn local_types(
+        &self,
+        function: &Function,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Vec<Type>> {
+        let make_types = |module: &CompiledModule| {
+            let module_cache = self.module_cache.lock().unwrap();
+            function
+                .locals
+                .0
+                .iter()
+                .map(|tok| module_cache.make_type(module, tok)?.subst(ty_args))
+                .collect()
+        };
+        match &function.scope {
+            Scope::Module(module_id) => make_types(&self.get_module(module_id).module),
+            Scope::Script(script_hash) => {
+                let (_, module) = self.get_script(script_hash).script.clone().into_module();
+                make_types(&module)
+            }
+        }
+    }
 }
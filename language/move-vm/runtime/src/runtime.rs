@@ -3,9 +3,11 @@
 
 use crate::{
     data_cache::{RemoteCache, TransactionDataCache},
+    debugger::DebugHook,
     interpreter::Interpreter,
     loader::Loader,
     session::Session,
+    tracing::{self, TraceSink},
 };
 use libra_logger::prelude::*;
 use move_core_types::{
@@ -15,6 +17,7 @@ use move_core_types::{
     vm_status::StatusCode,
 };
 use move_vm_types::{data_store::DataStore, gas_schedule::CostStrategy, values::Value};
+use std::sync::Arc;
 use vm::{
     access::ModuleAccess,
     errors::{verification_error, Location, PartialVMError, PartialVMResult, VMResult},
@@ -38,6 +41,8 @@ impl VMRuntime {
         Session {
             runtime: self,
             data_cache: TransactionDataCache::new(remote, &self.loader),
+            trace_sink: tracing::env_trace_sink(),
+            debug_hook: None,
         }
     }
 
@@ -94,6 +99,8 @@ impl VMRuntime {
         senders: Vec<AccountAddress>,
         data_store: &mut impl DataStore,
         cost_strategy: &mut CostStrategy,
+        trace_sink: Option<Arc<dyn TraceSink>>,
+        debug_hook: Option<Arc<dyn DebugHook>>,
     ) -> VMResult<()> {
         // signer helper closure
         fn is_signer_reference(s: &SignatureToken) -> bool {
@@ -133,6 +140,8 @@ impl VMRuntime {
             data_store,
             cost_strategy,
             &self.loader,
+            trace_sink,
+            debug_hook,
         )
     }
 
@@ -144,6 +153,8 @@ impl VMRuntime {
         args: Vec<Value>,
         data_store: &mut impl DataStore,
         cost_strategy: &mut CostStrategy,
+        trace_sink: Option<Arc<dyn TraceSink>>,
+        debug_hook: Option<Arc<dyn DebugHook>>,
     ) -> VMResult<()> {
         // load the function in the given module, perform verification of the module and
         // its dependencies if the module was not loaded
@@ -162,6 +173,8 @@ impl VMRuntime {
             data_store,
             cost_strategy,
             &self.loader,
+            trace_sink,
+            debug_hook,
         )
     }
 }
@@ -3,7 +3,9 @@
 
 use crate::{
     data_cache::{RemoteCache, TransactionDataCache, TransactionEffects},
+    debugger::DebugHook,
     runtime::VMRuntime,
+    tracing::TraceSink,
 };
 use move_core_types::{
     account_address::AccountAddress,
@@ -12,11 +14,14 @@ use move_core_types::{
     vm_status::VMStatus,
 };
 use move_vm_types::{gas_schedule::CostStrategy, values::Value};
+use std::sync::Arc;
 use vm::errors::*;
 
 pub struct Session<'r, 'l, R> {
     pub(crate) runtime: &'l VMRuntime,
     pub(crate) data_cache: TransactionDataCache<'r, 'l, R>,
+    pub(crate) trace_sink: Option<Arc<dyn TraceSink>>,
+    pub(crate) debug_hook: Option<Arc<dyn DebugHook>>,
 }
 
 impl<'r, 'l, R: RemoteCache> Session<'r, 'l, R> {
@@ -38,10 +43,34 @@ impl<'r, 'l, R: RemoteCache> Session<'r, 'l, R> {
                 args,
                 &mut self.data_cache,
                 cost_strategy,
+                self.trace_sink.clone(),
+                self.debug_hook.clone(),
             )
             .map_err(|e| error_specializer(e.into_vm_status()))
     }
 
+    /// Executes a function like `execute_function`, but returns the `VMError` of a failure as is,
+    /// along with the code offsets it happened at, for tools reporting where execution failed.
+    pub fn execute_function_with_vm_error(
+        &mut self,
+        module: &ModuleId,
+        function_name: &IdentStr,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Value>,
+        cost_strategy: &mut CostStrategy,
+    ) -> VMResult<()> {
+        self.runtime.execute_function(
+            module,
+            function_name,
+            ty_args,
+            args,
+            &mut self.data_cache,
+            cost_strategy,
+            self.trace_sink.clone(),
+            self.debug_hook.clone(),
+        )
+    }
+
     pub fn execute_script(
         &mut self,
         script: Vec<u8>,
@@ -57,6 +86,8 @@ impl<'r, 'l, R: RemoteCache> Session<'r, 'l, R> {
             senders,
             &mut self.data_cache,
             cost_strategy,
+            self.trace_sink.clone(),
+            self.debug_hook.clone(),
         )
     }
 
@@ -70,6 +101,27 @@ impl<'r, 'l, R: RemoteCache> Session<'r, 'l, R> {
             .publish_module(module, sender, &mut self.data_cache, cost_strategy)
     }
 
+    /// Trace the executions of this session to `sink`, instead of the default sink set with the
+    /// `MOVE_VM_TRACE` environment variable, if any.
+    pub fn set_trace_sink(&mut self, sink: Arc<dyn TraceSink>) {
+        self.trace_sink = Some(sink);
+    }
+
+    /// Disable tracing for the executions of this session.
+    pub fn clear_trace_sink(&mut self) {
+        self.trace_sink = None;
+    }
+
+    /// Debug the executions of this session with `hook`.
+    pub fn set_debug_hook(&mut self, hook: Arc<dyn DebugHook>) {
+        self.debug_hook = Some(hook);
+    }
+
+    /// Stop debugging the executions of this session.
+    pub fn clear_debug_hook(&mut self) {
+        self.debug_hook = None;
+    }
+
     pub fn num_mutated_accounts(&self) -> u64 {
         self.data_cache.num_mutated_accounts()
     }
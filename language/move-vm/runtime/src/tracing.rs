@@ -1,93 +1,268 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+//! Tracing of the execution of Move code.
+//!
+//! Tracing is disabled by default. It is enabled for the executions of a session by installing a
+//! `TraceSink` with `Session::set_trace_sink`. Setting the `MOVE_VM_TRACE` environment variable
+//! to the path of a file instead traces every session to that file by default (see
+//! `FileTraceSink`). When tracing is disabled, recording an event costs a single branch.
+//!
+//! In debug builds, setting the `MOVE_VM_STEP` environment variable also allows stepping through
+//! the execution of the bytecode instructions.
+
 #[cfg(debug_assertions)]
 use crate::debug::DebugContext;
-use crate::{
-    interpreter::Interpreter,
-    loader::{Function, Loader},
+use crate::loader::{Function, Loader};
+#[cfg(debug_assertions)]
+use crate::{interpreter::Interpreter, loader::Resolver};
+use libra_logger::prelude::*;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, TypeTag},
 };
+use move_vm_types::loaded_data::runtime_types::Type;
 #[cfg(debug_assertions)]
 use move_vm_types::values::Locals;
-#[cfg(debug_assertions)]
 use once_cell::sync::Lazy;
-#[cfg(debug_assertions)]
+use serde::{Deserialize, Serialize};
 use std::{
-    env,
+    env, fmt,
     fs::{File, OpenOptions},
-    io::Write,
-    sync::Mutex,
+    io::{self, BufReader, Read, Write},
+    path::Path,
+    sync::{Arc, Mutex},
 };
 #[cfg(debug_assertions)]
 use vm::file_format::Bytecode;
 
-#[cfg(debug_assertions)]
 const MOVE_VM_TRACING_ENV_VAR_NAME: &str = "MOVE_VM_TRACE";
 
 #[cfg(debug_assertions)]
 const MOVE_VM_STEPPING_ENV_VAR_NAME: &str = "MOVE_VM_STEP";
 
-#[cfg(debug_assertions)]
-static FILE_PATH: Lazy<String> = Lazy::new(|| {
-    env::var(MOVE_VM_TRACING_ENV_VAR_NAME).unwrap_or_else(|_| "move_vm_trace.trace".to_string())
+static ENV_TRACE_SINK: Lazy<Option<Arc<dyn TraceSink>>> = Lazy::new(|| {
+    env::var(MOVE_VM_TRACING_ENV_VAR_NAME)
+        .ok()
+        .and_then(|path| match FileTraceSink::new(&path) {
+            Ok(sink) => Some(Arc::new(sink) as Arc<dyn TraceSink>),
+            Err(err) => {
+                error!("[VM] failed to open trace file {}: {}", path, err);
+                None
+            }
+        })
 });
 
-#[cfg(debug_assertions)]
-static TRACING_ENABLED: Lazy<bool> = Lazy::new(|| env::var(MOVE_VM_TRACING_ENV_VAR_NAME).is_ok());
-
 #[cfg(debug_assertions)]
 static DEBUGGING_ENABLED: Lazy<bool> =
     Lazy::new(|| env::var(MOVE_VM_STEPPING_ENV_VAR_NAME).is_ok());
 
-#[cfg(debug_assertions)]
-static LOGGING_FILE: Lazy<Mutex<File>> = Lazy::new(|| {
-    Mutex::new(
-        OpenOptions::new()
-            .write(true)
-            .create(true)
-            .append(true)
-            .open(&*FILE_PATH)
-            .unwrap(),
-    )
-});
-
 #[cfg(debug_assertions)]
 static DEBUG_CONTEXT: Lazy<Mutex<DebugContext>> = Lazy::new(|| Mutex::new(DebugContext::new()));
 
+/// A destination for the events traced during the execution of Move code.
+pub trait TraceSink: Send + Sync {
+    fn record(&self, event: &TraceEvent);
+}
+
+/// A function, identified by its module and its name. Script functions have no module.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TracedFunction {
+    pub module: Option<ModuleId>,
+    pub name: String,
+}
+
+impl TracedFunction {
+    pub(crate) fn new(function: &Function) -> Self {
+        Self {
+            module: function.module_id().cloned(),
+            name: function.name().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TracedFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.module {
+            Some(id) => write!(f, "0x{}::{}::{}", id.address(), id.name(), self.name),
+            None => write!(f, "Script::{}", self.name),
+        }
+    }
+}
+
+/// The kinds of accesses to global storage.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GlobalAccessKind {
+    Exists,
+    BorrowGlobal,
+    MoveFrom,
+    MoveTo,
+}
+
+impl GlobalAccessKind {
+    /// Whether the access modifies global storage.
+    pub fn is_write(self) -> bool {
+        match self {
+            GlobalAccessKind::Exists | GlobalAccessKind::BorrowGlobal => false,
+            GlobalAccessKind::MoveFrom | GlobalAccessKind::MoveTo => true,
+        }
+    }
+}
+
+/// An event of the execution of Move code.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TraceEvent {
+    /// A Move function was called, with the given type arguments.
+    FunctionEnter {
+        function: TracedFunction,
+        ty_args: Vec<TypeTag>,
+    },
+    /// A Move function returned.
+    FunctionExit { function: TracedFunction },
+    /// The instruction at offset `pc` of a function is about to be executed.
+    Instruction {
+        function: TracedFunction,
+        pc: u16,
+        instruction: String,
+    },
+    /// A native function was called, with the given type arguments.
+    NativeCall {
+        function: TracedFunction,
+        ty_args: Vec<TypeTag>,
+    },
+    /// A resource of global storage is about to be accessed.
+    GlobalAccess {
+        kind: GlobalAccessKind,
+        address: AccountAddress,
+        resource: TypeTag,
+    },
+    /// A function aborted with the given code.
+    Abort { function: TracedFunction, code: u64 },
+}
+
+/// A `TraceSink` appending the events to a file. Each event is LCS serialized, and prefixed with
+/// its length as a little-endian `u32`. Trace files are read with `TraceFileReader`.
+pub struct FileTraceSink {
+    file: Mutex<File>,
+}
+
+impl FileTraceSink {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl TraceSink for FileTraceSink {
+    fn record(&self, event: &TraceEvent) {
+        let bytes = match lcs::to_bytes(event) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                error!("[VM] failed to serialize trace event {:?}: {}", event, err);
+                return;
+            }
+        };
+        let mut record = (bytes.len() as u32).to_le_bytes().to_vec();
+        record.extend_from_slice(&bytes);
+        // Records are written with a single call so that the events of concurrent executions
+        // do not interleave.
+        if let Err(err) = self.file.lock().unwrap().write_all(&record) {
+            error!("[VM] failed to write trace event: {}", err);
+        }
+    }
+}
+
+/// Reads back the events of a trace file written by a `FileTraceSink`.
+pub struct TraceFileReader {
+    reader: BufReader<File>,
+}
+
+impl TraceFileReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(File::open(path)?),
+        })
+    }
+}
+
+impl Iterator for TraceFileReader {
+    type Item = io::Result<TraceEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut len = [0u8; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return None,
+            Err(err) => return Some(Err(err)),
+        }
+        // Don't trust the length with the size of the buffer to allocate
+        let len = u64::from(u32::from_le_bytes(len));
+        let mut bytes = vec![];
+        Some(
+            (&mut self.reader)
+                .take(len)
+                .read_to_end(&mut bytes)
+                .and_then(|read| {
+                    if read as u64 != len {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "truncated trace event",
+                        ));
+                    }
+                    lcs::from_bytes(&bytes)
+                        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+                }),
+        )
+    }
+}
+
+/// The sink the sessions trace to by default, set with the `MOVE_VM_TRACE` environment variable.
+pub(crate) fn env_trace_sink() -> Option<Arc<dyn TraceSink>> {
+    ENV_TRACE_SINK.clone()
+}
+
+/// Convert types to type tags for tracing. Types which cannot be converted are skipped, they
+/// fail the execution anyway.
+pub(crate) fn type_tags(loader: &Loader, tys: &[Type]) -> Vec<TypeTag> {
+    tys.iter()
+        .filter_map(|ty| loader.type_to_type_tag(ty).ok())
+        .collect()
+}
+
 // Only include in debug builds
 #[cfg(debug_assertions)]
-pub(crate) fn trace(
+pub(crate) fn debug_step(
     function_desc: &Function,
     locals: &Locals,
     pc: u16,
     instr: &Bytecode,
-    loader: &Loader,
+    resolver: &Resolver,
     interp: &Interpreter,
 ) {
-    if *TRACING_ENABLED {
-        let f = &mut *LOGGING_FILE.lock().unwrap();
-        writeln!(f, "{},{},{:?}", function_desc.pretty_string(), pc, instr).unwrap();
-    }
     if *DEBUGGING_ENABLED {
-        DEBUG_CONTEXT
-            .lock()
-            .unwrap()
-            .debug_loop(function_desc, locals, pc, instr, loader, interp);
+        DEBUG_CONTEXT.lock().unwrap().debug_loop(
+            function_desc,
+            locals,
+            pc,
+            instr,
+            resolver.loader(),
+            interp,
+        );
     }
 }
 
 #[macro_export]
 macro_rules! trace {
     ($function_desc:expr, $locals:expr, $pc:expr, $instr:tt, $resolver:expr, $interp:expr) => {
+        $interp.trace_event(|| crate::tracing::TraceEvent::Instruction {
+            function: crate::tracing::TracedFunction::new(&$function_desc),
+            pc: $pc,
+            instruction: format!("{:?}", $instr),
+        });
         // Only include this code in debug releases
         #[cfg(debug_assertions)]
-        crate::tracing::trace(
-            &$function_desc,
-            $locals,
-            $pc,
-            &$instr,
-            $resolver.loader(),
-            $interp,
-        )
+        crate::tracing::debug_step(&$function_desc, $locals, $pc, &$instr, $resolver, $interp)
     };
 }
@@ -1,16 +1,27 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{data_cache::RemoteCache, move_vm::MoveVM};
+use crate::{
+    data_cache::RemoteCache,
+    debugger::{DebugFrame, DebugHook, DebugState, DebugValue},
+    move_vm::MoveVM,
+    session::Session,
+    tracing::{TraceEvent, TraceSink},
+};
 use move_core_types::{
     account_address::AccountAddress,
     gas_schedule::{GasAlgebra, GasUnits},
     identifier::{IdentStr, Identifier},
-    language_storage::{ModuleId, TypeTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
 };
 use move_lang::{compiled_unit::CompiledUnit, shared::Address};
 use move_vm_types::gas_schedule::{zero_cost_schedule, CostStrategy};
-use std::{collections::HashMap, path::PathBuf, sync::Arc, thread};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+};
 use vm::{
     errors::{PartialVMResult, VMResult},
     CompiledModule,
@@ -112,9 +123,19 @@ impl Adapter {
     }
 
     fn call_function(&self, module: &ModuleId, name: &IdentStr) {
+        self.call_function_in_session(module, name, |_| ())
+    }
+
+    fn call_function_in_session(
+        &self,
+        module: &ModuleId,
+        name: &IdentStr,
+        setup_session: impl FnOnce(&mut Session<DataStore>),
+    ) {
         let cost_table = zero_cost_schedule();
         let mut cost_strategy = CostStrategy::system(&cost_table, GasUnits::new(0));
         let mut session = self.vm.new_session(&self.store);
+        setup_session(&mut session);
         session
             .execute_function(
                 module,
@@ -209,3 +230,136 @@ fn load_concurrent_many() {
     // makes 150 threads
     adapter.call_functions_async(30);
 }
+
+#[derive(Default)]
+struct RecordingSink {
+    events: Mutex<Vec<TraceEvent>>,
+}
+
+impl TraceSink for RecordingSink {
+    fn record(&self, event: &TraceEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+#[test]
+fn trace() {
+    let data_store = DataStore::empty();
+    let mut adapter = Adapter::new(data_store);
+    let modules = compile_file(&[0; 16]);
+    adapter.publish_modules(modules);
+
+    let sink = Arc::new(RecordingSink::default());
+    let session_sink = sink.clone();
+    adapter.call_function_in_session(
+        &ModuleId::new(WORKING_ACCOUNT, Identifier::new("F").unwrap()),
+        IdentStr::new("entry_f").unwrap(),
+        |session| session.set_trace_sink(session_sink),
+    );
+    // Other sessions are not traced.
+    let num_events = sink.events.lock().unwrap().len();
+    adapter.call_function(
+        &ModuleId::new(WORKING_ACCOUNT, Identifier::new("F").unwrap()),
+        IdentStr::new("entry_f").unwrap(),
+    );
+
+    let events = sink.events.lock().unwrap();
+    assert_eq!(events.len(), num_events);
+    let entry_f = format!("0x{}::F::entry_f", WORKING_ACCOUNT);
+    let entry_a = format!("0x{}::A::entry_a", WORKING_ACCOUNT);
+    assert!(events.iter().any(|event| match event {
+        TraceEvent::FunctionEnter { function, ty_args } => {
+            function.to_string() == entry_f && ty_args.is_empty()
+        }
+        _ => false,
+    }));
+    assert!(events.iter().any(|event| match event {
+        TraceEvent::Instruction { function, pc, .. } => function.to_string() == entry_f && *pc == 0,
+        _ => false,
+    }));
+    assert!(events.iter().any(|event| match event {
+        TraceEvent::FunctionEnter { function, .. } => function.to_string() == entry_a,
+        _ => false,
+    }));
+    assert!(events.iter().any(|event| match event {
+        TraceEvent::FunctionExit { function } => function.to_string() == entry_f,
+        _ => false,
+    }));
+
+    // The events survive the round trip through the format of trace files.
+    for event in events.iter() {
+        let bytes = lcs::to_bytes(event).unwrap();
+        assert_eq!(&lcs::from_bytes::<TraceEvent>(&bytes).unwrap(), event);
+    }
+}
+
+#[derive(Default)]
+struct RecordingHook {
+    states: Mutex<Vec<(Vec<DebugFrame>, Vec<Option<DebugValue>>)>>,
+}
+
+impl DebugHook for RecordingHook {
+    fn on_instruction(&self, state: &mut DebugState) {
+        let call_stack = state.call_stack();
+        let locals = state.locals(call_stack.len() - 1);
+        self.states.lock().unwrap().push((call_stack, locals));
+    }
+}
+
+#[test]
+fn debug_hook() {
+    let data_store = DataStore::empty();
+    let mut adapter = Adapter::new(data_store);
+    let modules = compile_file(&[0; 16]);
+    adapter.publish_modules(modules);
+
+    let hook = Arc::new(RecordingHook::default());
+    let session_hook = hook.clone();
+    adapter.call_function_in_session(
+        &ModuleId::new(WORKING_ACCOUNT, Identifier::new("F").unwrap()),
+        IdentStr::new("entry_f").unwrap(),
+        |session| session.set_debug_hook(session_hook),
+    );
+
+    let states = hook.states.lock().unwrap();
+    let entry_f = format!("0x{}::F::entry_f", WORKING_ACCOUNT);
+    let b_and_c = format!("0x{}::B::b_and_c", WORKING_ACCOUNT);
+    // Only the execution of the session is debugged.
+    assert!(states
+        .iter()
+        .all(|(call_stack, _)| call_stack[0].function.to_string() == entry_f));
+    let (call_stack, locals) = states
+        .iter()
+        .find(|(call_stack, _)| {
+            call_stack.len() == 3
+                && call_stack[0].function.to_string() == entry_f
+                && call_stack[2].function.to_string() == b_and_c
+                && call_stack[2].pc == 0
+        })
+        .unwrap();
+    // The caller is suspended on its call.
+    assert_eq!(call_stack[0].pc, 0);
+
+    // `b` is a reference to `B::S { f1: 20, f2: 100 }` and `c` is `C::S { f1: 0x42, f2: true }`.
+    let b = locals[0].as_ref().unwrap();
+    assert!(b.is_reference);
+    assert_eq!(
+        b.type_,
+        TypeTag::Struct(StructTag {
+            address: WORKING_ACCOUNT,
+            module: Identifier::new("B").unwrap(),
+            name: Identifier::new("S").unwrap(),
+            type_params: vec![],
+        })
+    );
+    let mut b_blob = 20u64.to_le_bytes().to_vec();
+    b_blob.extend_from_slice(&100u128.to_le_bytes());
+    assert_eq!(b.blob, b_blob);
+    let c = locals[1].as_ref().unwrap();
+    assert!(!c.is_reference);
+    let mut c_blob = AccountAddress::from_hex_literal("0x42").unwrap().to_vec();
+    c_blob.push(1);
+    assert_eq!(c.blob, c_blob);
+    // The other locals are not assigned yet.
+    assert!(locals[2..].iter().all(Option::is_none));
+}
@@ -0,0 +1,241 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of the gas charged by the Move VM.
+//!
+//! When profiling is enabled on a `CostStrategy`, every metered charge is attributed to the
+//! call stack executing at the time of the charge, and, for bytecode instructions and native
+//! functions, to the instruction or native function charged. The resulting `GasProfile` can be
+//! rendered as folded stacks, the input format of flamegraph tools, or summarized in a
+//! serializable `GasReport`.
+//!
+//! Amounts are expressed in the internal gas units of the VM, that is before division by the
+//! `gas_unit_scaling_factor` of the gas schedule.
+
+use move_core_types::gas_schedule::GasCarrier;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+use vm::file_format_common::Opcodes;
+
+/// Name of the frame charges made outside of any function are attributed to, e.g. the
+/// intrinsic gas of a transaction.
+pub const TOP_LEVEL_FRAME: &str = "<top-level>";
+
+/// Records the gas charged by an execution, see the module documentation.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfiler {
+    /// The functions being executed, from the outermost one.
+    stack: Vec<String>,
+    /// Whether the innermost function of `stack` is a native function.
+    in_native: bool,
+    profile: GasProfile,
+}
+
+impl GasProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a call to the Move function with the given fully qualified name.
+    pub fn enter_function(&mut self, name: String) {
+        self.enter(name);
+        self.in_native = false;
+    }
+
+    /// Record a call to the native function with the given fully qualified name. Native
+    /// functions do not call other functions, so the call must be followed by `exit_function`
+    /// before any other function is entered.
+    pub fn enter_native(&mut self, name: String) {
+        self.profile.natives.entry(name.clone()).or_default().count += 1;
+        self.enter(name);
+        self.in_native = true;
+    }
+
+    /// Record the return from the innermost function.
+    pub fn exit_function(&mut self) {
+        self.stack.pop();
+        self.in_native = false;
+    }
+
+    /// The number of functions being executed.
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Drop the innermost functions of the stack until `depth` are left. Used when an
+    /// execution stops on an error, without returning from the functions it executes.
+    pub fn unwind(&mut self, depth: usize) {
+        self.stack.truncate(depth);
+        self.in_native = false;
+    }
+
+    /// Record an amount of gas charged, for the given instruction if any.
+    pub fn record_gas(&mut self, opcode: Option<Opcodes>, amount: GasCarrier) {
+        let profile = &mut self.profile;
+        profile.total_gas += amount;
+        match profile.stacks.get_mut(&self.stack) {
+            Some(gas) => *gas += amount,
+            None => {
+                profile.stacks.insert(self.stack.clone(), amount);
+            }
+        }
+        if let Some(opcode) = opcode {
+            profile
+                .instructions
+                .entry(format!("{:?}", opcode))
+                .or_default()
+                .record(amount);
+        }
+        if self.in_native {
+            if let Some(native) = self.stack.last() {
+                profile.natives.entry(native.clone()).or_default().gas += amount;
+            }
+        }
+    }
+
+    pub fn profile(&self) -> &GasProfile {
+        &self.profile
+    }
+
+    pub fn into_profile(self) -> GasProfile {
+        self.profile
+    }
+
+    fn enter(&mut self, name: String) {
+        *self.profile.calls.entry(name.clone()).or_default() += 1;
+        self.stack.push(name);
+    }
+}
+
+/// The gas charged by one or more executions, attributed to call stacks, instructions and
+/// native functions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GasProfile {
+    total_gas: GasCarrier,
+    /// Gas charged directly in each call stack, the stacks being listed from the outermost
+    /// function.
+    stacks: BTreeMap<Vec<String>, GasCarrier>,
+    /// Number of calls of each function.
+    calls: BTreeMap<String, u64>,
+    instructions: BTreeMap<String, GasUsage>,
+    natives: BTreeMap<String, GasUsage>,
+}
+
+/// How many times something was charged for, and the gas it was charged in total.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct GasUsage {
+    pub count: u64,
+    pub gas: GasCarrier,
+}
+
+impl GasUsage {
+    fn record(&mut self, amount: GasCarrier) {
+        self.count += 1;
+        self.gas += amount;
+    }
+
+    fn merge(&mut self, other: &GasUsage) {
+        self.count += other.count;
+        self.gas += other.gas;
+    }
+}
+
+/// The gas charged by a function.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FunctionGas {
+    pub calls: u64,
+    /// Gas charged by the function itself, excluding the functions it calls.
+    pub self_gas: GasCarrier,
+    /// Gas charged by the function and the functions it calls. Recursive calls are only
+    /// accounted once.
+    pub total_gas: GasCarrier,
+}
+
+/// A summary of a `GasProfile`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct GasReport {
+    pub total_gas: GasCarrier,
+    pub functions: BTreeMap<String, FunctionGas>,
+    pub instructions: BTreeMap<String, GasUsage>,
+    pub natives: BTreeMap<String, GasUsage>,
+}
+
+impl GasProfile {
+    pub fn total_gas(&self) -> GasCarrier {
+        self.total_gas
+    }
+
+    /// Add the gas recorded in `other` to this profile.
+    pub fn merge(&mut self, other: &GasProfile) {
+        self.total_gas += other.total_gas;
+        for (stack, gas) in &other.stacks {
+            *self.stacks.entry(stack.clone()).or_default() += gas;
+        }
+        for (function, calls) in &other.calls {
+            *self.calls.entry(function.clone()).or_default() += calls;
+        }
+        for (opcode, usage) in &other.instructions {
+            self.instructions
+                .entry(opcode.clone())
+                .or_default()
+                .merge(usage);
+        }
+        for (native, usage) in &other.natives {
+            self.natives.entry(native.clone()).or_default().merge(usage);
+        }
+    }
+
+    /// Render the profile as folded stacks: one line per call stack, with the functions of the
+    /// stack separated by `;` and followed by the gas charged in the stack. Stacks which were
+    /// not charged any gas are omitted.
+    pub fn folded_stacks(&self) -> String {
+        let mut folded = String::new();
+        for (stack, gas) in &self.stacks {
+            if *gas == 0 {
+                continue;
+            }
+            let frames = if stack.is_empty() {
+                TOP_LEVEL_FRAME.to_string()
+            } else {
+                stack.join(";")
+            };
+            // Writing to a `String` cannot fail.
+            writeln!(folded, "{} {}", frames, gas).unwrap();
+        }
+        folded
+    }
+
+    pub fn report(&self) -> GasReport {
+        let mut functions: BTreeMap<String, FunctionGas> = self
+            .calls
+            .iter()
+            .map(|(function, calls)| {
+                (
+                    function.clone(),
+                    FunctionGas {
+                        calls: *calls,
+                        ..FunctionGas::default()
+                    },
+                )
+            })
+            .collect();
+        for (stack, gas) in &self.stacks {
+            if let Some(innermost) = stack.last() {
+                functions.entry(innermost.clone()).or_default().self_gas += gas;
+            }
+            let distinct_functions = stack.iter().collect::<BTreeSet<_>>();
+            for function in distinct_functions {
+                functions.entry(function.clone()).or_default().total_gas += gas;
+            }
+        }
+        GasReport {
+            total_gas: self.total_gas,
+            functions,
+            instructions: self.instructions.clone(),
+            natives: self.natives.clone(),
+        }
+    }
+}
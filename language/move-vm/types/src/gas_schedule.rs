@@ -6,6 +6,7 @@
 //! It is important to note that the cost schedule defined in this file does not track hashing
 //! operations or other native operations; the cost of each native operation will be returned by the
 //! native function itself.
+use crate::gas_profiler::{GasProfile, GasProfiler};
 use libra_types::{transaction::MAX_TRANSACTION_SIZE_IN_BYTES, vm_status::StatusCode};
 use mirai_annotations::*;
 use move_core_types::gas_schedule::{
@@ -31,6 +32,7 @@ pub struct CostStrategy<'a> {
     cost_table: &'a CostTable,
     gas_left: GasUnits<GasCarrier>,
     charge: bool,
+    profiler: Option<GasProfiler>,
 }
 
 impl<'a> CostStrategy<'a> {
@@ -43,6 +45,7 @@ impl<'a> CostStrategy<'a> {
             gas_left: gas_left.map(|x| x * cost_table.gas_constants.gas_unit_scaling_factor),
             cost_table,
             charge: true,
+            profiler: None,
         }
     }
 
@@ -55,6 +58,7 @@ impl<'a> CostStrategy<'a> {
             gas_left: gas_left.map(|x| x * cost_table.gas_constants.gas_unit_scaling_factor),
             cost_table,
             charge: false,
+            profiler: None,
         }
     }
 
@@ -71,10 +75,19 @@ impl<'a> CostStrategy<'a> {
 
     /// Charge a given amount of gas and fail if not enough gas units are left.
     pub fn deduct_gas(&mut self, amount: GasUnits<GasCarrier>) -> PartialVMResult<()> {
+        self.deduct_gas_impl(None, amount)
+    }
+
+    fn deduct_gas_impl(
+        &mut self,
+        opcode: Option<Opcodes>,
+        amount: GasUnits<GasCarrier>,
+    ) -> PartialVMResult<()> {
         if !self.charge {
             return Ok(());
         }
-        if self
+        let gas_left = self.gas_left;
+        let result = if self
             .gas_left
             .app(&amount, |curr_gas, gas_amt| curr_gas >= gas_amt)
         {
@@ -84,7 +97,11 @@ impl<'a> CostStrategy<'a> {
             // Zero out the internal gas state
             self.gas_left = GasUnits::new(0);
             Err(PartialVMError::new(StatusCode::OUT_OF_GAS))
+        };
+        if let Some(profiler) = &mut self.profiler {
+            profiler.record_gas(opcode, gas_left.sub(self.gas_left).get());
         }
+        result
     }
 
     /// Charge an instruction over data with a given size and fail if not enough gas units are left.
@@ -93,7 +110,8 @@ impl<'a> CostStrategy<'a> {
         opcode: Opcodes,
         size: AbstractMemorySize<GasCarrier>,
     ) -> PartialVMResult<()> {
-        self.deduct_gas(
+        self.deduct_gas_impl(
+            Some(opcode),
             self.cost_table
                 .instruction_cost(opcode as u8)
                 .total()
@@ -103,7 +121,10 @@ impl<'a> CostStrategy<'a> {
 
     /// Charge an instruction and fail if not enough gas units are left.
     pub fn charge_instr(&mut self, opcode: Opcodes) -> PartialVMResult<()> {
-        self.deduct_gas(self.cost_table.instruction_cost(opcode as u8).total())
+        self.deduct_gas_impl(
+            Some(opcode),
+            self.cost_table.instruction_cost(opcode as u8).total(),
+        )
     }
 
     /// Charge gas related to the overall size of a transaction and fail if not enough
@@ -123,6 +144,24 @@ impl<'a> CostStrategy<'a> {
     pub fn enable_metering(&mut self) {
         self.charge = true
     }
+
+    /// Start recording where the gas goes. Only the charges made while metering is enabled are
+    /// recorded, see `gas_profiler` for details.
+    pub fn enable_profiling(&mut self) {
+        if self.profiler.is_none() {
+            self.profiler = Some(GasProfiler::new());
+        }
+    }
+
+    /// Return the profiler recording the gas charged, if profiling is enabled.
+    pub fn gas_profiler(&mut self) -> Option<&mut GasProfiler> {
+        self.profiler.as_mut()
+    }
+
+    /// Stop profiling and return the gas recorded so far, if profiling was enabled.
+    pub fn take_gas_profile(&mut self) -> Option<GasProfile> {
+        self.profiler.take().map(GasProfiler::into_profile)
+    }
 }
 
 pub fn new_from_instructions(
@@ -0,0 +1,78 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::gas_profiler::{FunctionGas, GasProfiler, GasUsage, TOP_LEVEL_FRAME};
+use vm::file_format_common::Opcodes;
+
+fn profile_recursion() -> GasProfiler {
+    let mut profiler = GasProfiler::new();
+    profiler.record_gas(None, 5);
+    profiler.enter_function("M::f".to_string());
+    profiler.record_gas(Some(Opcodes::CALL), 1);
+    profiler.enter_function("M::f".to_string());
+    profiler.record_gas(Some(Opcodes::ADD), 2);
+    profiler.enter_native("Vector::length".to_string());
+    profiler.record_gas(None, 3);
+    profiler.exit_function();
+    profiler.exit_function();
+    profiler.record_gas(Some(Opcodes::RET), 1);
+    profiler.exit_function();
+    profiler
+}
+
+#[test]
+fn folded_stacks() {
+    let profiler = profile_recursion();
+    assert_eq!(profiler.stack_depth(), 0);
+    assert_eq!(
+        profiler.profile().folded_stacks(),
+        format!(
+            "{} 5\nM::f 2\nM::f;M::f 2\nM::f;M::f;Vector::length 3\n",
+            TOP_LEVEL_FRAME
+        )
+    );
+}
+
+#[test]
+fn report() {
+    let report = profile_recursion().profile().report();
+    assert_eq!(report.total_gas, 12);
+    assert_eq!(
+        report.functions["M::f"],
+        FunctionGas {
+            calls: 2,
+            self_gas: 4,
+            total_gas: 7,
+        }
+    );
+    assert_eq!(
+        report.functions["Vector::length"],
+        FunctionGas {
+            calls: 1,
+            self_gas: 3,
+            total_gas: 3,
+        }
+    );
+    assert_eq!(report.instructions["ADD"], GasUsage { count: 1, gas: 2 });
+    assert_eq!(
+        report.natives["Vector::length"],
+        GasUsage { count: 1, gas: 3 }
+    );
+}
+
+#[test]
+fn merge_and_unwind() {
+    let mut profiler = GasProfiler::new();
+    profiler.enter_function("M::f".to_string());
+    profiler.enter_native("Vector::length".to_string());
+    profiler.unwind(0);
+    profiler.record_gas(None, 1);
+
+    let mut profile = profile_recursion().into_profile();
+    profile.merge(profiler.profile());
+    assert_eq!(profile.total_gas(), 13);
+    assert_eq!(profile.report().functions["M::f"].calls, 3);
+    assert!(profile
+        .folded_stacks()
+        .starts_with(&format!("{} 6\n", TOP_LEVEL_FRAME)));
+}
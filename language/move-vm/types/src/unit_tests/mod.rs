@@ -1,5 +1,6 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod gas_profiler_tests;
 #[cfg(feature = "fuzzing")]
 mod identifier_prop_tests;
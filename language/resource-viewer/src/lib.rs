@@ -14,7 +14,7 @@ use libra_types::{
 };
 use move_core_types::{
     identifier::Identifier,
-    language_storage::StructTag,
+    language_storage::{StructTag, TypeTag},
     value::{MoveStruct, MoveValue},
 };
 use std::{
@@ -32,13 +32,13 @@ mod module_cache;
 mod resolver;
 
 #[derive(Debug)]
-pub struct AnnotatedAccountStateBlob(BTreeMap<StructTag, AnnotatedMoveStruct>);
+pub struct AnnotatedAccountStateBlob(pub BTreeMap<StructTag, AnnotatedMoveStruct>);
 
 #[derive(Debug)]
 pub struct AnnotatedMoveStruct {
-    is_resource: bool,
-    type_: StructTag,
-    value: Vec<(Identifier, AnnotatedMoveValue)>,
+    pub is_resource: bool,
+    pub type_: StructTag,
+    pub value: Vec<(Identifier, AnnotatedMoveValue)>,
 }
 
 /// AnnotatedMoveValue is a fully expanded version of on chain Move data. This should only be used
@@ -70,6 +70,15 @@ impl<'a> MoveValueAnnotator<'a> {
         }
     }
 
+    /// Creates an annotator resolving every module from `view` instead of the compiled stdlib,
+    /// which is needed to correctly annotate historical state across stdlib upgrades.
+    pub fn new_no_stdlib(view: &'a dyn StateView) -> Self {
+        Self {
+            cache: Resolver::new(view, false),
+            _data_view: view,
+        }
+    }
+
     pub fn view_access_path(
         &self,
         access_path: AccessPath,
@@ -95,6 +104,17 @@ impl<'a> MoveValueAnnotator<'a> {
         self.annotate_value(&move_value, &ty)
     }
 
+    /// Annotate a value of type `ty_tag` serialized with LCS, e.g. a value of a Move execution.
+    pub fn view_value(&self, ty_tag: &TypeTag, blob: &[u8]) -> Result<AnnotatedMoveValue> {
+        let ty = self.cache.resolve_type(ty_tag)?;
+        let move_ty = (&ty)
+            .try_into()
+            .map_err(|e: PartialVMError| e.finish(Location::Undefined).into_vm_status())?;
+
+        let move_value = MoveValue::simple_deserialize(blob, &move_ty)?;
+        self.annotate_value(&move_value, &ty)
+    }
+
     pub fn view_account_state(&self, state: &AccountState) -> Result<AnnotatedAccountStateBlob> {
         let mut output = BTreeMap::new();
         for (k, v) in state.iter() {
@@ -150,6 +170,9 @@ impl<'a> MoveValueAnnotator<'a> {
             (MoveValue::U64(i), FatType::U64) => AnnotatedMoveValue::U64(*i),
             (MoveValue::U128(i), FatType::U128) => AnnotatedMoveValue::U128(*i),
             (MoveValue::Address(a), FatType::Address) => AnnotatedMoveValue::Address(*a),
+            // Signers never reach storage, they are only met when viewing the values of an
+            // execution, and are shown as the address they stand for.
+            (MoveValue::Signer(a), FatType::Signer) => AnnotatedMoveValue::Address(*a),
             (MoveValue::Vector(a), FatType::Vector(ty)) => match ty.as_ref() {
                 FatType::U8 => AnnotatedMoveValue::Bytes(
                     a.iter()
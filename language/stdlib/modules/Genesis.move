@@ -16,6 +16,7 @@ module Genesis {
     use 0x1::LibraAccount;
     use 0x1::LibraBlock;
     use 0x1::LibraConfig;
+    use 0x1::LibraConsensusConfig;
     use 0x1::LibraSystem;
     use 0x1::LibraTimestamp;
     use 0x1::LibraTransactionPublishingOption;
@@ -36,6 +37,7 @@ module Genesis {
         instruction_schedule: vector<u8>,
         native_schedule: vector<u8>,
         chain_id: u8,
+        consensus_config: vector<u8>,
     ) {
         let dummy_auth_key_prefix = x"00000000000000000000000000000000";
 
@@ -110,6 +112,11 @@ module Genesis {
             native_schedule,
         );
 
+        LibraConsensusConfig::initialize(
+            lr_account,
+            consensus_config,
+        );
+
         let tc_rotate_key_cap = LibraAccount::extract_key_rotation_capability(tc_account);
         LibraAccount::rotate_authentication_key(&tc_rotate_key_cap, copy genesis_auth_key);
         LibraAccount::restore_key_rotation_capability(tc_rotate_key_cap);
@@ -0,0 +1,36 @@
+address 0x1 {
+
+/// Maintains the consensus config of the Libra blockchain. The config is stored as the LCS
+/// serialized bytes of the Rust `OnChainConsensusConfig`, validators read it as of the
+/// reconfiguration that starts an epoch, so an update is picked up by all of them at the same
+/// epoch boundary.
+module LibraConsensusConfig {
+    use 0x1::LibraConfig;
+    use 0x1::LibraTimestamp;
+    use 0x1::Roles;
+
+    struct LibraConsensusConfig {
+        config: vector<u8>,
+    }
+
+    /// Publishes the LibraConsensusConfig config.
+    public fun initialize(lr_account: &signer, config: vector<u8>) {
+        LibraTimestamp::assert_genesis();
+        Roles::assert_libra_root(lr_account);
+        LibraConfig::publish_new_config(lr_account, LibraConsensusConfig { config });
+    }
+
+    /// Allows Libra root to update the config, which triggers a reconfiguration.
+    public fun set(lr_account: &signer, config: vector<u8>) {
+        LibraTimestamp::assert_operating();
+        Roles::assert_libra_root(lr_account);
+        LibraConfig::set(lr_account, LibraConsensusConfig { config });
+    }
+
+    spec module {
+        /// After genesis, the config is published.
+        invariant [global] LibraTimestamp::is_operating() ==>
+            LibraConfig::spec_is_published<LibraConsensusConfig>();
+    }
+}
+}
@@ -35,6 +35,7 @@ use move_core_types::{
 };
 use move_vm_runtime::move_vm::MoveVM;
 use move_vm_types::{
+    gas_profiler::GasProfile,
     gas_schedule::{zero_cost_schedule, CostStrategy},
     values::Value,
 };
@@ -210,6 +211,28 @@ impl FakeExecutor {
         )
     }
 
+    /// Alternate form of 'execute_block' that also profiles the gas charged to the transactions.
+    /// See `move_vm_types::gas_profiler`.
+    pub fn execute_block_with_gas_profile(
+        &self,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Result<(Vec<TransactionOutput>, GasProfile), VMStatus> {
+        let (outputs, gas_profile) = LibraVM::execute_block_with_gas_profile(
+            txn_block
+                .into_iter()
+                .map(Transaction::UserTransaction)
+                .collect(),
+            &self.data_store,
+        )?;
+        Ok((
+            outputs
+                .into_iter()
+                .map(|(_vm_status, txn_output)| txn_output)
+                .collect(),
+            gas_profile,
+        ))
+    }
+
     /// Executes the transaction as a singleton block and applies the resulting write set to the
     /// data store. Panics if execution fails
     pub fn execute_and_apply(&mut self, transaction: SignedTransaction) -> TransactionOutput {
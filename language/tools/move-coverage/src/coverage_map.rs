@@ -6,11 +6,12 @@
 use anyhow::{format_err, Result};
 use libra_types::account_address::AccountAddress;
 use move_core_types::identifier::{IdentStr, Identifier};
+use move_vm_runtime::tracing::{TraceEvent, TraceFileReader};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fs::File,
-    io::{BufRead, BufReader, Read, Write},
+    io::{Read, Write},
     path::Path,
 };
 
@@ -30,26 +31,27 @@ pub struct ModuleCoverageMap {
 
 impl CoverageMap {
     /// Takes in a file containing a raw VM trace, and returns an updated coverage map.
+    ///
+    /// The trace is made of the `TraceEvent`s written by the VM when the `MOVE_VM_TRACE`
+    /// environment variable is set, see `FileTraceSink`. Only the executed instructions are taken
+    /// into account.
     pub fn update_coverage_from_trace_file<P: AsRef<Path>>(mut self, filename: P) -> Self {
-        let file = File::open(filename).unwrap();
-        for line in BufReader::new(file).lines() {
-            let line = line.unwrap();
-            let mut splits = line.split(',');
-            let context = splits.next().unwrap();
-            let pc = splits.next().unwrap().parse::<u64>().unwrap();
-
-            let mut context_segs: Vec<_> = context.split("::").collect();
-            let is_script = context_segs.len() == 2;
-            // Don't count scripts (for now)
-            if !is_script {
-                let func_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let module_name = Identifier::new(context_segs.pop().unwrap()).unwrap();
-                let addr = AccountAddress::from_hex_literal(context_segs.pop().unwrap()).unwrap();
-                let entry = self
-                    .module_maps
-                    .entry((addr, module_name.clone()))
-                    .or_insert_with(|| ModuleCoverageMap::new(addr, module_name));
-                entry.insert(func_name, pc);
+        for event in TraceFileReader::open(filename).unwrap() {
+            let event = event
+                .map_err(|err| format_err!("Error reading trace event: {}", err))
+                .unwrap();
+            if let TraceEvent::Instruction { function, pc, .. } = event {
+                // Don't count scripts (for now)
+                if let Some(module_id) = function.module {
+                    let func_name = Identifier::new(function.name).unwrap();
+                    let addr = *module_id.address();
+                    let module_name = module_id.name().to_owned();
+                    let entry = self
+                        .module_maps
+                        .entry((addr, module_name.clone()))
+                        .or_insert_with(|| ModuleCoverageMap::new(addr, module_name));
+                    entry.insert(func_name, pc as u64);
+                }
             }
         }
         self
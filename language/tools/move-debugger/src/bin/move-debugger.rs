@@ -0,0 +1,118 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::{anyhow, bail, Result};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_schedule::{GasAlgebra, GasUnits},
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use move_debugger::{DapServer, LaunchRequest, SourceIndex};
+use move_lang::command_line as cli;
+use move_vm_runtime::{data_cache::RemoteCache, move_vm::MoveVM};
+use move_vm_types::gas_schedule::{zero_cost_schedule, CostStrategy};
+use std::sync::Arc;
+use vm::errors::{PartialVMResult, VMResult};
+
+/// The modules compiled from the sources. The global storage is otherwise empty.
+struct SourceStorage {
+    sources: Arc<SourceIndex>,
+}
+
+impl RemoteCache for SourceStorage {
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        Ok(self.sources.module(module_id).map(|blob| blob.to_vec()))
+    }
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _tag: &TypeTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// Parse a fully qualified function name, e.g. `0x2::M::f`.
+fn parse_function(name: &str) -> Result<(ModuleId, Identifier)> {
+    let parts: Vec<_> = name.split("::").collect();
+    if parts.len() != 3 {
+        bail!("Expected a function of the form 0x2::M::f, got {}", name);
+    }
+    let address = AccountAddress::from_hex_literal(parts[0])?;
+    let module = Identifier::new(parts[1])?;
+    let function = Identifier::new(parts[2])?;
+    Ok((ModuleId::new(address, module), function))
+}
+
+fn prepare(launch: &LaunchRequest) -> Result<(SourceIndex, ModuleId, Identifier)> {
+    let arguments = launch.arguments();
+    let targets = arguments["sources"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Missing launch argument: sources"))?
+        .iter()
+        .map(|target| {
+            target
+                .as_str()
+                .map(|target| target.to_string())
+                .ok_or_else(|| anyhow!("Invalid source: {}", target))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let sender = match arguments["sender"].as_str() {
+        Some(sender) => Some(cli::parse_address(sender).map_err(|e| anyhow!(e))?),
+        None => None,
+    };
+    let (module_id, function) = parse_function(
+        arguments["function"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Missing launch argument: function"))?,
+    )?;
+    let sources = SourceIndex::compile(&targets, sender)?;
+    if sources.module(&module_id).is_none() {
+        bail!("Module {} not found in the sources", module_id);
+    }
+    Ok((sources, module_id, function))
+}
+
+pub fn main() -> Result<()> {
+    let server = DapServer::stdio();
+    let launch = server.wait_for_launch()?;
+    let (sources, module_id, function) = match prepare(&launch) {
+        Ok(prepared) => prepared,
+        Err(error) => {
+            server.reject_launch(launch, &error);
+            return Err(error);
+        }
+    };
+
+    let sources = Arc::new(sources);
+    let storage = SourceStorage {
+        sources: Arc::clone(&sources),
+    };
+    let result = server.debug(launch, sources, |hook| {
+        let vm = MoveVM::new();
+        let cost_table = zero_cost_schedule();
+        let mut cost_strategy = CostStrategy::system(&cost_table, GasUnits::new(0));
+        let mut session = vm.new_session(&storage);
+        session.set_debug_hook(hook);
+        session.execute_function(
+            &module_id,
+            &function,
+            vec![],
+            vec![],
+            *module_id.address(),
+            &mut cost_strategy,
+            |e| e,
+        )
+    })?;
+    let outcome = match result {
+        Ok(()) => "Executed successfully".to_string(),
+        Err(status) => format!("Failed with {}", status),
+    };
+    server.output(&outcome);
+    server.wait_for_disconnect();
+    Ok(())
+}
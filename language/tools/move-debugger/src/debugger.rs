@@ -0,0 +1,437 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `DebugHook` suspending the execution where the client asks to, and inspecting it while
+//! it is suspended.
+
+use crate::{
+    protocol::{Output, Request},
+    sources::SourceIndex,
+};
+use move_vm_runtime::debugger::{DebugFrame, DebugHook, DebugState, DebugValue};
+use resource_viewer::{AnnotatedMoveValue, MoveValueAnnotator};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{mpsc::Receiver, Arc, Mutex},
+};
+
+/// The identifier of the only thread of the execution.
+pub(crate) const THREAD_ID: i64 = 1;
+
+/// When the execution should stop, besides breakpoints.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Mode {
+    Run,
+    /// Stop on the first line of source code executed.
+    Entry,
+    /// Stop as soon as possible, at the request of the client.
+    Pause,
+    /// Stop on the next line, in the current function or a function it calls.
+    StepIn {
+        depth: usize,
+        line: (&'static str, usize),
+    },
+    /// Stop on the next line of the current function, or in its caller if it returns.
+    StepOver {
+        depth: usize,
+        line: (&'static str, usize),
+    },
+    /// Stop once the current function returns.
+    StepOut {
+        depth: usize,
+    },
+}
+
+/// The state of the debugging session, shared by the thread reading the requests of the client
+/// and the thread executing the code.
+pub(crate) struct Shared {
+    pub(crate) output: Mutex<Output>,
+    pub(crate) control: Mutex<Control>,
+}
+
+pub(crate) struct Control {
+    pub(crate) sources: Option<Arc<SourceIndex>>,
+    /// The lines of each file where the execution must stop.
+    pub(crate) breakpoints: HashMap<&'static str, BTreeSet<usize>>,
+    pub(crate) mode: Mode,
+    /// Whether the request of the client to disconnect was served.
+    pub(crate) disconnected: bool,
+}
+
+impl Shared {
+    pub(crate) fn respond(&self, request: &Request, response: Result<Value, String>) {
+        let mut output = self.output.lock().unwrap();
+        match response {
+            Ok(body) => output.respond(request, body),
+            Err(message) => output.respond_error(request, &message),
+        }
+    }
+
+    pub(crate) fn event(&self, event: &str, body: Value) {
+        self.output.lock().unwrap().event(event, body);
+    }
+}
+
+pub(crate) struct Debugger {
+    shared: Arc<Shared>,
+    sources: Arc<SourceIndex>,
+    /// The requests to serve while the execution is suspended.
+    requests: Arc<Mutex<Receiver<Request>>>,
+    /// The line last executed by each frame of the call stack.
+    lines: Mutex<Vec<(&'static str, usize)>>,
+}
+
+/// What the `variablesReference`s given to the client designate, while the execution is
+/// suspended.
+#[derive(Clone)]
+enum Variables {
+    Locals(usize),
+    Globals,
+    Children(Vec<Value>),
+}
+
+impl DebugHook for Debugger {
+    fn on_instruction(&self, state: &mut DebugState) {
+        let call_stack = state.call_stack();
+        let depth = call_stack.len();
+        let frame = &call_stack[depth - 1];
+        let location = match self.sources.location(
+            frame.function.module.as_ref(),
+            frame.function_index,
+            frame.pc,
+        ) {
+            Some(location) => location,
+            // The code has no sources, step through it.
+            None => return,
+        };
+        let line = (location.file, location.line);
+        let is_new_line = {
+            let mut lines = self.lines.lock().unwrap();
+            lines.truncate(depth);
+            // Functions start on a new line, even if the previous function called at the same
+            // depth ended on the same line.
+            let is_new_line = frame.pc == 0 || lines.len() < depth || lines[depth - 1] != line;
+            lines.resize(depth, line);
+            lines[depth - 1] = line;
+            is_new_line
+        };
+
+        let reason = {
+            let control = self.shared.control.lock().unwrap();
+            let reason = match control.mode {
+                Mode::Run => None,
+                Mode::Entry => Some("entry"),
+                Mode::Pause => Some("pause"),
+                Mode::StepIn {
+                    depth: step_depth,
+                    line: step_line,
+                } => {
+                    if depth != step_depth || (is_new_line && line != step_line) {
+                        Some("step")
+                    } else {
+                        None
+                    }
+                }
+                Mode::StepOver {
+                    depth: step_depth,
+                    line: step_line,
+                } => {
+                    if depth < step_depth
+                        || (depth == step_depth && is_new_line && line != step_line)
+                    {
+                        Some("step")
+                    } else {
+                        None
+                    }
+                }
+                Mode::StepOut { depth: step_depth } => {
+                    if depth < step_depth {
+                        Some("step")
+                    } else {
+                        None
+                    }
+                }
+            };
+            reason.or_else(|| {
+                let is_breakpoint = control
+                    .breakpoints
+                    .get(location.file)
+                    .map_or(false, |lines| lines.contains(&location.line));
+                if is_new_line && is_breakpoint {
+                    Some("breakpoint")
+                } else {
+                    None
+                }
+            })
+        };
+        if let Some(reason) = reason {
+            self.stop(state, &call_stack, line, reason);
+        }
+    }
+}
+
+impl Debugger {
+    pub(crate) fn new(
+        shared: Arc<Shared>,
+        sources: Arc<SourceIndex>,
+        requests: Arc<Mutex<Receiver<Request>>>,
+    ) -> Self {
+        Self {
+            shared,
+            sources,
+            requests,
+            lines: Mutex::new(vec![]),
+        }
+    }
+
+    /// Suspend the execution, serving the requests of the client until it resumes it.
+    fn stop(
+        &self,
+        state: &mut DebugState,
+        call_stack: &[DebugFrame],
+        line: (&'static str, usize),
+        reason: &str,
+    ) {
+        self.shared.control.lock().unwrap().mode = Mode::Run;
+        self.shared.event(
+            "stopped",
+            json!({
+                "reason": reason,
+                "threadId": THREAD_ID,
+                "allThreadsStopped": true,
+            }),
+        );
+
+        let depth = call_stack.len();
+        let annotator = MoveValueAnnotator::new(&*self.sources);
+        let mut variables = vec![];
+        let requests = self.requests.lock().unwrap();
+        // If the client is gone, let the execution complete.
+        while let Ok(request) = requests.recv() {
+            let resume_mode = match request.command.as_str() {
+                "continue" | "disconnect" => Some(Mode::Run),
+                "next" => Some(Mode::StepOver { depth, line }),
+                "stepIn" => Some(Mode::StepIn { depth, line }),
+                "stepOut" => Some(Mode::StepOut { depth }),
+                _ => None,
+            };
+            if let Some(mode) = resume_mode {
+                {
+                    let mut control = self.shared.control.lock().unwrap();
+                    control.mode = mode;
+                    control.disconnected = request.command == "disconnect";
+                }
+                let body = if request.command == "continue" {
+                    json!({ "allThreadsContinued": true })
+                } else {
+                    json!({})
+                };
+                self.shared.respond(&request, Ok(body));
+                return;
+            }
+
+            let response = match request.command.as_str() {
+                "stackTrace" => Ok(self.stack_trace(call_stack)),
+                "scopes" => match request.arguments["frameId"].as_u64() {
+                    Some(frame_id) if (frame_id as usize) < depth => {
+                        variables.push(Variables::Locals(frame_id as usize));
+                        let locals_reference = variables.len();
+                        variables.push(Variables::Globals);
+                        let globals_reference = variables.len();
+                        Ok(json!({
+                            "scopes": [
+                                {
+                                    "name": "Locals",
+                                    "variablesReference": locals_reference,
+                                    "expensive": false,
+                                },
+                                {
+                                    "name": "Globals",
+                                    "variablesReference": globals_reference,
+                                    "expensive": false,
+                                },
+                            ],
+                        }))
+                    }
+                    _ => Err("Unknown frame".to_string()),
+                },
+                "variables" => match request.arguments["variablesReference"].as_u64() {
+                    Some(reference) if reference > 0 && reference as usize <= variables.len() => {
+                        let children = match variables[reference as usize - 1].clone() {
+                            Variables::Locals(frame_id) => self.locals(
+                                state,
+                                &call_stack[frame_id],
+                                frame_id,
+                                &annotator,
+                                &mut variables,
+                            ),
+                            Variables::Globals => {
+                                self.global_resources(state, &annotator, &mut variables)
+                            }
+                            Variables::Children(children) => children,
+                        };
+                        Ok(json!({ "variables": children }))
+                    }
+                    _ => Err("Unknown variables".to_string()),
+                },
+                command => Err(format!("Unsupported request while stopped: {}", command)),
+            };
+            self.shared.respond(&request, response);
+        }
+    }
+
+    fn stack_trace(&self, call_stack: &[DebugFrame]) -> Value {
+        let frames: Vec<_> = call_stack
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(frame_id, frame)| {
+                let name = frame.function.to_string();
+                match self.sources.location(
+                    frame.function.module.as_ref(),
+                    frame.function_index,
+                    frame.pc,
+                ) {
+                    Some(location) => json!({
+                        "id": frame_id,
+                        "name": name,
+                        "source": {
+                            "name": location.path.file_name().map(|name| name.to_string_lossy()),
+                            "path": location.path.to_string_lossy(),
+                        },
+                        "line": location.line,
+                        "column": location.column,
+                    }),
+                    None => json!({
+                        "id": frame_id,
+                        "name": name,
+                        "line": 0,
+                        "column": 0,
+                        "presentationHint": "subtle",
+                    }),
+                }
+            })
+            .collect();
+        json!({
+            "stackFrames": frames,
+            "totalFrames": call_stack.len(),
+        })
+    }
+
+    fn locals(
+        &self,
+        state: &DebugState,
+        frame: &DebugFrame,
+        frame_id: usize,
+        annotator: &MoveValueAnnotator,
+        variables: &mut Vec<Variables>,
+    ) -> Vec<Value> {
+        state
+            .locals(frame_id)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, value)| {
+                let name = self.sources.local_name(
+                    frame.function.module.as_ref(),
+                    frame.function_index,
+                    index,
+                )?;
+                // Locals which hold no value are not shown.
+                Some(debug_value_variable(name, &value?, annotator, variables))
+            })
+            .collect()
+    }
+
+    fn global_resources(
+        &self,
+        state: &mut DebugState,
+        annotator: &MoveValueAnnotator,
+        variables: &mut Vec<Variables>,
+    ) -> Vec<Value> {
+        state
+            .global_resources()
+            .into_iter()
+            .map(|resource| {
+                let name = format!("global<{}>({})", resource.type_, resource.address);
+                match &resource.value {
+                    Some(value) => debug_value_variable(name, value, annotator, variables),
+                    None => json!({
+                        "name": name,
+                        "value": "<none>",
+                        "variablesReference": 0,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A variable of the client showing a value of the execution, with the field names of its
+/// structs when they can be found in the sources.
+fn debug_value_variable(
+    name: String,
+    value: &DebugValue,
+    annotator: &MoveValueAnnotator,
+    variables: &mut Vec<Variables>,
+) -> Value {
+    let prefix = if value.is_reference { "&" } else { "" };
+    match annotator.view_value(&value.type_, &value.blob) {
+        Ok(annotated) => {
+            let mut variable = annotated_variable(name, &annotated, variables);
+            variable["value"] = json!(format!(
+                "{}{}",
+                prefix,
+                variable["value"].as_str().unwrap_or_default()
+            ));
+            variable["type"] = json!(format!("{}{}", prefix, value.type_));
+            variable
+        }
+        Err(_) => json!({
+            "name": name,
+            "value": format!("{}x\"{}\"", prefix, hex::encode(&value.blob)),
+            "type": format!("{}{}", prefix, value.type_),
+            "variablesReference": 0,
+        }),
+    }
+}
+
+fn annotated_variable(
+    name: String,
+    value: &AnnotatedMoveValue,
+    variables: &mut Vec<Variables>,
+) -> Value {
+    let (text, children) = match value {
+        AnnotatedMoveValue::Struct(value) => {
+            let children = value
+                .value
+                .iter()
+                .map(|(field, value)| annotated_variable(field.to_string(), value, variables))
+                .collect();
+            (value.type_.to_string(), Some(children))
+        }
+        AnnotatedMoveValue::Vector(elements) => {
+            let children = elements
+                .iter()
+                .enumerate()
+                .map(|(index, value)| annotated_variable(format!("[{}]", index), value, variables))
+                .collect();
+            (format!("vector[{}]", elements.len()), Some(children))
+        }
+        AnnotatedMoveValue::Bytes(bytes) => (format!("x\"{}\"", hex::encode(bytes)), None),
+        AnnotatedMoveValue::Address(address) => (address.to_string(), None),
+        value => (value.to_string(), None),
+    };
+    let reference = match children {
+        Some(children) => {
+            variables.push(Variables::Children(children));
+            variables.len()
+        }
+        None => 0,
+    };
+    json!({
+        "name": name,
+        "value": text,
+        "variablesReference": reference,
+    })
+}
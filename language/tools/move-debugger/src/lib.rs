@@ -0,0 +1,31 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! A source-level debugger of Move code, which editors drive through the
+//! [Debug Adapter Protocol](https://microsoft.github.io/debug-adapter-protocol/) over the
+//! standard input and output.
+//!
+//! The bytecode executed is mapped to the Move sources it was compiled from with its source
+//! maps. The debugger supports breakpoints on lines of the sources, stepping over, into and out
+//! of functions, and inspecting the locals of each frame of the call stack and the resources of
+//! global storage accessed so far, with the field names of their structs.
+//!
+//! The `move-debugger` binary debugs a function of modules compiled from sources, given by the
+//! arguments of the `launch` request of the client:
+//! - `sources`: the Move source files, or directories of them, to compile
+//! - `function`: the function to execute, e.g. `0x2::M::f`, which must take no arguments
+//! - `sender`: the address of the modules declared without one
+//! - `stopOnEntry`: whether to stop on the first line executed
+//!
+//! Other tools debug their own executions with a `DapServer`, e.g. `transaction-replay`
+//! debugs historical transactions.
+
+mod debugger;
+pub mod protocol;
+pub mod server;
+pub mod sources;
+
+pub use server::{DapServer, LaunchRequest};
+pub use sources::SourceIndex;
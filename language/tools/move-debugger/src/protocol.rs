@@ -0,0 +1,117 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The wire format of the Debug Adapter Protocol: JSON messages, each preceded by a
+//! `Content-Length` header. See https://microsoft.github.io/debug-adapter-protocol/specification
+//! for the messages themselves.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+const CONTENT_LENGTH: &str = "Content-Length:";
+
+/// A request of the client.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Request {
+    pub seq: i64,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// Read the next message from `reader`. Returns `None` at the end of the input.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(length) = header.strip_prefix(CONTENT_LENGTH) {
+            content_length = Some(length.trim().parse::<usize>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid Content-Length header")
+            })?);
+        }
+    }
+    let content_length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Read the next request from `reader`, skipping the other messages. Returns `None` at the end
+/// of the input.
+pub fn read_request<R: BufRead>(reader: &mut R) -> io::Result<Option<Request>> {
+    while let Some(message) = read_message(reader)? {
+        if message["type"] == "request" {
+            return Ok(Some(serde_json::from_value(message)?));
+        }
+    }
+    Ok(None)
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "{} {}\r\n\r\n{}",
+        CONTENT_LENGTH,
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
+
+/// The sending end of the connection to the client.
+pub struct Output {
+    writer: Box<dyn Write + Send>,
+    seq: i64,
+}
+
+impl Output {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer, seq: 0 }
+    }
+
+    pub fn respond(&mut self, request: &Request, body: Value) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "command": request.command,
+            "success": true,
+            "body": body,
+        }));
+    }
+
+    pub fn respond_error(&mut self, request: &Request, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "command": request.command,
+            "success": false,
+            "message": message,
+        }));
+    }
+
+    pub fn event(&mut self, event: &str, body: Value) {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }));
+    }
+
+    fn send(&mut self, mut message: Value) {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        // Failures mean that the client is gone, and there is nobody left to report them to.
+        let _ = write_message(&mut self.writer, &message);
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    debugger::{Control, Debugger, Mode, Shared, THREAD_ID},
+    protocol::{read_request, Output, Request},
+    sources::SourceIndex,
+};
+use anyhow::{bail, Result};
+use move_vm_runtime::debugger::DebugHook;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// A server of the Debug Adapter Protocol.
+///
+/// The requests of the client are read on a separate thread, so that the code is debugged on
+/// the thread of the caller:
+/// 1. `wait_for_launch` returns the arguments of the `launch` request of the client, which
+///    describe what to debug.
+/// 2. `debug` executes the code while the client sets breakpoints, steps through it and
+///    inspects it.
+/// 3. `wait_for_disconnect` returns once the client is done.
+pub struct DapServer {
+    shared: Arc<Shared>,
+    requests: Arc<Mutex<Receiver<Request>>>,
+}
+
+/// The `launch` request of the client.
+pub struct LaunchRequest {
+    request: Request,
+}
+
+impl LaunchRequest {
+    /// The arguments of the request. They are specific to the debugger, except for
+    /// `stopOnEntry`, which stops the execution on the first line of source code executed.
+    pub fn arguments(&self) -> &Value {
+        &self.request.arguments
+    }
+}
+
+impl DapServer {
+    pub fn new<R: BufRead + Send + 'static>(input: R, output: Box<dyn Write + Send>) -> Self {
+        let shared = Arc::new(Shared {
+            output: Mutex::new(Output::new(output)),
+            control: Mutex::new(Control {
+                sources: None,
+                breakpoints: HashMap::new(),
+                mode: Mode::Run,
+                disconnected: false,
+            }),
+        });
+        let (sender, receiver) = mpsc::channel();
+        let reader_shared = Arc::clone(&shared);
+        thread::spawn(move || read_requests(input, &reader_shared, sender));
+        Self {
+            shared,
+            requests: Arc::new(Mutex::new(receiver)),
+        }
+    }
+
+    /// A server talking to the client over the standard input and output.
+    pub fn stdio() -> Self {
+        Self::new(BufReader::new(io::stdin()), Box::new(io::stdout()))
+    }
+
+    /// Wait for the client to request the launch of the execution.
+    pub fn wait_for_launch(&self) -> Result<LaunchRequest> {
+        loop {
+            let request = self.next_request()?;
+            match request.command.as_str() {
+                "launch" => return Ok(LaunchRequest { request }),
+                "disconnect" => {
+                    self.shared.respond(&request, Ok(json!({})));
+                    bail!("The client disconnected before launching an execution");
+                }
+                command => self.shared.respond(
+                    &request,
+                    Err(format!("Unsupported request before launch: {}", command)),
+                ),
+            }
+        }
+    }
+
+    /// Report to the client that the execution could not be launched.
+    pub fn reject_launch(&self, launch: LaunchRequest, error: &anyhow::Error) {
+        self.shared
+            .respond(&launch.request, Err(format!("{:#}", error)));
+    }
+
+    /// Debug `execute`, which executes code compiled from `sources` in sessions debugged with
+    /// the hook it is given, once the client is configured.
+    pub fn debug<T>(
+        &self,
+        launch: LaunchRequest,
+        sources: Arc<SourceIndex>,
+        execute: impl FnOnce(Arc<dyn DebugHook>) -> T,
+    ) -> Result<T> {
+        let stop_on_entry = launch.arguments()["stopOnEntry"].as_bool().unwrap_or(false);
+        {
+            let mut control = self.shared.control.lock().unwrap();
+            control.sources = Some(Arc::clone(&sources));
+            if stop_on_entry {
+                control.mode = Mode::Entry;
+            }
+        }
+        self.shared.respond(&launch.request, Ok(json!({})));
+        // The client sets its breakpoints, then signals the end of its configuration.
+        self.shared.event("initialized", json!({}));
+        loop {
+            let request = self.next_request()?;
+            match request.command.as_str() {
+                "configurationDone" => {
+                    self.shared.respond(&request, Ok(json!({})));
+                    break;
+                }
+                "disconnect" => {
+                    self.shared.respond(&request, Ok(json!({})));
+                    bail!("The client disconnected before the execution started");
+                }
+                command => self.shared.respond(
+                    &request,
+                    Err(format!("Unsupported request before execution: {}", command)),
+                ),
+            }
+        }
+
+        let result = execute(Arc::new(Debugger::new(
+            Arc::clone(&self.shared),
+            sources,
+            Arc::clone(&self.requests),
+        )));
+        self.shared.event("terminated", json!({}));
+        Ok(result)
+    }
+
+    /// Show `text` to the user in the debug console of the client.
+    pub fn output(&self, text: &str) {
+        self.shared.event(
+            "output",
+            json!({
+                "category": "console",
+                "output": format!("{}\n", text),
+            }),
+        );
+    }
+
+    /// Wait for the client to disconnect, once the execution terminated.
+    pub fn wait_for_disconnect(&self) {
+        // The request may have been served while the execution was suspended.
+        if self.shared.control.lock().unwrap().disconnected {
+            return;
+        }
+        while let Ok(request) = self.next_request() {
+            if request.command == "disconnect" {
+                self.shared.respond(&request, Ok(json!({})));
+                return;
+            }
+            self.shared.respond(
+                &request,
+                Err(format!(
+                    "Unsupported request after execution: {}",
+                    request.command
+                )),
+            );
+        }
+    }
+
+    fn next_request(&self) -> Result<Request> {
+        match self.requests.lock().unwrap().recv() {
+            Ok(request) => Ok(request),
+            Err(_) => bail!("The connection to the client is closed"),
+        }
+    }
+}
+
+/// Read the requests of the client. The requests which do not depend on the execution are
+/// served right away, even while the code executes. The others are sent to the thread
+/// executing the code.
+fn read_requests<R: BufRead>(mut input: R, shared: &Shared, sender: Sender<Request>) {
+    while let Ok(Some(request)) = read_request(&mut input) {
+        match request.command.as_str() {
+            "initialize" => shared.respond(
+                &request,
+                Ok(json!({ "supportsConfigurationDoneRequest": true })),
+            ),
+            "threads" => shared.respond(
+                &request,
+                Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })),
+            ),
+            "setBreakpoints" => {
+                let response = set_breakpoints(shared, &request.arguments);
+                shared.respond(&request, Ok(response))
+            }
+            "setExceptionBreakpoints" => shared.respond(&request, Ok(json!({}))),
+            "pause" => {
+                shared.control.lock().unwrap().mode = Mode::Pause;
+                shared.respond(&request, Ok(json!({})))
+            }
+            _ => {
+                if request.command == "disconnect" {
+                    // Let the execution complete without stopping.
+                    let mut control = shared.control.lock().unwrap();
+                    control.breakpoints.clear();
+                    control.mode = Mode::Run;
+                }
+                if sender.send(request).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn set_breakpoints(shared: &Shared, arguments: &Value) -> Value {
+    let lines: Vec<u64> = arguments["breakpoints"]
+        .as_array()
+        .map(|breakpoints| {
+            breakpoints
+                .iter()
+                .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut control = shared.control.lock().unwrap();
+    let file = match (&control.sources, arguments["source"]["path"].as_str()) {
+        (Some(sources), Some(path)) => sources
+            .file(Path::new(path))
+            .map(|file| (Arc::clone(sources), file)),
+        _ => None,
+    };
+    let breakpoints = lines
+        .iter()
+        .map(|line| {
+            let verified = match &file {
+                Some((sources, file)) => sources.has_code(file, *line as usize),
+                None => false,
+            };
+            json!({ "verified": verified, "line": line })
+        })
+        .collect::<Vec<_>>();
+    if let Some((sources, file)) = file {
+        let lines: BTreeSet<_> = lines
+            .into_iter()
+            .map(|line| line as usize)
+            .filter(|line| sources.has_code(file, *line))
+            .collect();
+        control.breakpoints.insert(file, lines);
+    }
+    json!({ "breakpoints": breakpoints })
+}
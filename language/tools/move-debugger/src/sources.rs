@@ -0,0 +1,259 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The Move sources of the code being debugged, and the mapping of the bytecode to them.
+
+use anyhow::{anyhow, Context, Result};
+use bytecode_source_map::source_map::SourceMap;
+use libra_state_view::StateView;
+use libra_types::access_path::AccessPath;
+use move_core_types::language_storage::ModuleId;
+use move_ir_types::location::Loc;
+use move_lang::{
+    compiled_unit::CompiledUnit,
+    errors::{self, FilesSourceText},
+    shared::Address,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+use vm::{
+    access::ModuleAccess,
+    file_format::{CodeOffset, FunctionDefinitionIndex},
+};
+
+/// A position in a source file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceLocation {
+    /// The name of the file given to the compiler.
+    pub file: &'static str,
+    /// The canonical path of the file, which is how clients designate it.
+    pub path: PathBuf,
+    /// The line of the position, starting at 1.
+    pub line: usize,
+    /// The column of the position, starting at 1.
+    pub column: usize,
+}
+
+/// The bytecode compiled from Move sources along with its source maps.
+pub struct SourceIndex {
+    /// The offsets of the starts of the lines of each file.
+    line_starts: HashMap<&'static str, Vec<usize>>,
+    /// The lines of each file where the code of an instruction starts.
+    code_lines: HashMap<&'static str, BTreeSet<usize>>,
+    /// The files by their canonical path.
+    paths: BTreeMap<PathBuf, &'static str>,
+    module_source_maps: BTreeMap<ModuleId, SourceMap<Loc>>,
+    /// The modules serialized, by their access path in storage.
+    module_blobs: BTreeMap<AccessPath, Vec<u8>>,
+    /// The scripts serialized, with their source map.
+    scripts: Vec<(Vec<u8>, SourceMap<Loc>)>,
+    /// The script executed, as an index in `scripts`.
+    executed_script: Option<usize>,
+}
+
+impl SourceIndex {
+    /// Compile the Move source files `targets`, or the ones in the directories `targets`.
+    pub fn compile(targets: &[String], sender: Option<Address>) -> Result<Self> {
+        let (files, units) = move_lang::move_compile_no_report(targets, &[], sender)?;
+        match units {
+            Ok(units) => Self::new(files, units),
+            Err(errors) => Err(anyhow!(
+                "{}",
+                String::from_utf8_lossy(&errors::report_errors_to_buffer(files, errors))
+            )),
+        }
+    }
+
+    pub fn new(files: FilesSourceText, units: Vec<CompiledUnit>) -> Result<Self> {
+        let mut index = Self {
+            line_starts: HashMap::new(),
+            code_lines: HashMap::new(),
+            paths: BTreeMap::new(),
+            module_source_maps: BTreeMap::new(),
+            module_blobs: BTreeMap::new(),
+            scripts: vec![],
+            executed_script: None,
+        };
+        for (file, text) in &files {
+            let line_starts = std::iter::once(0)
+                .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+                .collect();
+            index.line_starts.insert(*file, line_starts);
+            if let Ok(path) = fs::canonicalize(file) {
+                index.paths.insert(path, *file);
+            }
+        }
+        for unit in units {
+            let (function_count, source_map) = match unit {
+                CompiledUnit::Module {
+                    module, source_map, ..
+                } => {
+                    let module_id = module.self_id();
+                    let mut blob = vec![];
+                    module
+                        .serialize(&mut blob)
+                        .with_context(|| format!("Unable to serialize module {}", module_id))?;
+                    index
+                        .module_blobs
+                        .insert(AccessPath::code_access_path(&module_id), blob);
+                    index
+                        .module_source_maps
+                        .insert(module_id.clone(), source_map);
+                    (
+                        module.function_defs().len(),
+                        &index.module_source_maps[&module_id],
+                    )
+                }
+                CompiledUnit::Script {
+                    script, source_map, ..
+                } => {
+                    let mut blob = vec![];
+                    script
+                        .serialize(&mut blob)
+                        .context("Unable to serialize script")?;
+                    index.scripts.push((blob, source_map));
+                    (1, &index.scripts.last().unwrap().1)
+                }
+            };
+            for function_index in 0..function_count {
+                let function_source_map = match source_map
+                    .get_function_source_map(FunctionDefinitionIndex(function_index as u16))
+                {
+                    Ok(function_source_map) => function_source_map,
+                    Err(_) => continue,
+                };
+                for loc in function_source_map.code_map.values() {
+                    let start = loc.span().start().to_usize();
+                    if let Some(line) = line_of(&index.line_starts, loc.file(), start) {
+                        index.code_lines.entry(loc.file()).or_default().insert(line);
+                    }
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Designate the script being executed by its bytecode. Returns false if it was not compiled
+    /// from the sources.
+    pub fn set_executed_script(&mut self, code: &[u8]) -> bool {
+        self.executed_script = self.scripts.iter().position(|(blob, _)| blob == code);
+        self.executed_script.is_some()
+    }
+
+    /// The source location of the instruction at `pc` in a function of `module`, or of the
+    /// executed script if `module` is `None`.
+    pub fn location(
+        &self,
+        module: Option<&ModuleId>,
+        function_index: FunctionDefinitionIndex,
+        pc: CodeOffset,
+    ) -> Option<SourceLocation> {
+        let loc = self
+            .source_map(module)?
+            .get_code_location(function_index, pc)
+            .ok()?;
+        let start = loc.span().start().to_usize();
+        let line = line_of(&self.line_starts, loc.file(), start)?;
+        let column = start - self.line_starts[&loc.file()][line - 1] + 1;
+        Some(SourceLocation {
+            file: loc.file(),
+            path: self.path(loc.file()),
+            line,
+            column,
+        })
+    }
+
+    /// The name of the local at `index` in a function of `module`, or of the executed script if
+    /// `module` is `None`. Temporaries introduced by the compiler have no name.
+    pub fn local_name(
+        &self,
+        module: Option<&ModuleId>,
+        function_index: FunctionDefinitionIndex,
+        index: usize,
+    ) -> Option<String> {
+        let (name, _) = self
+            .source_map(module)?
+            .get_parameter_or_local_name(function_index, index as u64)
+            .ok()?;
+        // The compiler names locals `<name>#<n>`, and temporaries `tmp%#<n>`.
+        if name.starts_with("tmp%") {
+            return None;
+        }
+        Some(match name.find('#') {
+            Some(end) => name[..end].to_string(),
+            None => name,
+        })
+    }
+
+    /// The module `module_id` compiled from the sources, serialized.
+    pub fn module(&self, module_id: &ModuleId) -> Option<&[u8]> {
+        self.module_blobs
+            .get(&AccessPath::code_access_path(module_id))
+            .map(|blob| blob.as_slice())
+    }
+
+    /// The file with the given path, if it is one of the sources.
+    pub fn file(&self, path: &Path) -> Option<&'static str> {
+        let path = fs::canonicalize(path).ok()?;
+        self.paths.get(&path).copied()
+    }
+
+    /// Whether the code of an instruction starts at `line` of `file`, that is whether the
+    /// execution can stop there.
+    pub fn has_code(&self, file: &'static str, line: usize) -> bool {
+        self.code_lines
+            .get(file)
+            .map_or(false, |lines| lines.contains(&line))
+    }
+
+    fn source_map(&self, module: Option<&ModuleId>) -> Option<&SourceMap<Loc>> {
+        match module {
+            Some(module_id) => self.module_source_maps.get(module_id),
+            None => self
+                .executed_script
+                .map(|script_index| &self.scripts[script_index].1),
+        }
+    }
+
+    fn path(&self, file: &'static str) -> PathBuf {
+        self.paths
+            .iter()
+            .find(|(_, path_file)| **path_file == file)
+            .map_or_else(|| PathBuf::from(file), |(path, _)| path.clone())
+    }
+}
+
+/// The modules compiled from the sources, which describe the types of the values of the
+/// execution.
+impl StateView for SourceIndex {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        Ok(self.module_blobs.get(access_path).cloned())
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths
+            .iter()
+            .map(|access_path| self.get(access_path))
+            .collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
+
+/// The line, starting at 1, of the byte at `offset` in `file`.
+fn line_of(
+    line_starts: &HashMap<&'static str, Vec<usize>>,
+    file: &'static str,
+    offset: usize,
+) -> Option<usize> {
+    let line_starts = line_starts.get(file)?;
+    Some(match line_starts.binary_search(&offset) {
+        Ok(line_index) => line_index + 1,
+        Err(line_index) => line_index,
+    })
+}
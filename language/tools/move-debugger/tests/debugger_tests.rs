@@ -0,0 +1,175 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_debugger::protocol::{read_message, write_message};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    io::BufReader,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+const STEPS: &str = "tests/sources/steps.move";
+
+/// A client of the `move-debugger` binary.
+struct Client {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: i64,
+    /// The messages received but not yet expected.
+    pending: Vec<Value>,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_move-debugger"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = process.stdin.take().unwrap();
+        let stdout = BufReader::new(process.stdout.take().unwrap());
+        Self {
+            process,
+            stdin,
+            stdout,
+            seq: 0,
+            pending: vec![],
+        }
+    }
+
+    /// Send a request and return the body of its response, which must succeed.
+    fn request(&mut self, command: &str, arguments: Value) -> Value {
+        self.seq += 1;
+        let seq = self.seq;
+        write_message(
+            &mut self.stdin,
+            &json!({
+                "seq": seq,
+                "type": "request",
+                "command": command,
+                "arguments": arguments,
+            }),
+        )
+        .unwrap();
+        let response =
+            self.expect(|message| message["type"] == "response" && message["request_seq"] == seq);
+        assert_eq!(response["success"], true, "{}", response);
+        response["body"].clone()
+    }
+
+    /// Wait for an event and return its body.
+    fn event(&mut self, event: &str) -> Value {
+        self.expect(|message| message["type"] == "event" && message["event"] == event)["body"]
+            .clone()
+    }
+
+    fn expect(&mut self, predicate: impl Fn(&Value) -> bool) -> Value {
+        if let Some(index) = self.pending.iter().position(|message| predicate(message)) {
+            return self.pending.remove(index);
+        }
+        loop {
+            let message = read_message(&mut self.stdout)
+                .unwrap()
+                .expect("the debugger exited");
+            if predicate(&message) {
+                return message;
+            }
+            self.pending.push(message);
+        }
+    }
+
+    /// The innermost frame of the call stack: its id, function and line.
+    fn top_frame(&mut self) -> (u64, String, u64) {
+        let body = self.request("stackTrace", json!({ "threadId": 1 }));
+        let frame = &body["stackFrames"][0];
+        (
+            frame["id"].as_u64().unwrap(),
+            frame["name"].as_str().unwrap().to_string(),
+            frame["line"].as_u64().unwrap(),
+        )
+    }
+
+    fn locals(&mut self, frame_id: u64) -> Vec<(String, String)> {
+        let scopes = self.request("scopes", json!({ "frameId": frame_id }));
+        assert_eq!(scopes["scopes"][0]["name"], "Locals");
+        let reference = scopes["scopes"][0]["variablesReference"].clone();
+        let body = self.request("variables", json!({ "variablesReference": reference }));
+        body["variables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variable| {
+                (
+                    variable["name"].as_str().unwrap().to_string(),
+                    variable["value"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_breakpoints_and_steps() {
+    let path = fs::canonicalize(STEPS).unwrap();
+    let mut client = Client::start();
+    client.request("initialize", json!({ "adapterID": "move" }));
+    client.request(
+        "launch",
+        json!({
+            "sources": [STEPS],
+            "function": "0x2::Steps::run",
+        }),
+    );
+    client.event("initialized");
+    let breakpoints = client.request(
+        "setBreakpoints",
+        json!({
+            "source": { "path": path },
+            "breakpoints": [{ "line": 7 }, { "line": 9 }, { "line": 11 }],
+        }),
+    );
+    let verified: Vec<_> = breakpoints["breakpoints"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|breakpoint| breakpoint["verified"].as_bool().unwrap())
+        .collect();
+    assert_eq!(verified, vec![false, true, true]);
+    client.request("configurationDone", json!({}));
+
+    assert_eq!(client.event("stopped")["reason"], "breakpoint");
+    let (_, function, line) = client.top_frame();
+    assert!(function.ends_with("::Steps::run"), "{}", function);
+    assert_eq!(line, 9);
+
+    client.request("stepIn", json!({ "threadId": 1 }));
+    assert_eq!(client.event("stopped")["reason"], "step");
+    let (frame_id, function, line) = client.top_frame();
+    assert!(function.ends_with("::Steps::add"), "{}", function);
+    assert_eq!(line, 4);
+    let locals = client.locals(frame_id);
+    assert!(locals.contains(&("a".to_string(), "1".to_string())));
+    assert!(locals.contains(&("b".to_string(), "2".to_string())));
+
+    client.request("stepOut", json!({ "threadId": 1 }));
+    assert_eq!(client.event("stopped")["reason"], "step");
+    let (_, function, _) = client.top_frame();
+    assert!(function.ends_with("::Steps::run"), "{}", function);
+
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.event("stopped")["reason"], "breakpoint");
+    let (_, _, line) = client.top_frame();
+    assert_eq!(line, 11);
+
+    client.request("continue", json!({ "threadId": 1 }));
+    client.event("terminated");
+    let output = client.event("output");
+    assert!(output["output"]
+        .as_str()
+        .unwrap()
+        .contains("Executed successfully"));
+    client.request("disconnect", json!({}));
+    assert!(client.process.wait().unwrap().success());
+}
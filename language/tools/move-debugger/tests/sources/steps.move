@@ -0,0 +1,14 @@
+address 0x2 {
+module Steps {
+    fun add(a: u64, b: u64): u64 {
+        let sum = a + b;
+        sum
+    }
+
+    public fun run() {
+        let y = add(1, 2);
+        let z = add(y, 3);
+        assert(z == 6, 42);
+    }
+}
+}
@@ -0,0 +1,812 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The analysis of the sources of a workspace: the errors found by the compiler, and the symbols
+//! of the typed program, i.e. the definitions of its modules and the places they are referenced.
+
+use move_ir_types::location::Loc;
+use move_lang::{
+    errors::{Errors, FilesSourceText},
+    naming::ast::{self as N, StructFields, TParam, TypeName_, Type_},
+    parser::ast::{FunctionVisibility, Kind_, ModuleIdent},
+    shared::Address,
+    typing::ast as T,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// A position in a file. Both the line and the character start at 0.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// A range of a file, from `start` included to `end` excluded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Location {
+    pub file: &'static str,
+    pub range: Range,
+}
+
+/// An error found by the compiler.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub range: Range,
+    pub message: String,
+    /// The other places of the sources involved in the error.
+    pub related: Vec<(Location, String)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum SymbolKind {
+    Module,
+    Struct,
+    Function,
+    Constant,
+}
+
+/// A member of a module which can be completed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: SymbolKind,
+    /// The signature of the member.
+    pub detail: String,
+}
+
+/// The sources to analyze. Targets and dependencies are both given by their file name, and
+/// their text is in `files`.
+pub struct Sources {
+    pub targets: Vec<&'static str>,
+    pub deps: Vec<&'static str>,
+    pub files: FilesSourceText,
+}
+
+pub struct Analysis {
+    /// The errors of the sources, by file. Files without errors are not included.
+    pub diagnostics: BTreeMap<&'static str, Vec<Diagnostic>>,
+    /// The symbols of the last sources which could be parsed, so that sources being edited can
+    /// still be navigated while they do not parse.
+    symbols: Arc<Symbols>,
+}
+
+/// The key of a symbol. Modules are designated by their address and name.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct SymbolKey {
+    module: (Address, String),
+    kind: SymbolKind,
+    name: String,
+}
+
+struct Symbol {
+    loc: Loc,
+    is_public: bool,
+    signature: String,
+}
+
+/// A place in the sources where a symbol is referenced, or defined.
+struct Reference {
+    start: usize,
+    end: usize,
+    key: SymbolKey,
+}
+
+#[derive(Default)]
+struct Symbols {
+    /// The sources the symbols were found in.
+    files: FilesSourceText,
+    line_starts: HashMap<&'static str, Vec<usize>>,
+    symbols: BTreeMap<SymbolKey, Symbol>,
+    /// The references of each file, sorted by position.
+    references: HashMap<&'static str, Vec<Reference>>,
+}
+
+impl Analysis {
+    /// Check `sources`. If they do not parse, the symbols of `previous` are kept.
+    pub fn check(
+        sources: &Sources,
+        sender_opt: Option<Address>,
+        previous: Option<&Analysis>,
+    ) -> Self {
+        let result = move_lang::move_check_sources_to_typing_no_report(
+            &sources.targets,
+            &sources.deps,
+            &sources.files,
+            sender_opt,
+        );
+        let (symbols, errors) = match result {
+            Ok((tprog, errors)) => {
+                let symbols = Arc::new(Symbols::new(sources.files.clone(), &tprog));
+                // The later phases of the compiler assume that the program typechecks
+                let errors = if errors.is_empty() {
+                    move_lang::move_check_typed_program(tprog)
+                } else {
+                    errors
+                };
+                (symbols, errors)
+            }
+            Err(errors) => {
+                let symbols =
+                    previous.map_or_else(Arc::default, |analysis| Arc::clone(&analysis.symbols));
+                (symbols, errors)
+            }
+        };
+        Self {
+            diagnostics: diagnostics(&sources.files, errors),
+            symbols,
+        }
+    }
+
+    /// The signature of the symbol at `position` of `file`, along with the range of the
+    /// reference to it.
+    pub fn hover(&self, file: &'static str, position: Position) -> Option<(Range, String)> {
+        let symbols = &self.symbols;
+        let reference = symbols.reference_at(file, position)?;
+        let symbol = symbols.symbols.get(&reference.key)?;
+        let range = Range {
+            start: symbols.position(file, reference.start)?,
+            end: symbols.position(file, reference.end)?,
+        };
+        Some((range, symbol.signature.clone()))
+    }
+
+    /// The definition of the symbol at `position` of `file`.
+    pub fn definition(&self, file: &'static str, position: Position) -> Option<Location> {
+        let symbols = &self.symbols;
+        let reference = symbols.reference_at(file, position)?;
+        symbols.location(symbols.symbols.get(&reference.key)?.loc)
+    }
+
+    /// The members of the module designated before `position` of `file`, e.g. with `Vector::`
+    /// or `0x1::Vector::`, whose current contents are `text`. Modules are designated by their
+    /// name or by an alias of the `use` declarations of `text`. Members which cannot be
+    /// accessed from `file`, e.g. the private functions of other modules, are not included.
+    pub fn completions(
+        &self,
+        file: &'static str,
+        text: &str,
+        position: Position,
+    ) -> Vec<Completion> {
+        let prefix = match text.lines().nth(position.line) {
+            Some(line) => line.get(..position.character).unwrap_or(line),
+            None => return vec![],
+        };
+        let (address, module_name) = match module_access(prefix) {
+            Some(module_access) => module_access,
+            None => return vec![],
+        };
+        let symbols = &self.symbols;
+        let enclosing_module = symbols.enclosing_module(file, position);
+        let modules: BTreeSet<(Address, String)> = match (address, module_name.as_str()) {
+            (None, "Self") => enclosing_module.iter().cloned().collect(),
+            (None, name) => match resolve_alias(text, name) {
+                Some(module) => vec![module].into_iter().collect(),
+                None => symbols.modules_named(name),
+            },
+            (Some(address), name) => vec![(address, name.to_string())].into_iter().collect(),
+        };
+
+        let mut completions = vec![];
+        for (key, symbol) in &symbols.symbols {
+            if !modules.contains(&key.module) || key.kind == SymbolKind::Module {
+                continue;
+            }
+            let is_accessible = match key.kind {
+                SymbolKind::Module | SymbolKind::Struct => true,
+                SymbolKind::Function => {
+                    symbol.is_public || enclosing_module.as_ref() == Some(&key.module)
+                }
+                SymbolKind::Constant => enclosing_module.as_ref() == Some(&key.module),
+            };
+            if is_accessible {
+                completions.push(Completion {
+                    label: key.name.clone(),
+                    kind: key.kind,
+                    detail: symbol.signature.clone(),
+                });
+            }
+        }
+        completions
+    }
+}
+
+impl Symbols {
+    fn new(files: FilesSourceText, prog: &T::Program) -> Self {
+        let line_starts = files
+            .iter()
+            .map(|(file, text)| (*file, line_starts(text)))
+            .collect();
+        let mut symbols = Self {
+            files,
+            line_starts,
+            symbols: BTreeMap::new(),
+            references: HashMap::new(),
+        };
+        for (module, mdef) in prog.modules.iter() {
+            symbols.module(&module, mdef);
+        }
+        for script in prog.scripts.values() {
+            symbols.function_signature(&script.function.signature);
+            symbols.function_body(&script.function);
+        }
+        for references in symbols.references.values_mut() {
+            references.sort_by_key(|reference| (reference.start, reference.end));
+        }
+        symbols
+    }
+
+    //**********************************************************************************************
+    // Definitions
+    //**********************************************************************************************
+
+    fn module(&mut self, module: &ModuleIdent, mdef: &T::ModuleDefinition) {
+        let module_name = &module.0.value.name.0;
+        self.define(
+            module,
+            SymbolKind::Module,
+            module_name.loc,
+            &module_name.value,
+            true,
+            format!("module {}", module),
+        );
+        for (name, sdef) in mdef.structs.iter() {
+            self.define(
+                module,
+                SymbolKind::Struct,
+                name.0.loc,
+                &name.0.value,
+                true,
+                struct_signature(&name.0.value, sdef),
+            );
+            if let StructFields::Defined(fields) = &sdef.fields {
+                for (_, (_, ty)) in fields.iter() {
+                    self.type_(ty);
+                }
+            }
+        }
+        for (name, constant) in mdef.constants.iter() {
+            self.define(
+                module,
+                SymbolKind::Constant,
+                name.0.loc,
+                &name.0.value,
+                false,
+                format!("const {}: {}", name, type_string(&constant.signature)),
+            );
+            self.type_(&constant.signature);
+        }
+        for (name, function) in mdef.functions.iter() {
+            self.define(
+                module,
+                SymbolKind::Function,
+                name.0.loc,
+                &name.0.value,
+                matches!(function.visibility, FunctionVisibility::Public(_)),
+                function_signature(&name.0.value, function),
+            );
+            self.function_signature(&function.signature);
+            for (name, loc) in &function.acquires {
+                self.reference(module, SymbolKind::Struct, &name.0.value, *loc);
+            }
+            self.function_body(function);
+        }
+    }
+
+    fn define(
+        &mut self,
+        module: &ModuleIdent,
+        kind: SymbolKind,
+        loc: Loc,
+        name: &str,
+        is_public: bool,
+        signature: String,
+    ) {
+        let key = symbol_key(module, kind, name);
+        self.symbols.insert(
+            key.clone(),
+            Symbol {
+                loc,
+                is_public,
+                signature,
+            },
+        );
+        self.add_reference(key, loc);
+    }
+
+    //**********************************************************************************************
+    // References
+    //**********************************************************************************************
+
+    fn reference(&mut self, module: &ModuleIdent, kind: SymbolKind, name: &str, loc: Loc) {
+        self.add_reference(symbol_key(module, kind, name), loc);
+    }
+
+    fn module_reference(&mut self, module: &ModuleIdent) {
+        let name = &module.0.value.name.0;
+        self.reference(module, SymbolKind::Module, &name.value, name.loc);
+    }
+
+    /// Add a reference to `key` at `loc`, if the name of the symbol is written there. The
+    /// locations of the compiler can designate where a name was declared rather than where it is
+    /// used, e.g. for aliases, and inferred types have the location of what they were inferred
+    /// from.
+    fn add_reference(&mut self, key: SymbolKey, loc: Loc) {
+        let start = loc.span().start().to_usize();
+        let end = loc.span().end().to_usize();
+        let is_written = self
+            .files
+            .get(loc.file())
+            .and_then(|text| text.get(start..end))
+            .map_or(false, |written| written == key.name);
+        if is_written {
+            self.references
+                .entry(loc.file())
+                .or_default()
+                .push(Reference { start, end, key });
+        }
+    }
+
+    fn function_signature(&mut self, signature: &N::FunctionSignature) {
+        for (_, ty) in &signature.parameters {
+            self.type_(ty);
+        }
+        self.type_(&signature.return_type);
+    }
+
+    fn function_body(&mut self, function: &T::Function) {
+        if let T::FunctionBody_::Defined(sequence) = &function.body.value {
+            self.sequence(sequence);
+        }
+    }
+
+    fn type_(&mut self, ty: &N::Type) {
+        match &ty.value {
+            Type_::Ref(_, ty) => self.type_(ty),
+            Type_::Apply(_, type_name, tys) => {
+                if let TypeName_::ModuleType(module, name) = &type_name.value {
+                    self.module_reference(module);
+                    self.reference(module, SymbolKind::Struct, &name.0.value, name.0.loc);
+                }
+                self.types(tys);
+            }
+            Type_::Unit | Type_::Param(_) | Type_::Var(_) | Type_::Anything => (),
+            Type_::UnresolvedError => (),
+        }
+    }
+
+    fn types(&mut self, tys: &[N::Type]) {
+        for ty in tys {
+            self.type_(ty);
+        }
+    }
+
+    fn sequence(&mut self, sequence: &T::Sequence) {
+        use T::SequenceItem_ as S;
+        for item in sequence {
+            match &item.value {
+                S::Seq(e) => self.exp(e),
+                S::Declare(lvalues) => self.lvalues(lvalues),
+                S::Bind(lvalues, _, e) => {
+                    self.lvalues(lvalues);
+                    self.exp(e);
+                }
+            }
+        }
+    }
+
+    fn lvalues(&mut self, lvalues: &T::LValueList) {
+        for lvalue in &lvalues.value {
+            self.lvalue(lvalue);
+        }
+    }
+
+    fn lvalue(&mut self, lvalue: &T::LValue) {
+        use T::LValue_ as L;
+        match &lvalue.value {
+            L::Ignore => (),
+            L::Var(_, ty) => self.type_(ty),
+            L::Unpack(module, name, tys, fields)
+            | L::BorrowUnpack(_, module, name, tys, fields) => {
+                self.module_reference(module);
+                self.reference(module, SymbolKind::Struct, &name.0.value, name.0.loc);
+                self.types(tys);
+                for (_, (_, (_, lvalue))) in fields.iter() {
+                    self.lvalue(lvalue);
+                }
+            }
+        }
+    }
+
+    fn exp(&mut self, e: &T::Exp) {
+        use T::{BuiltinFunction_ as B, ExpListItem as I, UnannotatedExp_ as E};
+        match &e.exp.value {
+            E::ModuleCall(call) => {
+                self.module_reference(&call.module);
+                self.reference(
+                    &call.module,
+                    SymbolKind::Function,
+                    &call.name.0.value,
+                    call.name.0.loc,
+                );
+                self.types(&call.type_arguments);
+                self.exp(&call.arguments);
+            }
+            E::Builtin(builtin, args) => {
+                match &builtin.value {
+                    B::MoveTo(ty)
+                    | B::MoveFrom(ty)
+                    | B::BorrowGlobal(_, ty)
+                    | B::Exists(ty)
+                    | B::Freeze(ty) => self.type_(ty),
+                    B::Assert => (),
+                }
+                self.exp(args);
+            }
+            E::Constant(Some(module), name) => {
+                self.module_reference(module);
+                self.reference(module, SymbolKind::Constant, &name.0.value, name.0.loc);
+            }
+            E::Pack(module, name, tys, fields) => {
+                self.module_reference(module);
+                self.reference(module, SymbolKind::Struct, &name.0.value, name.0.loc);
+                self.types(tys);
+                for (_, (_, (_, e))) in fields.iter() {
+                    self.exp(e);
+                }
+            }
+
+            E::IfElse(e1, e2, e3) => {
+                self.exp(e1);
+                self.exp(e2);
+                self.exp(e3);
+            }
+            E::While(e1, e2) | E::Mutate(e1, e2) | E::BinopExp(e1, _, _, e2) => {
+                self.exp(e1);
+                self.exp(e2);
+            }
+            E::Loop { body: e, .. }
+            | E::Return(e)
+            | E::Abort(e)
+            | E::Dereference(e)
+            | E::UnaryExp(_, e)
+            | E::Borrow(_, e, _)
+            | E::TempBorrow(_, e) => self.exp(e),
+            E::Block(sequence) => self.sequence(sequence),
+            E::Assign(lvalues, _, e) => {
+                self.lvalues(lvalues);
+                self.exp(e);
+            }
+            E::ExpList(items) => {
+                for item in items {
+                    match item {
+                        I::Single(e, _) | I::Splat(_, e, _) => self.exp(e),
+                    }
+                }
+            }
+            E::Cast(e, ty) | E::Annotate(e, ty) => {
+                self.exp(e);
+                self.type_(ty);
+            }
+
+            E::Unit { .. }
+            | E::Value(_)
+            | E::InferredNum(_)
+            | E::Move { .. }
+            | E::Copy { .. }
+            | E::Use(_)
+            | E::Constant(None, _)
+            | E::Break
+            | E::Continue
+            | E::BorrowLocal(_, _)
+            | E::Spec(_, _)
+            | E::UnresolvedError => (),
+        }
+    }
+
+    //**********************************************************************************************
+    // Queries
+    //**********************************************************************************************
+
+    fn reference_at(&self, file: &'static str, position: Position) -> Option<&Reference> {
+        let offset = self.offset(file, position)?;
+        // A position at the end of a name, e.g. where the cursor is after typing it, is included.
+        self.references
+            .get(file)?
+            .iter()
+            .find(|reference| reference.start <= offset && offset <= reference.end)
+    }
+
+    /// The module whose definition is the last one starting before `position` of `file`.
+    fn enclosing_module(
+        &self,
+        file: &'static str,
+        position: Position,
+    ) -> Option<(Address, String)> {
+        let offset = self.offset(file, position)?;
+        self.symbols
+            .iter()
+            .filter(|(key, symbol)| {
+                key.kind == SymbolKind::Module
+                    && symbol.loc.file() == file
+                    && symbol.loc.span().start().to_usize() <= offset
+            })
+            .max_by_key(|(_, symbol)| symbol.loc.span().start())
+            .map(|(key, _)| key.module.clone())
+    }
+
+    fn modules_named(&self, name: &str) -> BTreeSet<(Address, String)> {
+        self.symbols
+            .keys()
+            .filter(|key| key.kind == SymbolKind::Module && key.name == name)
+            .map(|key| key.module.clone())
+            .collect()
+    }
+
+    fn offset(&self, file: &'static str, position: Position) -> Option<usize> {
+        Some(self.line_starts.get(file)?.get(position.line)? + position.character)
+    }
+
+    fn position(&self, file: &'static str, offset: usize) -> Option<Position> {
+        position(self.line_starts.get(file)?, offset)
+    }
+
+    fn location(&self, loc: Loc) -> Option<Location> {
+        let line_starts = self.line_starts.get(loc.file())?;
+        range(line_starts, loc).map(|range| Location {
+            file: loc.file(),
+            range,
+        })
+    }
+}
+
+fn symbol_key(module: &ModuleIdent, kind: SymbolKind, name: &str) -> SymbolKey {
+    SymbolKey {
+        module: (module.0.value.address, module.0.value.name.0.value.clone()),
+        kind,
+        name: name.to_string(),
+    }
+}
+
+//**************************************************************************************************
+// Diagnostics
+//**************************************************************************************************
+
+fn diagnostics(files: &FilesSourceText, errors: Errors) -> BTreeMap<&'static str, Vec<Diagnostic>> {
+    let line_starts: HashMap<_, _> = files
+        .iter()
+        .map(|(file, text)| (*file, line_starts(text)))
+        .collect();
+    let location = |loc: Loc| {
+        let range = range(line_starts.get(loc.file())?, loc)?;
+        Some(Location {
+            file: loc.file(),
+            range,
+        })
+    };
+    let mut diagnostics: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for error in errors {
+        let mut labels = error.into_iter();
+        let (primary, message) = match labels.next() {
+            Some((loc, message)) => match location(loc) {
+                Some(primary) => (primary, message),
+                None => continue,
+            },
+            None => continue,
+        };
+        let related = labels
+            .filter_map(|(loc, message)| Some((location(loc)?, message)))
+            .collect();
+        let diagnostic = Diagnostic {
+            range: primary.range,
+            message,
+            related,
+        };
+        let file_diagnostics = diagnostics.entry(primary.file).or_default();
+        // The compiler can report an error several times
+        if !file_diagnostics.contains(&diagnostic) {
+            file_diagnostics.push(diagnostic);
+        }
+    }
+    diagnostics
+}
+
+//**************************************************************************************************
+// Positions
+//**************************************************************************************************
+
+/// The offsets of the starts of the lines of `text`.
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+        .collect()
+}
+
+/// The position of `offset`. Move sources only contain ASCII characters, so that characters and
+/// bytes are the same.
+fn position(line_starts: &[usize], offset: usize) -> Option<Position> {
+    let line = match line_starts.binary_search(&offset) {
+        Ok(line) => line,
+        Err(0) => return None,
+        Err(next_line) => next_line - 1,
+    };
+    Some(Position {
+        line,
+        character: offset - line_starts[line],
+    })
+}
+
+fn range(line_starts: &[usize], loc: Loc) -> Option<Range> {
+    Some(Range {
+        start: position(line_starts, loc.span().start().to_usize())?,
+        end: position(line_starts, loc.span().end().to_usize())?,
+    })
+}
+
+//**************************************************************************************************
+// Completion
+//**************************************************************************************************
+
+/// The module designated at the end of `prefix`, which is followed by `::` and possibly the
+/// start of the name of a member, e.g. `Vector::` or `0x1::Vector::le`.
+fn module_access(prefix: &str) -> Option<(Option<Address>, String)> {
+    let is_identifier_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let prefix = prefix.trim_end_matches(is_identifier_char);
+    let prefix = prefix.strip_suffix("::")?;
+    let module = prefix.trim_end_matches(is_identifier_char);
+    let name = &prefix[module.len()..];
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let address = match module.strip_suffix("::") {
+        Some(module) => {
+            let address = module.trim_end_matches(is_identifier_char);
+            Some(Address::parse_str(&module[address.len()..]).ok()?)
+        }
+        None => None,
+    };
+    Some((address, name.to_string()))
+}
+
+/// The module designated by `alias` in the `use` declarations of `text`, e.g.
+/// `use 0x1::Vector;` or `use 0x1::Vector as V;`.
+fn resolve_alias(text: &str, alias: &str) -> Option<(Address, String)> {
+    text.split(';').find_map(|statement| {
+        let words: Vec<_> = statement.split_whitespace().collect();
+        let use_index = words.iter().rposition(|word| *word == "use")?;
+        let (module, alias_name) = match &words[use_index + 1..] {
+            [module] => (*module, None),
+            [module, "as", alias_name] => (*module, Some(*alias_name)),
+            _ => return None,
+        };
+        let separator = module.find("::")?;
+        let (address, name) = (&module[..separator], &module[separator + 2..]);
+        if alias_name.unwrap_or(name) != alias || name.contains("::") {
+            return None;
+        }
+        Some((Address::parse_str(address).ok()?, name.to_string()))
+    })
+}
+
+//**************************************************************************************************
+// Signatures
+//**************************************************************************************************
+
+fn function_signature(name: &str, function: &T::Function) -> String {
+    let signature = &function.signature;
+    let mut result = String::new();
+    if let FunctionVisibility::Public(_) = function.visibility {
+        result.push_str("public ");
+    }
+    if let T::FunctionBody_::Native = function.body.value {
+        result.push_str("native ");
+    }
+    let parameters = signature
+        .parameters
+        .iter()
+        .map(|(var, ty)| format!("{}: {}", var, type_string(ty)))
+        .collect::<Vec<_>>();
+    result.push_str(&format!(
+        "fun {}{}({})",
+        name,
+        type_parameters_string(&signature.type_parameters),
+        parameters.join(", ")
+    ));
+    if signature.return_type.value != Type_::Unit {
+        result.push_str(&format!(": {}", type_string(&signature.return_type)));
+    }
+    if !function.acquires.is_empty() {
+        let acquires = function
+            .acquires
+            .keys()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        result.push_str(&format!(" acquires {}", acquires.join(", ")));
+    }
+    result
+}
+
+fn struct_signature(name: &str, sdef: &N::StructDefinition) -> String {
+    let resource = if sdef.resource_opt.is_some() {
+        "resource "
+    } else {
+        ""
+    };
+    let type_parameters = type_parameters_string(&sdef.type_parameters);
+    match &sdef.fields {
+        StructFields::Native(_) => format!("native {}struct {}{}", resource, name, type_parameters),
+        StructFields::Defined(fields) => {
+            let mut fields = fields
+                .iter()
+                .map(|(field, (index, ty))| (*index, format!("{}: {}", field, type_string(ty))))
+                .collect::<Vec<_>>();
+            fields.sort();
+            let fields = fields
+                .into_iter()
+                .map(|(_, field)| field)
+                .collect::<Vec<_>>();
+            format!(
+                "{}struct {}{} {{ {} }}",
+                resource,
+                name,
+                type_parameters,
+                fields.join(", ")
+            )
+        }
+    }
+}
+
+fn type_parameters_string(type_parameters: &[TParam]) -> String {
+    if type_parameters.is_empty() {
+        return String::new();
+    }
+    let type_parameters = type_parameters
+        .iter()
+        .map(|tparam| {
+            let name = &tparam.user_specified_name.value;
+            match tparam.kind.value {
+                Kind_::Resource => format!("{}: {}", name, Kind_::RESOURCE_CONSTRAINT),
+                Kind_::Copyable => format!("{}: {}", name, Kind_::VALUE_CONSTRAINT),
+                Kind_::Affine | Kind_::Unknown => name.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("<{}>", type_parameters.join(", "))
+}
+
+/// The type as written in the sources, with struct names qualified by their module name only.
+fn type_string(ty: &N::Type) -> String {
+    match &ty.value {
+        Type_::Unit => "()".to_string(),
+        Type_::Ref(mut_, ty) => {
+            let mut_ = if *mut_ { "mut " } else { "" };
+            format!("&{}{}", mut_, type_string(ty))
+        }
+        Type_::Param(tparam) => tparam.user_specified_name.value.clone(),
+        Type_::Apply(_, type_name, tys) => {
+            let name = match &type_name.value {
+                TypeName_::Multiple(_) => return format!("({})", types_string(tys)),
+                TypeName_::Builtin(builtin) => builtin.value.to_string(),
+                TypeName_::ModuleType(module, name) => format!("{}::{}", module.0.value.name, name),
+            };
+            if tys.is_empty() {
+                name
+            } else {
+                format!("{}<{}>", name, types_string(tys))
+            }
+        }
+        Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => "_".to_string(),
+    }
+}
+
+fn types_string(tys: &[N::Type]) -> String {
+    tys.iter().map(type_string).collect::<Vec<_>>().join(", ")
+}
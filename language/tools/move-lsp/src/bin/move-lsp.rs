@@ -0,0 +1,11 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use move_lsp::LanguageServerConfig;
+use structopt::StructOpt;
+
+pub fn main() -> anyhow::Result<()> {
+    LanguageServerConfig::from_args().run_stdio()
+}
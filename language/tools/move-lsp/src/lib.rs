@@ -0,0 +1,69 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! A language server of Move, which editors talk to through the
+//! [Language Server Protocol](https://microsoft.github.io/language-server-protocol/) over the
+//! standard input and output.
+//!
+//! The server checks the Move sources of the workspace with the compiler whenever they change,
+//! including the unsaved contents of the files open in the editor, and offers:
+//! - diagnostics, for the errors found by the compiler
+//! - hover, showing the signatures of the functions, structs and constants referenced
+//! - go to definition, for modules, functions, structs and constants
+//! - completion of the members of modules, e.g. after `Vector::`, from the sources of the
+//!   workspace and of its dependencies
+//!
+//! The compiler has no incremental mode: each check parses, expands and typechecks the whole
+//! workspace again, dependencies included, even when a single target changed. Changes made while
+//! a check runs are coalesced into the next one, but checks of large workspaces, e.g. with the
+//! standard library as a dependency, still take time proportional to all of their sources.
+
+pub mod analysis;
+pub mod protocol;
+pub mod server;
+pub mod workspace;
+
+use crate::{server::LanguageServer, workspace::Workspace};
+use move_lang::{command_line as cli, shared::Address};
+use std::io::{self, BufReader};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "Move Language Server",
+    about = "Language server of Move, talking the Language Server Protocol over stdio."
+)]
+pub struct LanguageServerConfig {
+    /// The source files, or directories of source files, of the workspace. They default to the
+    /// root directory of the workspace opened by the client
+    #[structopt(name = "PATH_TO_SOURCE_FILE")]
+    pub source_files: Vec<String>,
+
+    /// The library files needed as dependencies
+    #[structopt(
+        name = "PATH_TO_DEPENDENCY_FILE",
+        short = cli::DEPENDENCY_SHORT,
+        long = cli::DEPENDENCY,
+    )]
+    pub dep_files: Vec<String>,
+
+    /// The sender address for modules and scripts
+    #[structopt(
+        name = "ADDRESS",
+        short = cli::SENDER_SHORT,
+        long = cli::SENDER,
+        parse(try_from_str = cli::parse_address)
+    )]
+    pub sender: Option<Address>,
+}
+
+impl LanguageServerConfig {
+    /// Serve the client over the standard input and output, until it asks the server to exit.
+    pub fn run_stdio(&self) -> anyhow::Result<()> {
+        let workspace = Workspace::new(self.source_files.clone(), self.dep_files.clone());
+        let server = LanguageServer::new(workspace, self.sender, Box::new(io::stdout()));
+        server.run(BufReader::new(io::stdin()))
+    }
+}
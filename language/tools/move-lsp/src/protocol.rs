@@ -0,0 +1,119 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The wire format of the Language Server Protocol: JSON-RPC messages, each preceded by a
+//! `Content-Length` header. See https://microsoft.github.io/language-server-protocol/specification
+//! for the messages themselves.
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+const CONTENT_LENGTH: &str = "Content-Length:";
+
+/// The error code of requests which are not supported.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// The error code of requests with invalid parameters.
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// A request or a notification of the client. Notifications have no `id`.
+#[derive(Clone, Debug)]
+pub struct Message {
+    pub id: Option<Value>,
+    pub method: String,
+    pub params: Value,
+}
+
+/// Read the next message from `reader`. Returns `None` at the end of the input.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(length) = header.strip_prefix(CONTENT_LENGTH) {
+            content_length = Some(length.trim().parse::<usize>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid Content-Length header")
+            })?);
+        }
+    }
+    let content_length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Read the next request or notification from `reader`, skipping the responses to requests of
+/// the server. Returns `None` at the end of the input.
+pub fn read_client_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Message>> {
+    while let Some(mut message) = read_message(reader)? {
+        let method = match message["method"].as_str() {
+            Some(method) => method.to_string(),
+            None => continue,
+        };
+        return Ok(Some(Message {
+            id: message.get("id").cloned(),
+            method,
+            params: message["params"].take(),
+        }));
+    }
+    Ok(None)
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "{} {}\r\n\r\n{}",
+        CONTENT_LENGTH,
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
+
+/// The sending end of the connection to the client.
+pub struct Output {
+    writer: Box<dyn Write + Send>,
+}
+
+impl Output {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer }
+    }
+
+    pub fn respond(&mut self, id: &Value, result: Value) {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }));
+    }
+
+    pub fn respond_error(&mut self, id: &Value, code: i64, message: &str) {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }));
+    }
+
+    pub fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }));
+    }
+
+    fn send(&mut self, message: Value) {
+        // Failures mean that the client is gone, and there is nobody left to report them to.
+        let _ = write_message(&mut self.writer, &message);
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    analysis::{Analysis, Diagnostic, Location, Position, Range, SymbolKind},
+    protocol::{read_client_message, Message, Output, INVALID_PARAMS, METHOD_NOT_FOUND},
+    workspace::{path_of_uri, uri_of_path, Workspace},
+};
+use anyhow::Result;
+use move_lang::shared::Address;
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Write},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// A server of the Language Server Protocol.
+///
+/// The sources are compiled on a separate thread whenever they change, so that the requests of
+/// the client are answered while they compile, from the last analysis of the sources. Changes
+/// made while the sources compile are compiled together once it is done.
+pub struct LanguageServer {
+    state: Arc<Mutex<State>>,
+    output: Arc<Mutex<Output>>,
+    compile_requests: Sender<()>,
+}
+
+struct State {
+    workspace: Workspace,
+    sender_opt: Option<Address>,
+    /// The last analysis of the sources, if they were compiled yet.
+    analysis: Option<Arc<Analysis>>,
+    /// The diagnostics last published for each file.
+    published_diagnostics: BTreeMap<&'static str, Vec<Diagnostic>>,
+}
+
+impl LanguageServer {
+    pub fn new(
+        workspace: Workspace,
+        sender_opt: Option<Address>,
+        output: Box<dyn Write + Send>,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(State {
+            workspace,
+            sender_opt,
+            analysis: None,
+            published_diagnostics: BTreeMap::new(),
+        }));
+        let output = Arc::new(Mutex::new(Output::new(output)));
+        let (compile_requests, receiver) = mpsc::channel();
+        let compiler_state = Arc::clone(&state);
+        let compiler_output = Arc::clone(&output);
+        thread::spawn(move || compile(&compiler_state, &compiler_output, receiver));
+        Self {
+            state,
+            output,
+            compile_requests,
+        }
+    }
+
+    /// Serve the messages of the client read from `input`, until it asks the server to exit.
+    pub fn run<R: BufRead>(&self, mut input: R) -> Result<()> {
+        while let Some(message) = read_client_message(&mut input)? {
+            if message.method == "exit" {
+                break;
+            }
+            self.serve(message);
+        }
+        Ok(())
+    }
+
+    fn serve(&self, message: Message) {
+        let params = &message.params;
+        let response = match message.method.as_str() {
+            "initialize" => {
+                let root = match params["rootUri"].as_str() {
+                    Some(uri) => path_of_uri(uri),
+                    None => params["rootPath"].as_str().map(Into::into),
+                };
+                if let Some(root) = root {
+                    self.state
+                        .lock()
+                        .unwrap()
+                        .workspace
+                        .set_default_targets(vec![root.to_string_lossy().into_owned()]);
+                }
+                Some(Ok(json!({
+                    "capabilities": {
+                        // The full contents of the files are sent on changes
+                        "textDocumentSync": { "openClose": true, "change": 1 },
+                        "hoverProvider": true,
+                        "definitionProvider": true,
+                        "completionProvider": { "triggerCharacters": [":"] },
+                    },
+                })))
+            }
+            "initialized" => {
+                self.compile();
+                None
+            }
+            "shutdown" => Some(Ok(Value::Null)),
+
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                if let Some(text) = document["text"].as_str() {
+                    self.open(&document["uri"], text.to_string());
+                }
+                None
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let text = changes.and_then(|changes| changes.last()?["text"].as_str());
+                if let Some(text) = text {
+                    self.open(&params["textDocument"]["uri"], text.to_string());
+                }
+                None
+            }
+            "textDocument/didClose" => {
+                let mut state = self.state.lock().unwrap();
+                if let Some(file) =
+                    file_of_uri(&mut state.workspace, &params["textDocument"]["uri"])
+                {
+                    state.workspace.close(file);
+                }
+                drop(state);
+                self.compile();
+                None
+            }
+            "workspace/didChangeWatchedFiles" => {
+                let mut state = self.state.lock().unwrap();
+                for change in params["changes"].as_array().into_iter().flatten() {
+                    if let Some(file) = file_of_uri(&mut state.workspace, &change["uri"]) {
+                        state.workspace.changed_on_disk(file);
+                    }
+                }
+                drop(state);
+                self.compile();
+                None
+            }
+
+            "textDocument/hover" => Some(self.query(params, |analysis, file, _, position| {
+                match analysis.hover(file, position) {
+                    Some((range, signature)) => json!({
+                        "contents": {
+                            "kind": "markdown",
+                            "value": format!("```move\n{}\n```", signature),
+                        },
+                        "range": range_json(range),
+                    }),
+                    None => Value::Null,
+                }
+            })),
+            "textDocument/definition" => Some(self.query(params, |analysis, file, _, position| {
+                match analysis.definition(file, position) {
+                    Some(location) => location_json(location),
+                    None => Value::Null,
+                }
+            })),
+            "textDocument/completion" => {
+                Some(self.query(params, |analysis, file, text, position| {
+                    let completions = match text {
+                        Some(text) => analysis.completions(file, text, position),
+                        None => vec![],
+                    };
+                    let items: Vec<_> = completions
+                        .into_iter()
+                        .map(|completion| {
+                            json!({
+                                "label": completion.label,
+                                "kind": completion_kind(completion.kind),
+                                "detail": completion.detail,
+                            })
+                        })
+                        .collect();
+                    json!(items)
+                }))
+            }
+
+            _ => {
+                if let Some(id) = &message.id {
+                    self.output.lock().unwrap().respond_error(
+                        id,
+                        METHOD_NOT_FOUND,
+                        &format!("Unsupported request: {}", message.method),
+                    );
+                }
+                return;
+            }
+        };
+        if let (Some(id), Some(response)) = (&message.id, response) {
+            let mut output = self.output.lock().unwrap();
+            match response {
+                Ok(result) => output.respond(id, result),
+                Err(message) => output.respond_error(id, INVALID_PARAMS, &message),
+            }
+        }
+    }
+
+    fn open(&self, uri: &Value, text: String) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(file) = file_of_uri(&mut state.workspace, uri) {
+            state.workspace.open(file, text);
+        }
+        drop(state);
+        self.compile();
+    }
+
+    fn compile(&self) {
+        // The compiling thread only stops with the server
+        let _ = self.compile_requests.send(());
+    }
+
+    /// Answer a request about a position of a document with `answer`, which is given the last
+    /// analysis, the file of the document, its current contents if it is open and the position.
+    fn query(
+        &self,
+        params: &Value,
+        answer: impl FnOnce(&Analysis, &'static str, Option<&str>, Position) -> Value,
+    ) -> Result<Value, String> {
+        let mut state = self.state.lock().unwrap();
+        let file = file_of_uri(&mut state.workspace, &params["textDocument"]["uri"]);
+        let position = match (
+            params["position"]["line"].as_u64(),
+            params["position"]["character"].as_u64(),
+        ) {
+            (Some(line), Some(character)) => Some(Position {
+                line: line as usize,
+                character: character as usize,
+            }),
+            _ => None,
+        };
+        match (file, position, &state.analysis) {
+            (Some(file), Some(position), Some(analysis)) => Ok(answer(
+                analysis,
+                file,
+                state.workspace.open_file(file),
+                position,
+            )),
+            // The sources are not compiled yet
+            (Some(_), Some(_), None) => Ok(Value::Null),
+            _ => Err("Expected a document and a position".to_string()),
+        }
+    }
+}
+
+fn file_of_uri(workspace: &mut Workspace, uri: &Value) -> Option<&'static str> {
+    workspace.file_name_of_uri(uri.as_str()?)
+}
+
+/// Compile the sources each time it is requested, and publish the diagnostics which changed.
+/// The whole workspace is checked every time, as the compiler has no incremental mode.
+fn compile(state: &Mutex<State>, output: &Mutex<Output>, requests: Receiver<()>) {
+    while requests.recv().is_ok() {
+        while requests.try_recv().is_ok() {}
+        let (sources, sender_opt, previous) = {
+            let mut state = state.lock().unwrap();
+            (
+                state.workspace.sources(),
+                state.sender_opt,
+                state.analysis.clone(),
+            )
+        };
+        let analysis = Analysis::check(&sources, sender_opt, previous.as_deref());
+
+        let mut state = state.lock().unwrap();
+        let mut output = output.lock().unwrap();
+        for (file, diagnostics) in &analysis.diagnostics {
+            if state.published_diagnostics.get(file) != Some(diagnostics) {
+                publish_diagnostics(&mut output, file, diagnostics);
+            }
+        }
+        for file in state.published_diagnostics.keys() {
+            if !analysis.diagnostics.contains_key(file) {
+                publish_diagnostics(&mut output, file, &[]);
+            }
+        }
+        state.published_diagnostics = analysis.diagnostics.clone();
+        state.analysis = Some(Arc::new(analysis));
+    }
+}
+
+fn publish_diagnostics(output: &mut Output, file: &str, diagnostics: &[Diagnostic]) {
+    let diagnostics: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let related: Vec<_> = diagnostic
+                .related
+                .iter()
+                .map(|(location, message)| {
+                    json!({ "location": location_json(*location), "message": message })
+                })
+                .collect();
+            json!({
+                "range": range_json(diagnostic.range),
+                // Errors
+                "severity": 1,
+                "source": "move",
+                "message": diagnostic.message,
+                "relatedInformation": related,
+            })
+        })
+        .collect();
+    output.notify(
+        "textDocument/publishDiagnostics",
+        json!({ "uri": uri_of_path(file), "diagnostics": diagnostics }),
+    );
+}
+
+fn range_json(range: Range) -> Value {
+    json!({
+        "start": { "line": range.start.line, "character": range.start.character },
+        "end": { "line": range.end.line, "character": range.end.character },
+    })
+}
+
+fn location_json(location: Location) -> Value {
+    json!({ "uri": uri_of_path(location.file), "range": range_json(location.range) })
+}
+
+fn completion_kind(kind: SymbolKind) -> u64 {
+    match kind {
+        SymbolKind::Module => 9,
+        SymbolKind::Struct => 22,
+        SymbolKind::Function => 3,
+        SymbolKind::Constant => 21,
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The Move sources of a workspace, read from the disk or from the files open in the client.
+
+use crate::analysis::Sources;
+use move_lang::{errors::FilesSourceText, MOVE_EXTENSION};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+const FILE_SCHEME: &str = "file://";
+
+pub struct Workspace {
+    /// The source files, or directories of source files, of the workspace.
+    targets: Vec<String>,
+    /// The dependency files, or directories of dependency files, of the workspace.
+    deps: Vec<String>,
+    /// The contents of the files open in the client. They are the latest contents of the files,
+    /// which may not be saved yet.
+    open_files: HashMap<&'static str, String>,
+    /// The contents of the other files, as last read from the disk.
+    disk_files: HashMap<&'static str, String>,
+    /// The file names given to the compiler, which must live as long as the program.
+    file_names: HashMap<PathBuf, &'static str>,
+}
+
+impl Workspace {
+    pub fn new(targets: Vec<String>, deps: Vec<String>) -> Self {
+        Self {
+            targets,
+            deps,
+            open_files: HashMap::new(),
+            disk_files: HashMap::new(),
+            file_names: HashMap::new(),
+        }
+    }
+
+    /// Use `targets` as the source files of the workspace, if it has none yet.
+    pub fn set_default_targets(&mut self, targets: Vec<String>) {
+        if self.targets.is_empty() {
+            self.targets = targets;
+        }
+    }
+
+    /// The file name given to the compiler for the file at `path`.
+    pub fn file_name(&mut self, path: &Path) -> &'static str {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(file) = self.file_names.get(&path) {
+            return *file;
+        }
+        let file: &'static str = Box::leak(path.to_string_lossy().into_owned().into_boxed_str());
+        self.file_names.insert(path, file);
+        file
+    }
+
+    /// The file name given to the compiler for the file with the given `file://` URI.
+    pub fn file_name_of_uri(&mut self, uri: &str) -> Option<&'static str> {
+        let path = path_of_uri(uri)?;
+        Some(self.file_name(&path))
+    }
+
+    pub fn open(&mut self, file: &'static str, text: String) {
+        self.open_files.insert(file, text);
+    }
+
+    /// The latest contents of `file`, if it is open.
+    pub fn open_file(&self, file: &'static str) -> Option<&str> {
+        self.open_files.get(file).map(|text| text.as_str())
+    }
+
+    pub fn close(&mut self, file: &'static str) {
+        self.open_files.remove(file);
+        self.disk_files.remove(file);
+    }
+
+    /// Forget the contents of `file` read from the disk, as it changed there.
+    pub fn changed_on_disk(&mut self, file: &'static str) {
+        self.disk_files.remove(file);
+    }
+
+    /// The sources to compile: the Move files of the targets and dependencies, along with the
+    /// files open in the client, which are targets unless they are dependencies. Only the files
+    /// which changed since the last call are read from the disk, but all of them are returned
+    /// to be compiled again.
+    pub fn sources(&mut self) -> Sources {
+        let deps = self.find_files(&self.deps.clone());
+        let mut targets = self.find_files(&self.targets.clone());
+        let move_extension = format!(".{}", MOVE_EXTENSION);
+        targets.extend(
+            self.open_files
+                .keys()
+                .filter(|file| file.ends_with(&move_extension))
+                .copied(),
+        );
+        // Targets can be dependencies of other targets, but not be compiled twice
+        let targets: BTreeSet<_> = targets.difference(&deps).copied().collect();
+
+        let mut files = FilesSourceText::new();
+        for file in targets.iter().chain(&deps) {
+            let text = match self.open_files.get(file) {
+                Some(text) => text.clone(),
+                None => match self.disk_files.get(file) {
+                    Some(text) => text.clone(),
+                    None => match fs::read_to_string(file) {
+                        Ok(text) => {
+                            self.disk_files.insert(*file, text.clone());
+                            text
+                        }
+                        // The file was removed since it was found
+                        Err(_) => continue,
+                    },
+                },
+            };
+            files.insert(*file, text);
+        }
+        Sources {
+            targets: targets
+                .into_iter()
+                .filter(|file| files.contains_key(file))
+                .collect(),
+            deps: deps
+                .into_iter()
+                .filter(|file| files.contains_key(file))
+                .collect(),
+            files,
+        }
+    }
+
+    fn find_files(&mut self, paths: &[String]) -> BTreeSet<&'static str> {
+        let existing_paths: Vec<_> = paths
+            .iter()
+            .filter(|path| Path::new(path).exists())
+            .cloned()
+            .collect();
+        move_lang::find_move_filenames(&existing_paths)
+            .unwrap_or_default()
+            .iter()
+            .map(|file| self.file_name(Path::new(file)))
+            .collect()
+    }
+}
+
+/// The path of a `file://` URI.
+pub fn path_of_uri(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix(FILE_SCHEME)?;
+    let mut bytes = vec![];
+    let mut encoded_bytes = path.bytes();
+    while let Some(byte) = encoded_bytes.next() {
+        if byte == b'%' {
+            let hex = [encoded_bytes.next()?, encoded_bytes.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(bytes).ok()?))
+}
+
+/// The `file://` URI of a path.
+pub fn uri_of_path(path: &str) -> String {
+    let mut uri = FILE_SCHEME.to_string();
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
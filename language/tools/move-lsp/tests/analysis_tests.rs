@@ -0,0 +1,119 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_lsp::{
+    analysis::{Analysis, Position, SymbolKind},
+    workspace::Workspace,
+};
+use std::{fs, path::Path};
+
+const SOURCES: &str = "tests/sources";
+const COUNTING: &str = "tests/sources/counting.move";
+const USER: &str = "tests/sources/user.move";
+
+/// The position of the first occurrence of `needle` in `text`.
+fn position_of(text: &str, needle: &str) -> Position {
+    let offset = text.find(needle).unwrap();
+    let line = text[..offset].matches('\n').count();
+    let line_start = text[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+    Position {
+        line,
+        character: offset - line_start,
+    }
+}
+
+fn check(workspace: &mut Workspace) -> Analysis {
+    Analysis::check(&workspace.sources(), None, None)
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut workspace = Workspace::new(vec![SOURCES.to_string()], vec![]);
+    let analysis = check(&mut workspace);
+    assert!(analysis.diagnostics.is_empty());
+
+    // The contents of the open files are checked, rather than the ones on disk
+    let user = workspace.file_name(Path::new(USER));
+    let text = fs::read_to_string(USER)
+        .unwrap()
+        .replace("Counting::value(", "Counting::missing(");
+    workspace.open(user, text.clone());
+    let analysis = check(&mut workspace);
+    assert_eq!(analysis.diagnostics.len(), 1);
+    let diagnostics = &analysis.diagnostics[user];
+    assert_eq!(
+        diagnostics[0].range.start.line,
+        position_of(&text, "Counting::missing").line
+    );
+
+    workspace.close(user);
+    assert!(check(&mut workspace).diagnostics.is_empty());
+}
+
+#[test]
+fn test_hover_and_definition() {
+    let mut workspace = Workspace::new(vec![SOURCES.to_string()], vec![]);
+    let analysis = check(&mut workspace);
+    let user = workspace.file_name(Path::new(USER));
+    let counting = workspace.file_name(Path::new(COUNTING));
+    let user_text = fs::read_to_string(USER).unwrap();
+    let counting_text = fs::read_to_string(COUNTING).unwrap();
+
+    let mut increment = position_of(&user_text, "increment(&mut");
+    increment.character += 2;
+    let (range, signature) = analysis.hover(user, increment).unwrap();
+    assert_eq!(
+        signature,
+        "public fun increment(counter: &mut Counting::Counter)"
+    );
+    assert_eq!(range.start, position_of(&user_text, "increment(&mut"));
+
+    let definition = analysis.definition(user, increment).unwrap();
+    assert_eq!(definition.file, counting);
+    assert_eq!(
+        definition.range.start,
+        position_of(&counting_text, "increment(counter")
+    );
+
+    let (_, signature) = analysis
+        .hover(counting, position_of(&counting_text, "Counter {"))
+        .unwrap();
+    assert_eq!(signature, "struct Counter { value: u64 }");
+
+    // Nothing is referenced in comments, keywords and the like
+    assert!(analysis
+        .hover(user, position_of(&user_text, "public fun count"))
+        .is_none());
+}
+
+#[test]
+fn test_completions() {
+    let mut workspace = Workspace::new(vec![SOURCES.to_string()], vec![]);
+    let analysis = check(&mut workspace);
+    let user = workspace.file_name(Path::new(USER));
+    let text = fs::read_to_string(USER)
+        .unwrap()
+        .replace("Counting::value(&counter)", "Counting::v");
+    let mut position = position_of(&text, "Counting::v");
+    position.character += "Counting::".len();
+
+    let mut completions: Vec<_> = analysis
+        .completions(user, &text, position)
+        .into_iter()
+        .map(|completion| (completion.label, completion.kind))
+        .collect();
+    completions.sort();
+    // Private functions and constants of other modules are not accessible
+    assert_eq!(
+        completions,
+        vec![
+            ("Counter".to_string(), SymbolKind::Struct),
+            ("increment".to_string(), SymbolKind::Function),
+            ("new".to_string(), SymbolKind::Function),
+            ("value".to_string(), SymbolKind::Function),
+        ]
+    );
+
+    let position = position_of(&text, "let counter");
+    assert!(analysis.completions(user, &text, position).is_empty());
+}
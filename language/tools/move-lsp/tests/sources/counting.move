@@ -0,0 +1,24 @@
+address 0x2 {
+module Counting {
+    struct Counter { value: u64 }
+
+    const EMAX: u64 = 1;
+
+    public fun new(): Counter {
+        Counter { value: 0 }
+    }
+
+    public fun increment(counter: &mut Counter) {
+        check(counter);
+        counter.value = counter.value + 1
+    }
+
+    public fun value(counter: &Counter): u64 {
+        counter.value
+    }
+
+    fun check(counter: &Counter) {
+        assert(counter.value < 100, EMAX)
+    }
+}
+}
@@ -0,0 +1,11 @@
+address 0x2 {
+module User {
+    use 0x2::Counting;
+
+    public fun count(): u64 {
+        let counter = Counting::new();
+        Counting::increment(&mut counter);
+        Counting::value(&counter)
+    }
+}
+}
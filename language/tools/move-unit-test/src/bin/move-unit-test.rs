@@ -0,0 +1,15 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use move_unit_test::UnitTestingConfig;
+use structopt::StructOpt;
+
+pub fn main() -> anyhow::Result<()> {
+    let config = UnitTestingConfig::from_args();
+    if !config.run_and_report(&mut std::io::stdout())? {
+        std::process::exit(1)
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use move_lang::{command_line as cli, errors, shared::Address};
+use std::{collections::BTreeSet, io::Write};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "Move Unit Test", about = "Run the unit tests of Move modules.")]
+pub struct UnitTestingConfig {
+    /// Only run the tests whose fully qualified name, e.g. `0x2::M::test_f`, contains this string
+    #[structopt(name = "FILTER", short = "f", long = "filter")]
+    pub filter: Option<String>,
+
+    /// The gas each test may use, one unit per instruction executed. Tests which run out of gas,
+    /// e.g., because they never terminate, fail
+    #[structopt(
+        name = "GAS_BUDGET",
+        short = "i",
+        long = "gas-budget",
+        default_value = "1000000"
+    )]
+    pub gas_budget: u64,
+
+    /// The source files containing the unit tests to run
+    #[structopt(name = "PATH_TO_SOURCE_FILE")]
+    pub source_files: Vec<String>,
+
+    /// The library files needed as dependencies
+    #[structopt(
+        name = "PATH_TO_DEPENDENCY_FILE",
+        short = cli::DEPENDENCY_SHORT,
+        long = cli::DEPENDENCY,
+    )]
+    pub dep_files: Vec<String>,
+
+    /// The sender address for modules
+    #[structopt(
+        name = "ADDRESS",
+        short = cli::SENDER_SHORT,
+        long = cli::SENDER,
+        parse(try_from_str = cli::parse_address)
+    )]
+    pub sender: Option<Address>,
+}
+
+impl UnitTestingConfig {
+    /// Compiles the sources and their dependencies for testing. Compilation errors are reported
+    /// to stderr, and exit the process.
+    pub fn build_test_runner(&self) -> anyhow::Result<TestRunner> {
+        // The dependencies are compiled to bytecode as well, as the tests execute their code
+        let targets = [&self.source_files[..], &self.dep_files[..]].concat();
+        let (files, units_res) =
+            move_lang::move_compile_for_testing_no_report(&targets, &[], self.sender)?;
+        let (compiled_units, test_plan) = match units_res {
+            Err(errors) => errors::report_errors(files, errors),
+            Ok(units_and_plan) => units_and_plan,
+        };
+
+        let source_files = move_lang::find_move_filenames(&self.source_files)?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        let test_plan = test_plan
+            .into_iter()
+            .filter(|module_plan| source_files.contains(module_plan.module.loc().file()))
+            .collect();
+        Ok(TestRunner::new(
+            files,
+            compiled_units,
+            test_plan,
+            self.filter.clone(),
+            self.gas_budget,
+        ))
+    }
+
+    /// Runs the unit tests and reports their results to `writer`. Returns true if all of them
+    /// passed.
+    pub fn run_and_report<W: Write>(&self, writer: &mut W) -> anyhow::Result<bool> {
+        let results = self.build_test_runner()?.run();
+        results.report(writer)?;
+        Ok(results.all_passed())
+    }
+}
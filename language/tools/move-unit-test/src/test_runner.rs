@@ -0,0 +1,307 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_source_map::source_map::SourceMap;
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_schedule::{CostTable, GasAlgebra, GasCost, GasUnits},
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, TypeTag},
+    vm_status::StatusCode,
+};
+use move_ir_types::location::Loc;
+use move_lang::{
+    compiled_unit::CompiledUnit,
+    errors::FilesSourceText,
+    parser::ast::{ModuleIdent, ModuleIdent_},
+    unit_test::{ExpectedFailure, ModuleTestPlan, TestCase, TestPlan},
+};
+use move_vm_runtime::{data_cache::RemoteCache, move_vm::MoveVM};
+use move_vm_types::gas_schedule::{zero_cost_schedule, CostStrategy};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+use vm::errors::{Location, PartialVMResult, VMError, VMResult};
+
+/// The modules under test. The tests run in sessions which are never committed, so they all
+/// start from this same state.
+struct InMemoryStorage {
+    modules: BTreeMap<ModuleId, Vec<u8>>,
+}
+
+impl RemoteCache for InMemoryStorage {
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        Ok(self.modules.get(module_id).cloned())
+    }
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _tag: &TypeTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+pub struct TestRunner {
+    files: FilesSourceText,
+    storage: InMemoryStorage,
+    source_maps: BTreeMap<ModuleId, SourceMap<Loc>>,
+    test_plan: TestPlan,
+    filter: Option<String>,
+    cost_table: CostTable,
+    gas_budget: u64,
+}
+
+#[derive(Debug)]
+pub struct TestOutcome {
+    /// The fully qualified name of the test, e.g. `0x2::M::test_f`
+    pub name: String,
+    /// The reason of the failure of the test, if it failed
+    pub failure: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct TestResults {
+    pub outcomes: Vec<TestOutcome>,
+}
+
+impl TestRunner {
+    pub fn new(
+        files: FilesSourceText,
+        compiled_units: Vec<CompiledUnit>,
+        test_plan: TestPlan,
+        filter: Option<String>,
+        gas_budget: u64,
+    ) -> Self {
+        let mut modules = BTreeMap::new();
+        let mut source_maps = BTreeMap::new();
+        for unit in compiled_units {
+            if let CompiledUnit::Module {
+                ident,
+                module,
+                source_map,
+                ..
+            } = unit
+            {
+                let module_id = module_id(&ident);
+                let mut binary = vec![];
+                module
+                    .serialize(&mut binary)
+                    .expect("compiled modules must serialize");
+                modules.insert(module_id.clone(), binary);
+                source_maps.insert(module_id, source_map);
+            }
+        }
+        Self {
+            files,
+            storage: InMemoryStorage { modules },
+            source_maps,
+            test_plan,
+            filter,
+            cost_table: unit_cost_table(),
+            gas_budget,
+        }
+    }
+
+    /// Runs each test of the test plan which matches the filter, in a fresh session
+    pub fn run(&self) -> TestResults {
+        let vm = MoveVM::new();
+        let mut results = TestResults::default();
+        for ModuleTestPlan { module, tests } in &self.test_plan {
+            let module_id = module_id(module);
+            for (test_name, test_case) in tests {
+                let name = format!("{}::{}", module, test_name);
+                if let Some(filter) = &self.filter {
+                    if !name.contains(filter.as_str()) {
+                        continue;
+                    }
+                }
+                let failure = self
+                    .run_test(&vm, &module_id, test_name, test_case)
+                    .map(|reason| {
+                        format!(
+                            "{}\n    Test declared at {}",
+                            reason,
+                            self.format_loc(test_case.loc)
+                        )
+                    });
+                results.outcomes.push(TestOutcome { name, failure })
+            }
+        }
+        results
+    }
+
+    /// Returns the reason of the failure of the test, if it failed
+    fn run_test(
+        &self,
+        vm: &MoveVM,
+        module_id: &ModuleId,
+        test_name: &str,
+        test_case: &TestCase,
+    ) -> Option<String> {
+        let function_name = IdentStr::new(test_name).expect("test names are valid identifiers");
+        // Metered, so that tests which never terminate fail once they run out of gas
+        let mut cost_strategy =
+            CostStrategy::transaction(&self.cost_table, GasUnits::new(self.gas_budget));
+        let mut session = vm.new_session(&self.storage);
+        let result = session.execute_function_with_vm_error(
+            module_id,
+            function_name,
+            vec![],
+            vec![],
+            &mut cost_strategy,
+        );
+
+        match (test_case.expected_failure, result) {
+            (None, Ok(())) => None,
+            (Some(ExpectedFailure::Any), Err(err))
+                if err.major_status() == StatusCode::ABORTED
+                    || err.major_status() == StatusCode::ARITHMETIC_ERROR =>
+            {
+                None
+            }
+            (Some(ExpectedFailure::AbortCode(expected)), Err(err))
+                if err.major_status() == StatusCode::ABORTED
+                    && err.sub_status() == Some(expected) =>
+            {
+                None
+            }
+            (None, Err(err)) => Some(format!(
+                "Test was not expected to fail, but it {}",
+                self.describe_failure(&err)
+            )),
+            (Some(ExpectedFailure::Any), Ok(())) => {
+                Some("Test was expected to fail, but it succeeded".to_string())
+            }
+            (Some(ExpectedFailure::Any), Err(err)) => Some(format!(
+                "Test was expected to abort or to fail with an arithmetic error, but it {}",
+                self.describe_failure(&err)
+            )),
+            (Some(ExpectedFailure::AbortCode(expected)), Ok(())) => Some(format!(
+                "Test was expected to abort with code {}, but it succeeded",
+                expected
+            )),
+            (Some(ExpectedFailure::AbortCode(expected)), Err(err)) => Some(format!(
+                "Test was expected to abort with code {}, but it {}",
+                expected,
+                self.describe_failure(&err)
+            )),
+        }
+    }
+
+    /// Describes a failure, along with the source location it happened at when known
+    fn describe_failure(&self, err: &VMError) -> String {
+        let failure = match err.major_status() {
+            StatusCode::ABORTED => match err.sub_status() {
+                Some(code) => format!("aborted with code {}", code),
+                None => "aborted".to_string(),
+            },
+            StatusCode::OUT_OF_GAS => format!(
+                "ran out of gas, after the {} units of its budget",
+                self.gas_budget
+            ),
+            status_code => format!("failed with {:?}", status_code),
+        };
+        let source_loc = match (err.location(), err.offsets().last()) {
+            (Location::Module(module_id), Some((function, code_offset))) => self
+                .source_maps
+                .get(module_id)
+                .and_then(|source_map| source_map.get_code_location(*function, *code_offset).ok()),
+            _ => None,
+        };
+        match (source_loc, err.location()) {
+            (Some(loc), _) => format!("{} at {}", failure, self.format_loc(loc)),
+            (None, Location::Module(module_id)) => format!("{} in module {}", failure, module_id),
+            (None, Location::Script) => format!("{} in a script", failure),
+            (None, Location::Undefined) => failure,
+        }
+    }
+
+    /// Formats a location as `file:line:column`
+    fn format_loc(&self, loc: Loc) -> String {
+        let start = loc.span().start().to_usize();
+        match self.files.get(&loc.file()) {
+            Some(source) if start <= source.len() => {
+                let before = &source[..start];
+                let line = before.matches('\n').count() + 1;
+                let column = start - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+                format!("{}:{}:{}", loc.file(), line, column)
+            }
+            _ => loc.file().to_string(),
+        }
+    }
+}
+
+impl TestResults {
+    pub fn all_passed(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|outcome| outcome.failure.is_none())
+    }
+
+    pub fn num_failed(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.failure.is_some())
+            .count()
+    }
+
+    pub fn report<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "Running Move unit tests")?;
+        for outcome in &self.outcomes {
+            let status = if outcome.failure.is_none() {
+                "PASS"
+            } else {
+                "FAIL"
+            };
+            writeln!(writer, "[ {:<4} ] {}", status, outcome.name)?;
+        }
+
+        let failures = self
+            .outcomes
+            .iter()
+            .filter_map(|outcome| Some((&outcome.name, outcome.failure.as_ref()?)))
+            .collect::<Vec<_>>();
+        if !failures.is_empty() {
+            writeln!(writer, "\nTest failures:")?;
+            for (name, failure) in failures {
+                writeln!(writer, "\n{}:\n    {}", name, failure)?;
+            }
+        }
+
+        let num_failed = self.num_failed();
+        writeln!(
+            writer,
+            "\nTest result: {}. Total tests: {}; passed: {}; failed: {}",
+            if num_failed == 0 { "OK" } else { "FAILED" },
+            self.outcomes.len(),
+            self.outcomes.len() - num_failed,
+            num_failed
+        )
+    }
+}
+
+fn module_id(ident: &ModuleIdent) -> ModuleId {
+    let ModuleIdent_ { address, name } = &ident.0.value;
+    ModuleId::new(
+        AccountAddress::new(address.to_u8()),
+        Identifier::new(name.0.value.as_str()).expect("module names are valid identifiers"),
+    )
+}
+
+/// A cost table charging one unit of gas per instruction and per native function call, scaled by
+/// the size of the data they operate on, so that the gas budget of a test bounds its execution
+fn unit_cost_table() -> CostTable {
+    let mut cost_table = zero_cost_schedule();
+    for cost in cost_table
+        .instruction_table
+        .iter_mut()
+        .chain(cost_table.native_table.iter_mut())
+    {
+        *cost = GasCost::new(1, 0);
+    }
+    cost_table.gas_constants.gas_unit_scaling_factor = 1;
+    cost_table
+}
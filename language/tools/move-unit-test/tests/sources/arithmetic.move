@@ -0,0 +1,42 @@
+address 0x2 {
+module Arithmetic {
+    const EOVERFLOW: u64 = 1;
+
+    public fun checked_add(a: u64, b: u64): u64 {
+        assert(a <= 18446744073709551615 - b, EOVERFLOW);
+        a + b
+    }
+
+    public fun div(a: u64, b: u64): u64 {
+        a / b
+    }
+
+    #[test]
+    fun add() {
+        assert(checked_add(1, 2) == 3, 0)
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1)]
+    fun add_overflow() {
+        checked_add(18446744073709551615, 1);
+    }
+
+    #[test]
+    #[expected_failure]
+    fun div_by_zero() {
+        div(1, 0);
+    }
+
+    #[test]
+    fun wrong_sum() {
+        assert(checked_add(1, 1) == 3, 42)
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 2)]
+    fun wrong_abort_code() {
+        checked_add(18446744073709551615, 1);
+    }
+}
+}
@@ -0,0 +1,20 @@
+address 0x2 {
+module Failures {
+    #[test]
+    fun loops_forever() {
+        loop {}
+    }
+
+    #[test]
+    #[expected_failure]
+    fun cast_overflow() {
+        (256 as u8);
+    }
+
+    #[test]
+    #[expected_failure]
+    fun expected_loop() {
+        loop {}
+    }
+}
+}
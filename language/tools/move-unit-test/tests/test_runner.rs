@@ -0,0 +1,81 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::{test_runner::TestResults, UnitTestingConfig};
+
+const ARITHMETIC: &str = "tests/sources/arithmetic.move";
+const FAILURES: &str = "tests/sources/failures.move";
+
+fn run_file(source_file: &str, filter: Option<&str>) -> TestResults {
+    let config = UnitTestingConfig {
+        filter: filter.map(|f| f.to_string()),
+        gas_budget: 10_000,
+        source_files: vec![source_file.to_string()],
+        dep_files: vec![],
+        sender: None,
+    };
+    config.build_test_runner().unwrap().run()
+}
+
+fn run(filter: Option<&str>) -> TestResults {
+    run_file(ARITHMETIC, filter)
+}
+
+fn failure<'a>(results: &'a TestResults, test_name: &str) -> Option<&'a str> {
+    let outcome = results
+        .outcomes
+        .iter()
+        .find(|outcome| outcome.name.ends_with(&format!("::{}", test_name)))
+        .unwrap_or_else(|| panic!("no outcome for test {}", test_name));
+    outcome.failure.as_deref()
+}
+
+#[test]
+fn test_outcomes() {
+    let results = run(None);
+    assert_eq!(results.outcomes.len(), 5);
+    assert!(!results.all_passed());
+    assert_eq!(results.num_failed(), 2);
+
+    assert!(failure(&results, "add").is_none());
+    assert!(failure(&results, "add_overflow").is_none());
+    assert!(failure(&results, "div_by_zero").is_none());
+
+    let wrong_sum = failure(&results, "wrong_sum").unwrap();
+    assert!(wrong_sum.contains("not expected to fail, but it aborted with code 42"));
+    // The abort is located in the source
+    assert!(wrong_sum.contains(&format!("aborted with code 42 at {}:", ARITHMETIC)));
+    assert!(wrong_sum.contains(&format!("Test declared at {}:", ARITHMETIC)));
+    let wrong_abort_code = failure(&results, "wrong_abort_code").unwrap();
+    assert!(wrong_abort_code.contains("expected to abort with code 2, but it aborted with code 1"));
+}
+
+#[test]
+fn test_filter() {
+    let results = run(Some("::add"));
+    let mut names = results
+        .outcomes
+        .iter()
+        .map(|outcome| outcome.name.rsplit("::").next().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["add", "add_overflow"]);
+    assert!(results.all_passed());
+}
+
+#[test]
+fn test_failures() {
+    let results = run_file(FAILURES, None);
+    assert_eq!(results.outcomes.len(), 3);
+    assert_eq!(results.num_failed(), 2);
+
+    // Tests are bounded by their gas budget
+    let loops_forever = failure(&results, "loops_forever").unwrap();
+    assert!(loops_forever.contains("not expected to fail, but it ran out of gas"));
+
+    // Only aborts and arithmetic errors are expected failures
+    assert!(failure(&results, "cast_overflow").is_none());
+    let expected_loop = failure(&results, "expected_loop").unwrap();
+    assert!(expected_loop
+        .contains("expected to abort or to fail with an arithmetic error, but it ran out of gas"));
+}
@@ -11,9 +11,10 @@ use libra_types::{
 use libra_vm::{
     data_cache::RemoteStorage, txn_effects_to_writeset_and_events, LibraVM, VMExecutor,
 };
-use move_vm_runtime::{move_vm::MoveVM, session::Session};
+use move_vm_runtime::{debugger::DebugHook, move_vm::MoveVM, session::Session};
+use move_vm_types::gas_profiler::GasProfile;
 use resource_viewer::{AnnotatedAccountStateBlob, MoveValueAnnotator};
-use std::{convert::TryFrom, path::Path};
+use std::{convert::TryFrom, path::Path, sync::Arc};
 use vm::errors::VMResult;
 
 pub use crate::transaction_debugger_interface::{DebuggerStateView, StorageDebuggerInterface};
@@ -53,10 +54,67 @@ impl LibraDebugger {
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
+    /// Alternate form of 'execute_transactions_at_version' that debugs the executions of the
+    /// transactions with `hook`.
+    pub fn execute_transactions_at_version_with_debug_hook(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+        hook: Arc<dyn DebugHook>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version);
+        let outputs = LibraVM::execute_block_with_debug_hook(txns, &state_view, hook)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+        Ok(outputs
+            .into_iter()
+            .map(|(_vm_status, output)| output)
+            .collect())
+    }
+
+    /// Alternate form of 'execute_transactions_at_version' that also profiles the gas charged to
+    /// the transactions.
+    pub fn execute_transactions_at_version_with_gas_profile(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+    ) -> Result<(Vec<TransactionOutput>, GasProfile)> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version);
+        let (outputs, gas_profile) = LibraVM::execute_block_with_gas_profile(txns, &state_view)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+        Ok((
+            outputs
+                .into_iter()
+                .map(|(_vm_status, output)| output)
+                .collect(),
+            gas_profile,
+        ))
+    }
+
     pub fn execute_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>> {
+        self.execute_past_transactions_impl(begin, limit, None)
+    }
+
+    /// Alternate form of 'execute_past_transactions' that also profiles the gas charged to the
+    /// transactions.
+    pub fn execute_past_transactions_with_gas_profile(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> Result<(Vec<TransactionOutput>, GasProfile)> {
+        let mut gas_profile = GasProfile::default();
+        let outputs = self.execute_past_transactions_impl(begin, limit, Some(&mut gas_profile))?;
+        Ok((outputs, gas_profile))
+    }
+
+    fn execute_past_transactions_impl(
         &self,
         mut begin: Version,
         mut limit: u64,
+        mut gas_profile: Option<&mut GasProfile>,
     ) -> Result<Vec<TransactionOutput>> {
         let mut txns = self.debugger.get_committed_transactions(begin, limit)?;
         let mut ret = vec![];
@@ -65,7 +123,11 @@ impl LibraDebugger {
                 "Starting epoch execution at {:?}, {:?} transactions remaining",
                 begin, limit
             );
-            let mut epoch_result = self.execute_transactions_by_epoch(begin, txns.clone())?;
+            let mut epoch_result = self.execute_transactions_by_epoch_impl(
+                begin,
+                txns.clone(),
+                gas_profile.as_deref_mut(),
+            )?;
             begin += epoch_result.len() as u64;
             limit -= epoch_result.len() as u64;
             txns = txns.split_off(epoch_result.len());
@@ -79,7 +141,26 @@ impl LibraDebugger {
         begin: Version,
         txns: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>> {
-        let results = self.execute_transactions_at_version(begin, txns)?;
+        self.execute_transactions_by_epoch_impl(begin, txns, None)
+    }
+
+    fn execute_transactions_by_epoch_impl(
+        &self,
+        begin: Version,
+        txns: Vec<Transaction>,
+        gas_profile: Option<&mut GasProfile>,
+    ) -> Result<Vec<TransactionOutput>> {
+        // The transactions following a reconfiguration are not executed, so they are not
+        // charged any gas.
+        let results = match gas_profile {
+            Some(gas_profile) => {
+                let (results, epoch_profile) =
+                    self.execute_transactions_at_version_with_gas_profile(begin, txns)?;
+                gas_profile.merge(&epoch_profile);
+                results
+            }
+            None => self.execute_transactions_at_version(begin, txns)?,
+        };
         let mut ret = vec![];
         let mut is_reconfig = false;
 
@@ -116,6 +197,13 @@ impl LibraDebugger {
         )
     }
 
+    pub fn get_committed_transaction(&self, version: Version) -> Result<Transaction> {
+        self.debugger
+            .get_committed_transactions(version, 1)?
+            .pop()
+            .ok_or_else(|| format_err!("Transaction not found at version {}", version))
+    }
+
     pub fn get_latest_version(&self) -> Result<Version> {
         self.debugger.get_latest_version()
     }
@@ -1,10 +1,19 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use libra_transaction_replay::LibraDebugger;
-use libra_types::{account_address::AccountAddress, transaction::Version};
-use std::path::PathBuf;
+use libra_types::{
+    account_address::AccountAddress,
+    transaction::{Transaction, TransactionOutput, TransactionPayload, Version},
+};
+use move_debugger::{DapServer, LaunchRequest, SourceIndex};
+use move_lang::command_line as cli;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -15,6 +24,11 @@ struct Opt {
     /// Full URL address to connect to - should include port number, if applicable
     #[structopt(short = "u", long)]
     url: Option<String>,
+    /// Profile the gas charged to the replayed transactions. The profile is written as folded
+    /// stacks, for flamegraph tools, to `<gas-profile>.folded` and as a JSON report to
+    /// `<gas-profile>.json`
+    #[structopt(long, parse(from_os_str))]
+    gas_profile: Option<PathBuf>,
     #[structopt(subcommand)] // Note that we mark a field as a subcommand
     cmd: Command,
 }
@@ -37,6 +51,76 @@ enum Command {
         account: AccountAddress,
         version: Version,
     },
+    /// Debug a transaction with a Debug Adapter Protocol client talking over the standard input
+    /// and output. The client gives the Move sources the modules and the script of the
+    /// transaction were compiled from as the `sources` argument of its `launch` request, along
+    /// with the `sender` address of the modules declared without one.
+    #[structopt(name = "debug-transaction")]
+    DebugTransaction { version: Version },
+}
+
+fn execute_past_transactions(
+    debugger: &LibraDebugger,
+    begin: Version,
+    limit: u64,
+    gas_profile_path: Option<&Path>,
+) -> Result<Vec<TransactionOutput>> {
+    let path = match gas_profile_path {
+        Some(path) => path,
+        None => return debugger.execute_past_transactions(begin, limit),
+    };
+    let (outputs, gas_profile) =
+        debugger.execute_past_transactions_with_gas_profile(begin, limit)?;
+    fs::write(path.with_extension("folded"), gas_profile.folded_stacks())?;
+    fs::write(
+        path.with_extension("json"),
+        serde_json::to_string_pretty(&gas_profile.report())?,
+    )?;
+    Ok(outputs)
+}
+
+fn compile_sources(launch: &LaunchRequest, txn: &Transaction) -> Result<SourceIndex> {
+    let arguments = launch.arguments();
+    let targets = arguments["sources"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Missing launch argument: sources"))?
+        .iter()
+        .filter_map(|target| target.as_str().map(|target| target.to_string()))
+        .collect::<Vec<_>>();
+    let sender = match arguments["sender"].as_str() {
+        Some(sender) => Some(cli::parse_address(sender).map_err(|e| anyhow!(e))?),
+        None => None,
+    };
+    let mut sources = SourceIndex::compile(&targets, sender)?;
+    if let Transaction::UserTransaction(signed_txn) = txn {
+        if let TransactionPayload::Script(script) = signed_txn.payload() {
+            // The script is stepped through if it was not compiled from the sources.
+            sources.set_executed_script(script.code());
+        }
+    }
+    Ok(sources)
+}
+
+fn debug_transaction(debugger: &LibraDebugger, version: Version) -> Result<()> {
+    let server = DapServer::stdio();
+    let launch = server.wait_for_launch()?;
+    let prepared = debugger.get_committed_transaction(version).and_then(|txn| {
+        let sources = compile_sources(&launch, &txn)?;
+        Ok((txn, sources))
+    });
+    let (txn, sources) = match prepared {
+        Ok(prepared) => prepared,
+        Err(error) => {
+            server.reject_launch(launch, &error);
+            return Err(error);
+        }
+    };
+    let outputs = server.debug(launch, Arc::new(sources), |hook| {
+        debugger.execute_transactions_at_version_with_debug_hook(version, vec![txn], hook)
+    })?;
+    server.output(&format!("{:#?}", outputs));
+    server.wait_for_disconnect();
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -49,11 +133,19 @@ fn main() -> Result<()> {
         panic!("No debugger attached")
     };
 
+    // The standard output carries the messages of the debugger while debugging.
+    if let Command::DebugTransaction { version } = opt.cmd {
+        return debug_transaction(&debugger, version);
+    }
     println!("Connection Succeeded");
+    let gas_profile_path = opt.gas_profile.as_deref();
 
     match opt.cmd {
         Command::ReplayTransactions { start, limit } => {
-            println!("{:#?}", debugger.execute_past_transactions(start, limit));
+            println!(
+                "{:#?}",
+                execute_past_transactions(&debugger, start, limit, gas_profile_path)
+            );
         }
         Command::ReplayRecentTransactions { txns } => {
             let latest_version = debugger
@@ -62,7 +154,7 @@ fn main() -> Result<()> {
             assert!(latest_version >= txns);
             println!(
                 "{:#?}",
-                debugger.execute_past_transactions(latest_version - txns, txns)
+                execute_past_transactions(&debugger, latest_version - txns, txns, gas_profile_path)
             );
         }
         Command::ReplayTransactionBySequence { account, seq } => {
@@ -72,7 +164,7 @@ fn main() -> Result<()> {
             println!(
                 "Executing transaction at version: {:?}\n{:#?}",
                 version,
-                debugger.execute_past_transactions(version, 1)
+                execute_past_transactions(&debugger, version, 1, gas_profile_path)
             );
         }
         Command::AnnotateAccount { account, version } => println!(
@@ -81,6 +173,7 @@ fn main() -> Result<()> {
                 .annotate_account_state_at_version(account, version)?
                 .expect("Account not found")
         ),
+        Command::DebugTransaction { .. } => unreachable!("transactions are debugged above"),
     }
     Ok(())
 }
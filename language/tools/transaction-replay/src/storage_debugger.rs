@@ -16,7 +16,13 @@ pub(crate) struct DBDebuggerInterface(Arc<dyn DbReader>);
 
 impl DBDebuggerInterface {
     pub fn open<P: AsRef<Path> + Clone>(db_root_path: P) -> Result<Self> {
-        Ok(Self(Arc::new(LibraDB::open(db_root_path, true, None)?)))
+        Ok(Self(Arc::new(LibraDB::open(
+            db_root_path,
+            true,
+            None,
+            None,
+            None,
+        )?)))
     }
 }
 
@@ -23,7 +23,7 @@ use libra_types::{
     account_address, account_config,
     chain_id::ChainId,
     contract_event::ContractEvent,
-    on_chain_config::{new_epoch_event_key, VMPublishingOption},
+    on_chain_config::{new_epoch_event_key, OnChainConsensusConfig, VMPublishingOption},
     transaction::{
         authenticator::AuthenticationKey, ChangeSet, Script, Transaction, TransactionArgument,
         WriteSetPayload,
@@ -255,6 +255,10 @@ fn create_and_initialize_main_accounts(
             Value::vector_u8(INITIAL_GAS_SCHEDULE.0.clone()),
             Value::vector_u8(INITIAL_GAS_SCHEDULE.1.clone()),
             Value::u8(chain_id.id()),
+            Value::vector_u8(
+                lcs::to_bytes(&OnChainConsensusConfig::default())
+                    .expect("Failed to serialize the consensus config"),
+            ),
         ],
     );
 
@@ -1,9 +1,13 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{ensure, format_err, Result};
 use backup_service::start_backup_service;
 use consensus::{consensus_provider::start_consensus, gen_consensus_reconfig_subscription};
-use debug_interface::node_debug_service::NodeDebugService;
+use debug_interface::{
+    mempool::{MempoolDebugInterface, MempoolPeerView, MempoolTransactionView},
+    node_debug_service::NodeDebugService,
+};
 use executor::{db_bootstrapper::maybe_bootstrap, Executor};
 use executor_types::ChunkExecutor;
 use futures::{channel::mpsc::channel, executor::block_on};
@@ -14,16 +18,22 @@ use libra_config::{
 };
 use libra_json_rpc::bootstrap_from_config as bootstrap_rpc;
 use libra_logger::prelude::*;
-use libra_mempool::gen_mempool_reconfig_subscription;
+use libra_mempool::{
+    gen_mempool_reconfig_subscription, MempoolDebugHandle, MempoolTransactionSnapshot,
+};
 use libra_metrics::metric_server;
 use libra_types::{
-    account_config::libra_root_address, account_state::AccountState, chain_id::ChainId,
+    account_address::AccountAddress, account_config::libra_root_address,
+    account_state::AccountState, chain_id::ChainId,
 };
 use libra_vm::LibraVM;
 use libradb::LibraDB;
 use network_builder::builder::NetworkBuilder;
 use state_synchronizer::StateSynchronizer;
-use std::{boxed::Box, convert::TryFrom, net::ToSocketAddrs, sync::Arc, thread, time::Instant};
+use std::{
+    boxed::Box, convert::TryFrom, net::ToSocketAddrs, str::FromStr, sync::Arc, thread,
+    time::Instant,
+};
 use storage_interface::DbReaderWriter;
 use storage_service::start_storage_service_with_db;
 use tokio::runtime::{Builder, Runtime};
@@ -60,7 +70,84 @@ fn setup_chunk_executor(db: DbReaderWriter) -> Box<dyn ChunkExecutor> {
     Box::new(Executor::<LibraVM>::new(db))
 }
 
-fn setup_debug_interface(config: &NodeConfig) -> NodeDebugService {
+/// Serves the state of mempool to the debug interface. The views are built from snapshots of
+/// mempool, outside of its locks.
+struct MempoolDebug(MempoolDebugHandle);
+
+impl MempoolDebugInterface for MempoolDebug {
+    fn get_transactions(
+        &self,
+        account: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<MempoolTransactionView>> {
+        let account = match account {
+            Some(account) => {
+                let hex = account.trim_start_matches("0x");
+                ensure!(!hex.is_empty(), "invalid account address: {}", account);
+                Some(
+                    AccountAddress::from_str(hex)
+                        .map_err(|e| format_err!("invalid account address {}: {}", account, e))?,
+                )
+            }
+            None => None,
+        };
+        Ok(self
+            .0
+            .get_transactions(account, limit)
+            .into_iter()
+            .map(mempool_transaction_view)
+            .collect())
+    }
+
+    fn get_parking_lot(&self, limit: usize) -> Vec<MempoolTransactionView> {
+        self.0
+            .get_parking_lot(limit)
+            .into_iter()
+            .map(mempool_transaction_view)
+            .collect()
+    }
+
+    fn get_timeline(&self, timeline_id: u64, limit: usize) -> Vec<MempoolTransactionView> {
+        self.0
+            .get_timeline(timeline_id, limit)
+            .into_iter()
+            .map(mempool_transaction_view)
+            .collect()
+    }
+
+    fn get_peers(&self) -> Vec<MempoolPeerView> {
+        self.0
+            .get_peers()
+            .into_iter()
+            .map(|peer| MempoolPeerView {
+                peer: peer.peer.to_string(),
+                is_alive: peer.is_alive,
+                timeline_id: peer.timeline_id,
+                pending_batches: peer.pending_batches.into_iter().collect(),
+                retry_txns: peer.retry_txns,
+                backoff_mode: peer.backoff_mode,
+            })
+            .collect()
+    }
+}
+
+fn mempool_transaction_view(txn: MempoolTransactionSnapshot) -> MempoolTransactionView {
+    MempoolTransactionView {
+        sender: txn.sender.to_string(),
+        sequence_number: txn.sequence_number,
+        gas_unit_price: txn.gas_unit_price,
+        ranking_score: txn.ranking_score,
+        timeline_id: txn.timeline_id,
+        parked: txn.parked,
+        expiration_timestamp_secs: txn.expiration_timestamp_secs,
+        system_ttl_expiration_secs: txn.expiration_time.as_secs(),
+    }
+}
+
+fn setup_debug_interface(
+    config: &NodeConfig,
+    mempool: Arc<dyn MempoolDebugInterface>,
+) -> NodeDebugService {
     let addr = format!(
         "{}:{}",
         config.debug_interface.address, config.debug_interface.admission_control_node_debug_port,
@@ -73,7 +160,7 @@ fn setup_debug_interface(config: &NodeConfig) -> NodeDebugService {
     libra_trace::set_libra_trace(&config.debug_interface.libra_trace.sampling)
         .expect("Failed to set libra trace sampling rate.");
 
-    NodeDebugService::new(addr)
+    NodeDebugService::new(addr, mempool)
 }
 
 pub fn setup_environment(node_config: &NodeConfig) -> LibraHandle {
@@ -99,6 +186,8 @@ pub fn setup_environment(node_config: &NodeConfig) -> LibraHandle {
             &node_config.storage.dir(),
             false, /* readonly */
             node_config.storage.prune_window,
+            node_config.storage.ledger_prune_window,
+            node_config.storage.write_set_window,
         )
         .expect("DB should open."),
     );
@@ -251,7 +340,7 @@ pub fn setup_environment(node_config: &NodeConfig) -> LibraHandle {
     let (consensus_to_mempool_sender, consensus_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
 
     instant = Instant::now();
-    let mempool = libra_mempool::bootstrap(
+    let (mempool, mempool_debug) = libra_mempool::bootstrap(
         node_config,
         Arc::clone(&db_rw.reader),
         mempool_network_handles,
@@ -289,7 +378,7 @@ pub fn setup_environment(node_config: &NodeConfig) -> LibraHandle {
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     }
 
-    let debug_if = setup_debug_interface(&node_config);
+    let debug_if = setup_debug_interface(&node_config, Arc::new(MempoolDebug(mempool_debug)));
 
     LibraHandle {
         _network_runtimes: network_runtimes,
@@ -59,6 +59,21 @@ impl PriorityIndex {
         }
     }
 
+    /// returns the transaction with the lowest priority, if it ranks strictly below `txn`
+    /// transactions of the sender of `txn` are skipped, since evicting them would make `txn`
+    /// itself non-ready
+    pub(crate) fn get_evictable(&self, txn: &MempoolTransaction) -> Option<TxnPointer> {
+        let key = self.make_key(txn);
+        self.data
+            .iter()
+            .take_while(|lowest| {
+                (lowest.governance_role.priority(), lowest.gas_ranking_score)
+                    < (key.governance_role.priority(), key.gas_ranking_score)
+            })
+            .find(|lowest| lowest.address != key.address)
+            .map(|lowest| (lowest.address, lowest.sequence_number))
+    }
+
     /// returns iterator over priority queue
     pub(crate) fn iter(&self) -> PriorityQueueIter {
         self.data.iter().rev()
@@ -214,7 +229,7 @@ impl TimelineIndex {
 
     /// read all transactions from timeline since <timeline_id>
     pub(crate) fn read_timeline(
-        &mut self,
+        &self,
         timeline_id: u64,
         count: usize,
     ) -> Vec<(u64, (AccountAddress, u64))> {
@@ -300,6 +315,15 @@ impl ParkingLotIndex {
         }
     }
 
+    /// returns iterator over the parked transactions, grouped by account
+    pub(crate) fn iter(&self) -> impl Iterator<Item = TxnPointer> + '_ {
+        self.data.iter().flat_map(|(address, sequence_numbers)| {
+            sequence_numbers
+                .iter()
+                .map(move |sequence_number| (*address, *sequence_number))
+        })
+    }
+
     /// remove transaction from index
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
         let sender = &txn.txn.sender();
@@ -324,6 +348,13 @@ impl ParkingLotIndex {
         }
     }
 
+    pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
+        self.account_indices
+            .get(&txn.get_sender())
+            .and_then(|index| self.data.get(*index))
+            .map_or(false, |(_, txns)| txns.contains(&txn.get_sequence_number()))
+    }
+
     /// returns random "non-ready" transaction (with highest sequence number for that account)
     pub(crate) fn get_poppable(&mut self) -> Option<TxnPointer> {
         let mut rng = rand::thread_rng();
@@ -0,0 +1,129 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-disk journal of core mempool
+//!
+//! Every transaction accepted by mempool is recorded in the journal and deleted from it once it
+//! leaves mempool (commit, rejection, GC, eviction or replacement), so on restart the journal holds
+//! exactly the transactions that were pending when the node went down.
+//!
+//! Writes are applied by a dedicated thread, so that mempool does not wait for the disk while it
+//! holds its lock. The writes queued while the previous ones reach the disk are applied together
+//! in a single batch.
+mod schema;
+
+use crate::core_mempool::{index::TxnPointer, transaction::MempoolTransaction};
+use anyhow::Result;
+use libra_logger::prelude::*;
+use schema::{TransactionSchema, TRANSACTION_CF_NAME};
+use schemadb::{ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME};
+use std::{
+    iter,
+    path::Path,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+pub struct MempoolJournal {
+    db: Arc<DB>,
+    // writes not applied yet; dropped on shutdown to stop the writer
+    writes: Option<Sender<JournalWrite>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+enum JournalWrite {
+    Save(MempoolTransaction),
+    Delete(TxnPointer),
+}
+
+impl MempoolJournal {
+    pub fn open<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let column_families = vec![/* UNUSED CF = */ DEFAULT_CF_NAME, TRANSACTION_CF_NAME];
+
+        let path = db_root_path.as_ref().join("mempooldb");
+        let instant = Instant::now();
+        let db = Arc::new(DB::open(path.clone(), "mempool", column_families)?);
+
+        info!(
+            "Opened MempoolJournal at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        let (writes, receiver) = mpsc::channel();
+        let writer_db = Arc::clone(&db);
+        let writer = thread::Builder::new()
+            .name("mempool-journal".into())
+            .spawn(move || write_journal(&writer_db, receiver))?;
+        Ok(Self {
+            db,
+            writes: Some(writes),
+            writer: Some(writer),
+        })
+    }
+
+    /// Records transaction accepted by mempool
+    pub fn save_transaction(&self, txn: &MempoolTransaction) {
+        self.write(JournalWrite::Save(txn.clone()));
+    }
+
+    /// Deletes transactions that left mempool
+    pub fn delete_transactions(&self, txns: &[TxnPointer]) {
+        for txn in txns {
+            self.write(JournalWrite::Delete(*txn));
+        }
+    }
+
+    /// Get all recorded transactions, ordered by sender and sequence number
+    pub fn get_transactions(&self) -> Result<Vec<MempoolTransaction>> {
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.map(|value| value.map(|(_, txn)| txn))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn write(&self, write: JournalWrite) {
+        let sent = match &self.writes {
+            Some(writes) => writes.send(write).is_ok(),
+            None => false,
+        };
+        if !sent {
+            error!("[Mempool] journal writer stopped, the journal is no longer updated");
+        }
+    }
+}
+
+impl Drop for MempoolJournal {
+    /// Waits for the pending writes to reach the disk
+    fn drop(&mut self) {
+        self.writes.take();
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                error!("[Mempool] journal writer panicked");
+            }
+        }
+    }
+}
+
+fn write_journal(db: &DB, writes: Receiver<JournalWrite>) {
+    while let Ok(write) = writes.recv() {
+        let mut batch = SchemaBatch::new();
+        // later writes of the same transaction replace the earlier ones in the batch
+        let batched = iter::once(write)
+            .chain(writes.try_iter())
+            .map(|write| match write {
+                JournalWrite::Save(txn) => {
+                    batch.put::<TransactionSchema>(&TxnPointer::from(&txn), &txn)
+                }
+                JournalWrite::Delete(txn) => batch.delete::<TransactionSchema>(&txn),
+            })
+            .collect::<Result<()>>();
+        if let Err(e) = batched.and_then(|()| db.write_schemas(batch)) {
+            error!("[Mempool] failed to write journal: {}", e);
+        }
+    }
+}
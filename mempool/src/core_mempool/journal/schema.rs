@@ -0,0 +1,65 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for transactions recorded in the mempool journal.
+//!
+//! Serialized mempool transaction identified by its sender and sequence number.
+//! ```text
+//! |<-------key------->|<--------value-------->|
+//! | address | seq_num |  mempool transaction  |
+//! ```
+
+use crate::core_mempool::{index::TxnPointer, transaction::MempoolTransaction};
+use anyhow::{ensure, Result};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use libra_types::account_address::AccountAddress;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName,
+};
+use std::{convert::TryFrom, mem::size_of};
+
+pub(super) const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
+
+define_schema!(
+    TransactionSchema,
+    TxnPointer,
+    MempoolTransaction,
+    TRANSACTION_CF_NAME
+);
+
+impl KeyCodec<TransactionSchema> for TxnPointer {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, seq_num) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.write_u64::<BigEndian>(seq_num)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == size_of::<Self>(),
+            "Unexpected data len {}, expected {}.",
+            data.len(),
+            size_of::<Self>(),
+        );
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let seq_num = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, seq_num))
+    }
+}
+
+impl ValueCodec<TransactionSchema> for MempoolTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(lcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(lcs::from_bytes(data)?)
+    }
+}
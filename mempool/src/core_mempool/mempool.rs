@@ -6,12 +6,14 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        journal::MempoolJournal,
+        transaction::{MempoolTransaction, MempoolTransactionSnapshot, TimelineState},
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
     },
     counters, OP_COUNTERS,
 };
+use anyhow::Result;
 use libra_config::config::NodeConfig;
 use libra_logger::prelude::*;
 use libra_trace::prelude::*;
@@ -22,9 +24,11 @@ use libra_types::{
 };
 use std::{
     cmp::max,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     time::{Duration, SystemTime},
 };
+use storage_interface::DbReader;
+use vm_validator::vm_validator::get_account_sequence_number;
 
 pub struct Mempool {
     // stores metadata of all transactions in mempool (of all states)
@@ -125,41 +129,94 @@ impl Mempool {
             txn.sequence_number(),
             db_sequence_number,
         );
-        let cached_value = self.sequence_number_cache.get(&txn.sender());
+        let expiration_time = libra_time::duration_since_epoch() + self.system_transaction_timeout;
+        let txn_info = MempoolTransaction::new(
+            txn,
+            expiration_time,
+            gas_amount,
+            rankin_score,
+            timeline_state,
+            governance_role,
+        );
+        self.insert(txn_info, db_sequence_number)
+    }
+
+    fn insert(&mut self, txn_info: MempoolTransaction, db_sequence_number: u64) -> MempoolStatus {
+        let sender = txn_info.get_sender();
+        let cached_value = self.sequence_number_cache.get(&sender);
         let sequence_number =
             cached_value.map_or(db_sequence_number, |value| max(*value, db_sequence_number));
-        self.sequence_number_cache
-            .insert(txn.sender(), sequence_number);
+        self.sequence_number_cache.insert(sender, sequence_number);
 
         // don't accept old transactions (e.g. seq is less than account's current seq_number)
-        if txn.sequence_number() < sequence_number {
+        if txn_info.get_sequence_number() < sequence_number {
             return MempoolStatus::new(MempoolStatusCode::InvalidSeqNumber).with_message(format!(
                 "transaction sequence number is {}, current sequence number is  {}",
-                txn.sequence_number(),
+                txn_info.get_sequence_number(),
                 sequence_number,
             ));
         }
 
-        let expiration_time = libra_time::duration_since_epoch() + self.system_transaction_timeout;
-        if timeline_state != TimelineState::NonQualified {
+        if txn_info.timeline_state != TimelineState::NonQualified {
             self.metrics_cache
-                .insert((txn.sender(), txn.sequence_number()), SystemTime::now());
+                .insert((sender, txn_info.get_sequence_number()), SystemTime::now());
         }
 
-        let txn_info = MempoolTransaction::new(
-            txn,
-            expiration_time,
-            gas_amount,
-            rankin_score,
-            timeline_state,
-            governance_role,
-        );
-
         let status = self.transactions.insert(txn_info, sequence_number);
         OP_COUNTERS.inc(&format!("insert.{:?}", status));
         status
     }
 
+    /// Opens the journal under the storage directory, restores the transactions recorded in it
+    /// before the node restarted and records the transactions in mempool from now on
+    /// Transactions committed in the meantime (according to account sequence numbers in `db`)
+    /// or expired by TTL are dropped from the journal instead
+    /// On error, mempool is left untouched and without journal
+    pub(crate) fn open_journal(&mut self, config: &NodeConfig, db: &dyn DbReader) -> Result<()> {
+        let journal = MempoolJournal::open(config.storage.dir())?;
+        let txns = journal.get_transactions()?;
+        // everything that can fail happens before mempool is changed
+        let mut sequence_numbers = HashMap::new();
+        for txn in &txns {
+            let sender = txn.get_sender();
+            if !sequence_numbers.contains_key(&sender) {
+                sequence_numbers.insert(sender, get_account_sequence_number(db, sender)?);
+            }
+        }
+
+        // nothing fails from here on, and the journal is attached before the restore so that
+        // txns evicted by it are dropped from the journal as well
+        self.transactions.set_journal(journal);
+        let now = libra_time::duration_since_epoch();
+        let mut restored = 0;
+        let mut stale = vec![];
+        for txn in txns {
+            let db_sequence_number = sequence_numbers[&txn.get_sender()];
+            let pointer = TxnPointer::from(&txn);
+            if txn.get_sequence_number() < db_sequence_number
+                || txn.expiration_time <= now
+                || Duration::from_secs(txn.txn.expiration_timestamp_secs()) <= now
+            {
+                stale.push(pointer);
+                continue;
+            }
+            if self.insert(txn, db_sequence_number).code == MempoolStatusCode::Accepted {
+                restored += 1;
+            } else {
+                stale.push(pointer);
+            }
+        }
+        if let Some(journal) = self.transactions.journal() {
+            journal.delete_transactions(&stale);
+        }
+        info!(
+            "[Mempool] restored {} txns from journal, dropped {} stale txns",
+            restored,
+            stale.len()
+        );
+        Ok(())
+    }
+
     /// Fetches next block of transactions for consensus
     /// `batch_size` - size of requested block
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet
@@ -249,6 +306,34 @@ impl Mempool {
             .gc_by_expiration_time(block_time, &self.metrics_cache);
     }
 
+    /// Returns snapshots of up to `limit` pending transactions, only of those sent by `account`
+    /// if it is set
+    pub(crate) fn get_transaction_snapshots(
+        &self,
+        account: Option<AccountAddress>,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        self.transactions.get_transaction_snapshots(account, limit)
+    }
+
+    /// Returns snapshots of up to `limit` transactions of the parking lot
+    pub(crate) fn get_parking_lot_snapshots(
+        &self,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        self.transactions.get_parking_lot_snapshots(limit)
+    }
+
+    /// Returns snapshots of up to `limit` transactions ready for broadcast, in timeline order
+    /// since `timeline_id`
+    pub(crate) fn get_timeline_snapshots(
+        &self,
+        timeline_id: u64,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        self.transactions.get_timeline_snapshots(timeline_id, limit)
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`
     /// Returns block of transactions and new last_timeline_id
     pub(crate) fn read_timeline(
@@ -8,7 +8,7 @@ use libra_types::{
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct MempoolTransaction {
     pub txn: SignedTransaction,
     // system expiration time of transaction. It should be removed from mempool by that time
@@ -48,6 +48,23 @@ impl MempoolTransaction {
     }
 }
 
+/// Copy of the state of a transaction in mempool, taken for inspection
+#[derive(Clone, Debug, PartialEq)]
+pub struct MempoolTransactionSnapshot {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub gas_unit_price: u64,
+    pub ranking_score: u64,
+    // position in the log of transactions ready for broadcast, unset for non-ready transactions
+    pub timeline_id: Option<u64>,
+    // whether the transaction is in the parking lot, i.e. can't be included in the next block
+    pub parked: bool,
+    // client-specified expiration time, in seconds since epoch
+    pub expiration_timestamp_secs: u64,
+    // system expiration time of transaction
+    pub expiration_time: Duration,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Hash, Serialize)]
 pub enum TimelineState {
     // transaction is ready for broadcast
@@ -5,9 +5,10 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            TimelineIndex, TxnPointer,
         },
-        transaction::{MempoolTransaction, TimelineState},
+        journal::MempoolJournal,
+        transaction::{MempoolTransaction, MempoolTransactionSnapshot, TimelineState},
         ttl_cache::TtlCache,
     },
     counters, OP_COUNTERS,
@@ -21,6 +22,7 @@ use libra_types::{
     transaction::SignedTransaction,
 };
 use std::{
+    cmp::max,
     collections::HashMap,
     ops::Bound,
     time::{Duration, SystemTime},
@@ -42,10 +44,16 @@ pub struct TransactionStore {
     timeline_index: TimelineIndex,
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
+    // gas prices of recently evicted txns, so their resubmission can be told apart
+    evicted_txns: TtlCache<TxnPointer, u64>,
+    // optional on-disk record of all txns in mempool
+    journal: Option<MempoolJournal>,
 
     // configuration
     capacity: usize,
     capacity_per_user: usize,
+    capacity_bytes_per_user: Option<usize>,
+    replace_by_fee_bump_percent: u64,
 }
 
 impl TransactionStore {
@@ -62,10 +70,17 @@ impl TransactionStore {
             priority_index: PriorityIndex::new(),
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
+            evicted_txns: TtlCache::new(
+                config.capacity,
+                Duration::from_secs(config.system_transaction_timeout_secs),
+            ),
+            journal: None,
 
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
+            capacity_bytes_per_user: config.capacity_bytes_per_user,
+            replace_by_fee_bump_percent: config.replace_by_fee_bump_percent,
         }
     }
 
@@ -92,32 +107,22 @@ impl TransactionStore {
         txn: MempoolTransaction,
         current_sequence_number: u64,
     ) -> MempoolStatus {
-        if self.handle_gas_price_update(&txn).is_err() {
+        if let Err(e) = self.handle_gas_price_update(&txn) {
             return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(format!(
-                "Failed to update gas price to {}",
-                txn.get_gas_price()
-            ));
-        }
-
-        if self.check_if_full(&txn, current_sequence_number) {
-            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
-                "mempool size: {}, capacity: {}",
-                self.system_ttl_index.size(),
-                self.capacity,
+                "Failed to update gas price to {}: {}",
+                txn.get_gas_price(),
+                e
             ));
         }
 
         let address = txn.get_sender();
         let sequence_number = txn.get_sequence_number();
 
-        self.transactions
-            .entry(address)
-            .or_insert_with(AccountTransactions::new);
-
         self.clean_committed_transactions(&address, current_sequence_number);
 
-        if let Some(txns) = self.transactions.get_mut(&address) {
-            // capacity check
+        // quotas per account are checked before making room in a full mempool, so that a txn
+        // rejected by them doesn't evict the txns of other accounts
+        if let Some(txns) = self.transactions.get(&address) {
             if txns.len() >= self.capacity_per_user {
                 return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
                     format!(
@@ -127,13 +132,51 @@ impl TransactionStore {
                     ),
                 );
             }
+            if let Some(capacity_bytes_per_user) = self.capacity_bytes_per_user {
+                let txns_bytes = txns
+                    .values()
+                    .map(|t| t.txn.raw_txn_bytes_len())
+                    .sum::<usize>();
+                if txns_bytes + txn.txn.raw_txn_bytes_len() > capacity_bytes_per_user {
+                    return MempoolStatus::new(MempoolStatusCode::TooManyBytes).with_message(
+                        format!(
+                            "txns bytes: {} capacity bytes per user: {}",
+                            txns_bytes, capacity_bytes_per_user,
+                        ),
+                    );
+                }
+            }
+        }
 
-            // insert into storage and other indexes
-            self.system_ttl_index.insert(&txn);
-            self.expiration_time_index.insert(&txn);
-            txns.insert(sequence_number, txn);
-            self.track_indices();
+        if self.check_if_full(&txn, current_sequence_number) {
+            if let Some(gas_price) = self.evicted_txns.get(&(address, sequence_number)) {
+                return MempoolStatus::new(MempoolStatusCode::Evicted).with_message(format!(
+                    "txn was evicted from full mempool with gas price {}, mempool size: {}, capacity: {}",
+                    gas_price,
+                    self.system_ttl_index.size(),
+                    self.capacity,
+                ));
+            }
+            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
+                "mempool size: {}, capacity: {}",
+                self.system_ttl_index.size(),
+                self.capacity,
+            ));
+        }
+
+        if let Some(journal) = &self.journal {
+            journal.save_transaction(&txn);
         }
+
+        // insert into storage and other indexes
+        self.system_ttl_index.insert(&txn);
+        self.expiration_time_index.insert(&txn);
+        self.transactions
+            .entry(address)
+            .or_insert_with(AccountTransactions::new)
+            .insert(sequence_number, txn);
+        self.evicted_txns.remove(&(address, sequence_number));
+        self.track_indices();
         self.process_ready_transactions(&address, current_sequence_number);
         MempoolStatus::new(MempoolStatusCode::Accepted)
     }
@@ -157,26 +200,59 @@ impl TransactionStore {
     }
 
     /// checks if Mempool is full
-    /// If it's full, tries to free some space by evicting transactions from ParkingLot
+    /// If it's full, tries to free some space by evicting, in order of preference:
+    /// 1. "non-ready" transactions from ParkingLot
+    /// 2. ready transactions with the lowest priority, as long as they rank below the new one
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion
     fn check_if_full(&mut self, txn: &MempoolTransaction, curr_sequence_number: u64) -> bool {
         if self.system_ttl_index.size() >= self.capacity
             && self.check_txn_ready(txn, curr_sequence_number)
         {
-            // try to free some space in Mempool from ParkingLot
-            if let Some((address, sequence_number)) = self.parking_lot_index.get_poppable() {
-                if let Some(txn) = self
-                    .transactions
-                    .get_mut(&address)
-                    .and_then(|txns| txns.remove(&sequence_number))
-                {
-                    self.index_remove(&txn);
+            while self.system_ttl_index.size() >= self.capacity {
+                let evicted = if let Some(pointer) = self.parking_lot_index.get_poppable() {
+                    self.evict(pointer, counters::EVICT_PARKED_TXN_LABEL)
+                } else if let Some(pointer) = self.priority_index.get_evictable(txn) {
+                    self.evict(pointer, counters::EVICT_LOW_PRIORITY_TXN_LABEL)
+                } else {
+                    false
+                };
+                if !evicted {
+                    break;
                 }
             }
         }
         self.system_ttl_index.size() >= self.capacity
     }
 
+    /// removes transaction from Mempool to make room for a new one
+    /// all following transactions of the same account can't be ready anymore, so they get parked
+    fn evict(&mut self, (address, sequence_number): TxnPointer, evict_type: &str) -> bool {
+        if let Some(txns) = self.transactions.get_mut(&address) {
+            if let Some(txn) = txns.remove(&sequence_number) {
+                for (_, t) in txns.range((Bound::Excluded(sequence_number), Bound::Unbounded)) {
+                    self.parking_lot_index.insert(&t);
+                    self.priority_index.remove(&t);
+                    self.timeline_index.remove(&t);
+                }
+                debug!(
+                    "[Mempool] evicting {} txn {}:{} with gas price {}",
+                    evict_type,
+                    address,
+                    sequence_number,
+                    txn.get_gas_price()
+                );
+                counters::CORE_MEMPOOL_EVICTED_TXNS
+                    .with_label_values(&[evict_type])
+                    .inc();
+                self.evicted_txns
+                    .insert((address, sequence_number), txn.get_gas_price());
+                self.index_remove(&txn);
+                return true;
+            }
+        }
+        false
+    }
+
     /// check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it)
     /// Two ways this can happen:
     /// 1. txn sequence number == curr_sequence_number
@@ -205,23 +281,37 @@ impl TransactionStore {
 
     /// check if transaction is already present in Mempool
     /// e.g. given request is update
-    /// we allow increase in gas price to speed up process
+    /// we allow replace-by-fee, i.e. increase in gas price by at least `replace_by_fee_bump_percent`
+    /// to speed up process
+    /// The replaced transaction is removed from all indexes, so that the new one gets a new
+    /// position in the timeline and is broadcast again by shared mempool
     fn handle_gas_price_update(&mut self, txn: &MempoolTransaction) -> Result<()> {
         if let Some(txns) = self.transactions.get_mut(&txn.get_sender()) {
             if let Some(current_version) = txns.get_mut(&txn.get_sequence_number()) {
+                let min_gas_price = min_replacement_gas_price(
+                    current_version.get_gas_price(),
+                    self.replace_by_fee_bump_percent,
+                );
                 if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
                     && current_version.txn.payload() == txn.txn.payload()
                     && current_version.txn.expiration_timestamp_secs()
                         == txn.txn.expiration_timestamp_secs()
-                    && current_version.get_gas_price() < txn.get_gas_price()
+                    && txn.get_gas_price() >= min_gas_price
                 {
                     if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
                         self.index_remove(&txn);
                     }
+                    counters::CORE_MEMPOOL_REPLACED_TXNS
+                        .with_label_values(&[counters::REPLACE_ACCEPTED_LABEL])
+                        .inc();
                 } else {
-                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}",
+                    counters::CORE_MEMPOOL_REPLACED_TXNS
+                        .with_label_values(&[counters::REPLACE_REJECTED_LABEL])
+                        .inc();
+                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}, min gas price for replacement: {}",
                             txn.get_gas_price(),
-                            current_version.get_gas_price()));
+                            current_version.get_gas_price(),
+                            min_gas_price));
                 }
             }
         }
@@ -314,9 +404,21 @@ impl TransactionStore {
         self.priority_index.remove(&txn);
         self.timeline_index.remove(&txn);
         self.parking_lot_index.remove(&txn);
+        if let Some(journal) = &self.journal {
+            journal.delete_transactions(&[TxnPointer::from(txn)]);
+        }
         self.track_indices();
     }
 
+    pub(crate) fn journal(&self) -> Option<&MempoolJournal> {
+        self.journal.as_ref()
+    }
+
+    /// Records the txns inserted or removed from now on in `journal`
+    pub(crate) fn set_journal(&mut self, journal: MempoolJournal) {
+        self.journal = Some(journal);
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`
     /// Returns block of transactions and new last_timeline_id
     pub(crate) fn read_timeline(
@@ -376,6 +478,7 @@ impl TransactionStore {
         let now = libra_time::duration_since_epoch();
 
         self.gc(now, true, metrics_cache);
+        self.evicted_txns.gc(SystemTime::now());
     }
 
     /// GC old transactions based on client-specified expiration time
@@ -455,6 +558,92 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Returns snapshots of up to `limit` transactions in mempool, or only of those sent by
+    /// `account`
+    pub(crate) fn get_transaction_snapshots(
+        &self,
+        account: Option<AccountAddress>,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        match account {
+            Some(account) => self
+                .transactions
+                .get(&account)
+                .into_iter()
+                .flat_map(|txns| txns.values())
+                .take(limit)
+                .map(|txn| self.snapshot(txn))
+                .collect(),
+            None => self
+                .transactions
+                .values()
+                .flat_map(|txns| txns.values())
+                .take(limit)
+                .map(|txn| self.snapshot(txn))
+                .collect(),
+        }
+    }
+
+    /// Returns snapshots of up to `limit` transactions of the parking lot
+    pub(crate) fn get_parking_lot_snapshots(
+        &self,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        self.parking_lot_index
+            .iter()
+            .filter_map(|(address, sequence_number)| {
+                self.get_mempool_txn(&address, sequence_number)
+            })
+            .take(limit)
+            .map(|txn| self.snapshot(txn))
+            .collect()
+    }
+
+    /// Returns snapshots of up to `limit` transactions ready for broadcast, in timeline order
+    /// since `timeline_id`
+    pub(crate) fn get_timeline_snapshots(
+        &self,
+        timeline_id: u64,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        self.timeline_index
+            .read_timeline(timeline_id, limit)
+            .into_iter()
+            .filter_map(|(_, (address, sequence_number))| {
+                self.get_mempool_txn(&address, sequence_number)
+            })
+            .map(|txn| self.snapshot(txn))
+            .collect()
+    }
+
+    fn get_mempool_txn(
+        &self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<&MempoolTransaction> {
+        self.transactions
+            .get(address)
+            .and_then(|txns| txns.get(&sequence_number))
+    }
+
+    fn snapshot(&self, txn: &MempoolTransaction) -> MempoolTransactionSnapshot {
+        let parked = self.parking_lot_index.contains(txn);
+        let timeline_id = match txn.timeline_state {
+            TimelineState::Ready(timeline_id) if !parked => Some(timeline_id),
+            _ => None,
+        };
+        MempoolTransactionSnapshot {
+            sender: txn.get_sender(),
+            sequence_number: txn.get_sequence_number(),
+            gas_unit_price: txn.get_gas_price(),
+            ranking_score: txn.ranking_score,
+            timeline_id,
+            parked,
+            expiration_timestamp_secs: txn.txn.expiration_timestamp_secs(),
+            expiration_time: txn.expiration_time,
+        }
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
@@ -464,3 +653,10 @@ impl TransactionStore {
         self.parking_lot_index.size()
     }
 }
+
+/// Returns the minimum gas price for a transaction to replace one paying `gas_price`: it has to be
+/// higher by at least `bump_percent` percent, and by at least 1 in any case
+fn min_replacement_gas_price(gas_price: u64, bump_percent: u64) -> u64 {
+    let bumped = u128::from(gas_price) * (100 + u128::from(bump_percent)) / 100;
+    max(bumped, u128::from(gas_price) + 1).min(u128::from(u64::max_value())) as u64
+}
@@ -19,6 +19,14 @@ pub const GET_BLOCK_STAGE_LABEL: &str = "get_block";
 pub const COMMIT_ACCEPTED_LABEL: &str = "commit_accepted";
 pub const COMMIT_REJECTED_LABEL: &str = "commit_rejected";
 
+// Core mempool replace-by-fee result labels
+pub const REPLACE_ACCEPTED_LABEL: &str = "accepted";
+pub const REPLACE_REJECTED_LABEL: &str = "rejected";
+
+// Core mempool eviction type labels
+pub const EVICT_PARKED_TXN_LABEL: &str = "parked";
+pub const EVICT_LOW_PRIORITY_TXN_LABEL: &str = "low_priority";
+
 // Core mempool GC type labels
 pub const GC_SYSTEM_TTL_LABEL: &str = "system_ttl";
 pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
@@ -67,6 +75,26 @@ pub static CORE_MEMPOOL_TXN_COMMIT_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of txns evicted from full core mempool to make room for new ones
+pub static CORE_MEMPOOL_EVICTED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_core_mempool_evicted_txns",
+        "Number of txns evicted from full core mempool",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Counter of attempts to replace a transaction in core mempool with a higher gas price one
+pub static CORE_MEMPOOL_REPLACED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_core_mempool_replaced_txns",
+        "Number of attempts to replace a txn in core mempool by one paying a higher gas price",
+        &["result"]
+    )
+    .unwrap()
+});
+
 pub static CORE_MEMPOOL_GC_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "libra_core_mempool_gc_latency",
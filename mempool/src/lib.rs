@@ -59,6 +59,7 @@
 /// This module provides mocks of shared mempool for tests.
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
+pub use core_mempool::MempoolTransactionSnapshot;
 pub use shared_mempool::{
     bootstrap, network,
     types::{
@@ -66,6 +67,7 @@ pub use shared_mempool::{
         CommittedTransaction, ConsensusRequest, ConsensusResponse, MempoolClientSender,
         SubmissionStatus, TransactionExclusion,
     },
+    MempoolDebugHandle, MempoolPeerSnapshot,
 };
 #[cfg(any(test, feature = "fuzzing"))]
 pub use tests::{fuzzing, mocks};
@@ -0,0 +1,62 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    core_mempool::{CoreMempool, MempoolTransactionSnapshot},
+    shared_mempool::peer_manager::{MempoolPeerSnapshot, PeerManager},
+};
+use libra_types::account_address::AccountAddress;
+use std::sync::{Arc, Mutex};
+
+/// Read-only access to the state of shared mempool, e.g. for the node debug interface
+/// The locks of mempool are only held while the state is copied, and at most `limit`
+/// transactions are copied at once, so that inspecting mempool doesn't hold up transaction
+/// processing
+#[derive(Clone)]
+pub struct MempoolDebugHandle {
+    mempool: Arc<Mutex<CoreMempool>>,
+    peer_manager: Arc<PeerManager>,
+}
+
+impl MempoolDebugHandle {
+    pub(crate) fn new(mempool: Arc<Mutex<CoreMempool>>, peer_manager: Arc<PeerManager>) -> Self {
+        Self {
+            mempool,
+            peer_manager,
+        }
+    }
+
+    /// Returns up to `limit` pending transactions, only those sent by `account` if it is set
+    pub fn get_transactions(
+        &self,
+        account: Option<AccountAddress>,
+        limit: usize,
+    ) -> Vec<MempoolTransactionSnapshot> {
+        self.mempool
+            .lock()
+            .expect("[shared mempool] failed to acquire mempool lock")
+            .get_transaction_snapshots(account, limit)
+    }
+
+    /// Returns up to `limit` transactions of the parking lot
+    pub fn get_parking_lot(&self, limit: usize) -> Vec<MempoolTransactionSnapshot> {
+        self.mempool
+            .lock()
+            .expect("[shared mempool] failed to acquire mempool lock")
+            .get_parking_lot_snapshots(limit)
+    }
+
+    /// Returns up to `limit` transactions ready for broadcast, in timeline order since
+    /// `timeline_id`
+    pub fn get_timeline(&self, timeline_id: u64, limit: usize) -> Vec<MempoolTransactionSnapshot> {
+        self.mempool
+            .lock()
+            .expect("[shared mempool] failed to acquire mempool lock")
+            .get_timeline_snapshots(timeline_id, limit)
+    }
+
+    /// Returns the broadcast state of every upstream peer
+    pub fn get_peers(&self) -> Vec<MempoolPeerSnapshot> {
+        self.peer_manager.get_peer_snapshots()
+    }
+}
@@ -1,9 +1,12 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod debug;
 pub mod network;
 mod runtime;
 pub(crate) mod types;
+pub use debug::MempoolDebugHandle;
+pub use peer_manager::MempoolPeerSnapshot;
 pub use runtime::bootstrap;
 #[cfg(any(test, feature = "fuzzing"))]
 pub(crate) use runtime::start_shared_mempool;
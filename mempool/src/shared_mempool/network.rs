@@ -9,7 +9,7 @@ use libra_metrics::IntCounterVec;
 use libra_types::{transaction::SignedTransaction, PeerId};
 use network::{
     error::NetworkError,
-    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    peer_manager::{ConnectionRequestSender, Misbehavior, PeerManagerRequestSender},
     protocols::network::{NetworkEvents, NetworkSender, NewNetworkSender},
     ProtocolId,
 };
@@ -103,4 +103,13 @@ impl MempoolNetworkSender {
         let protocol = ProtocolId::MempoolDirectSend;
         self.inner.send_to(recipient, protocol, message)
     }
+
+    /// Report the misbehavior of a peer to the network.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.inner.report_misbehavior(peer, misbehavior)
+    }
 }
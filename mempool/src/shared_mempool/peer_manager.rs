@@ -19,6 +19,20 @@ use std::{
 /// stores only peers that receive txns from this node
 pub(crate) type PeerInfo = HashMap<PeerNetworkId, PeerSyncState>;
 
+/// Copy of the broadcast state of an upstream peer, taken for inspection
+#[derive(Clone, Debug, PartialEq)]
+pub struct MempoolPeerSnapshot {
+    pub peer: PeerNetworkId,
+    pub is_alive: bool,
+    // timeline position the next broadcast to this peer starts from
+    pub timeline_id: u64,
+    // timeline IDs of broadcast batches that were not ACK'ed yet, by batch ID
+    pub pending_batches: Vec<(String, Vec<u64>)>,
+    // timeline IDs of txns to broadcast again
+    pub retry_txns: Vec<u64>,
+    pub backoff_mode: bool,
+}
+
 /// state of last sync with peer
 /// `timeline_id` is position in log of ready transactions
 /// `is_alive` - is connection healthy
@@ -324,6 +338,33 @@ impl PeerManager {
             .clone()
     }
 
+    /// returns broadcast state of all upstream peers
+    pub fn get_peer_snapshots(&self) -> Vec<MempoolPeerSnapshot> {
+        self.peer_info
+            .lock()
+            .expect("failed to acquire peer info lock")
+            .iter()
+            .map(|(peer, state)| MempoolPeerSnapshot {
+                peer: peer.clone(),
+                is_alive: state.is_alive,
+                timeline_id: state.timeline_id,
+                pending_batches: state
+                    .broadcast_info
+                    .sent_batches
+                    .iter()
+                    .map(|(batch_id, batch)| (batch_id.clone(), batch.timeline_ids.clone()))
+                    .collect(),
+                retry_txns: state
+                    .broadcast_info
+                    .total_retry_txns
+                    .iter()
+                    .cloned()
+                    .collect(),
+                backoff_mode: state.broadcast_info.backoff_mode,
+            })
+            .collect()
+    }
+
     // checks whether a peer is a chosen broadcast recipient:
     // - all primary peers
     // - fallback peers, if k-policy is enabled
@@ -6,6 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator},
+        debug::MempoolDebugHandle,
         peer_manager::PeerManager,
         types::{SharedMempool, SharedMempoolNotification},
     },
@@ -18,6 +19,7 @@ use futures::channel::{
     oneshot,
 };
 use libra_config::{config::NodeConfig, network_id::NodeNetworkId};
+use libra_logger::prelude::*;
 use libra_types::{on_chain_config::OnChainConfigPayload, transaction::SignedTransaction};
 use std::{
     collections::HashMap,
@@ -32,6 +34,7 @@ use vm_validator::vm_validator::{TransactionValidation, VMValidator};
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers)
 ///   - inbound_network_task (task that handles inbound mempool messages and network events)
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL)
+/// returns a handle the node debug interface reads mempool state through
 pub(crate) fn start_shared_mempool<V>(
     executor: &Handle,
     config: &NodeConfig,
@@ -46,7 +49,8 @@ pub(crate) fn start_shared_mempool<V>(
     db: Arc<dyn DbReader>,
     validator: Arc<RwLock<V>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
-) where
+) -> MempoolDebugHandle
+where
     V: TransactionValidation + 'static,
 {
     let upstream_config = config.upstream.clone();
@@ -59,6 +63,7 @@ pub(crate) fn start_shared_mempool<V>(
         network_senders.insert(network_id, network_sender);
     }
 
+    let debug_handle = MempoolDebugHandle::new(mempool.clone(), peer_manager.clone());
     let smp = SharedMempool {
         mempool: mempool.clone(),
         config: config.mempool.clone(),
@@ -83,6 +88,8 @@ pub(crate) fn start_shared_mempool<V>(
         mempool,
         config.mempool.system_transaction_gc_interval_ms,
     ));
+
+    debug_handle
 }
 
 /// method used to bootstrap shared mempool for a node
@@ -96,16 +103,23 @@ pub fn bootstrap(
     consensus_requests: Receiver<ConsensusRequest>,
     state_sync_requests: Receiver<CommitNotification>,
     mempool_reconfig_events: libra_channel::Receiver<(), OnChainConfigPayload>,
-) -> Runtime {
+) -> (Runtime, MempoolDebugHandle) {
     let runtime = Builder::new()
         .thread_name("shared-mem-")
         .threaded_scheduler()
         .enable_all()
         .build()
         .expect("[shared mempool] failed to create runtime");
-    let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
+    let mut mempool = CoreMempool::new(&config);
+    if config.mempool.enable_journal {
+        // mempool still works without its journal, pending txns just don't survive a restart
+        if let Err(e) = mempool.open_journal(&config, db.as_ref()) {
+            error!("[shared mempool] failed to open journal: {}", e);
+        }
+    }
+    let mempool = Arc::new(Mutex::new(mempool));
     let vm_validator = Arc::new(RwLock::new(VMValidator::new(Arc::clone(&db))));
-    start_shared_mempool(
+    let debug_handle = start_shared_mempool(
         runtime.handle(),
         config,
         mempool,
@@ -118,5 +132,5 @@ pub fn bootstrap(
         vm_validator,
         vec![],
     );
-    runtime
+    (runtime, debug_handle)
 }
@@ -24,6 +24,7 @@ use libra_types::{
     vm_status::DiscardedVMStatus,
     PeerId,
 };
+use network::peer_manager::Misbehavior;
 use std::{
     cmp,
     collections::HashSet,
@@ -275,6 +276,11 @@ pub(crate) async fn process_transaction_broadcast<V>(
             .data("vm_status", &maybe_vm_status)
             .data("from_peer", &peer));
     }
+    // An honest peer validates the transactions it broadcasts, and signatures don't depend on the
+    // state of the ledger, so only a misbehaving peer sends transactions with invalid signatures.
+    let has_invalid_signature = results
+        .iter()
+        .any(|(_, maybe_vm_status)| *maybe_vm_status == Some(DiscardedVMStatus::INVALID_SIGNATURE));
 
     // send back ACK
     let ack_response = gen_ack_response(request_id, results);
@@ -282,6 +288,16 @@ pub(crate) async fn process_transaction_broadcast<V>(
         .network_senders
         .get_mut(&peer.network_id())
         .expect("[shared mempool] missing network sender");
+    if has_invalid_signature {
+        if let Err(e) =
+            network_sender.report_misbehavior(peer.peer_id(), Misbehavior::InvalidSignature)
+        {
+            error!(
+                "[shared mempool] failed to report misbehavior of peer {:?}: {}",
+                peer, e
+            );
+        }
+    }
     if let Err(e) = send_mempool_sync_msg(ack_response, peer.peer_id(), &mut network_sender) {
         error!(
             "[shared mempool] failed to send ACK back to peer {:?}: {}",
@@ -297,7 +313,9 @@ fn gen_ack_response(request_id: String, results: Vec<SubmissionStatus>) -> Mempo
         .into_iter()
         .enumerate()
         .filter_map(|(idx, result)| {
-            backoff = backoff || result.0.code == MempoolStatusCode::MempoolIsFull;
+            backoff = backoff
+                || result.0.code == MempoolStatusCode::MempoolIsFull
+                || result.0.code == MempoolStatusCode::Evicted;
 
             if is_txn_retryable(result) {
                 Some(idx as u64)
@@ -317,7 +335,9 @@ fn gen_ack_response(request_id: String, results: Vec<SubmissionStatus>) -> Mempo
 fn is_txn_retryable(result: SubmissionStatus) -> bool {
     let mempool_status = result.0.code;
     mempool_status == MempoolStatusCode::TooManyTransactions
+        || mempool_status == MempoolStatusCode::TooManyBytes
         || mempool_status == MempoolStatusCode::MempoolIsFull
+        || mempool_status == MempoolStatusCode::Evicted
 }
 
 /// submits a list of SignedTransaction to the local mempool
@@ -5,15 +5,20 @@ use crate::{
     core_mempool::{CoreMempool, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
 };
 use libra_config::config::NodeConfig;
-use libra_types::transaction::{GovernanceRole, SignedTransaction};
+use libra_temppath::TempPath;
+use libra_types::{
+    mempool_status::MempoolStatusCode,
+    transaction::{GovernanceRole, SignedTransaction},
+};
 use std::{
     collections::HashSet,
     time::{Duration, SystemTime},
 };
+use storage_interface::mock::MockDbReader;
 
 #[test]
 fn test_transaction_ordering() {
@@ -170,6 +175,40 @@ fn test_update_invalid_transaction_in_mempool() {
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_replace_by_fee() {
+    let mut config = NodeConfig::random();
+    config.mempool.replace_by_fee_bump_percent = 50;
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 10),
+            TestTransaction::new(0, 2, 10),
+        ],
+    );
+    assert_eq!(1, pool.get_parking_lot_size());
+
+    // gas price is not increased enough to replace the ready transaction
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 0, 14)).is_err());
+    let (timeline, _) = pool.read_timeline(0, 10);
+    assert_eq!(timeline, vec![(1, txns[0].clone())]);
+
+    // replacement takes a new position in timeline, so that it is broadcast again
+    let replacement = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 15)]);
+    let (timeline, _) = pool.read_timeline(0, 10);
+    assert_eq!(timeline, vec![(2, replacement[0].clone())]);
+
+    // replacing a parked transaction keeps it in parking lot
+    let parked_replacement = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 2, 20)]);
+    assert_eq!(1, pool.get_parking_lot_size());
+
+    assert_eq!(consensus.get_block(&mut pool, 10), replacement);
+    pool.remove_transaction(&TestTransaction::get_address(0), 1, false);
+    assert_eq!(consensus.get_block(&mut pool, 10), parked_replacement);
+}
+
 #[test]
 fn test_remove_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -383,6 +422,237 @@ fn test_parking_lot_evict_only_for_ready_txn_insertion() {
     }
 }
 
+#[test]
+fn test_low_priority_eviction() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 2),
+            TestTransaction::new(1, 0, 3),
+        ],
+    );
+
+    // lowest gas price txn is evicted and the following txn of its account gets parked
+    add_txn(&mut pool, TestTransaction::new(2, 0, 4)).unwrap();
+    assert_eq!(1, pool.get_parking_lot_size());
+    // parked txns are evicted first
+    add_txn(&mut pool, TestTransaction::new(3, 0, 2)).unwrap();
+    assert_eq!(0, pool.get_parking_lot_size());
+    let block = consensus.get_block(&mut pool, 10);
+    let gas_prices: Vec<_> = block.iter().map(|t| t.gas_unit_price()).collect();
+    assert_eq!(gas_prices, vec![4, 3, 2]);
+
+    // txns that don't pay more than the lowest gas price in mempool are rejected
+    assert!(add_txn(&mut pool, TestTransaction::new(4, 0, 2)).is_err());
+
+    // resubmission of an evicted txn is reported as such while mempool is still full
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let status = pool.add_txn(
+        txn,
+        0,
+        1,
+        0,
+        TimelineState::NotReady,
+        GovernanceRole::NonGovernanceRole,
+    );
+    assert_eq!(status.code, MempoolStatusCode::Evicted);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 5)).unwrap();
+}
+
+#[test]
+fn test_eviction_skips_own_txns() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(1, 0, 2),
+            TestTransaction::new(2, 0, 3),
+        ],
+    );
+
+    // the lowest gas price txn belongs to the sender, so the next lowest one is evicted
+    add_txn(&mut pool, TestTransaction::new(0, 1, 5)).unwrap();
+    let mut gas_prices: Vec<_> = consensus
+        .get_block(&mut pool, 10)
+        .iter()
+        .map(|t| t.gas_unit_price())
+        .collect();
+    gas_prices.sort();
+    assert_eq!(gas_prices, vec![1, 3, 5]);
+}
+
+#[test]
+fn test_quota_rejection_does_not_evict() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 2;
+    config.mempool.capacity_per_user = 1;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 1)],
+    );
+
+    let txn = TestTransaction::new(1, 1, 5).make_signed_transaction();
+    let status = pool.add_txn(
+        txn,
+        0,
+        5,
+        0,
+        TimelineState::NotReady,
+        GovernanceRole::NonGovernanceRole,
+    );
+    assert_eq!(status.code, MempoolStatusCode::TooManyTransactions);
+    // the txns of other accounts are still there
+    assert_eq!(pool.get_block(10, HashSet::new()).len(), 2);
+}
+
+#[test]
+fn test_capacity_bytes_per_user() {
+    let txn_bytes = TestTransaction::new(0, 0, 1)
+        .make_signed_transaction()
+        .raw_txn_bytes_len();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity_bytes_per_user = Some(2 * txn_bytes);
+    let mut pool = CoreMempool::new(&config);
+
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 1, 1)).unwrap();
+    let txn = TestTransaction::new(0, 2, 1).make_signed_transaction();
+    let status = pool.add_txn(
+        txn,
+        0,
+        1,
+        0,
+        TimelineState::NotReady,
+        GovernanceRole::NonGovernanceRole,
+    );
+    assert_eq!(status.code, MempoolStatusCode::TooManyBytes);
+    // quota is tracked per account
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+
+    // committed txns free the quota
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    add_txn(&mut pool, TestTransaction::new(0, 2, 1)).unwrap();
+}
+
+#[test]
+fn test_journal_restore() {
+    let tmp_dir = TempPath::new();
+    let mut config = NodeConfig::random();
+    config.mempool.enable_journal = true;
+    config.storage.dir = tmp_dir.path().to_path_buf();
+
+    let mut pool = CoreMempool::new(&config);
+    pool.open_journal(&config, &MockDbReader).unwrap();
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 2),
+        ],
+    );
+    // committed txns don't survive restart
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    pool.open_journal(&config, &MockDbReader).unwrap();
+    let mut consensus = ConsensusMock::new();
+    // sequence number from storage is behind, so remaining txn of first account is parked
+    assert_eq!(1, pool.get_parking_lot_size());
+    assert_eq!(consensus.get_block(&mut pool, 10), vec![txns[2].clone()]);
+
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    assert_eq!(consensus.get_block(&mut pool, 10), vec![txns[1].clone()]);
+}
+
+#[test]
+fn test_journal_drops_expired_txns() {
+    let tmp_dir = TempPath::new();
+    let mut config = NodeConfig::random();
+    config.mempool.enable_journal = true;
+    config.mempool.system_transaction_timeout_secs = 0;
+    config.storage.dir = tmp_dir.path().to_path_buf();
+
+    let mut pool = CoreMempool::new(&config);
+    pool.open_journal(&config, &MockDbReader).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    pool.open_journal(&config, &MockDbReader).unwrap();
+    assert!(pool.get_block(10, HashSet::new()).is_empty());
+}
+
+#[test]
+fn test_journal_open_error() {
+    let tmp_file = TempPath::new();
+    tmp_file.create_as_file().unwrap();
+    let mut config = NodeConfig::random();
+    config.mempool.enable_journal = true;
+    // the journal can't be created under a file
+    config.storage.dir = tmp_file.path().to_path_buf();
+
+    let mut pool = CoreMempool::new(&config);
+    assert!(pool.open_journal(&config, &MockDbReader).is_err());
+    // mempool keeps working without its journal
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    assert_eq!(pool.get_block(10, HashSet::new()).len(), 1);
+}
+
+#[test]
+fn test_transaction_snapshots() {
+    let (mut pool, _) = setup_mempool();
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 2, 3),
+            TestTransaction::new(1, 0, 2),
+        ],
+    );
+
+    let mut snapshots = pool.get_transaction_snapshots(Some(TestTransaction::get_address(0)), 10);
+    snapshots.sort_by_key(|snapshot| snapshot.sequence_number);
+    let state: Vec<_> = snapshots
+        .iter()
+        .map(|snapshot| {
+            (
+                snapshot.sequence_number,
+                snapshot.gas_unit_price,
+                snapshot.timeline_id,
+                snapshot.parked,
+            )
+        })
+        .collect();
+    assert_eq!(state, vec![(0, 1, Some(1), false), (2, 3, None, true)]);
+    assert_eq!(pool.get_transaction_snapshots(None, 10).len(), 3);
+    // at most `limit` transactions are copied
+    assert_eq!(pool.get_transaction_snapshots(None, 2).len(), 2);
+
+    let parking_lot = pool.get_parking_lot_snapshots(10);
+    assert_eq!(parking_lot.len(), 1);
+    assert_eq!(parking_lot[0].sequence_number, 2);
+
+    // the timeline is paged through by timeline ID
+    let timeline = pool.get_timeline_snapshots(0, 1);
+    assert_eq!(timeline.len(), 1);
+    assert_eq!(timeline[0].timeline_id, Some(1));
+    let timeline = pool.get_timeline_snapshots(timeline[0].timeline_id.unwrap(), 10);
+    assert_eq!(timeline.len(), 1);
+    assert_eq!(timeline[0].sender, TestTransaction::get_address(1));
+}
+
 #[test]
 fn test_gc_ready_transaction() {
     let mut pool = setup_mempool().0;
@@ -11,7 +11,10 @@
 //! long as the latter is in its trusted peers set.
 use channel::{self, message_queues::QueueStyle};
 use libra_config::{
-    config::{DiscoveryMethod, GossipConfig, NetworkConfig, RoleType, HANDSHAKE_VERSION},
+    config::{
+        DiscoveryMethod, GossipConfig, InboundRateLimitConfig, NetworkConfig, PeerScoringConfig,
+        RoleType, HANDSHAKE_VERSION,
+    },
     network_id::NetworkContext,
 };
 use libra_crypto::x25519;
@@ -144,6 +147,10 @@ impl NetworkBuilder {
             authentication_mode,
             config.max_frame_size,
         );
+        network_builder
+            .set_inbound_rate_limit(&config.inbound_rate_limit)
+            .expect("Invalid inbound rate limits");
+        network_builder.set_peer_scoring(&config.peer_scoring);
 
         network_builder.add_connection_monitoring(
             // TODO: Encode this value in NetworkConfig
@@ -252,6 +259,23 @@ impl NetworkBuilder {
         self.peer_manager_builder.listen_address()
     }
 
+    /// Set how the peers of the network get scored on their misbehavior and banned.
+    pub fn set_peer_scoring(&mut self, config: &PeerScoringConfig) -> &mut Self {
+        self.peer_manager_builder.set_peer_scoring(config);
+        self
+    }
+
+    /// Set the limits on the inbound messages of every peer of the network, failing if they name
+    /// an unknown protocol.
+    pub fn set_inbound_rate_limit(
+        &mut self,
+        inbound_rate_limit: &InboundRateLimitConfig,
+    ) -> Result<&mut Self, libra_config::config::Error> {
+        self.peer_manager_builder
+            .set_inbound_rate_limit(inbound_rate_limit)?;
+        Ok(self)
+    }
+
     fn build_peer_manager(&mut self) -> &mut Self {
         self.peer_manager_builder
             .build(self.executor.as_mut().expect("Executor must exist"));
@@ -305,6 +329,7 @@ impl NetworkBuilder {
         self.connectivity_manager_builder = Some(ConnectivityManagerBuilder::create(
             self.network_context(),
             trusted_peers,
+            self.peer_manager_builder.peer_scores(),
             seed_addrs,
             seed_pubkeys,
             connectivity_check_interval_ms,
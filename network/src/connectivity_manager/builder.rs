@@ -4,7 +4,7 @@
 use crate::{
     connectivity_manager::{ConnectivityManager, ConnectivityRequest},
     counters,
-    peer_manager::{conn_notifs_channel, ConnectionRequestSender},
+    peer_manager::{conn_notifs_channel, ConnectionRequestSender, PeerScores},
 };
 use futures::stream::StreamExt;
 use futures_util::stream::Fuse;
@@ -29,6 +29,7 @@ pub type ConnectivityManagerService = ConnectivityManager<Fuse<Interval>, Expone
 struct ConnectivityManagerBuilderConfig {
     network_context: Arc<NetworkContext>,
     eligible: Arc<RwLock<HashMap<PeerId, HashSet<x25519::PublicKey>>>>,
+    peer_scores: Arc<RwLock<PeerScores>>,
     seed_addrs: HashMap<PeerId, Vec<NetworkAddress>>,
     seed_pubkeys: HashMap<PeerId, HashSet<x25519::PublicKey>>,
     connectivity_check_interval_ms: u64,
@@ -58,6 +59,7 @@ impl ConnectivityManagerBuilder {
     pub fn create(
         network_context: Arc<NetworkContext>,
         eligible: Arc<RwLock<HashMap<PeerId, HashSet<x25519::PublicKey>>>>,
+        peer_scores: Arc<RwLock<PeerScores>>,
         seed_addrs: HashMap<PeerId, Vec<NetworkAddress>>,
         seed_pubkeys: HashMap<PeerId, HashSet<x25519::PublicKey>>,
         connectivity_check_interval_ms: u64,
@@ -76,6 +78,7 @@ impl ConnectivityManagerBuilder {
             config: Some(ConnectivityManagerBuilderConfig {
                 network_context,
                 eligible,
+                peer_scores,
                 seed_addrs,
                 seed_pubkeys,
                 connectivity_check_interval_ms,
@@ -109,6 +112,7 @@ impl ConnectivityManagerBuilder {
                 ConnectivityManager::new(
                     config.network_context,
                     config.eligible,
+                    config.peer_scores,
                     config.seed_addrs,
                     config.seed_pubkeys,
                     interval(Duration::from_millis(config.connectivity_check_interval_ms)).fuse(),
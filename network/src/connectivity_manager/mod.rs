@@ -29,7 +29,9 @@
 
 use crate::{
     logging::{network_events::DISCOVERY_SOURCE, *},
-    peer_manager::{self, conn_notifs_channel, ConnectionRequestSender, PeerManagerError},
+    peer_manager::{
+        self, conn_notifs_channel, ConnectionRequestSender, PeerManagerError, PeerScores,
+    },
 };
 use futures::{
     channel::oneshot,
@@ -66,6 +68,8 @@ pub struct ConnectivityManager<TTicker, TBackoff> {
     network_context: Arc<NetworkContext>,
     /// Nodes which are eligible to join the network.
     eligible: Arc<RwLock<HashMap<PeerId, HashSet<x25519::PublicKey>>>>,
+    /// Scores of the peers on their misbehavior, banned peers are not dialed.
+    peer_scores: Arc<RwLock<PeerScores>>,
     /// PeerId and address of remote peers to which this peer is connected.
     connected: HashMap<PeerId, NetworkAddress>,
     /// Addresses of peers received from discovery sources.
@@ -193,6 +197,7 @@ where
     pub fn new(
         network_context: Arc<NetworkContext>,
         eligible: Arc<RwLock<HashMap<PeerId, HashSet<x25519::PublicKey>>>>,
+        peer_scores: Arc<RwLock<PeerScores>>,
         seed_addrs: HashMap<PeerId, Vec<NetworkAddress>>,
         seed_pubkeys: HashMap<PeerId, HashSet<x25519::PublicKey>>,
         ticker: TTicker,
@@ -214,6 +219,7 @@ where
         let mut connmgr = Self {
             network_context,
             eligible,
+            peer_scores,
             connected: HashMap::new(),
             peer_addrs: PeerAddresses::new(),
             peer_pubkeys: PeerPublicKeys::new(),
@@ -330,19 +336,24 @@ where
         }
     }
 
-    /// Cancel all pending dials to peers that are no longer eligible.
+    /// Cancel all pending dials to peers that are no longer eligible, or that got banned.
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If there is a pending dial to this validator, calling
     /// this function will remove it from the dial queue.
     async fn cancel_stale_dials(&mut self) {
         let eligible = self.eligible.read().unwrap().clone();
+        let peer_scores = self.peer_scores.read().unwrap();
+        let now = Instant::now();
         let stale_dials: Vec<_> = self
             .dial_queue
             .keys()
-            .filter(|peer_id| !eligible.contains_key(peer_id))
+            .filter(|peer_id| {
+                !eligible.contains_key(peer_id) || peer_scores.is_banned(peer_id, now)
+            })
             .cloned()
             .collect();
+        drop(peer_scores);
         for p in stale_dials.into_iter() {
             self.dial_queue.remove(&p);
         }
@@ -353,17 +364,21 @@ where
         pending_dials: &'a mut FuturesUnordered<BoxFuture<'static, PeerId>>,
     ) {
         let eligible = self.eligible.read().unwrap().clone();
+        let peer_scores = self.peer_scores.read().unwrap();
+        let now = Instant::now();
         let to_connect: Vec<_> = self
             .peer_addrs
             .0
             .iter()
             .filter(|(peer_id, addrs)| {
                 eligible.contains_key(peer_id)  // The node is eligible to be dialed.
+                    && !peer_scores.is_banned(peer_id, now) // The node is not banned.
                     && self.connected.get(peer_id).is_none() // The node is not already connected.
                     && self.dial_queue.get(peer_id).is_none() // There is no pending dial to this node.
                     && !addrs.is_empty() // There is an address to dial.
             })
             .collect();
+        drop(peer_scores);
 
         // Limit the number of dialed connections from a Full Node
         // This does not limit the number of incoming connections
@@ -4,12 +4,15 @@
 use super::*;
 use crate::{
     peer::DisconnectReason,
-    peer_manager::{conn_notifs_channel, ConnectionRequest},
+    peer_manager::{conn_notifs_channel, ConnectionRequest, Misbehavior},
 };
 use channel::{libra_channel, message_queues::QueueStyle};
 use core::str::FromStr;
 use futures::SinkExt;
-use libra_config::{config::RoleType, network_id::NetworkId};
+use libra_config::{
+    config::{PeerScoringConfig, RoleType},
+    network_id::NetworkId,
+};
 use libra_crypto::{test_utils::TEST_SEED, x25519, Uniform};
 use libra_logger::info;
 use libra_network_address::NetworkAddress;
@@ -43,7 +46,17 @@ fn setup_conn_mgr(
         })
         .collect();
 
-    setup_conn_mgr_with_context(network_context, rt, seed_addrs, seed_pubkeys)
+    setup_conn_mgr_with_context(
+        network_context,
+        rt,
+        seed_addrs,
+        seed_pubkeys,
+        default_peer_scores(),
+    )
+}
+
+fn default_peer_scores() -> Arc<RwLock<PeerScores>> {
+    Arc::new(RwLock::new(PeerScores::new(&PeerScoringConfig::default())))
 }
 
 fn setup_conn_mgr_with_context(
@@ -51,6 +64,7 @@ fn setup_conn_mgr_with_context(
     rt: &mut Runtime,
     seed_addrs: HashMap<PeerId, Vec<NetworkAddress>>,
     seed_pubkeys: HashMap<PeerId, HashSet<x25519::PublicKey>>,
+    peer_scores: Arc<RwLock<PeerScores>>,
 ) -> (
     libra_channel::Receiver<PeerId, ConnectionRequest>,
     conn_notifs_channel::Sender,
@@ -67,6 +81,7 @@ fn setup_conn_mgr_with_context(
         ConnectivityManager::new(
             Arc::new(network_context),
             Arc::new(RwLock::new(HashMap::new())),
+            peer_scores,
             seed_addrs,
             seed_pubkeys,
             ticker_rx,
@@ -1238,7 +1253,13 @@ fn public_connection_limit() {
         PeerId::random(),
     );
     let (mut connection_reqs_rx, mut connection_notifs_tx, mut conn_mgr_reqs_tx, mut ticker_tx) =
-        setup_conn_mgr_with_context(network_context, &mut rt, seed_addrs, seed_pubkeys);
+        setup_conn_mgr_with_context(
+            network_context,
+            &mut rt,
+            seed_addrs,
+            seed_pubkeys,
+            default_peer_scores(),
+        );
 
     // Fake peer manager and discovery.
     let f_peer_mgr = async move {
@@ -1268,6 +1289,71 @@ fn public_connection_limit() {
     rt.block_on(f_peer_mgr);
 }
 
+#[test]
+fn banned_peer_not_dialed() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let (peer_id_a, _, pubkeys_a, addr_a) = gen_peer();
+    let (peer_id_b, _, pubkeys_b, addr_b) = gen_peer();
+    let seed_addrs: HashMap<_, _> =
+        vec![(peer_id_a, vec![addr_a.clone()]), (peer_id_b, vec![addr_b])]
+            .into_iter()
+            .collect();
+    let seed_pubkeys: HashMap<_, _> = vec![(peer_id_a, pubkeys_a), (peer_id_b, pubkeys_b)]
+        .into_iter()
+        .collect();
+
+    // Peer b misbehaves until it gets banned.
+    let peer_scores = default_peer_scores();
+    while !peer_scores
+        .read()
+        .unwrap()
+        .is_banned(&peer_id_b, Instant::now())
+    {
+        peer_scores.write().unwrap().report(
+            peer_id_b,
+            Misbehavior::InvalidSignature,
+            Instant::now(),
+        );
+    }
+
+    let network_context =
+        NetworkContext::new(NetworkId::Validator, RoleType::Validator, PeerId::random());
+    let (mut connection_reqs_rx, mut connection_notifs_tx, mut conn_mgr_reqs_tx, mut ticker_tx) =
+        setup_conn_mgr_with_context(
+            network_context,
+            &mut rt,
+            seed_addrs,
+            seed_pubkeys,
+            peer_scores,
+        );
+
+    // Fake peer manager and discovery.
+    let f_peer_mgr = async move {
+        // Only the peer which isn't banned gets dialed.
+        info!("Waiting to receive dial request");
+        expect_dial_request(
+            &mut connection_reqs_rx,
+            &mut connection_notifs_tx,
+            &mut conn_mgr_reqs_tx,
+            peer_id_a,
+            addr_a,
+            Ok(()),
+        )
+        .await;
+
+        // Trigger connectivity check.
+        info!("Sending tick to trigger connectivity check");
+        ticker_tx.send(()).await.unwrap();
+
+        // The banned peer is still not dialed.
+        info!("Check queue size");
+        let queue_size = get_dial_queue_size(&mut conn_mgr_reqs_tx).await;
+        assert_eq!(0, queue_size);
+    };
+    rt.block_on(f_peer_mgr);
+}
+
 #[test]
 fn basic_update_eligible_peers() {
     // setup a basic connectivity manager without starting its event loop
@@ -1290,6 +1376,7 @@ fn basic_update_eligible_peers() {
     let mut conn_mgr = ConnectivityManager::new(
         network_context,
         trusted_peers.clone(),
+        default_peer_scores(),
         seed_addrs,
         seed_pubkeys,
         ticker_rx,
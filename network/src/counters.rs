@@ -116,6 +116,47 @@ pub static LIBRA_NETWORK_DIRECT_SEND_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Size of the compressed messages, before ("uncompressed") and after ("compressed") compression.
+pub static LIBRA_NETWORK_COMPRESSION_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_compression_bytes",
+        "Libra network compressed messages bytes counter",
+        &["direction", "state"]
+    )
+    .unwrap()
+});
+
+/// Inbound messages dropped for going over the rate limits ("dropped"), and peers disconnected for
+/// repeatedly doing so ("disconnected").
+pub static LIBRA_NETWORK_INBOUND_RATE_LIMIT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_inbound_rate_limit",
+        "Libra network inbound rate limit counter",
+        &["protocol_id", "state"]
+    )
+    .unwrap()
+});
+
+/// Misbehavior of the peers, as reported by the applications.
+pub static LIBRA_NETWORK_PEER_MISBEHAVIOR: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_peer_misbehavior",
+        "Libra network peer misbehavior counter",
+        &["misbehavior"]
+    )
+    .unwrap()
+});
+
+/// Peers banned for their misbehavior, labeled by the misbehavior which got them banned.
+pub static LIBRA_NETWORK_PEER_BANS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_network_peer_bans",
+        "Libra network peer ban counter",
+        &["misbehavior"]
+    )
+    .unwrap()
+});
+
 /// Counters(queued,dequeued,dropped) related to inbound network notifications for RPCs and
 /// DirectSends.
 pub static PENDING_NETWORK_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
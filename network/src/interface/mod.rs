@@ -13,7 +13,7 @@
 //! handler, determined using the protocol negotiated on the RPC substream.
 use crate::{
     constants, counters,
-    peer::{Peer, PeerHandle, PeerNotification},
+    peer::{InboundRateLimiter, Peer, PeerHandle, PeerNotification},
     peer_manager::TransportNotification,
     protocols::{
         direct_send::{DirectSend, DirectSendNotification, DirectSendRequest, Message},
@@ -70,6 +70,7 @@ where
         max_concurrent_notifs: usize,
         channel_size: usize,
         max_frame_size: usize,
+        rate_limiter: InboundRateLimiter,
     ) -> (
         libra_channel::Sender<ProtocolId, NetworkRequest>,
         libra_channel::Receiver<ProtocolId, NetworkNotification>,
@@ -112,6 +113,7 @@ where
             peer_rpc_notifs_tx,
             peer_ds_notifs_tx,
             max_frame_size,
+            rate_limiter,
         );
         executor.spawn(peer.start());
 
@@ -5,13 +5,16 @@
 //! and opening substreams as well as negotiating particular protocols on those substreams.
 use crate::{
     counters,
+    peer::rate_limit::RateLimitOutcome,
     peer_manager::PeerManagerError,
-    protocols::wire::messaging::v1::{ErrorCode, NetworkMessage},
+    protocols::wire::messaging::v1::{
+        compression::MessageCompression, ErrorCode, MessageHeader, NetworkMessage, RateLimitedType,
+    },
     transport,
     transport::{Connection, ConnectionMetadata},
     ProtocolId,
 };
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use futures::{
     self,
     channel::oneshot,
@@ -23,7 +26,11 @@ use libra_logger::prelude::*;
 use libra_types::PeerId;
 use netcore::compat::IoCompat;
 use serde::{export::Formatter, Serialize};
-use std::{fmt::Debug, io, time::Duration};
+use std::{
+    fmt::Debug,
+    io,
+    time::{Duration, Instant},
+};
 use stream_ratelimiter::*;
 use tokio::runtime::Handle;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
@@ -32,9 +39,12 @@ use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 pub const MESSAGE_RATE_LIMIT_WINDOW: Duration = Duration::from_millis(10);
 pub const MESSAGE_RATE_LIMIT_COUNT: usize = 100;
 
+mod rate_limit;
 #[cfg(test)]
 mod test;
 
+pub use rate_limit::InboundRateLimiter;
+
 #[derive(Debug)]
 pub enum PeerRequest {
     SendMessage(
@@ -49,6 +59,7 @@ pub enum PeerRequest {
 pub enum DisconnectReason {
     Requested,
     ConnectionLost,
+    RateLimited,
 }
 
 impl std::fmt::Display for DisconnectReason {
@@ -59,6 +70,7 @@ impl std::fmt::Display for DisconnectReason {
             match self {
                 DisconnectReason::Requested => "Requested",
                 DisconnectReason::ConnectionLost => "ConnectionLost",
+                DisconnectReason::RateLimited => "RateLimited",
             }
         )
     }
@@ -95,8 +107,20 @@ pub struct Peer<TSocket> {
     /// The maximum size of an inbound or outbound request frame
     /// Currently, requests are only a single frame
     max_frame_size: usize,
+    /// Compression of the messages, if negotiated during the handshake.
+    compression: Option<MessageCompression>,
+    /// Limits on the inbound messages, applied before delivering them upstream.
+    rate_limiter: InboundRateLimiter,
 }
 
+/// An outbound message, along with the application protocol it is sent for (if any) and a channel
+/// to notify once it is written on the wire.
+type WriteRequest = (
+    NetworkMessage,
+    Option<ProtocolId>,
+    oneshot::Sender<Result<(), PeerManagerError>>,
+);
+
 impl<TSocket> Peer<TSocket>
 where
     TSocket: AsyncRead + AsyncWrite + Send + 'static,
@@ -109,11 +133,22 @@ where
         rpc_notifs_tx: channel::Sender<PeerNotification>,
         direct_send_notifs_tx: channel::Sender<PeerNotification>,
         max_frame_size: usize,
+        rate_limiter: InboundRateLimiter,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
             socket,
         } = connection;
+        let compression =
+            connection_metadata
+                .compression()
+                .map(|(algorithm, compressed_protocols)| {
+                    MessageCompression::new(
+                        *algorithm,
+                        compressed_protocols.clone(),
+                        max_frame_size,
+                    )
+                });
         Self {
             executor,
             connection_metadata,
@@ -124,6 +159,8 @@ where
             direct_send_notifs_tx,
             state: State::Connected,
             max_frame_size,
+            compression,
+            rate_limiter,
         }
     }
 
@@ -140,9 +177,15 @@ where
 
         // Split the connection into a ReadHalf and a WriteHalf.
         let (reader, writer) = tokio::io::split(IoCompat::new(self.connection.take().unwrap()));
+        // With compression, frames start with a flag byte before the message of up to
+        // `max_frame_size` bytes.
+        let max_frame_length = match self.compression {
+            Some(_) => self.max_frame_size + 1,
+            None => self.max_frame_size,
+        };
         let mut codec_builder = LengthDelimitedCodec::builder();
         codec_builder
-            .max_frame_length(self.max_frame_size)
+            .max_frame_length(max_frame_length)
             .length_field_length(4)
             .big_endian();
         // Convert ReadHalf to Stream of length-delimited messages.
@@ -159,8 +202,12 @@ where
         // the task:
         // `write_reqs_tx`: Instruction to send a NetworkMessage on the wire.
         // `close_tx`: Instruction to close the underlying connection.
-        let (write_reqs_tx, close_tx) =
-            Self::start_writer_task(&self.executor, self_peer_id, writer);
+        let (write_reqs_tx, close_tx) = Self::start_writer_task(
+            &self.executor,
+            self_peer_id,
+            writer,
+            self.compression.clone(),
+        );
         // Start main Peer event loop.
         loop {
             match self.state {
@@ -239,32 +286,26 @@ where
         executor: &Handle,
         self_peer_id: PeerId,
         mut writer: FramedWrite<T, LengthDelimitedCodec>,
-    ) -> (
-        channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
-        oneshot::Sender<()>,
-    ) {
-        let (write_reqs_tx, mut write_reqs_rx): (
-            channel::Sender<(
-                NetworkMessage,
-                oneshot::Sender<Result<(), PeerManagerError>>,
-            )>,
-            _,
-        ) = channel::new(1024, &counters::PENDING_WIRE_MESSAGES);
+        compression: Option<MessageCompression>,
+    ) -> (channel::Sender<WriteRequest>, oneshot::Sender<()>) {
+        let (write_reqs_tx, mut write_reqs_rx): (channel::Sender<WriteRequest>, _) =
+            channel::new(1024, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, close_rx) = oneshot::channel();
         let writer_task = async move {
             let mut close_rx = close_rx.into_stream();
             loop {
                 futures::select! {
-                    (message, ack_ch) = write_reqs_rx.select_next_some() => {
+                    (message, protocol, ack_ch) = write_reqs_rx.select_next_some() => {
+                        let message = lcs::to_bytes(&message)
+                            .expect("Outbound message failed to serialize");
+                        let frame = match &compression {
+                            Some(compression) => compression
+                                .compress(&message, protocol)
+                                .expect("Outbound message failed to compress"),
+                            None => message,
+                        };
                         if let Err(e) = writer
-                            .send(
-                                lcs::to_bytes(&message)
-                                    .expect("Outbound message failed to serialize")
-                                    .into(),
-                            )
+                            .send(frame.into())
                             .map_ok(|_| ack_ch.send(Ok(())))
                             .await
                         {
@@ -313,14 +354,59 @@ where
     async fn handle_inbound_message(
         &mut self,
         message: BytesMut,
-        mut write_reqs_tx: channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
+        mut write_reqs_tx: channel::Sender<WriteRequest>,
     ) -> Result<(), PeerManagerError> {
         trace!("Received message from Peer {}", self.peer_id().short_str());
+        // Check the rate limits before spending any effort on the message.
+        let header = MessageHeader::parse(match &self.compression {
+            Some(compression) => compression.header(&message),
+            None => &message[..],
+        });
+        let protocol = match header {
+            MessageHeader::Reply => None,
+            MessageHeader::RpcRequest(protocol, _) => Some(protocol),
+            MessageHeader::Other(protocol) => protocol,
+        };
+        let outcome = match header {
+            MessageHeader::Reply => self.rate_limiter.check_reply(message.len(), Instant::now()),
+            _ => self
+                .rate_limiter
+                .check(protocol, message.len(), Instant::now()),
+        };
+        match outcome {
+            RateLimitOutcome::Allowed => (),
+            outcome => {
+                let protocol = protocol.map_or("none", ProtocolId::as_str);
+                counters::LIBRA_NETWORK_INBOUND_RATE_LIMIT
+                    .with_label_values(&[protocol, "dropped"])
+                    .inc();
+                if outcome == RateLimitOutcome::Disconnect {
+                    warn!(
+                        "Disconnecting from peer {}, which keeps going over its rate limits",
+                        self.peer_id().short_str()
+                    );
+                    counters::LIBRA_NETWORK_INBOUND_RATE_LIMIT
+                        .with_label_values(&[protocol, "disconnected"])
+                        .inc();
+                    self.close_connection(DisconnectReason::RateLimited).await;
+                } else if let MessageHeader::RpcRequest(protocol, request_id) = header {
+                    // Let the peer fail the request rather than wait for it to time out.
+                    let error =
+                        ErrorCode::RateLimited(RateLimitedType::RpcRequest(protocol, request_id));
+                    let (ack_tx, _) = oneshot::channel();
+                    write_reqs_tx
+                        .send((NetworkMessage::Error(error), None, ack_tx))
+                        .await?;
+                }
+                return Ok(());
+            }
+        }
+
         // Read inbound message from stream.
-        let message = message.freeze();
+        let message = match &self.compression {
+            Some(compression) => Bytes::from(compression.decompress(&message)?),
+            None => message.freeze(),
+        };
         let message = match lcs::from_bytes(&message) {
             Ok(message) => message,
             Err(err) => {
@@ -329,7 +415,7 @@ where
                     let error = ErrorCode::parsing_error(message[0], message[1]);
                     let message = NetworkMessage::Error(error);
                     let (ack_tx, _) = oneshot::channel();
-                    write_reqs_tx.send((message, ack_tx)).await?;
+                    write_reqs_tx.send((message, None, ack_tx)).await?;
                 }
                 return Err(err.into());
             }
@@ -348,6 +434,16 @@ where
                         err
                     })?;
             }
+            // The RPC actor also fails the requests dropped by the peer.
+            NetworkMessage::RpcRequest(_)
+            | NetworkMessage::RpcResponse(_)
+            | NetworkMessage::Error(ErrorCode::RateLimited(_)) => {
+                let notif = PeerNotification::NewMessage(message);
+                self.rpc_notifs_tx.send(notif).await.map_err(|err| {
+                    warn!("Failed to send notification to RPC actor. Error: {:?}", err);
+                    err
+                })?;
+            }
             NetworkMessage::Error(error) => {
                 warn!(
                     "Peer {} sent an error message: {:?}",
@@ -355,13 +451,6 @@ where
                     error,
                 );
             }
-            NetworkMessage::RpcRequest(_) | NetworkMessage::RpcResponse(_) => {
-                let notif = PeerNotification::NewMessage(message);
-                self.rpc_notifs_tx.send(notif).await.map_err(|err| {
-                    warn!("Failed to send notification to RPC actor. Error: {:?}", err);
-                    err
-                })?;
-            }
         };
         Ok(())
     }
@@ -369,10 +458,7 @@ where
     async fn handle_request<'a>(
         &'a mut self,
         request: PeerRequest,
-        mut write_reqs_tx: channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
+        mut write_reqs_tx: channel::Sender<WriteRequest>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -381,7 +467,7 @@ where
         );
         match request {
             PeerRequest::SendMessage(message, protocol, channel) => {
-                if let Err(e) = write_reqs_tx.send((message, channel)).await {
+                if let Err(e) = write_reqs_tx.send((message, Some(protocol), channel)).await {
                     error!(
                         "Failed to send message for protocol {} to peer: {}. Error: {:?}",
                         protocol,
@@ -0,0 +1,309 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Token-bucket limits on the inbound messages of a peer, as a whole and per application protocol.
+//!
+//! A message is let through if every bucket it draws from holds tokens, and then takes its tokens
+//! from all of them. A bucket may go into debt for a message larger than what is left in it, so
+//! that messages larger than the burst size still get through once the bucket is full.
+
+use crate::ProtocolId;
+use libra_config::config::{Error, InboundRateLimitConfig, RateLimitConfig};
+use std::{collections::HashMap, str::FromStr, time::Instant};
+
+#[derive(Clone)]
+struct TokenBucket {
+    tokens: f64,
+    burst: f64,
+    rate_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_per_sec: u64, burst: u64, now: Instant) -> Self {
+        Self {
+            tokens: burst as f64,
+            burst: burst as f64,
+            rate_per_sec: rate_per_sec as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate_per_sec).min(self.burst);
+        self.last_refill = now;
+    }
+
+    fn has_tokens(&self, amount: f64) -> bool {
+        self.tokens >= amount.min(self.burst)
+    }
+
+    fn take(&mut self, amount: f64) {
+        self.tokens -= amount;
+    }
+}
+
+#[derive(Clone)]
+struct Limits {
+    messages: TokenBucket,
+    bytes: TokenBucket,
+}
+
+impl Limits {
+    fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            messages: TokenBucket::new(config.messages_per_sec, config.burst_messages, now),
+            bytes: TokenBucket::new(config.bytes_per_sec, config.burst_bytes, now),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        self.messages.refill(now);
+        self.bytes.refill(now);
+    }
+
+    fn has_tokens(&self, size: usize) -> bool {
+        self.messages.has_tokens(1.0) && self.bytes.has_tokens(size as f64)
+    }
+
+    fn take(&mut self, size: usize) {
+        self.messages.take(1.0);
+        self.bytes.take(size as f64);
+    }
+}
+
+/// The verdict on an inbound message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitOutcome {
+    Allowed,
+    /// The message goes over the limits of the peer, or of its protocol, and must be dropped.
+    Dropped,
+    /// The message got dropped and the peer kept going over its limits, it must be disconnected.
+    Disconnect,
+}
+
+/// The limits on the inbound messages of a connection, none by default.
+#[derive(Clone, Default)]
+pub struct InboundRateLimiter {
+    peer: Option<Limits>,
+    protocols: HashMap<ProtocolId, Limits>,
+    max_consecutive_dropped_messages: Option<u64>,
+    consecutive_dropped_messages: u64,
+}
+
+impl InboundRateLimiter {
+    pub fn new(config: &InboundRateLimitConfig) -> Result<Self, Error> {
+        let now = Instant::now();
+        let protocols = config
+            .protocols
+            .iter()
+            .map(|(protocol, limits)| {
+                let protocol = ProtocolId::from_str(protocol).map_err(|_| {
+                    Error::InvariantViolation(format!(
+                        "Unknown protocol in the inbound rate limits: {}",
+                        protocol
+                    ))
+                })?;
+                Ok((protocol, Limits::new(limits, now)))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self {
+            peer: config.peer.as_ref().map(|limits| Limits::new(limits, now)),
+            protocols,
+            max_consecutive_dropped_messages: config.max_consecutive_dropped_messages,
+            consecutive_dropped_messages: 0,
+        })
+    }
+
+    /// Checks an inbound message of the given size, sent for the given protocol if any.
+    pub fn check(
+        &mut self,
+        protocol: Option<ProtocolId>,
+        size: usize,
+        now: Instant,
+    ) -> RateLimitOutcome {
+        let mut limits: Vec<&mut Limits> = self.peer.iter_mut().collect();
+        if let Some(protocol_limits) = protocol.and_then(|p| self.protocols.get_mut(&p)) {
+            limits.push(protocol_limits);
+        }
+        limits.iter_mut().for_each(|limits| limits.refill(now));
+
+        let allowed = limits.iter().all(|limits| limits.has_tokens(size));
+        if allowed {
+            limits.into_iter().for_each(|limits| limits.take(size));
+        }
+        self.outcome(allowed)
+    }
+
+    /// Checks an inbound reply of the given size. Replies only draw from the byte limit of the
+    /// peer, as their count is bounded by the messages sent by this end but their content isn't.
+    pub fn check_reply(&mut self, size: usize, now: Instant) -> RateLimitOutcome {
+        let allowed = match &mut self.peer {
+            Some(limits) => {
+                limits.bytes.refill(now);
+                let allowed = limits.bytes.has_tokens(size as f64);
+                if allowed {
+                    limits.bytes.take(size as f64);
+                }
+                allowed
+            }
+            None => true,
+        };
+        self.outcome(allowed)
+    }
+
+    fn outcome(&mut self, allowed: bool) -> RateLimitOutcome {
+        if allowed {
+            self.consecutive_dropped_messages = 0;
+            return RateLimitOutcome::Allowed;
+        }
+        self.consecutive_dropped_messages += 1;
+        match self.max_consecutive_dropped_messages {
+            Some(max) if self.consecutive_dropped_messages >= max => RateLimitOutcome::Disconnect,
+            _ => RateLimitOutcome::Dropped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limits(messages_per_sec: u64, burst_messages: u64) -> RateLimitConfig {
+        RateLimitConfig {
+            messages_per_sec,
+            burst_messages,
+            bytes_per_sec: 1000,
+            burst_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let mut rate_limiter = InboundRateLimiter::default();
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert_eq!(
+                rate_limiter.check(Some(ProtocolId::MempoolDirectSend), 1 << 20, now),
+                RateLimitOutcome::Allowed
+            );
+        }
+    }
+
+    #[test]
+    fn test_reply_limits() {
+        let mut config = InboundRateLimitConfig::default();
+        config.peer = Some(limits(0, 1));
+        let mut rate_limiter = InboundRateLimiter::new(&config).unwrap();
+        let now = Instant::now();
+
+        // Replies don't draw from the message limit of the peer, only from its byte limit
+        for _ in 0..10 {
+            assert_eq!(rate_limiter.check_reply(10, now), RateLimitOutcome::Allowed);
+        }
+        assert_eq!(rate_limiter.check_reply(10, now), RateLimitOutcome::Dropped);
+        // which they share with the other messages
+        assert_eq!(rate_limiter.check(None, 1, now), RateLimitOutcome::Dropped);
+    }
+
+    #[test]
+    fn test_peer_and_protocol_limits() {
+        let mut config = InboundRateLimitConfig::default();
+        config.peer = Some(limits(10, 3));
+        config
+            .protocols
+            .insert("MempoolDirectSend".to_string(), limits(1, 1));
+        let mut rate_limiter = InboundRateLimiter::new(&config).unwrap();
+        let now = Instant::now();
+
+        // The protocol limit kicks in first
+        let mempool = Some(ProtocolId::MempoolDirectSend);
+        assert_eq!(
+            rate_limiter.check(mempool, 10, now),
+            RateLimitOutcome::Allowed
+        );
+        assert_eq!(
+            rate_limiter.check(mempool, 10, now),
+            RateLimitOutcome::Dropped
+        );
+        // Other protocols only draw from the peer limit
+        let consensus = Some(ProtocolId::ConsensusDirectSend);
+        for _ in 0..2 {
+            assert_eq!(
+                rate_limiter.check(consensus, 10, now),
+                RateLimitOutcome::Allowed
+            );
+        }
+        assert_eq!(rate_limiter.check(None, 10, now), RateLimitOutcome::Dropped);
+
+        // Buckets get refilled over time
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            rate_limiter.check(mempool, 10, later),
+            RateLimitOutcome::Allowed
+        );
+        assert_eq!(
+            rate_limiter.check(consensus, 10, later),
+            RateLimitOutcome::Allowed
+        );
+    }
+
+    #[test]
+    fn test_byte_limit() {
+        let mut config = InboundRateLimitConfig::default();
+        config.peer = Some(limits(100, 100));
+        let mut rate_limiter = InboundRateLimiter::new(&config).unwrap();
+        let now = Instant::now();
+
+        // A message larger than the burst goes through a full bucket, and puts it into debt
+        assert_eq!(
+            rate_limiter.check(None, 1000, now),
+            RateLimitOutcome::Allowed
+        );
+        assert_eq!(rate_limiter.check(None, 1, now), RateLimitOutcome::Dropped);
+        let later = now + Duration::from_millis(500);
+        assert_eq!(
+            rate_limiter.check(None, 1, later),
+            RateLimitOutcome::Dropped
+        );
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            rate_limiter.check(None, 1, later),
+            RateLimitOutcome::Allowed
+        );
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let mut config = InboundRateLimitConfig::default();
+        config.peer = Some(limits(1, 1));
+        config.max_consecutive_dropped_messages = Some(2);
+        let mut rate_limiter = InboundRateLimiter::new(&config).unwrap();
+        let now = Instant::now();
+
+        assert_eq!(rate_limiter.check(None, 1, now), RateLimitOutcome::Allowed);
+        assert_eq!(rate_limiter.check(None, 1, now), RateLimitOutcome::Dropped);
+        // An allowed message resets the count of dropped messages
+        let now = now + Duration::from_secs(1);
+        assert_eq!(rate_limiter.check(None, 1, now), RateLimitOutcome::Allowed);
+        assert_eq!(rate_limiter.check(None, 1, now), RateLimitOutcome::Dropped);
+        assert_eq!(
+            rate_limiter.check(None, 1, now),
+            RateLimitOutcome::Disconnect
+        );
+    }
+
+    #[test]
+    fn test_unknown_protocol() {
+        let mut config = InboundRateLimitConfig::default();
+        config
+            .protocols
+            .insert("UnknownProtocol".to_string(), limits(1, 1));
+        assert!(matches!(
+            InboundRateLimiter::new(&config),
+            Err(Error::InvariantViolation(_))
+        ));
+    }
+}
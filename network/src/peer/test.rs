@@ -3,15 +3,19 @@
 
 use crate::{
     constants,
-    peer::{DisconnectReason, Peer, PeerHandle, PeerNotification},
+    peer::{DisconnectReason, InboundRateLimiter, Peer, PeerHandle, PeerNotification},
     protocols::wire::{
-        handshake::v1::MessagingProtocolVersion,
-        messaging::v1::{DirectSendMsg, NetworkMessage},
+        handshake::v1::{CompressionAlgorithm, MessagingProtocolVersion, SupportedProtocols},
+        messaging::v1::{
+            compression::MessageCompression, DirectSendMsg, ErrorCode, NetworkMessage,
+            RateLimitedType, RpcRequest, RpcResponse,
+        },
     },
     transport::{Connection, ConnectionId, ConnectionMetadata},
     ProtocolId,
 };
 use futures::{future::join, io::AsyncWriteExt, stream::StreamExt, SinkExt};
+use libra_config::config::{InboundRateLimitConfig, RateLimitConfig};
 use libra_network_address::NetworkAddress;
 use libra_types::PeerId;
 use memsocket::MemorySocket;
@@ -35,6 +39,22 @@ fn build_test_peer(
     channel::Receiver<PeerNotification>,
     channel::Receiver<PeerNotification>,
     channel::Receiver<PeerNotification>,
+) {
+    build_custom_test_peer(executor, origin, None, &InboundRateLimitConfig::default())
+}
+
+fn build_custom_test_peer(
+    executor: Handle,
+    origin: ConnectionOrigin,
+    compression: Option<(CompressionAlgorithm, SupportedProtocols)>,
+    inbound_rate_limit: &InboundRateLimitConfig,
+) -> (
+    Peer<MemorySocket>,
+    PeerHandle,
+    MemorySocket,
+    channel::Receiver<PeerNotification>,
+    channel::Receiver<PeerNotification>,
+    channel::Receiver<PeerNotification>,
 ) {
     let (a, b) = MemorySocket::new_pair();
     let peer_id = PeerId::random();
@@ -50,6 +70,7 @@ fn build_test_peer(
             origin,
             MessagingProtocolVersion::V1,
             [].iter().into(),
+            compression,
         ),
         socket: a,
     };
@@ -62,6 +83,7 @@ fn build_test_peer(
         peer_rpc_notifs_tx,
         peer_direct_send_notifs_tx,
         constants::MAX_FRAME_SIZE,
+        InboundRateLimiter::new(inbound_rate_limit).unwrap(),
     );
     let peer_handle = PeerHandle::new(peer_id, peer_req_tx);
 
@@ -240,6 +262,320 @@ fn peer_recv_message() {
     rt.block_on(join(server, client));
 }
 
+#[test]
+fn peer_send_recv_compressed_message() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let compressed_protocols: SupportedProtocols = [PROTOCOL].iter().into();
+    let (
+        peer,
+        mut peer_handle,
+        connection,
+        _peer_notifs_rx,
+        _peer_rpc_notifs_rx,
+        mut peer_direct_send_notifs_rx,
+    ) = build_custom_test_peer(
+        rt.handle().clone(),
+        ConnectionOrigin::Inbound,
+        Some((CompressionAlgorithm::Lz4, compressed_protocols.clone())),
+        &InboundRateLimitConfig::default(),
+    );
+    let compression = MessageCompression::new(
+        CompressionAlgorithm::Lz4,
+        compressed_protocols,
+        constants::MAX_FRAME_SIZE,
+    );
+
+    let send_msg = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: "hello world".repeat(1000).into_bytes(),
+    });
+    let recv_msg = send_msg.clone();
+
+    let server = async move {
+        let mut connection = Framed::new(IoCompat::new(connection), LengthDelimitedCodec::new());
+        // The peer sends the message compressed.
+        let frame = connection.next().await.unwrap().unwrap();
+        let message = lcs::to_bytes(&recv_msg).unwrap();
+        assert!(frame.len() < message.len());
+        let msg: NetworkMessage =
+            lcs::from_bytes(&compression.decompress(&frame).unwrap()).unwrap();
+        assert_eq!(msg, recv_msg);
+        // The peer decompresses the message it receives.
+        let frame = compression.compress(&message, Some(PROTOCOL)).unwrap();
+        connection.send(frame.into()).await.unwrap();
+        connection.close().await.unwrap();
+    };
+
+    let client = async move {
+        peer_handle
+            .send_message(send_msg.clone(), PROTOCOL)
+            .await
+            .unwrap();
+        let received = peer_direct_send_notifs_rx.next().await.unwrap();
+        assert!(
+            matches!(received, PeerNotification::NewMessage(received_msg) if received_msg == send_msg)
+        );
+        ManuallyDrop::new(peer_handle);
+    };
+    rt.spawn(peer.start());
+    rt.block_on(join(server, client));
+}
+
+#[test]
+fn peer_recv_max_size_compressed_frame() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let (
+        peer,
+        peer_handle,
+        connection,
+        _peer_notifs_rx,
+        _peer_rpc_notifs_rx,
+        mut peer_direct_send_notifs_rx,
+    ) = build_custom_test_peer(
+        rt.handle().clone(),
+        ConnectionOrigin::Inbound,
+        Some((CompressionAlgorithm::Lz4, [PROTOCOL].iter().into())),
+        &InboundRateLimitConfig::default(),
+    );
+
+    // A message of the maximum frame size, sent as is, takes one more byte with its flag.
+    let mut msg = DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: vec![],
+    };
+    let overhead = lcs::to_bytes(&NetworkMessage::DirectSendMsg(msg.clone()))
+        .unwrap()
+        .len()
+        + 3; /* longer length prefix of raw_msg */
+    msg.raw_msg = vec![0; constants::MAX_FRAME_SIZE - overhead];
+    let send_msg = NetworkMessage::DirectSendMsg(msg);
+    let message = lcs::to_bytes(&send_msg).unwrap();
+    assert_eq!(message.len(), constants::MAX_FRAME_SIZE);
+
+    let server = async move {
+        let codec = LengthDelimitedCodec::builder()
+            .max_frame_length(constants::MAX_FRAME_SIZE + 1)
+            .new_codec();
+        let mut connection = Framed::new(IoCompat::new(connection), codec);
+        let mut frame = vec![0];
+        frame.extend_from_slice(&message);
+        connection.send(frame.into()).await.unwrap();
+    };
+
+    let client = async move {
+        let received = peer_direct_send_notifs_rx.next().await.unwrap();
+        assert!(
+            matches!(received, PeerNotification::NewMessage(received_msg) if received_msg == send_msg)
+        );
+        ManuallyDrop::new(peer_handle);
+    };
+    rt.spawn(peer.start());
+    rt.block_on(join(server, client));
+}
+
+#[test]
+fn peer_recv_rate_limited_message() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let mut inbound_rate_limit = InboundRateLimitConfig::default();
+    inbound_rate_limit.peer = Some(RateLimitConfig {
+        messages_per_sec: 0,
+        burst_messages: 1,
+        bytes_per_sec: 0,
+        burst_bytes: 1 << 20,
+    });
+    inbound_rate_limit.max_consecutive_dropped_messages = Some(2);
+    let (
+        peer,
+        _peer_handle,
+        connection,
+        mut peer_notifs_rx,
+        _peer_rpc_notifs_rx,
+        mut peer_direct_send_notifs_rx,
+    ) = build_custom_test_peer(
+        rt.handle().clone(),
+        ConnectionOrigin::Inbound,
+        None,
+        &inbound_rate_limit,
+    );
+    let peer_id = peer.peer_id();
+
+    let send_msg = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: Vec::from("hello world"),
+    });
+    let recv_msg = send_msg.clone();
+
+    let server = async move {
+        let mut connection = Framed::new(IoCompat::new(connection), LengthDelimitedCodec::new());
+        // The first message is delivered, the next two go over the limit and get dropped.
+        for _ in 0..3 {
+            connection
+                .send(lcs::to_bytes(&send_msg).unwrap().into())
+                .await
+                .unwrap();
+        }
+        // The peer then gets disconnected.
+        assert!(connection.next().await.is_none());
+    };
+
+    let client = async move {
+        let received = peer_direct_send_notifs_rx.next().await.unwrap();
+        assert!(
+            matches!(received, PeerNotification::NewMessage(received_msg) if received_msg == recv_msg)
+        );
+        assert_peer_disconnected_event(peer_id, DisconnectReason::RateLimited, &mut peer_notifs_rx)
+            .await;
+    };
+    rt.spawn(peer.start());
+    rt.block_on(join(server, client));
+}
+
+#[test]
+fn peer_recv_rate_limited_rpc_request() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let mut inbound_rate_limit = InboundRateLimitConfig::default();
+    inbound_rate_limit.peer = Some(RateLimitConfig {
+        messages_per_sec: 0,
+        burst_messages: 1,
+        bytes_per_sec: 0,
+        burst_bytes: 1 << 20,
+    });
+    let (
+        peer,
+        _peer_handle,
+        connection,
+        _peer_notifs_rx,
+        mut peer_rpc_notifs_rx,
+        _peer_direct_send_notifs_rx,
+    ) = build_custom_test_peer(
+        rt.handle().clone(),
+        ConnectionOrigin::Inbound,
+        None,
+        &inbound_rate_limit,
+    );
+
+    let request = |request_id| {
+        NetworkMessage::RpcRequest(RpcRequest {
+            protocol_id: ProtocolId::ConsensusRpc,
+            request_id,
+            priority: 0,
+            raw_request: Vec::from("hello world"),
+        })
+    };
+    let response = NetworkMessage::RpcResponse(RpcResponse {
+        request_id: 0,
+        priority: 0,
+        raw_response: Vec::from("hello world"),
+    });
+    let recv_request = request(1);
+    let recv_response = response.clone();
+
+    let server = async move {
+        let mut connection = Framed::new(IoCompat::new(connection), LengthDelimitedCodec::new());
+        // The first request is delivered, the second one goes over the limit and gets dropped.
+        for request_id in 1..=2 {
+            connection
+                .send(lcs::to_bytes(&request(request_id)).unwrap().into())
+                .await
+                .unwrap();
+        }
+        // The dropped request is answered with an error.
+        let error: NetworkMessage =
+            lcs::from_bytes(&connection.next().await.unwrap().unwrap()).unwrap();
+        assert_eq!(
+            error,
+            NetworkMessage::Error(ErrorCode::RateLimited(RateLimitedType::RpcRequest(
+                ProtocolId::ConsensusRpc,
+                2
+            )))
+        );
+        // Responses don't draw from the message limit.
+        connection
+            .send(lcs::to_bytes(&response).unwrap().into())
+            .await
+            .unwrap();
+        connection.close().await.unwrap();
+    };
+
+    let client = async move {
+        for recv_msg in &[recv_request, recv_response] {
+            let received = peer_rpc_notifs_rx.next().await.unwrap();
+            assert!(
+                matches!(received, PeerNotification::NewMessage(received_msg) if &received_msg == recv_msg)
+            );
+        }
+    };
+    rt.spawn(peer.start());
+    rt.block_on(join(server, client));
+}
+
+#[test]
+fn peer_recv_rate_limited_responses() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut rt = Runtime::new().unwrap();
+    let mut inbound_rate_limit = InboundRateLimitConfig::default();
+    inbound_rate_limit.peer = Some(RateLimitConfig {
+        messages_per_sec: 1000,
+        burst_messages: 1000,
+        bytes_per_sec: 0,
+        burst_bytes: 64,
+    });
+    inbound_rate_limit.max_consecutive_dropped_messages = Some(2);
+    let (
+        peer,
+        _peer_handle,
+        connection,
+        mut peer_notifs_rx,
+        mut peer_rpc_notifs_rx,
+        _peer_direct_send_notifs_rx,
+    ) = build_custom_test_peer(
+        rt.handle().clone(),
+        ConnectionOrigin::Inbound,
+        None,
+        &inbound_rate_limit,
+    );
+    let peer_id = peer.peer_id();
+
+    let response = NetworkMessage::RpcResponse(RpcResponse {
+        request_id: 0,
+        priority: 0,
+        raw_response: vec![0; 40],
+    });
+    let recv_response = response.clone();
+
+    let server = async move {
+        let mut connection = Framed::new(IoCompat::new(connection), LengthDelimitedCodec::new());
+        // Flood unsolicited responses: the first one is delivered, the next two go over the byte
+        // limit and get dropped.
+        for _ in 0..3 {
+            connection
+                .send(lcs::to_bytes(&response).unwrap().into())
+                .await
+                .unwrap();
+        }
+        // The peer then gets disconnected.
+        assert!(connection.next().await.is_none());
+    };
+
+    let client = async move {
+        let received = peer_rpc_notifs_rx.next().await.unwrap();
+        assert!(
+            matches!(received, PeerNotification::NewMessage(received_msg) if received_msg == recv_response)
+        );
+        assert_peer_disconnected_event(peer_id, DisconnectReason::RateLimited, &mut peer_notifs_rx)
+            .await;
+    };
+    rt.spawn(peer.start());
+    rt.block_on(join(server, client));
+}
+
 // Test that if two peers request to open a substream with each other simultaneously that
 // we won't deadlock.
 #[test]
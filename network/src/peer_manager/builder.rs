@@ -4,16 +4,20 @@
 use crate::{
     counters,
     noise::stream::NoiseStream,
+    peer::InboundRateLimiter,
     peer_manager::{
         conn_notifs_channel, ConnectionRequest, ConnectionRequestSender, PeerManager,
-        PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
+        PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender, PeerScores,
     },
     protocols::wire::handshake::v1::SupportedProtocols,
     transport::{self, Connection, LibraNetTransport, LIBRA_TCP_TRANSPORT},
     ProtocolId,
 };
 use channel::{self, libra_channel, message_queues::QueueStyle};
-use libra_config::{config::HANDSHAKE_VERSION, network_id::NetworkContext};
+use libra_config::{
+    config::{Error, InboundRateLimitConfig, PeerScoringConfig, HANDSHAKE_VERSION},
+    network_id::NetworkContext,
+};
 use libra_crypto::x25519;
 use libra_logger::prelude::*;
 use libra_metrics::IntCounterVec;
@@ -183,6 +187,8 @@ pub struct PeerManagerBuilder {
     listen_address: NetworkAddress,
     state: State,
     max_frame_size: usize,
+    rate_limiter: InboundRateLimiter,
+    peer_scores: Arc<RwLock<PeerScores>>,
 }
 
 impl PeerManagerBuilder {
@@ -237,9 +243,32 @@ impl PeerManagerBuilder {
             listen_address,
             state: State::CREATED,
             max_frame_size,
+            rate_limiter: InboundRateLimiter::default(),
+            peer_scores: Arc::new(RwLock::new(PeerScores::new(&PeerScoringConfig::default()))),
         }
     }
 
+    /// Set the limits on the inbound messages of every peer, no limits by default.
+    pub fn set_inbound_rate_limit(
+        &mut self,
+        inbound_rate_limit: &InboundRateLimitConfig,
+    ) -> Result<(), Error> {
+        assert_eq!(self.state, State::CREATED);
+        self.rate_limiter = InboundRateLimiter::new(inbound_rate_limit)?;
+        Ok(())
+    }
+
+    /// Set how the peers get scored on their misbehavior and banned.
+    pub fn set_peer_scoring(&mut self, config: &PeerScoringConfig) {
+        assert_eq!(self.state, State::CREATED);
+        *self.peer_scores.write().unwrap() = PeerScores::new(config);
+    }
+
+    /// The scores of the peers, shared with the PeerManager once built.
+    pub fn peer_scores(&self) -> Arc<RwLock<PeerScores>> {
+        self.peer_scores.clone()
+    }
+
     pub fn listen_address(&self) -> NetworkAddress {
         self.listen_address.clone()
     }
@@ -350,6 +379,8 @@ impl PeerManagerBuilder {
             pm_context.max_concurrent_network_notifs,
             pm_context.channel_size,
             self.max_frame_size,
+            self.rate_limiter.clone(),
+            self.peer_scores.clone(),
         );
 
         // PeerManager constructor appends a public key to the listen_address.
@@ -14,7 +14,7 @@ use crate::{
     counters,
     interface::{NetworkNotification, NetworkProvider, NetworkRequest},
     logging::*,
-    peer::DisconnectReason,
+    peer::{DisconnectReason, InboundRateLimiter},
     protocols::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, OutboundRpcRequest},
@@ -42,18 +42,22 @@ use std::{
     collections::{hash_map::Entry, HashMap},
     fmt::Debug,
     marker::PhantomData,
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 use tokio::runtime::Handle;
 
 pub mod builder;
 pub mod conn_notifs_channel;
 mod error;
+mod scoring;
 #[cfg(test)]
 mod tests;
 
-pub use self::error::PeerManagerError;
+pub use self::{
+    error::PeerManagerError,
+    scoring::{Misbehavior, PeerScores},
+};
 use crate::logging::network_events::{CONNECTION_METADATA, TRANSPORT_EVENT, TYPE};
 use serde::export::Formatter;
 
@@ -86,6 +90,7 @@ pub enum ConnectionRequest {
         PeerId,
         #[serde(skip)] oneshot::Sender<Result<(), PeerManagerError>>,
     ),
+    ReportMisbehavior(PeerId, Misbehavior),
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize)]
@@ -235,6 +240,21 @@ impl ConnectionRequestSender {
             .push(peer, ConnectionRequest::DisconnectPeer(peer, oneshot_tx))?;
         oneshot_rx.await?
     }
+
+    /// Report the misbehavior of a peer, which lowers its score and may get it banned.
+    ///
+    /// The function returns when the report has been enqueued on the network actor's event queue.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), PeerManagerError> {
+        self.inner.push(
+            peer,
+            ConnectionRequest::ReportMisbehavior(peer, misbehavior),
+        )?;
+        Ok(())
+    }
 }
 
 /// Responsible for handling and maintaining connections to other Peers
@@ -287,6 +307,10 @@ where
     channel_size: usize,
     /// Max network frame size
     max_frame_size: usize,
+    /// Limits on the inbound messages of a peer, copied for every new connection.
+    rate_limiter: InboundRateLimiter,
+    /// Scores of the peers on their misbehavior, shared with ConnectivityManager.
+    peer_scores: Arc<RwLock<PeerScores>>,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -312,6 +336,8 @@ where
         max_concurrent_network_reqs: usize,
         max_concurrent_network_notifs: usize,
         max_frame_size: usize,
+        rate_limiter: InboundRateLimiter,
+        peer_scores: Arc<RwLock<PeerScores>>,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = channel::new(
             channel_size,
@@ -350,6 +376,8 @@ where
             max_concurrent_network_notifs,
             channel_size,
             max_frame_size,
+            rate_limiter,
+            peer_scores,
         }
     }
 
@@ -499,8 +527,24 @@ where
         trace!("{} PeerManagerRequest::{:?}", self.network_context, request);
         match request {
             ConnectionRequest::DialPeer(requested_peer_id, addr, response_tx) => {
-                // Only dial peers which we aren't already connected with
-                if let Some((curr_connection, _)) = self.active_peers.get(&requested_peer_id) {
+                // Only dial peers which we aren't already connected with, and which aren't banned
+                if self.is_banned(&requested_peer_id) {
+                    debug!(
+                        "{} Peer {} is banned. Not dialing address {}",
+                        self.network_context,
+                        requested_peer_id.short_str(),
+                        addr
+                    );
+                    let error = PeerManagerError::Banned(requested_peer_id);
+                    if response_tx.send(Err(error)).is_err() {
+                        warn!(
+                            "{} Receiver for DialPeer {} dropped",
+                            self.network_context,
+                            requested_peer_id.short_str()
+                        );
+                    }
+                } else if let Some((curr_connection, _)) = self.active_peers.get(&requested_peer_id)
+                {
                     let error = PeerManagerError::AlreadyConnected(curr_connection.addr().clone());
                     debug!(
                         "{} Already connected with Peer {} using connection {:?}. Not dialing address {}",
@@ -544,9 +588,39 @@ where
                     }
                 }
             }
+            ConnectionRequest::ReportMisbehavior(peer_id, misbehavior) => {
+                counters::LIBRA_NETWORK_PEER_MISBEHAVIOR
+                    .with_label_values(&[misbehavior.as_str()])
+                    .inc();
+                let banned =
+                    self.peer_scores
+                        .write()
+                        .unwrap()
+                        .report(peer_id, misbehavior, Instant::now());
+                if banned {
+                    warn!(
+                        "{} Banning peer {} after its misbehavior: {}",
+                        self.network_context,
+                        peer_id.short_str(),
+                        misbehavior
+                    );
+                    counters::LIBRA_NETWORK_PEER_BANS
+                        .with_label_values(&[misbehavior.as_str()])
+                        .inc();
+                    // Dropping the NetworkRequest sender closes the connection.
+                    self.active_peers.remove(&peer_id);
+                }
+            }
         }
     }
 
+    fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peer_scores
+            .read()
+            .unwrap()
+            .is_banned(peer_id, Instant::now())
+    }
+
     async fn handle_request(&mut self, request: PeerManagerRequest) {
         trace!("{} PeerManagerRequest::{:?}", self.network_context, request);
         match request {
@@ -626,6 +700,17 @@ where
 
         let mut send_new_peer_notification = true;
 
+        // Refuse connections with banned peers
+        if self.is_banned(&peer_id) {
+            info!(
+                "{} Closing connection with banned Peer {}",
+                self.network_context,
+                peer_id.short_str()
+            );
+            self.close_connection(connection);
+            return;
+        }
+
         // Check for and handle simultaneous dialing
         if let Entry::Occupied(active_entry) = self.active_peers.entry(peer_id) {
             let (curr_conn_metadata, _) = active_entry.get();
@@ -650,25 +735,8 @@ where
                     self.network_context,
                     peer_id.short_str()
                 );
-                let network_context = self.network_context.clone();
                 // Drop the new connection and keep the one already stored in active_peers
-                let drop_fut = async move {
-                    let mut connection = connection;
-                    if let Err(e) = tokio::time::timeout(
-                        transport::TRANSPORT_TIMEOUT,
-                        connection.socket.close(),
-                    )
-                    .await
-                    {
-                        error!(
-                            "{} Closing connection with Peer {} failed with error: {}",
-                            network_context,
-                            peer_id.short_str(),
-                            e
-                        );
-                    };
-                };
-                self.executor.spawn(drop_fut);
+                self.close_connection(connection);
                 return;
             }
         }
@@ -682,6 +750,7 @@ where
             self.max_concurrent_network_notifs,
             self.channel_size,
             self.max_frame_size,
+            self.rate_limiter.clone(),
         );
         // Start background task to handle events (RPCs and DirectSend messages) received from
         // peer.
@@ -701,6 +770,26 @@ where
         }
     }
 
+    /// Closes a connection which never made it to `active_peers`.
+    fn close_connection(&self, connection: Connection<TSocket>) {
+        let network_context = self.network_context.clone();
+        let peer_id = connection.metadata.peer_id();
+        let drop_fut = async move {
+            let mut connection = connection;
+            if let Err(e) =
+                tokio::time::timeout(transport::TRANSPORT_TIMEOUT, connection.socket.close()).await
+            {
+                error!(
+                    "{} Closing connection with Peer {} failed with error: {}",
+                    network_context,
+                    peer_id.short_str(),
+                    e
+                );
+            };
+        };
+        self.executor.spawn(drop_fut);
+    }
+
     /// Sends a `ConnectionNotification` to all event handlers, warns on failures
     fn send_conn_notification(&mut self, peer_id: PeerId, notification: ConnectionNotification) {
         for handler in self.connection_event_handlers.iter_mut() {
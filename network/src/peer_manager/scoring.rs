@@ -0,0 +1,220 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scoring of the peers on the misbehavior reported by the applications.
+//!
+//! A peer starts with a score of zero, and every misbehavior reported on it lowers its score by a
+//! penalty. Timeouts are only counted: honest peers may be slow or far away, and banning them
+//! would hurt the liveness of the network more than it would protect it. Scores decay
+//! exponentially back towards zero, so that only peers misbehaving repeatedly within a short time
+//! end up with a low score. A peer whose score drops to the ban threshold gets banned:
+//! PeerManager disconnects from it and refuses its connections, and ConnectivityManager stops
+//! dialing it, until the ban expires.
+
+use libra_config::config::PeerScoringConfig;
+use libra_types::PeerId;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Scores below this magnitude are forgotten.
+const NEGLIGIBLE_SCORE: f64 = 1.0;
+
+/// Misbehavior of a peer, as reported by an application.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+pub enum Misbehavior {
+    /// The peer sent a message which could not be deserialized or is malformed.
+    InvalidMessage,
+    /// The peer sent a message with an invalid signature.
+    InvalidSignature,
+    /// The peer sent a chunk of the ledger which could not be verified.
+    InvalidChunk,
+    /// The peer did not answer a request in time. This never lowers the score of the peer.
+    RequestTimeout,
+}
+
+impl Misbehavior {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Misbehavior::InvalidMessage => "InvalidMessage",
+            Misbehavior::InvalidSignature => "InvalidSignature",
+            Misbehavior::InvalidChunk => "InvalidChunk",
+            Misbehavior::RequestTimeout => "RequestTimeout",
+        }
+    }
+
+    fn penalty(self) -> Option<f64> {
+        match self {
+            Misbehavior::InvalidMessage => Some(20.0),
+            Misbehavior::InvalidSignature => Some(50.0),
+            Misbehavior::InvalidChunk => Some(25.0),
+            // Honest peers time out too, so timeouts can't tell them apart from malicious ones.
+            Misbehavior::RequestTimeout => None,
+        }
+    }
+}
+
+impl fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug)]
+struct Score {
+    value: f64,
+    last_update: Instant,
+}
+
+impl Score {
+    fn decayed(&self, half_life: Duration, now: Instant) -> f64 {
+        if half_life == Duration::from_secs(0) {
+            return 0.0;
+        }
+        let elapsed = now.saturating_duration_since(self.last_update);
+        self.value * 0.5f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64())
+    }
+}
+
+/// The scores and bans of the peers of a network, shared between PeerManager and
+/// ConnectivityManager.
+#[derive(Debug)]
+pub struct PeerScores {
+    decay_half_life: Duration,
+    ban_threshold: f64,
+    ban_duration: Duration,
+    scores: HashMap<PeerId, Score>,
+    /// Banned peers, along with the expiration of their ban.
+    bans: HashMap<PeerId, Instant>,
+}
+
+impl PeerScores {
+    pub fn new(config: &PeerScoringConfig) -> Self {
+        Self {
+            decay_half_life: Duration::from_secs(config.decay_half_life_secs),
+            ban_threshold: config.ban_threshold as f64,
+            ban_duration: Duration::from_secs(config.ban_duration_secs),
+            scores: HashMap::new(),
+            bans: HashMap::new(),
+        }
+    }
+
+    /// The current score of a peer, zero for a peer with no misbehavior on record.
+    pub fn score(&self, peer_id: &PeerId, now: Instant) -> f64 {
+        self.scores
+            .get(peer_id)
+            .map_or(0.0, |score| score.decayed(self.decay_half_life, now))
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId, now: Instant) -> bool {
+        self.bans.get(peer_id).map_or(false, |until| now < *until)
+    }
+
+    /// Lowers the score of a peer for the given misbehavior. Returns `true` if the peer just got
+    /// banned.
+    pub fn report(&mut self, peer_id: PeerId, misbehavior: Misbehavior, now: Instant) -> bool {
+        self.remove_expired(now);
+        let penalty = match misbehavior.penalty() {
+            Some(penalty) if !self.is_banned(&peer_id, now) => penalty,
+            _ => return false,
+        };
+
+        let value = self.score(&peer_id, now) - penalty;
+        if value > self.ban_threshold {
+            self.scores.insert(
+                peer_id,
+                Score {
+                    value,
+                    last_update: now,
+                },
+            );
+            return false;
+        }
+        // The peer starts over once the ban expires.
+        self.scores.remove(&peer_id);
+        self.bans.insert(peer_id, now + self.ban_duration);
+        true
+    }
+
+    /// Forgets the expired bans and the scores which decayed back close to zero.
+    fn remove_expired(&mut self, now: Instant) {
+        self.bans.retain(|_, until| now < *until);
+        let half_life = self.decay_half_life;
+        self.scores
+            .retain(|_, score| score.decayed(half_life, now).abs() >= NEGLIGIBLE_SCORE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_scores() -> PeerScores {
+        PeerScores::new(&PeerScoringConfig {
+            decay_half_life_secs: 10,
+            ban_threshold: -100,
+            ban_duration_secs: 60,
+        })
+    }
+
+    #[test]
+    fn test_score_decay() {
+        let mut peer_scores = peer_scores();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        assert!(!peer_scores.report(peer_id, Misbehavior::InvalidSignature, now));
+        assert!((peer_scores.score(&peer_id, now) + 50.0).abs() < 1e-9);
+        let later = now + Duration::from_secs(10);
+        assert!((peer_scores.score(&peer_id, later) + 25.0).abs() < 1e-9);
+        // Misbehavior spread over time doesn't add up to a ban.
+        assert!(!peer_scores.report(peer_id, Misbehavior::InvalidSignature, later));
+        assert!((peer_scores.score(&peer_id, later) + 75.0).abs() < 1e-9);
+
+        // Scores decayed close to zero are forgotten.
+        let much_later = now + Duration::from_secs(100);
+        peer_scores.report(PeerId::random(), Misbehavior::RequestTimeout, much_later);
+        assert!(!peer_scores.scores.contains_key(&peer_id));
+        assert!(peer_scores.score(&peer_id, much_later).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ban() {
+        let mut peer_scores = peer_scores();
+        let (peer_id, other_peer_id) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+
+        assert!(!peer_scores.report(peer_id, Misbehavior::InvalidSignature, now));
+        assert!(peer_scores.report(peer_id, Misbehavior::InvalidSignature, now));
+        assert!(peer_scores.is_banned(&peer_id, now));
+        assert!(!peer_scores.is_banned(&other_peer_id, now));
+        // Reports on a banned peer don't extend its ban.
+        assert!(!peer_scores.report(peer_id, Misbehavior::InvalidSignature, now));
+
+        // The peer starts over once the ban expires.
+        let later = now + Duration::from_secs(60);
+        assert!(!peer_scores.is_banned(&peer_id, later));
+        assert!(peer_scores.score(&peer_id, later).abs() < 1e-9);
+        assert!(!peer_scores.report(peer_id, Misbehavior::InvalidSignature, later));
+    }
+
+    #[test]
+    fn test_slow_peer_never_banned() {
+        let mut peer_scores = peer_scores();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        // An honest but slow peer times out on every request.
+        for _ in 0..1000 {
+            assert!(!peer_scores.report(peer_id, Misbehavior::RequestTimeout, now));
+        }
+        assert!(!peer_scores.is_banned(&peer_id, now));
+        assert!(peer_scores.score(&peer_id, now).abs() < 1e-9);
+        // Nor do its timeouts bring it any closer to a ban.
+        assert!(!peer_scores.report(peer_id, Misbehavior::InvalidSignature, now));
+        assert!(!peer_scores.is_banned(&peer_id, now));
+    }
+}
@@ -3,10 +3,11 @@
 
 use crate::{
     constants,
-    peer::DisconnectReason,
+    peer::{DisconnectReason, InboundRateLimiter},
     peer_manager::{
         conn_notifs_channel, error::PeerManagerError, ConnectionNotification, ConnectionRequest,
-        PeerManager, PeerManagerNotification, PeerManagerRequest, TransportNotification,
+        Misbehavior, PeerManager, PeerManagerNotification, PeerManagerRequest, PeerScores,
+        TransportNotification,
     },
     protocols::wire::{
         handshake::v1::MessagingProtocolVersion,
@@ -19,7 +20,7 @@ use crate::{
 use channel::{libra_channel, message_queues::QueueStyle};
 use futures::{channel::oneshot, io::AsyncWriteExt, sink::SinkExt, stream::StreamExt};
 use libra_config::{
-    config::RoleType,
+    config::{PeerScoringConfig, RoleType},
     network_id::{NetworkContext, NetworkId},
 };
 use libra_network_address::NetworkAddress;
@@ -29,7 +30,12 @@ use netcore::{
     compat::IoCompat,
     transport::{boxed::BoxedTransport, memory::MemoryTransport, ConnectionOrigin, TransportExt},
 };
-use std::{collections::HashMap, iter::FromIterator, num::NonZeroUsize, sync::Arc};
+use std::{
+    collections::HashMap,
+    iter::FromIterator,
+    num::NonZeroUsize,
+    sync::{Arc, RwLock},
+};
 use tokio::runtime::Handle;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
 
@@ -53,6 +59,7 @@ pub fn build_test_transport(
                     origin,
                     MessagingProtocolVersion::V1,
                     [TEST_PROTOCOL].iter().into(),
+                    None,
                 ),
             })
         })
@@ -110,6 +117,8 @@ fn build_test_peer_manager(
         constants::MAX_CONCURRENT_NETWORK_REQS,
         constants::MAX_CONCURRENT_NETWORK_NOTIFS,
         constants::MAX_FRAME_SIZE,
+        InboundRateLimiter::default(),
+        Arc::new(RwLock::new(PeerScores::new(&PeerScoringConfig::default()))),
     );
 
     (
@@ -229,6 +238,7 @@ fn create_connection<TSocket: transport::TSocket>(
             origin,
             MessagingProtocolVersion::V1,
             [TEST_PROTOCOL].iter().into(),
+            None,
         ),
     }
 }
@@ -553,6 +563,7 @@ fn peer_manager_simultaneous_dial_disconnect_event() {
                 ConnectionOrigin::Inbound,
                 MessagingProtocolVersion::V1,
                 [TEST_PROTOCOL].iter().into(),
+                None,
             ),
             DisconnectReason::ConnectionLost,
         );
@@ -610,6 +621,7 @@ fn test_dial_disconnect() {
                 ConnectionOrigin::Outbound,
                 MessagingProtocolVersion::V1,
                 [TEST_PROTOCOL].iter().into(),
+                None,
             ),
             DisconnectReason::Requested,
         );
@@ -628,3 +640,118 @@ fn test_dial_disconnect() {
 
     runtime.block_on(test);
 }
+
+#[test]
+fn test_ban_misbehaving_peer() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut runtime = ::tokio::runtime::Runtime::new().unwrap();
+
+    let ids = ordered_peer_ids(2);
+    let (mut peer_manager, _request_tx, _connection_reqs_tx, _hello_rx, mut conn_status_rx) =
+        build_test_peer_manager(runtime.handle().clone(), ids[1]);
+
+    let test = async move {
+        let (outbound, _inbound) = build_test_connection();
+        peer_manager.add_peer(create_connection(
+            outbound,
+            ids[0],
+            NetworkAddress::mock(),
+            ConnectionOrigin::Outbound,
+            ConnectionId::from(0),
+        ));
+        let conn_notif = conn_status_rx.next().await.unwrap();
+        assert!(matches!(
+            conn_notif,
+            ConnectionNotification::NewPeer(_, _, _, _)
+        ));
+
+        // The peer misbehaves until it gets banned, which closes the connection.
+        for _ in 0..2 {
+            peer_manager
+                .handle_connection_request(ConnectionRequest::ReportMisbehavior(
+                    ids[0],
+                    Misbehavior::InvalidSignature,
+                ))
+                .await;
+        }
+        assert_peer_disconnected_event(
+            ids[0],
+            ConnectionOrigin::Outbound,
+            DisconnectReason::Requested,
+            &mut peer_manager,
+        )
+        .await;
+        let conn_notif = conn_status_rx.next().await.unwrap();
+        assert!(matches!(
+            conn_notif,
+            ConnectionNotification::LostPeer(_, _, _, _)
+        ));
+
+        // Connections with the banned peer are refused.
+        let (_outbound, inbound) = build_test_connection();
+        peer_manager.add_peer(create_connection(
+            inbound,
+            ids[0],
+            NetworkAddress::mock(),
+            ConnectionOrigin::Inbound,
+            ConnectionId::from(1),
+        ));
+        assert!(!peer_manager.active_peers.contains_key(&ids[0]));
+
+        // The banned peer is not dialed.
+        let (dial_resp_tx, dial_resp_rx) = oneshot::channel();
+        peer_manager
+            .handle_connection_request(ConnectionRequest::DialPeer(
+                ids[0],
+                NetworkAddress::mock(),
+                dial_resp_tx,
+            ))
+            .await;
+        assert!(matches!(
+            dial_resp_rx.await.unwrap(),
+            Err(PeerManagerError::Banned(peer_id)) if peer_id == ids[0]
+        ));
+    };
+
+    runtime.block_on(test);
+}
+
+#[test]
+fn test_slow_peer_not_banned() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+    let mut runtime = ::tokio::runtime::Runtime::new().unwrap();
+
+    let ids = ordered_peer_ids(2);
+    let (mut peer_manager, _request_tx, _connection_reqs_tx, _hello_rx, mut conn_status_rx) =
+        build_test_peer_manager(runtime.handle().clone(), ids[1]);
+
+    let test = async move {
+        let (outbound, _inbound) = build_test_connection();
+        peer_manager.add_peer(create_connection(
+            outbound,
+            ids[0],
+            NetworkAddress::mock(),
+            ConnectionOrigin::Outbound,
+            ConnectionId::from(0),
+        ));
+        let conn_notif = conn_status_rx.next().await.unwrap();
+        assert!(matches!(
+            conn_notif,
+            ConnectionNotification::NewPeer(_, _, _, _)
+        ));
+
+        // An honest but slow peer keeps timing out, and stays connected.
+        for _ in 0..1000 {
+            peer_manager
+                .handle_connection_request(ConnectionRequest::ReportMisbehavior(
+                    ids[0],
+                    Misbehavior::RequestTimeout,
+                ))
+                .await;
+        }
+        assert!(peer_manager.active_peers.contains_key(&ids[0]));
+        assert!(!peer_manager.is_banned(&ids[0]));
+    };
+
+    runtime.block_on(test);
+}
@@ -7,7 +7,7 @@ pub use crate::protocols::rpc::error::RpcError;
 use crate::{
     error::NetworkError,
     peer_manager::{
-        ConnectionNotification, ConnectionRequestSender, PeerManagerNotification,
+        ConnectionNotification, ConnectionRequestSender, Misbehavior, PeerManagerNotification,
         PeerManagerRequestSender,
     },
     ProtocolId,
@@ -227,6 +227,18 @@ impl<TMessage> NetworkSender<TMessage> {
         self.connection_reqs_tx.disconnect_peer(peer).await?;
         Ok(())
     }
+
+    /// Report the misbehavior of a peer. Peers which keep misbehaving get disconnected and
+    /// temporarily banned.
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.connection_reqs_tx
+            .report_misbehavior(peer, misbehavior)?;
+        Ok(())
+    }
 }
 
 impl<TMessage: Message> NetworkSender<TMessage> {
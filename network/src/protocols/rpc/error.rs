@@ -47,6 +47,9 @@ pub enum RpcError {
 
     #[error("Rpc timed out")]
     TimedOut,
+
+    #[error("Rpc request dropped by the rate limits of the remote peer")]
+    RateLimited,
 }
 
 impl From<PeerManagerError> for RpcError {
@@ -15,7 +15,8 @@
 //! Internally, the RPC actor consists of a single event loop. The event loop processes 4 kinds of
 //! messages:
 //! (1) outbound RPC requests received from upstream,
-//! (2) notifications for inbound RpcRequest/RpcResponse from the Peer actor,
+//! (2) notifications for inbound RpcRequest/RpcResponse and rate limited RpcRequest errors from
+//! the Peer actor,
 //! (3) completion notification for tasks processing inbound RPC, and
 //! (4) completion notification for tasks processing outbound RPCs.
 //! The tasks for inbound and outbound RPCs are spawned onto the same runtime as the one driving
@@ -37,8 +38,8 @@
 //! -------------
 //! * For outbound RPCs, the RPC actors maintains a HashMap from the RequestId to a channel over
 //! which inbound responses can be delivered to the task driving the request. Entries are removed
-//! on completion of the task, which happens either on receipt of the response, or on
-//! failure/timeout.
+//! on completion of the task, which happens either on receipt of the response, on receipt of an
+//! error for a request the remote peer dropped because of its rate limits, or on failure/timeout.
 //! * The RPC actor also maintains a RequestIdGenerator for generating request ids for outbound
 //! RPCs. The RequestIdGenerator increments the request id by 1 for each subsequent outbound RPC.
 
@@ -50,7 +51,7 @@ use crate::{
     },
     peer::{PeerHandle, PeerNotification},
     protocols::wire::messaging::v1::{
-        NetworkMessage, Priority, RequestId, RpcRequest, RpcResponse,
+        ErrorCode, NetworkMessage, Priority, RateLimitedType, RequestId, RpcRequest, RpcResponse,
     },
     ProtocolId,
 };
@@ -181,7 +182,8 @@ pub struct Rpc {
     /// The timeout duration for inbound rpc calls.
     inbound_rpc_timeout: Duration,
     /// Channels to send Rpc responses to pending outbound RPC tasks.
-    pending_outbound_rpcs: HashMap<RequestId, (ProtocolId, oneshot::Sender<RpcResponse>)>,
+    pending_outbound_rpcs:
+        HashMap<RequestId, (ProtocolId, oneshot::Sender<Result<RpcResponse, RpcError>>)>,
     /// RequestId to use for next outbound RPC.
     request_id_gen: RequestIdGenerator,
     /// The maximum number of concurrent outbound rpc requests that we will
@@ -267,6 +269,13 @@ impl Rpc {
                     NetworkMessage::RpcRequest(request) => {
                         self.handle_inbound_request(request, inbound_rpc_tasks);
                     }
+                    // The remote peer dropped a pending outbound RPC because of its rate limits.
+                    NetworkMessage::Error(ErrorCode::RateLimited(RateLimitedType::RpcRequest(
+                        protocol,
+                        request_id,
+                    ))) => {
+                        self.handle_rate_limited_request(protocol, request_id);
+                    }
                     _ => {
                         error!("Received non-RPC message from Peer actor: {:?}", message);
                     }
@@ -290,7 +299,7 @@ impl Rpc {
                 "Waiting to notify outbound rpc task about inbound response for request_id {}",
                 request_id
             );
-            if let Err(e) = response_tx.send(response) {
+            if let Err(e) = response_tx.send(Ok(response)) {
                 warn!(
                     "Failed to handle inbount RPC response from peer: {} for protocol: {}. Error: {:?}",
                     peer_id.short_str(),
@@ -312,6 +321,31 @@ impl Rpc {
         }
     }
 
+    // Fails the pending outbound RPC dropped by the remote peer, so that the task waiting for its
+    // response doesn't have to wait for the timeout.
+    fn handle_rate_limited_request(&mut self, protocol: ProtocolId, request_id: RequestId) {
+        let peer_id = self.peer_handle.peer_id();
+        match self.pending_outbound_rpcs.remove(&request_id) {
+            Some((pending_protocol, response_tx)) if pending_protocol == protocol => {
+                warn!(
+                    "Peer {} dropped rpc request {} for protocol: {} because of its rate limits",
+                    peer_id.short_str(),
+                    request_id,
+                    protocol
+                );
+                let _ = response_tx.send(Err(RpcError::RateLimited));
+            }
+            Some(pending) => {
+                // Not the request the error is about, keep waiting for its response.
+                self.pending_outbound_rpcs.insert(request_id, pending);
+            }
+            None => info!(
+                "Received rate limit error for expired request from {}. Discarding.",
+                peer_id.short_str()
+            ),
+        }
+    }
+
     // Handle inbound request by spawning task (with timeout).
     fn handle_inbound_request(
         &mut self,
@@ -459,7 +493,7 @@ async fn handle_outbound_rpc_inner(
     request_id: RequestId,
     protocol: ProtocolId,
     req_data: Bytes,
-    response_rx: oneshot::Receiver<RpcResponse>,
+    response_rx: oneshot::Receiver<Result<RpcResponse, RpcError>>,
 ) -> Result<Bytes, RpcError> {
     let req_len = req_data.len();
     let peer_id = peer_handle.peer_id();
@@ -502,7 +536,7 @@ async fn handle_outbound_rpc_inner(
         request_id,
         peer_id_str
     );
-    let response = response_rx.await?;
+    let response = response_rx.await??;
     let latency = timer.stop_and_record();
     trace!(
         "Received response for request_id {} from peer: {:?} \
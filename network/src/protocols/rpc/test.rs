@@ -330,6 +330,56 @@ fn outbound_rpc_timeout() {
     rt.block_on(f);
 }
 
+// Test that outbound rpc calls fail without waiting for the timeout when the remote peer drops
+// the request because of its rate limits.
+#[test]
+#[serial]
+fn outbound_rpc_rate_limited() {
+    ::libra_logger::Logger::new().environment_only(true).init();
+
+    let mut rt = Runtime::new().unwrap();
+    let (mut rpc_requests_tx, _rpc_notifs_rx, mut peer_reqs_rx, mut peer_notifs_tx) =
+        start_rpc_actor(rt.handle().clone());
+
+    let protocol_id = RPC_PROTOCOL_A;
+    let req_data = Bytes::from_static(b"hello");
+    let message = create_network_request(
+        0, // This is the first request.
+        protocol_id,
+        req_data.clone(),
+    );
+
+    // Remote peer receives the request and answers with a rate limit error.
+    let f_mock_peer = async move {
+        expect_successful_send(&mut peer_reqs_rx, protocol_id, message).await;
+        let error = ErrorCode::RateLimited(RateLimitedType::RpcRequest(protocol_id, 0));
+        peer_notifs_tx
+            .send(PeerNotification::NewMessage(NetworkMessage::Error(error)))
+            .await
+            .unwrap();
+    };
+
+    let f_send_rpc = async move {
+        let (res_tx, res_rx) = oneshot::channel();
+        rpc_requests_tx
+            .send(OutboundRpcRequest {
+                protocol: protocol_id,
+                data: req_data,
+                res_tx,
+                timeout: Duration::from_secs(100),
+            })
+            .await
+            .unwrap();
+
+        // Check error is rate limited error
+        let result: Result<Bytes, RpcError> = res_rx.await.unwrap();
+        assert!(matches!(result, Err(RpcError::RateLimited)));
+    };
+
+    let f = join(f_mock_peer, f_send_rpc);
+    rt.block_on(f);
+}
+
 // Test that outbound rpcs can be canceled immediately after request.
 #[test]
 #[serial]
@@ -9,11 +9,17 @@
 //! supported messaging protocol versions to a bit vector representing application protocols
 //! supported over that messaging protocol. On receipt, both ends will determine the highest
 //! intersecting messaging protocol version and use that for the remainder of the session.
+//!
+//! Support for a compression algorithm is flagged by a bit of the application protocols, out of
+//! the range of `ProtocolId`s. Nodes unaware of compression never set such a bit, so it doesn't
+//! survive the intersection with their protocols and the `HandshakeMsg` stays readable by them. If
+//! both ends flag a common algorithm, the messages of the intersecting `COMPRESSED_PROTOCOLS` get
+//! compressed with the most preferred one.
 
 use libra_config::network_id::NetworkId;
 use libra_types::chain_id::ChainId;
 use serde::{export::Formatter, Deserialize, Serialize};
-use std::{collections::BTreeMap, convert::TryInto, fmt, iter::Iterator};
+use std::{collections::BTreeMap, convert::TryInto, fmt, iter::Iterator, str::FromStr};
 
 #[cfg(test)]
 mod test;
@@ -44,6 +50,23 @@ impl ProtocolId {
     }
 }
 
+impl FromStr for ProtocolId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        use ProtocolId::*;
+        Ok(match s {
+            "ConsensusRpc" => ConsensusRpc,
+            "ConsensusDirectSend" => ConsensusDirectSend,
+            "MempoolDirectSend" => MempoolDirectSend,
+            "StateSynchronizerDirectSend" => StateSynchronizerDirectSend,
+            "DiscoveryDirectSend" => DiscoveryDirectSend,
+            "HealthCheckerRpc" => HealthCheckerRpc,
+            _ => anyhow::bail!("Unknown protocol: {}", s),
+        })
+    }
+}
+
 impl fmt::Debug for ProtocolId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self)
@@ -56,6 +79,17 @@ impl fmt::Display for ProtocolId {
     }
 }
 
+/// Application protocols whose messages are large and compressible enough to be worth compressing:
+/// mempool broadcasts, state sync chunks and block retrievals.
+pub const COMPRESSED_PROTOCOLS: [ProtocolId; 3] = [
+    ProtocolId::ConsensusRpc,
+    ProtocolId::MempoolDirectSend,
+    ProtocolId::StateSynchronizerDirectSend,
+];
+
+/// The bits from this one on are reserved for the compression flags, `ProtocolId`s stay below.
+const FIRST_COMPRESSION_FLAG: u8 = 224;
+
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct SupportedProtocols(bitvec::BitVec);
 
@@ -110,6 +144,40 @@ impl fmt::Display for MessagingProtocolVersion {
     }
 }
 
+/// Enum representing the compression algorithms which can be applied to messages.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+pub enum CompressionAlgorithm {
+    Lz4 = 0,
+}
+
+impl CompressionAlgorithm {
+    /// All the algorithms, from most to least preferred.
+    const ALL: [CompressionAlgorithm; 1] = [CompressionAlgorithm::Lz4];
+
+    /// The bit of the application protocols flagging support for the algorithm.
+    fn flag(self) -> u8 {
+        u8::max_value() - self as u8
+    }
+}
+
+impl fmt::Debug for CompressionAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CompressionAlgorithm::Lz4 => "Lz4",
+            }
+        )
+    }
+}
+
 impl TryInto<Vec<ProtocolId>> for SupportedProtocols {
     type Error = lcs::Error;
 
@@ -137,9 +205,22 @@ impl<'a, T: Iterator<Item = &'a ProtocolId>> From<T> for SupportedProtocols {
 
 impl SupportedProtocols {
     /// Returns a new SupportedProtocols struct that is an intersection.
-    fn intersection(self, other: SupportedProtocols) -> SupportedProtocols {
+    pub fn intersection(self, other: SupportedProtocols) -> SupportedProtocols {
         SupportedProtocols(self.0 & other.0)
     }
+
+    pub fn contains(&self, protocol: ProtocolId) -> bool {
+        self.0.is_set(protocol as u8)
+    }
+
+    /// Returns the application protocols, leaving out the compression flags.
+    fn without_flags(&self) -> SupportedProtocols {
+        let mut bv = bitvec::BitVec::default();
+        (0..FIRST_COMPRESSION_FLAG)
+            .filter(|i| self.0.is_set(*i))
+            .for_each(|i| bv.set(i));
+        Self(bv)
+    }
 }
 
 impl HandshakeMsg {
@@ -160,6 +241,13 @@ impl HandshakeMsg {
             .insert(messaging_protocol, application_protocols);
     }
 
+    /// Flags support for the compression algorithm over every messaging protocol added so far.
+    pub fn add_compression(&mut self, algorithm: CompressionAlgorithm) {
+        self.supported_protocols
+            .values_mut()
+            .for_each(|protocols| protocols.0.set(algorithm.flag()));
+    }
+
     pub fn verify(&self, other: &HandshakeMsg) -> bool {
         self.chain_id == other.chain_id && self.network_id == other.network_id
     }
@@ -167,6 +255,34 @@ impl HandshakeMsg {
     pub fn find_common_protocols(
         &self,
         other: &HandshakeMsg,
+    ) -> Option<(MessagingProtocolVersion, SupportedProtocols)> {
+        self.find_common_protocols_and_flags(other)
+            .map(|(version, protocols)| (version, protocols.without_flags()))
+    }
+
+    /// Finds the most preferred compression algorithm supported by both nodes over the highest
+    /// common messaging protocol version, along with the application protocols to compress.
+    pub fn find_common_compression(
+        &self,
+        other: &HandshakeMsg,
+    ) -> Option<(CompressionAlgorithm, SupportedProtocols)> {
+        let (_, protocols) = self.find_common_protocols_and_flags(other)?;
+        CompressionAlgorithm::ALL
+            .iter()
+            .find(|algorithm| protocols.0.is_set(algorithm.flag()))
+            .map(|algorithm| {
+                (
+                    *algorithm,
+                    protocols
+                        .without_flags()
+                        .intersection(COMPRESSED_PROTOCOLS.iter().into()),
+                )
+            })
+    }
+
+    fn find_common_protocols_and_flags(
+        &self,
+        other: &HandshakeMsg,
     ) -> Option<(MessagingProtocolVersion, SupportedProtocols)> {
         // First, find the highest MessagingProtocolVersion supported by both nodes.
         let mut inner = other.supported_protocols.iter().rev().peekable();
@@ -11,6 +11,21 @@ fn net_protocol() -> lcs::Result<()> {
     Ok(())
 }
 
+#[test]
+fn protocol_id_from_str() {
+    for protocol in &[
+        ProtocolId::ConsensusRpc,
+        ProtocolId::ConsensusDirectSend,
+        ProtocolId::MempoolDirectSend,
+        ProtocolId::StateSynchronizerDirectSend,
+        ProtocolId::DiscoveryDirectSend,
+        ProtocolId::HealthCheckerRpc,
+    ] {
+        assert_eq!(ProtocolId::from_str(protocol.as_str()).unwrap(), *protocol);
+    }
+    ProtocolId::from_str("Unknown").unwrap_err();
+}
+
 #[test]
 fn protocols_to_from_vec() {
     let supported_protocols: SupportedProtocols =
@@ -91,3 +106,85 @@ fn common_protocols() {
         h1.find_common_protocols(&h2)
     );
 }
+
+#[test]
+fn common_compression() {
+    let network_id = NetworkId::default();
+    let chain_id = ChainId::default();
+
+    let mut h1 = HandshakeMsg::new(chain_id, network_id.clone());
+    h1.add(
+        MessagingProtocolVersion::V1,
+        [
+            ProtocolId::ConsensusDirectSend,
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::StateSynchronizerDirectSend,
+        ]
+        .iter()
+        .into(),
+    );
+    h1.add_compression(CompressionAlgorithm::Lz4);
+
+    // Case 1: Only the intersecting protocols among the compressed ones get compressed, and the
+    // compression flag is not mistaken for an application protocol.
+    let mut h2 = HandshakeMsg::new(chain_id, network_id.clone());
+    h2.add(
+        MessagingProtocolVersion::V1,
+        [
+            ProtocolId::ConsensusDirectSend,
+            ProtocolId::MempoolDirectSend,
+        ]
+        .iter()
+        .into(),
+    );
+    h2.add_compression(CompressionAlgorithm::Lz4);
+    let (algorithm, protocols) = h1.find_common_compression(&h2).unwrap();
+    assert_eq!(algorithm, CompressionAlgorithm::Lz4);
+    assert_eq!(protocols, [ProtocolId::MempoolDirectSend].iter().into());
+    assert_eq!(
+        Some((
+            MessagingProtocolVersion::V1,
+            [
+                ProtocolId::ConsensusDirectSend,
+                ProtocolId::MempoolDirectSend
+            ]
+            .iter()
+            .into()
+        )),
+        h1.find_common_protocols(&h2)
+    );
+
+    // Case 2: No compression is used with a node which doesn't support any.
+    let mut h2 = HandshakeMsg::new(chain_id, network_id);
+    h2.add(
+        MessagingProtocolVersion::V1,
+        [ProtocolId::MempoolDirectSend].iter().into(),
+    );
+    assert_eq!(None, h1.find_common_compression(&h2));
+    assert_eq!(None, h2.find_common_compression(&h1));
+}
+
+// Nodes unaware of compression read the handshake of nodes supporting it, as the original
+// HandshakeMsg without the compression flag.
+#[test]
+fn compression_is_backward_compatible() {
+    #[derive(Deserialize, Serialize)]
+    struct OriginalHandshakeMsg {
+        supported_protocols: BTreeMap<MessagingProtocolVersion, SupportedProtocols>,
+        chain_id: ChainId,
+        network_id: NetworkId,
+    }
+
+    let mut handshake = HandshakeMsg::new(ChainId::default(), NetworkId::default());
+    handshake.add(
+        MessagingProtocolVersion::V1,
+        [ProtocolId::MempoolDirectSend].iter().into(),
+    );
+    handshake.add_compression(CompressionAlgorithm::Lz4);
+    let original: OriginalHandshakeMsg =
+        lcs::from_bytes(&lcs::to_bytes(&handshake).unwrap()).unwrap();
+    let protocols = original.supported_protocols[&MessagingProtocolVersion::V1]
+        .clone()
+        .intersection([ProtocolId::MempoolDirectSend].iter().into());
+    assert_eq!(protocols, [ProtocolId::MempoolDirectSend].iter().into());
+}
@@ -0,0 +1,132 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the compression of the messages of a connection, once negotiated
+//! during the handshake.
+//!
+//! On a connection with compression, every frame starts with a byte flagging whether the
+//! serialized `NetworkMessage` which follows is compressed. Only the messages of the negotiated
+//! application protocols get compressed, and only when that actually makes them smaller. The
+//! header of a compressed message, i.e., its first `HEADER_LEN` bytes, is left uncompressed so
+//! that the message can be told apart without decompressing it. LZ4 compressed bytes are
+//! prefixed with their uncompressed size, as a little-endian `u32`.
+
+use crate::{
+    counters,
+    protocols::wire::{
+        handshake::v1::{CompressionAlgorithm, SupportedProtocols},
+        messaging::v1::HEADER_LEN,
+    },
+    ProtocolId,
+};
+use std::{convert::TryInto, io};
+
+/// Messages smaller than this are not worth compressing.
+const MIN_COMPRESSION_SIZE: usize = 1024;
+
+const UNCOMPRESSED_FLAG: u8 = 0;
+const COMPRESSED_FLAG: u8 = 1;
+
+#[derive(Clone, Debug)]
+pub struct MessageCompression {
+    algorithm: CompressionAlgorithm,
+    compressed_protocols: SupportedProtocols,
+    max_message_size: usize,
+}
+
+impl MessageCompression {
+    pub fn new(
+        algorithm: CompressionAlgorithm,
+        compressed_protocols: SupportedProtocols,
+        max_message_size: usize,
+    ) -> Self {
+        Self {
+            algorithm,
+            compressed_protocols,
+            max_message_size,
+        }
+    }
+
+    /// Builds the frame of a serialized message sent for the given application protocol.
+    pub fn compress(&self, message: &[u8], protocol: Option<ProtocolId>) -> io::Result<Vec<u8>> {
+        let should_compress = message.len() >= MIN_COMPRESSION_SIZE
+            && protocol.map_or(false, |protocol| {
+                self.compressed_protocols.contains(protocol)
+            });
+        if should_compress {
+            let (header, body) = message.split_at(HEADER_LEN);
+            let compressed = match self.algorithm {
+                CompressionAlgorithm::Lz4 => lz4::block::compress(body, None, true)?,
+            };
+            if compressed.len() < body.len() {
+                record_compression("sent", body.len(), compressed.len());
+                let mut frame = frame(COMPRESSED_FLAG, header);
+                frame.extend_from_slice(&compressed);
+                return Ok(frame);
+            }
+        }
+        Ok(frame(UNCOMPRESSED_FLAG, message))
+    }
+
+    /// Returns the leading bytes of the serialized message out of a frame, without decompressing
+    /// it, see `MessageHeader`.
+    pub fn header<'a>(&self, frame: &'a [u8]) -> &'a [u8] {
+        let header = frame.get(1..).unwrap_or_default();
+        &header[..header.len().min(HEADER_LEN)]
+    }
+
+    /// Extracts the serialized message out of a frame.
+    pub fn decompress(&self, frame: &[u8]) -> io::Result<Vec<u8>> {
+        match frame.split_first() {
+            Some((&UNCOMPRESSED_FLAG, message)) => Ok(message.to_vec()),
+            Some((&COMPRESSED_FLAG, message)) => {
+                if message.len() < HEADER_LEN {
+                    return Err(invalid_data("Truncated compressed message"));
+                }
+                let (header, compressed) = message.split_at(HEADER_LEN);
+                let body = match self.algorithm {
+                    CompressionAlgorithm::Lz4 => {
+                        // Don't trust the peer with the size of the buffer to allocate
+                        let size = compressed
+                            .get(..4)
+                            .map(|size| u32::from_le_bytes(size.try_into().unwrap()) as usize)
+                            .ok_or_else(|| invalid_data("Truncated compressed message"))?;
+                        if size > self.max_message_size.saturating_sub(HEADER_LEN) {
+                            return Err(invalid_data(format!(
+                                "Compressed message too large: {} bytes",
+                                size
+                            )));
+                        }
+                        lz4::block::decompress(compressed, None)?
+                    }
+                };
+                record_compression("received", body.len(), compressed.len());
+                let mut message = header.to_vec();
+                message.extend_from_slice(&body);
+                Ok(message)
+            }
+            Some((flag, _)) => Err(invalid_data(format!("Unknown compression flag: {}", flag))),
+            None => Err(invalid_data("Empty frame")),
+        }
+    }
+}
+
+fn frame(flag: u8, message: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(message.len() + 1);
+    frame.push(flag);
+    frame.extend_from_slice(message);
+    frame
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn record_compression(direction: &str, uncompressed_size: usize, compressed_size: usize) {
+    counters::LIBRA_NETWORK_COMPRESSION_BYTES
+        .with_label_values(&[direction, "uncompressed"])
+        .inc_by(uncompressed_size as i64);
+    counters::LIBRA_NETWORK_COMPRESSION_BYTES
+        .with_label_values(&[direction, "compressed"])
+        .inc_by(compressed_size as i64);
+}
@@ -7,6 +7,7 @@
 use crate::protocols::wire::handshake::v1::ProtocolId;
 use serde::{Deserialize, Serialize};
 
+pub mod compression;
 #[cfg(test)]
 mod test;
 
@@ -25,6 +26,8 @@ pub enum ErrorCode {
     ParsingError(ParsingErrorType),
     /// A message was received for a protocol that is not supported over this connection.
     NotSupported(NotSupportedType),
+    /// A message was dropped as the sender went over its rate limits.
+    RateLimited(RateLimitedType),
 }
 
 impl ErrorCode {
@@ -49,6 +52,12 @@ pub enum NotSupportedType {
     DirectSendMsg(ProtocolId),
 }
 
+/// Flags a dropped network message, so that the sender doesn't wait for a response to it.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum RateLimitedType {
+    RpcRequest(ProtocolId, RequestId),
+}
+
 /// Create alias RequestId for u32.
 pub type RequestId = u32;
 
@@ -90,3 +99,36 @@ pub struct DirectSendMsg {
     #[serde(with = "serde_bytes")]
     pub raw_msg: Vec<u8>,
 }
+
+/// Number of leading bytes of a serialized `NetworkMessage` covering its type, its protocol and,
+/// for an `RpcRequest`, its request id.
+pub const HEADER_LEN: usize = 6;
+
+/// What the leading bytes of a serialized `NetworkMessage` tell about it, before it gets parsed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageHeader {
+    /// An `Error` or an `RpcResponse`, answering a message of this end.
+    Reply,
+    RpcRequest(ProtocolId, RequestId),
+    /// Any other message, along with its protocol if known.
+    Other(Option<ProtocolId>),
+}
+
+impl MessageHeader {
+    /// Reads the header out of the leading bytes of a serialized message.
+    pub fn parse(header: &[u8]) -> Self {
+        let protocol = |protocol: &u8| lcs::from_bytes(&[*protocol]).ok();
+        // The first byte is the index of the variant of `NetworkMessage`.
+        match header {
+            [0, ..] | [2, ..] => MessageHeader::Reply,
+            [1, protocol_id, a, b, c, d, ..] => match protocol(protocol_id) {
+                Some(protocol) => {
+                    MessageHeader::RpcRequest(protocol, u32::from_le_bytes([*a, *b, *c, *d]))
+                }
+                None => MessageHeader::Other(None),
+            },
+            [3, protocol_id, ..] => MessageHeader::Other(protocol(protocol_id)),
+            _ => MessageHeader::Other(None),
+        }
+    }
+}
@@ -40,3 +40,80 @@ fn rpc_request() -> lcs::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn message_header() {
+    let request = NetworkMessage::RpcRequest(RpcRequest {
+        request_id: 25,
+        protocol_id: ProtocolId::ConsensusRpc,
+        priority: 0,
+        raw_request: vec![],
+    });
+    let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::MempoolDirectSend,
+        priority: 0,
+        raw_msg: vec![],
+    });
+    let response = NetworkMessage::RpcResponse(RpcResponse {
+        request_id: 25,
+        priority: 0,
+        raw_response: vec![],
+    });
+    let error = NetworkMessage::Error(ErrorCode::parsing_error(9, 5));
+    let header = |message: NetworkMessage| MessageHeader::parse(&lcs::to_bytes(&message).unwrap());
+
+    assert_eq!(
+        header(request),
+        MessageHeader::RpcRequest(ProtocolId::ConsensusRpc, 25)
+    );
+    assert_eq!(
+        header(message),
+        MessageHeader::Other(Some(ProtocolId::MempoolDirectSend))
+    );
+    assert_eq!(header(response), MessageHeader::Reply);
+    assert_eq!(header(error), MessageHeader::Reply);
+    assert_eq!(MessageHeader::parse(&[3, 200]), MessageHeader::Other(None));
+    assert_eq!(MessageHeader::parse(&[]), MessageHeader::Other(None));
+}
+
+#[test]
+fn compression() {
+    use super::compression::MessageCompression;
+    use crate::protocols::wire::handshake::v1::CompressionAlgorithm;
+
+    let compression = MessageCompression::new(
+        CompressionAlgorithm::Lz4,
+        [ProtocolId::MempoolDirectSend].iter().into(),
+        64 * 1024,
+    );
+    let large_message = vec![7; 16 * 1024];
+    let small_message = vec![7; 16];
+
+    // Only large messages of the compressed protocols get compressed
+    let frame = compression
+        .compress(&large_message, Some(ProtocolId::MempoolDirectSend))
+        .unwrap();
+    assert!(frame.len() < large_message.len());
+    assert_eq!(compression.decompress(&frame).unwrap(), large_message);
+    // The header is left uncompressed
+    assert_eq!(compression.header(&frame), &large_message[..HEADER_LEN]);
+    for (message, protocol) in &[
+        (&large_message, Some(ProtocolId::ConsensusDirectSend)),
+        (&large_message, None),
+        (&small_message, Some(ProtocolId::MempoolDirectSend)),
+    ] {
+        let frame = compression.compress(message, *protocol).unwrap();
+        assert_eq!(frame.len(), message.len() + 1);
+        assert_eq!(&compression.decompress(&frame).unwrap(), *message);
+    }
+
+    // Messages decompressing past the maximum size are rejected
+    let compression = MessageCompression::new(
+        CompressionAlgorithm::Lz4,
+        [ProtocolId::MempoolDirectSend].iter().into(),
+        1024,
+    );
+    compression.decompress(&frame).unwrap_err();
+    compression.decompress(&[]).unwrap_err();
+    compression.decompress(&[2, 0, 0]).unwrap_err();
+}
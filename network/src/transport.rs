@@ -6,7 +6,9 @@ use crate::{
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
         identity::exchange_handshake,
-        wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, SupportedProtocols},
+        wire::handshake::v1::{
+            CompressionAlgorithm, HandshakeMsg, MessagingProtocolVersion, SupportedProtocols,
+        },
     },
 };
 use futures::{
@@ -42,6 +44,9 @@ pub const TRANSPORT_TIMEOUT: Duration = Duration::from_secs(30);
 /// TODO: Add ability to support more than one messaging protocol.
 pub const SUPPORTED_MESSAGING_PROTOCOL: MessagingProtocolVersion = MessagingProtocolVersion::V1;
 
+/// Currently supported compression algorithm.
+pub const SUPPORTED_COMPRESSION: CompressionAlgorithm = CompressionAlgorithm::Lz4;
+
 /// Global connection-id generator.
 static CONNECTION_ID_GENERATOR: ConnectionIdGenerator = ConnectionIdGenerator::new();
 
@@ -98,6 +103,7 @@ pub struct ConnectionMetadata {
     origin: ConnectionOrigin,
     messaging_protocol: MessagingProtocolVersion,
     application_protocols: SupportedProtocols,
+    compression: Option<(CompressionAlgorithm, SupportedProtocols)>,
 }
 
 impl ConnectionMetadata {
@@ -108,6 +114,7 @@ impl ConnectionMetadata {
         origin: ConnectionOrigin,
         messaging_protocol: MessagingProtocolVersion,
         application_protocols: SupportedProtocols,
+        compression: Option<(CompressionAlgorithm, SupportedProtocols)>,
     ) -> ConnectionMetadata {
         ConnectionMetadata {
             peer_id,
@@ -116,6 +123,7 @@ impl ConnectionMetadata {
             origin,
             messaging_protocol,
             application_protocols,
+            compression,
         }
     }
 
@@ -134,6 +142,12 @@ impl ConnectionMetadata {
     pub fn origin(&self) -> ConnectionOrigin {
         self.origin
     }
+
+    /// The compression algorithm negotiated for the connection, along with the application
+    /// protocols whose messages get compressed.
+    pub fn compression(&self) -> Option<&(CompressionAlgorithm, SupportedProtocols)> {
+        self.compression.as_ref()
+    }
 }
 
 impl std::fmt::Debug for ConnectionMetadata {
@@ -146,12 +160,13 @@ impl std::fmt::Display for ConnectionMetadata {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[{},{},{},{},{:?}]",
+            "[{},{},{},{},{:?},{:?}]",
             self.peer_id,
             self.addr,
             self.origin,
             self.messaging_protocol,
-            self.application_protocols
+            self.application_protocols,
+            self.compression
         )
     }
 }
@@ -184,6 +199,7 @@ pub async fn perform_handshake<T: TSocket>(
     }
 
     let intersecting_protocols = own_handshake.find_common_protocols(&remote_handshake);
+    let compression = own_handshake.find_common_compression(&remote_handshake);
     match intersecting_protocols {
         None => {
             info!(
@@ -205,6 +221,7 @@ pub async fn perform_handshake<T: TSocket>(
                 origin,
                 messaging_protocol,
                 application_protocols,
+                compression,
             ),
         }),
     }
@@ -319,6 +336,7 @@ where
     ) -> Self {
         let mut own_handshake = HandshakeMsg::new(chain_id, network_context.network_id().clone());
         own_handshake.add(SUPPORTED_MESSAGING_PROTOCOL, application_protocols);
+        own_handshake.add_compression(SUPPORTED_COMPRESSION);
         let identity_pubkey = identity_key.public_key();
 
         let auth_mode = match trusted_peers.as_ref() {
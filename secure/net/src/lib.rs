@@ -14,27 +14,65 @@
 //!
 //! Internally both the client and server leverage a NetworkStream that communications in blocks
 //! where a block is a length prefixed array of bytes.
+//!
+//! Optionally the client and server mutually authenticate with the Noise IK handshake upon
+//! connecting, every block is then encrypted. As a Noise message is limited to 64 KiB, a block
+//! is sent as several Noise messages: first its length, then its data split in chunks.
 
+use libra_crypto::{
+    noise::{self, NoiseConfig, NoiseError, NoiseSession},
+    x25519,
+};
 use libra_logger::{debug, trace};
 use std::{
     io::{Read, Write},
+    iter,
     net::{Shutdown, SocketAddr, TcpListener, TcpStream},
     thread, time,
 };
 use thiserror::Error;
 
+/// Prologue of the Noise handshake, binding the handshake to this protocol.
+const NOISE_PROLOGUE: &[u8] = b"libra-secure-net";
+
+/// Largest chunk of a block fitting in a single Noise message.
+const MAX_NOISE_CHUNK_SIZE: usize = noise::MAX_SIZE_NOISE_MSG - noise::AES_GCM_TAGLEN;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Already called shutdown")]
     AlreadyShutdown,
     #[error("Found data that is too large to decode: {0}")]
     DataTooLarge(usize),
+    #[error("Found a malformed encrypted message")]
+    MalformedMessage,
     #[error("Internal network error:")]
     NetworkError(#[from] std::io::Error),
     #[error("No active stream")]
     NoActiveStream,
+    #[error("Noise error: {0}")]
+    NoiseError(#[from] NoiseError),
     #[error("Remote stream cleanly closed")]
     RemoteStreamClosed,
+    #[error("Remote peer authenticated with an unexpected key: {0}")]
+    UnexpectedPeerKey(x25519::PublicKey),
+}
+
+/// Keys used to mutually authenticate and encrypt a connection with the Noise IK handshake.
+struct NoiseKeys {
+    /// Holds the static key of this end of the connection.
+    config: NoiseConfig,
+    /// The static public key the remote end has to authenticate with.
+    remote_public_key: x25519::PublicKey,
+}
+
+impl NoiseKeys {
+    fn new(identity_key: x25519::PrivateKey, remote_public_key: x25519::PublicKey) -> Self {
+        Self {
+            config: NoiseConfig::new(identity_key),
+            remote_public_key,
+        }
+    }
 }
 
 pub struct NetworkClient {
@@ -42,6 +80,7 @@ pub struct NetworkClient {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    noise: Option<NoiseKeys>,
 }
 
 impl NetworkClient {
@@ -50,9 +89,21 @@ impl NetworkClient {
             server,
             stream: None,
             timeout_ms,
+            noise: None,
         }
     }
 
+    /// Authenticates as `identity_key` to the server, which must authenticate as
+    /// `server_public_key`, and encrypts all the messages.
+    pub fn with_noise(
+        mut self,
+        identity_key: x25519::PrivateKey,
+        server_public_key: x25519::PublicKey,
+    ) -> Self {
+        self.noise = Some(NoiseKeys::new(identity_key, server_public_key));
+        self
+    }
+
     /// Blocking read until able to successfully read an entire message
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
         let stream = self.server()?;
@@ -98,7 +149,11 @@ impl NetworkClient {
 
             let stream = stream?;
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.timeout_ms);
+            if let Some(noise) = &self.noise {
+                stream.initiate_noise_handshake(noise)?;
+            }
+            self.stream = Some(stream);
             debug!("Connection established to upstream {}", self.server);
         }
 
@@ -111,6 +166,7 @@ pub struct NetworkServer {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    noise: Option<NoiseKeys>,
 }
 
 impl NetworkServer {
@@ -120,9 +176,21 @@ impl NetworkServer {
             listener: Some(listener.unwrap()),
             stream: None,
             timeout_ms,
+            noise: None,
         }
     }
 
+    /// Authenticates as `identity_key` to the clients, only accepts the client authenticating as
+    /// `client_public_key` and encrypts all the messages.
+    pub fn with_noise(
+        mut self,
+        identity_key: x25519::PrivateKey,
+        client_public_key: x25519::PublicKey,
+    ) -> Self {
+        self.noise = Some(NoiseKeys::new(identity_key, client_public_key));
+        self
+    }
+
     /// If there isn't already a downstream client, it accepts. Otherwise it
     /// blocks until able to successfully read an entire message
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
@@ -164,9 +232,13 @@ impl NetworkServer {
                 .as_mut()
                 .ok_or_else(|| Error::AlreadyShutdown)?;
             let (stream, stream_addr) = listener.accept()?;
-            debug!("Connection established with downstream {}", stream_addr);
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.timeout_ms);
+            if let Some(noise) = &self.noise {
+                stream.respond_noise_handshake(noise)?;
+            }
+            debug!("Connection established with downstream {}", stream_addr);
+            self.stream = Some(stream);
         }
 
         self.stream.as_mut().ok_or_else(|| Error::NoActiveStream)
@@ -177,6 +249,8 @@ struct NetworkStream {
     stream: TcpStream,
     buffer: Vec<u8>,
     temp_buffer: [u8; 1024],
+    /// Encrypts the blocks once the Noise handshake completed.
+    session: Option<NoiseSession>,
 }
 
 impl NetworkStream {
@@ -190,11 +264,86 @@ impl NetworkStream {
             stream,
             buffer: Vec::new(),
             temp_buffer: [0; 1024],
+            session: None,
+        }
+    }
+
+    /// Performs the initiator side of the Noise IK handshake, the remote end must be the server.
+    fn initiate_noise_handshake(&mut self, noise: &NoiseKeys) -> Result<(), Error> {
+        let mut rng = rand::rngs::OsRng;
+        let mut init_message = vec![0; noise::handshake_init_msg_len(0)];
+        let handshake_state = noise.config.initiate_connection(
+            &mut rng,
+            NOISE_PROLOGUE,
+            noise.remote_public_key,
+            None,
+            &mut init_message,
+        )?;
+        self.write_frame(&init_message)?;
+        let response = self.read_frame()?;
+        let (_, session) = noise
+            .config
+            .finalize_connection(handshake_state, &response)?;
+        self.session = Some(session);
+        Ok(())
+    }
+
+    /// Performs the responder side of the Noise IK handshake, rejecting any client but the
+    /// expected one.
+    fn respond_noise_handshake(&mut self, noise: &NoiseKeys) -> Result<(), Error> {
+        let init_message = self.read_frame()?;
+        let (client_public_key, handshake_state, _) = noise
+            .config
+            .parse_client_init_message(NOISE_PROLOGUE, &init_message)?;
+        if client_public_key != noise.remote_public_key {
+            return Err(Error::UnexpectedPeerKey(client_public_key));
         }
+        let mut rng = rand::rngs::OsRng;
+        let mut response = vec![0; noise::handshake_resp_msg_len(0)];
+        let session =
+            noise
+                .config
+                .respond_to_client(&mut rng, handshake_state, None, &mut response)?;
+        self.write_frame(&response)?;
+        self.session = Some(session);
+        Ok(())
     }
 
     /// Blocking read until able to successfully read an entire message
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        if self.session.is_none() {
+            return self.read_frame();
+        }
+
+        let length = self.read_encrypted_frame()?;
+        if length.len() != 4 {
+            return Err(Error::MalformedMessage);
+        }
+        let mut u32_bytes = [0; 4];
+        u32_bytes.copy_from_slice(&length);
+        let data_size = u32::from_le_bytes(u32_bytes) as usize;
+
+        let mut data = Vec::with_capacity(data_size);
+        while data.len() < data_size {
+            data.extend(self.read_encrypted_frame()?);
+        }
+        if data.len() != data_size {
+            return Err(Error::MalformedMessage);
+        }
+        Ok(data)
+    }
+
+    /// Blocking read of a frame of the stream, decrypted by the Noise session
+    fn read_encrypted_frame(&mut self) -> Result<Vec<u8>, Error> {
+        let mut frame = self.read_frame()?;
+        let session = self.session.as_mut().ok_or(Error::NoActiveStream)?;
+        let plaintext_len = session.read_message_in_place(&mut frame)?.len();
+        frame.truncate(plaintext_len);
+        Ok(frame)
+    }
+
+    /// Blocking read until able to successfully read an entire frame
+    fn read_frame(&mut self) -> Result<Vec<u8>, Error> {
         let result = self.read_buffer();
         if !result.is_empty() {
             return Ok(result);
@@ -224,6 +373,33 @@ impl NetworkStream {
 
     /// Blocking write until able to successfully send an entire message
     pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        let session = match self.session.as_mut() {
+            Some(session) => session,
+            None => return self.write_frame(data),
+        };
+
+        let u32_max = u32::max_value() as usize;
+        if u32_max <= data.len() {
+            return Err(Error::DataTooLarge(data.len()));
+        }
+        let data_len = (data.len() as u32).to_le_bytes();
+        let frames = iter::once(&data_len[..])
+            .chain(data.chunks(MAX_NOISE_CHUNK_SIZE))
+            .map(|chunk| {
+                let mut frame = chunk.to_vec();
+                let auth_tag = session.write_message_in_place(&mut frame)?;
+                frame.extend_from_slice(&auth_tag);
+                Ok(frame)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        for frame in frames {
+            self.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Blocking write until able to successfully send an entire frame
+    fn write_frame(&mut self, data: &[u8]) -> Result<(), Error> {
         let u32_max = u32::max_value() as usize;
         if u32_max <= data.len() {
             return Err(Error::DataTooLarge(data.len()));
@@ -281,6 +457,8 @@ impl NetworkStream {
 mod test {
     use super::*;
     use libra_config::utils;
+    use libra_crypto::Uniform;
+    use rand::{rngs::StdRng, SeedableRng};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     /// Read, Write, Connect timeout in milliseconds.
@@ -419,4 +597,59 @@ mod test {
         let result2 = server2.read().unwrap();
         assert_eq!(data2, result2);
     }
+
+    #[test]
+    fn test_noise_ping() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let client_public_key = client_key.public_key();
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server =
+            NetworkServer::new(server_addr, TIMEOUT).with_noise(server_key, client_public_key);
+        let mut client =
+            NetworkClient::new(server_addr, TIMEOUT).with_noise(client_key, server_public_key);
+
+        // The handshake needs the server to be reading, so it runs apart and echoes the messages
+        let server_thread = thread::spawn(move || {
+            for _ in 0..2 {
+                let data = server.read().unwrap();
+                server.write(&data).unwrap();
+            }
+        });
+
+        // The second message doesn't fit in a single Noise message
+        for data in &[vec![0, 1, 2, 3], vec![4; 3 * noise::MAX_SIZE_NOISE_MSG]] {
+            client.write(data).unwrap();
+            let result = client.read().unwrap();
+            assert_eq!(data, &result);
+        }
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_noise_unexpected_client() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let expected_client_public_key = x25519::PrivateKey::generate(&mut rng).public_key();
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server = NetworkServer::new(server_addr, TIMEOUT)
+            .with_noise(server_key, expected_client_public_key);
+        let mut client =
+            NetworkClient::new(server_addr, TIMEOUT).with_noise(client_key, server_public_key);
+
+        let server_thread = thread::spawn(move || server.read());
+
+        // The server drops the connection instead of completing the handshake
+        client.write(&[0, 1, 2, 3]).unwrap_err();
+        let result = server_thread.join().unwrap();
+        assert!(matches!(result, Err(Error::UnexpectedPeerKey(_))));
+    }
 }
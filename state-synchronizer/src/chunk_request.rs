@@ -79,15 +79,25 @@ pub struct GetChunkRequest {
     pub limit: u64,
     /// The target of the given request.
     target: TargetType,
+    /// Whether the response should carry the transaction outputs to be applied instead of the
+    /// transactions to be executed.
+    pub fetch_outputs: bool,
 }
 
 impl GetChunkRequest {
-    pub fn new(known_version: Version, current_epoch: u64, limit: u64, target: TargetType) -> Self {
+    pub fn new(
+        known_version: Version,
+        current_epoch: u64,
+        limit: u64,
+        target: TargetType,
+        fetch_outputs: bool,
+    ) -> Self {
         Self {
             known_version,
             current_epoch,
             limit,
             target,
+            fetch_outputs,
         }
     }
 
@@ -106,11 +116,12 @@ impl fmt::Display for GetChunkRequest {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "[ChunkRequest: known version: {}, epoch: {}, limit: {}, target: {:?}]",
+            "[ChunkRequest: known version: {}, epoch: {}, limit: {}, target: {:?}, outputs: {}]",
             self.known_version,
             self.current_epoch,
             self.limit,
             self.target(),
+            self.fetch_outputs,
         )
     }
 }
@@ -3,7 +3,7 @@
 
 use libra_types::{
     ledger_info::LedgerInfoWithSignatures,
-    transaction::{TransactionListWithProof, Version},
+    transaction::{Transaction, TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -49,6 +49,62 @@ impl ResponseLedgerInfo {
     }
 }
 
+/// The transactions carried on a chunk response, depending on whether the requester executes them
+/// or applies their outputs.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransactionChunk {
+    /// Transactions to be executed.
+    Transactions(TransactionListWithProof),
+    /// Transactions along with their outputs, to be applied without re-execution.
+    TransactionOutputs(TransactionOutputListWithProof),
+}
+
+impl TransactionChunk {
+    pub fn first_version(&self) -> Option<Version> {
+        match self {
+            TransactionChunk::Transactions(txn_list_with_proof) => {
+                txn_list_with_proof.first_transaction_version
+            }
+            TransactionChunk::TransactionOutputs(txn_output_list_with_proof) => {
+                txn_output_list_with_proof.first_transaction_output_version
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            TransactionChunk::Transactions(txn_list_with_proof) => txn_list_with_proof.len(),
+            TransactionChunk::TransactionOutputs(txn_output_list_with_proof) => {
+                txn_output_list_with_proof.len()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_outputs(&self) -> bool {
+        match self {
+            TransactionChunk::Transactions(_) => false,
+            TransactionChunk::TransactionOutputs(_) => true,
+        }
+    }
+
+    pub fn into_transactions(self) -> Vec<Transaction> {
+        match self {
+            TransactionChunk::Transactions(txn_list_with_proof) => txn_list_with_proof.transactions,
+            TransactionChunk::TransactionOutputs(txn_output_list_with_proof) => {
+                txn_output_list_with_proof
+                    .transactions_and_outputs
+                    .into_iter()
+                    .map(|(txn, _)| txn)
+                    .collect()
+            }
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
 /// The returned chunk is bounded by the end of the known_epoch of the requester
 /// (i.e., a chunk never crosses epoch boundaries).
@@ -57,18 +113,12 @@ pub struct GetChunkResponse {
     /// The specifics of ledger info verification depend on its type.
     pub response_li: ResponseLedgerInfo,
     /// chunk of transactions with proof corresponding to the ledger info carried by the response.
-    pub txn_list_with_proof: TransactionListWithProof,
+    pub chunk: TransactionChunk,
 }
 
 impl GetChunkResponse {
-    pub fn new(
-        response_li: ResponseLedgerInfo,
-        txn_list_with_proof: TransactionListWithProof,
-    ) -> Self {
-        Self {
-            response_li,
-            txn_list_with_proof,
-        }
+    pub fn new(response_li: ResponseLedgerInfo, chunk: TransactionChunk) -> Self {
+        Self { response_li, chunk }
     }
 }
 impl fmt::Debug for GetChunkResponse {
@@ -79,12 +129,17 @@ impl fmt::Debug for GetChunkResponse {
 
 impl fmt::Display for GetChunkResponse {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let txns_repr = match self.txn_list_with_proof.first_transaction_version {
+        let txns_repr = match self.chunk.first_version() {
             None => "empty".to_string(),
             Some(first_ver) => format!(
-                "versions [{} - {}]",
+                "{}versions [{} - {}]",
+                if self.chunk.is_outputs() {
+                    "outputs of "
+                } else {
+                    ""
+                },
                 first_ver,
-                first_ver - 1 + self.txn_list_with_proof.len() as u64
+                first_ver - 1 + self.chunk.len() as u64
             ),
         };
         let response_li_repr = match &self.response_li {
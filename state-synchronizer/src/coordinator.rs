@@ -3,11 +3,13 @@
 
 use crate::{
     chunk_request::{GetChunkRequest, TargetType},
-    chunk_response::{GetChunkResponse, ResponseLedgerInfo},
+    chunk_response::{GetChunkResponse, ResponseLedgerInfo, TransactionChunk},
     counters,
     executor_proxy::ExecutorProxyTrait,
     network::{StateSynchronizerEvents, StateSynchronizerMsg, StateSynchronizerSender},
     request_manager::{PeerScoreUpdateType, RequestManager},
+    snapshot_request::{GetAccountChunkRequest, GetStateSnapshotRequest},
+    snapshot_response::{GetAccountChunkResponse, GetStateSnapshotResponse},
     SynchronizerState,
 };
 use anyhow::{bail, ensure, format_err, Result};
@@ -20,11 +22,12 @@ use libra_config::{
     config::{PeerNetworkId, RoleType, StateSyncConfig, UpstreamConfig},
     network_id::NodeNetworkId,
 };
+use libra_crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
 use libra_logger::prelude::*;
 use libra_mempool::{CommitNotification, CommitResponse, CommittedTransaction};
 use libra_types::{
     contract_event::ContractEvent,
-    epoch_change::Verifier,
+    epoch_change::{EpochChangeProof, Verifier},
     ledger_info::LedgerInfoWithSignatures,
     transaction::{Transaction, TransactionListWithProof, Version},
     waypoint::Waypoint,
@@ -33,7 +36,7 @@ use network::protocols::network::Event;
 use std::{
     collections::{BTreeMap, HashMap},
     ops::Bound::Included,
-    time::{Duration, SystemTime},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tokio::time::{interval, timeout};
 
@@ -69,6 +72,7 @@ struct PendingRequestInfo {
     known_version: u64,
     request_epoch: u64,
     limit: u64,
+    fetch_outputs: bool,
 }
 
 // DS to help sync requester to keep track of ledger infos in the future
@@ -142,6 +146,28 @@ impl PendingLedgerInfos {
     }
 }
 
+/// Progress of fast sync: restoring the state snapshot of a peer instead of executing all the
+/// transactions before it.
+struct PendingStateSnapshot {
+    // Version of the state snapshot being restored, `None` until a state snapshot response has
+    // been verified.
+    version: Option<Version>,
+    // Key of the last account restored.
+    known_key: Option<HashValue>,
+    // Time of the last state snapshot or account chunk request, used for resending it on timeout.
+    last_request_time: SystemTime,
+}
+
+impl PendingStateSnapshot {
+    fn new() -> Self {
+        Self {
+            version: None,
+            known_key: None,
+            last_request_time: UNIX_EPOCH,
+        }
+    }
+}
+
 /// Coordination of synchronization process is driven by SyncCoordinator, which `start()` function
 /// runs an infinite event loop and triggers actions based on external / internal requests.
 /// The coordinator can work in two modes:
@@ -181,6 +207,8 @@ pub(crate) struct SyncCoordinator<T> {
     // queue of incoming long polling requests
     // peer will be notified about new chunk of transactions if it's available before expiry time
     subscriptions: HashMap<PeerNetworkId, PendingRequestInfo>,
+    // Fast sync progress. Chunks of transactions are only requested once it is `None`.
+    state_snapshot: Option<PendingStateSnapshot>,
     executor_proxy: T,
 }
 
@@ -201,6 +229,13 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             RoleType::Validator => 2 * config.tick_interval_ms,
         };
         let multicast_timeout = Duration::from_millis(config.multicast_timeout_ms);
+        // A state snapshot can only be restored into an empty ledger.
+        let state_snapshot =
+            if config.enable_fast_sync && initial_state.highest_version_in_local_storage() == 0 {
+                Some(PendingStateSnapshot::new())
+            } else {
+                None
+            };
 
         Self {
             client_events,
@@ -220,6 +255,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             subscriptions: HashMap::new(),
             sync_request: None,
             initialization_listener: None,
+            state_snapshot,
             executor_proxy,
         }
     }
@@ -348,6 +384,72 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                         .inc();
                 }
             }
+            StateSynchronizerMsg::GetStateSnapshotRequest(request) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        counters::STATE_SNAPSHOT_REQUEST_MSG_LABEL,
+                        &peer.peer_id().to_string(),
+                    ])
+                    .start_timer();
+                if let Err(err) = self.process_state_snapshot_request(peer.clone(), *request) {
+                    error!(
+                        "[state sync] failed to serve state snapshot request from {:?}: {}",
+                        peer, err
+                    );
+                }
+            }
+            StateSynchronizerMsg::GetStateSnapshotResponse(response) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        counters::STATE_SNAPSHOT_RESPONSE_MSG_LABEL,
+                        &peer.peer_id().to_string(),
+                    ])
+                    .start_timer();
+                if let Err(err) = self.process_state_snapshot_response(&peer, *response) {
+                    error!(
+                        "[state sync] failed to apply state snapshot response from {:?}: {}",
+                        peer, err
+                    );
+                    counters::APPLY_ACCOUNT_CHUNK_FAILURE
+                        .with_label_values(&[&peer.peer_id().to_string()])
+                        .inc();
+                }
+            }
+            StateSynchronizerMsg::GetAccountChunkRequest(request) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        counters::ACCOUNT_CHUNK_REQUEST_MSG_LABEL,
+                        &peer.peer_id().to_string(),
+                    ])
+                    .start_timer();
+                if let Err(err) = self.process_account_chunk_request(peer.clone(), *request) {
+                    error!(
+                        "[state sync] failed to serve account chunk request from {:?}: {}",
+                        peer, err
+                    );
+                }
+            }
+            StateSynchronizerMsg::GetAccountChunkResponse(response) => {
+                let _timer = counters::PROCESS_MSG_LATENCY
+                    .with_label_values(&[
+                        counters::ACCOUNT_CHUNK_RESPONSE_MSG_LABEL,
+                        &peer.peer_id().to_string(),
+                    ])
+                    .start_timer();
+                if let Err(err) = self.process_account_chunk_response(&peer, *response).await {
+                    error!(
+                        "[state sync] failed to apply account chunk from {:?}: {}",
+                        peer, err
+                    );
+                    counters::APPLY_ACCOUNT_CHUNK_FAILURE
+                        .with_label_values(&[&peer.peer_id().to_string()])
+                        .inc();
+                } else {
+                    counters::APPLY_ACCOUNT_CHUNK_SUCCESS
+                        .with_label_values(&[&peer.peer_id().to_string()])
+                        .inc();
+                }
+            }
         }
     }
 
@@ -369,6 +471,12 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         Ok(())
     }
 
+    /// Full nodes that trust the quorum-signed transaction infos can apply the synced transaction
+    /// outputs instead of re-executing the transactions.
+    fn fetch_outputs(&self) -> bool {
+        self.role == RoleType::FullNode && self.config.apply_transaction_outputs
+    }
+
     /// In case waypoint is set verify that the local LI has reached the waypoint version.
     fn is_initialized(&self) -> bool {
         self.waypoint.version() <= self.local_state.highest_local_li.ledger_info().version()
@@ -621,6 +729,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             request.known_version,
             ResponseLedgerInfo::VerifiableLedgerInfo(response_li),
             limit,
+            request.fetch_outputs,
         )
     }
 
@@ -648,6 +757,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                     known_version: request.known_version,
                     request_epoch: request.current_epoch,
                     limit,
+                    fetch_outputs: request.fetch_outputs,
                 };
                 self.subscriptions.insert(peer, request_info);
             }
@@ -674,6 +784,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                 highest_li,
             },
             limit,
+            request.fetch_outputs,
         )
     }
 
@@ -720,6 +831,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                 end_of_epoch_li,
             },
             limit,
+            request.fetch_outputs,
         )
     }
 
@@ -727,25 +839,52 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     /// The chunk response contains transactions from the local storage with the proofs relative to
     /// the given target ledger info.
     /// In case target is None, the ledger info is set to the local highest ledger info.
+    /// If `fetch_outputs` is set, the transactions are sent along with their outputs when the local
+    /// storage keeps them, otherwise the transactions are sent alone and re-executed by the peer.
     fn deliver_chunk(
         &mut self,
         peer: PeerNetworkId,
         known_version: u64,
         response_li: ResponseLedgerInfo,
         limit: u64,
+        fetch_outputs: bool,
     ) -> Result<()> {
-        let txns = self
-            .executor_proxy
-            .get_chunk(known_version, limit, response_li.version())?;
-        let chunk_response = GetChunkResponse::new(response_li, txns);
+        let outputs = if fetch_outputs {
+            self.executor_proxy
+                .get_chunk_with_outputs(known_version, limit, response_li.version())
+                .map_err(|e| {
+                    debug!(
+                        "[state sync] Transaction outputs unavailable, sending transactions: {:?}",
+                        e
+                    )
+                })
+                .ok()
+        } else {
+            None
+        };
+        let chunk = match outputs {
+            Some(txn_output_list_with_proof) => {
+                TransactionChunk::TransactionOutputs(txn_output_list_with_proof)
+            }
+            None => TransactionChunk::Transactions(self.executor_proxy.get_chunk(
+                known_version,
+                limit,
+                response_li.version(),
+            )?),
+        };
+        let chunk_response = GetChunkResponse::new(response_li, chunk);
         let msg = StateSynchronizerMsg::GetChunkResponse(Box::new(chunk_response));
+        self.send_response(peer, msg);
+        Ok(())
+    }
 
+    fn send_response(&mut self, peer: PeerNetworkId, msg: StateSynchronizerMsg) {
         let network_sender = self
             .network_senders
             .get_mut(&peer.network_id())
             .expect("missing network sender");
         let send_result_label = if let Err(e) = network_sender.send_to(peer.peer_id(), msg) {
-            error!("[state sync] failed to deliver chunk: {:?}", e);
+            error!("[state sync] failed to deliver response: {:?}", e);
             counters::SEND_FAIL_LABEL
         } else {
             counters::SEND_SUCCESS_LABEL
@@ -753,7 +892,6 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         counters::RESPONSES_SENT
             .with_label_values(&[&peer.peer_id().to_string(), send_result_label])
             .inc();
-        Ok(())
     }
 
     /// The choice of the LedgerInfo in the response follows the following logic:
@@ -784,16 +922,27 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         response: GetChunkResponse,
     ) -> Result<()> {
         debug!("[state sync] Processing chunk response {}", response);
-        let txn_list_with_proof = response.txn_list_with_proof.clone();
+        ensure!(
+            self.state_snapshot.is_none(),
+            "[state sync] Chunk response from {:?} while restoring a state snapshot",
+            peer
+        );
+        let chunk = response.chunk.clone();
         let known_version = self.local_state.highest_version_in_local_storage();
-        let chunk_start_version =
-            txn_list_with_proof
-                .first_transaction_version
-                .ok_or_else(|| {
-                    self.request_manager
-                        .update_score(&peer, PeerScoreUpdateType::EmptyChunk);
-                    format_err!("[state sync] Empty chunk from {:?}", peer)
-                })?;
+        let chunk_start_version = chunk.first_version().ok_or_else(|| {
+            self.request_manager
+                .update_score(&peer, PeerScoreUpdateType::EmptyChunk);
+            format_err!("[state sync] Empty chunk from {:?}", peer)
+        })?;
+        // Peers that do not keep the transaction outputs send the transactions to re-execute.
+        if chunk.is_outputs() && !self.fetch_outputs() {
+            self.request_manager
+                .update_score(&peer, PeerScoreUpdateType::InvalidChunk);
+            bail!(
+                "[state sync] Chunk from {:?} carries transaction outputs that were not requested",
+                peer
+            );
+        }
 
         if chunk_start_version != known_version + 1 {
             // Old / wrong chunk.
@@ -804,11 +953,11 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             )?;
         }
 
-        let chunk_size = txn_list_with_proof.len() as u64;
+        let chunk_size = chunk.len() as u64;
         let new_version = known_version + chunk_size;
         match response.response_li {
             ResponseLedgerInfo::VerifiableLedgerInfo(li) => {
-                self.process_response_with_verifiable_li(txn_list_with_proof, li, None)
+                self.process_response_with_verifiable_li(chunk, li, None)
             }
             ResponseLedgerInfo::ProgressiveLedgerInfo {
                 target_li,
@@ -821,20 +970,12 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                     target_li,
                     highest_li
                 );
-                self.process_response_with_verifiable_li(
-                    txn_list_with_proof,
-                    target_li,
-                    Some(highest_li),
-                )
+                self.process_response_with_verifiable_li(chunk, target_li, Some(highest_li))
             }
             ResponseLedgerInfo::LedgerInfoForWaypoint {
                 waypoint_li,
                 end_of_epoch_li,
-            } => self.process_response_with_waypoint_li(
-                txn_list_with_proof,
-                waypoint_li,
-                end_of_epoch_li,
-            ),
+            } => self.process_response_with_waypoint_li(chunk, waypoint_li, end_of_epoch_li),
         }
         .map_err(|e| {
             self.request_manager
@@ -861,7 +1002,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             }
         }
 
-        self.process_commit(response.txn_list_with_proof.transactions, None)
+        self.process_commit(response.chunk.into_transactions(), None)
             .await?;
 
         // at this point, chunk has been successfully processed
@@ -873,7 +1014,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     /// current local trusted validator set.
     fn process_response_with_verifiable_li(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: TransactionChunk,
         response_li: LedgerInfoWithSignatures,
         // LI to verify and add to pending_ledger_infos
         // may be the same as response_li
@@ -895,8 +1036,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         }
         // Optimistically fetch the next chunk assuming the current chunk is going to be applied
         // successfully.
-        let new_version =
-            self.local_state.highest_version_in_local_storage() + txn_list_with_proof.len() as u64;
+        let new_version = self.local_state.highest_version_in_local_storage() + chunk.len() as u64;
         let new_epoch = if response_li.ledger_info().version() == new_version
             && response_li.ledger_info().ends_epoch()
         {
@@ -914,7 +1054,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             }
             self.pending_ledger_infos.add_li(li);
         }
-        self.validate_and_store_chunk(txn_list_with_proof, response_li, None)?;
+        self.validate_and_store_chunk(chunk, response_li, None)?;
 
         // need to sync with local storage to see whether response LI was actually committed
         // and update pending_ledger_infos accordingly
@@ -926,7 +1066,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     /// Processing chunk responses that carry a LedgerInfo corresponding to the waypoint.
     fn process_response_with_waypoint_li(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: TransactionChunk,
         waypoint_li: LedgerInfoWithSignatures,
         end_of_epoch_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<()> {
@@ -935,8 +1075,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             "Response with a waypoint LI but we're already initialized"
         );
         // Optimistically fetch the next chunk.
-        let new_version =
-            self.local_state.highest_version_in_local_storage() + txn_list_with_proof.len() as u64;
+        let new_version = self.local_state.highest_version_in_local_storage() + chunk.len() as u64;
         // The epoch in the optimistic request should be the next epoch if the current chunk
         // is the last one in its epoch.
         let new_epoch = end_of_epoch_li
@@ -953,13 +1092,13 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         }
 
         self.waypoint.verify(waypoint_li.ledger_info())?;
-        self.validate_and_store_chunk(txn_list_with_proof, waypoint_li, end_of_epoch_li)
+        self.validate_and_store_chunk(chunk, waypoint_li, end_of_epoch_li)
     }
 
     // Assumes that the target LI has been already verified by the caller.
     fn validate_and_store_chunk(
         &mut self,
-        txn_list_with_proof: TransactionListWithProof,
+        chunk: TransactionChunk,
         target: LedgerInfoWithSignatures,
         intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
     ) -> Result<()> {
@@ -977,13 +1116,24 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             return Ok(());
         }
 
-        self.executor_proxy.execute_chunk(
-            txn_list_with_proof,
-            target,
-            intermediate_end_of_epoch_li,
-            &mut self.local_state.synced_trees,
-        )?;
-        Ok(())
+        match chunk {
+            TransactionChunk::Transactions(txn_list_with_proof) => {
+                self.executor_proxy.execute_chunk(
+                    txn_list_with_proof,
+                    target,
+                    intermediate_end_of_epoch_li,
+                    &mut self.local_state.synced_trees,
+                )
+            }
+            TransactionChunk::TransactionOutputs(txn_output_list_with_proof) => {
+                self.executor_proxy.apply_chunk(
+                    txn_output_list_with_proof,
+                    target,
+                    intermediate_end_of_epoch_li,
+                    &mut self.local_state.synced_trees,
+                )
+            }
+        }
     }
 
     /// Ensures that StateSynchronizer is making progress:
@@ -992,6 +1142,19 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         if self.request_manager.no_available_peers() {
             return;
         }
+        // Chunks of transactions are requested once the state snapshot is restored.
+        if let Some(state_snapshot) = self.state_snapshot.as_ref() {
+            if self
+                .request_manager
+                .is_request_timeout(state_snapshot.last_request_time)
+            {
+                if let Err(e) = self.send_state_snapshot_request() {
+                    error!("[state sync] Failed to send state snapshot request: {}", e);
+                }
+                counters::TIMEOUT.inc();
+            }
+            return;
+        }
         if self.role == RoleType::Validator && self.sync_request.is_none() && self.is_initialized()
         {
             return;
@@ -1036,6 +1199,10 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         if self.request_manager.no_available_peers() {
             bail!("No peers found for chunk request.");
         }
+        if self.state_snapshot.is_some() {
+            debug!("[state sync] Restoring a state snapshot, chunks are requested afterwards");
+            return Ok(());
+        }
 
         let target = if !self.is_initialized() {
             let waypoint_version = self.waypoint.version();
@@ -1063,10 +1230,276 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             }
         };
 
-        let req = GetChunkRequest::new(known_version, known_epoch, self.config.chunk_limit, target);
+        let req = GetChunkRequest::new(
+            known_version,
+            known_epoch,
+            self.config.chunk_limit,
+            target,
+            self.fetch_outputs(),
+        );
         self.request_manager.send_chunk_request(req)
     }
 
+    /// Sends the next fast sync request: a state snapshot request if no state snapshot is being
+    /// restored yet, otherwise a request for the accounts after the last restored one.
+    fn send_state_snapshot_request(&mut self) -> Result<()> {
+        let state_snapshot = match self.state_snapshot.as_mut() {
+            Some(state_snapshot) => state_snapshot,
+            None => return Ok(()),
+        };
+        let msg = match state_snapshot.version {
+            None => StateSynchronizerMsg::GetStateSnapshotRequest(Box::new(
+                GetStateSnapshotRequest::new(
+                    self.local_state.highest_local_li.ledger_info().version(),
+                ),
+            )),
+            Some(version) => {
+                StateSynchronizerMsg::GetAccountChunkRequest(Box::new(GetAccountChunkRequest::new(
+                    version,
+                    state_snapshot.known_key,
+                    self.config.chunk_limit,
+                )))
+            }
+        };
+        state_snapshot.last_request_time = SystemTime::now();
+        self.request_manager.send_state_snapshot_request(msg)
+    }
+
+    /// The state snapshot is taken at the end of the previous epoch, or at the highest local LI if
+    /// it ends an epoch, so that the requester learns the validator set of the following epoch
+    /// along with it.
+    fn process_state_snapshot_request(
+        &mut self,
+        peer: PeerNetworkId,
+        request: GetStateSnapshotRequest,
+    ) -> Result<()> {
+        self.sync_state_with_local_storage()?;
+        debug!(
+            "[state sync] state snapshot request: peer: {}, local li version: {}, req: {}",
+            peer,
+            self.local_state.highest_local_li.ledger_info().version(),
+            request,
+        );
+
+        let highest_local_li = self.local_state.highest_local_li.clone();
+        let target_li = if highest_local_li.ledger_info().ends_epoch() {
+            highest_local_li
+        } else {
+            self.executor_proxy
+                .get_epoch_proof(highest_local_li.ledger_info().epoch() - 1)?
+        };
+        let epoch_change_proof = if target_li.ledger_info().version() > request.known_version {
+            self.executor_proxy
+                .get_epoch_change_proof(request.known_version, target_li)?
+        } else {
+            EpochChangeProof::new(vec![], /* more = */ false)
+        };
+        // In case there are too many epochs in between, the proof is cut short and the snapshot is
+        // taken at its last LI instead.
+        let snapshot_version = epoch_change_proof
+            .ledger_info_with_sigs
+            .last()
+            .map(|li| li.ledger_info().version());
+        let response = match snapshot_version {
+            Some(version) if version > request.known_version => GetStateSnapshotResponse::new(
+                epoch_change_proof,
+                self.executor_proxy.get_transaction_with_events(version)?,
+            ),
+            _ => GetStateSnapshotResponse::new(
+                EpochChangeProof::new(vec![], /* more = */ false),
+                TransactionListWithProof::new_empty(),
+            ),
+        };
+        let msg = StateSynchronizerMsg::GetStateSnapshotResponse(Box::new(response));
+        self.send_response(peer, msg);
+        Ok(())
+    }
+
+    fn process_account_chunk_request(
+        &mut self,
+        peer: PeerNetworkId,
+        request: GetAccountChunkRequest,
+    ) -> Result<()> {
+        debug!(
+            "[state sync] account chunk request: peer: {}, req: {}",
+            peer, request,
+        );
+        let limit = std::cmp::min(request.limit, self.config.max_chunk_limit);
+        let (accounts, proof) =
+            self.executor_proxy
+                .get_account_chunk(request.version, request.known_key, limit)?;
+        let account_chunk_response = GetAccountChunkResponse::new(request.version, accounts, proof);
+        let msg = StateSynchronizerMsg::GetAccountChunkResponse(Box::new(account_chunk_response));
+        self.send_response(peer, msg);
+        Ok(())
+    }
+
+    /// Verifies the state snapshot offered by a peer and starts restoring it, or falls back to
+    /// executing all the transactions if the peer has no state snapshot ahead of the local ledger.
+    fn process_state_snapshot_response(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: GetStateSnapshotResponse,
+    ) -> Result<()> {
+        debug!(
+            "[state sync] Processing state snapshot response {}",
+            response
+        );
+        match self.state_snapshot.as_ref() {
+            Some(state_snapshot) if state_snapshot.version.is_none() => (),
+            // E.g. a response to a multicast request another peer responded to first.
+            _ => bail!(
+                "[state sync] Unexpected state snapshot response from {:?}",
+                peer
+            ),
+        }
+        if response.epoch_change_proof.ledger_info_with_sigs.is_empty() {
+            info!(
+                "[state sync] {:?} has no state snapshot ahead of local storage, disabling fast sync",
+                peer
+            );
+            self.state_snapshot = None;
+            self.request_manager.process_success_response(peer);
+            return self.send_chunk_request(
+                self.local_state.highest_version_in_local_storage(),
+                self.local_state.epoch(),
+            );
+        }
+
+        let (version, known_key) = self.start_state_snapshot(response).map_err(|e| {
+            self.request_manager
+                .update_score(peer, PeerScoreUpdateType::InvalidChunk);
+            format_err!(
+                "[state sync] failed to start restoring state snapshot: {}",
+                e
+            )
+        })?;
+        info!(
+            "[state sync] Restoring state snapshot at version {} from {:?}",
+            version, peer
+        );
+
+        self.state_snapshot = Some(PendingStateSnapshot {
+            version: Some(version),
+            known_key,
+            last_request_time: SystemTime::now(),
+        });
+        self.request_manager.process_success_response(peer);
+        self.send_state_snapshot_request()
+    }
+
+    /// Verifies the LI of the state snapshot with the epoch change proof and starts restoring the
+    /// state snapshot at its version.
+    /// Returns the version and the key of the last account restored by a previous attempt.
+    fn start_state_snapshot(
+        &mut self,
+        response: GetStateSnapshotResponse,
+    ) -> Result<(Version, Option<HashValue>)> {
+        let target_li = if self.is_initialized() {
+            response
+                .epoch_change_proof
+                .verify(&self.local_state.trusted_epoch)?
+                .clone()
+        } else {
+            response.epoch_change_proof.verify(&self.waypoint)?.clone()
+        };
+        // Only an LI ending an epoch carries the validator set needed to verify what comes next.
+        ensure!(
+            target_li.ledger_info().ends_epoch(),
+            "State snapshot LI {} does not end an epoch",
+            target_li
+        );
+        let version = target_li.ledger_info().version();
+        let known_key = self
+            .executor_proxy
+            .start_state_snapshot(response.txn_list_with_proof, target_li)?;
+        Ok((version, known_key))
+    }
+
+    /// * Verifies and saves the accounts.
+    /// * Requests the next account chunk, or commits the state snapshot once all accounts are
+    /// restored and resumes syncing the transactions after it.
+    async fn process_account_chunk_response(
+        &mut self,
+        peer: &PeerNetworkId,
+        response: GetAccountChunkResponse,
+    ) -> Result<()> {
+        debug!(
+            "[state sync] Processing account chunk response {}",
+            response
+        );
+        let (version, known_key) = match self.state_snapshot.as_ref() {
+            Some(PendingStateSnapshot {
+                version: Some(version),
+                known_key,
+                ..
+            }) => (*version, *known_key),
+            _ => bail!(
+                "[state sync] Unexpected account chunk response from {:?}",
+                peer
+            ),
+        };
+        ensure!(
+            response.version == version,
+            "[state sync] Account chunk from {:?} has version {}, expecting {}",
+            peer,
+            response.version,
+            version
+        );
+        let (first_key, last_key) = match (response.accounts.first(), response.accounts.last()) {
+            (Some((first_key, _)), Some((last_key, _))) => (*first_key, *last_key),
+            _ => {
+                self.request_manager
+                    .update_score(peer, PeerScoreUpdateType::EmptyChunk);
+                bail!("[state sync] Empty account chunk from {:?}", peer);
+            }
+        };
+        if known_key.map_or(false, |known_key| first_key <= known_key) {
+            // Old chunk, e.g. a response to a multicast request another peer responded to first.
+            bail!(
+                "[state sync] Account chunk from {:?} starts at {:x}, not after known key {:?}",
+                peer,
+                first_key,
+                known_key
+            );
+        }
+
+        // The range proof of the rightmost account has nothing but empty subtrees on its right.
+        let is_last_chunk = response
+            .proof
+            .right_siblings()
+            .iter()
+            .all(|hash| *hash == *SPARSE_MERKLE_PLACEHOLDER_HASH);
+        let num_accounts = response.accounts.len();
+        self.executor_proxy
+            .save_account_chunk(response.accounts, response.proof)
+            .map_err(|e| {
+                self.request_manager
+                    .update_score(peer, PeerScoreUpdateType::InvalidChunk);
+                format_err!("[state sync] failed to apply account chunk: {}", e)
+            })?;
+        counters::STATE_SNAPSHOT_ACCOUNTS.inc_by(num_accounts as i64);
+        self.request_manager.process_success_response(peer);
+        if let Some(state_snapshot) = self.state_snapshot.as_mut() {
+            state_snapshot.known_key = Some(last_key);
+        }
+        if !is_last_chunk {
+            return self.send_state_snapshot_request();
+        }
+
+        self.executor_proxy.finalize_state_snapshot()?;
+        self.state_snapshot = None;
+        info!(
+            "[state sync] Restored state snapshot at version {}",
+            version
+        );
+        self.process_commit(vec![], None).await?;
+        self.send_chunk_request(
+            self.local_state.highest_version_in_local_storage(),
+            self.local_state.epoch(),
+        )
+    }
+
     fn deliver_subscription(
         &mut self,
         peer: PeerNetworkId,
@@ -1080,6 +1513,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             request_info.known_version,
             ResponseLedgerInfo::VerifiableLedgerInfo(response_li),
             request_info.limit,
+            request_info.fetch_outputs,
         )
     }
 
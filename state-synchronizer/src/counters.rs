@@ -17,6 +17,10 @@ pub const SYNC_MSG_LABEL: &str = "sync";
 pub const COMMIT_MSG_LABEL: &str = "commit";
 pub const CHUNK_REQUEST_MSG_LABEL: &str = "chunk_request";
 pub const CHUNK_RESPONSE_MSG_LABEL: &str = "chunk_response";
+pub const STATE_SNAPSHOT_REQUEST_MSG_LABEL: &str = "state_snapshot_request";
+pub const STATE_SNAPSHOT_RESPONSE_MSG_LABEL: &str = "state_snapshot_response";
+pub const ACCOUNT_CHUNK_REQUEST_MSG_LABEL: &str = "account_chunk_request";
+pub const ACCOUNT_CHUNK_RESPONSE_MSG_LABEL: &str = "account_chunk_response";
 
 // msg sender label
 pub const CONSENSUS_SENDER_LABEL: &str = "consensus";
@@ -87,6 +91,35 @@ pub static STATE_SYNC_CHUNK_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of Success results of applying an account chunk of a state snapshot
+pub static APPLY_ACCOUNT_CHUNK_SUCCESS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_state_sync_apply_account_chunk_success_total",
+        "Number of Success results of applying an account chunk of a state snapshot",
+        &["chunk_sender_id"]
+    )
+    .unwrap()
+});
+
+/// Number of failed attempts to apply a state snapshot response or an account chunk
+pub static APPLY_ACCOUNT_CHUNK_FAILURE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "libra_state_sync_apply_account_chunk_failure_total",
+        "Number of failed attempts to apply a state snapshot response or an account chunk",
+        &["chunk_sender_id"]
+    )
+    .unwrap()
+});
+
+/// Number of accounts restored from state snapshots
+pub static STATE_SNAPSHOT_ACCOUNTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "libra_state_sync_state_snapshot_accounts_total",
+        "Number of accounts restored from state snapshots"
+    )
+    .unwrap()
+});
+
 /// Number of peers that are currently active and upstream.
 /// They are the set of nodes a node can make sync requests to
 pub static ACTIVE_UPSTREAM_PEERS: Lazy<IntGauge> = Lazy::new(|| {
@@ -146,6 +179,15 @@ pub static EXECUTE_CHUNK_DURATION: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Time it takes for state sync to fully apply the outputs of a chunk (via executor proxy)
+pub static APPLY_CHUNK_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "libra_state_sync_apply_chunk_duration_s",
+        "Histogram of time it takes for state sync's executor proxy to fully apply the outputs of a chunk"
+    )
+    .unwrap()
+});
+
 /// Number of times a long-poll subscription is delivered
 pub static SUBSCRIPTION_DELIVERY_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
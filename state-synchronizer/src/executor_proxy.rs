@@ -5,13 +5,17 @@ use crate::{counters, SynchronizerState};
 use anyhow::{format_err, Result};
 use executor_types::{ChunkExecutor, ExecutedTrees};
 use itertools::Itertools;
+use libra_crypto::HashValue;
 use libra_types::{
     account_state::AccountState,
+    account_state_blob::AccountStateBlob,
     contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
     move_resource::MoveStorage,
     on_chain_config::{config_address, OnChainConfigPayload, ON_CHAIN_CONFIG_REGISTRY},
-    transaction::TransactionListWithProof,
+    proof::SparseMerkleRangeProof,
+    transaction::{TransactionListWithProof, TransactionOutputListWithProof},
 };
 use std::{collections::HashSet, convert::TryFrom, sync::Arc};
 use storage_interface::DbReader;
@@ -31,6 +35,15 @@ pub trait ExecutorProxyTrait: Send {
         synced_trees: &mut ExecutedTrees,
     ) -> Result<()>;
 
+    /// Apply the outputs of a batch of transactions and commit them
+    fn apply_chunk(
+        &mut self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
+        synced_trees: &mut ExecutedTrees,
+    ) -> Result<()>;
+
     /// Gets chunk of transactions given the known version, target version and the max limit.
     fn get_chunk(
         &self,
@@ -39,6 +52,18 @@ pub trait ExecutorProxyTrait: Send {
         target_version: u64,
     ) -> Result<TransactionListWithProof>;
 
+    /// Same as `get_chunk`, with the outputs of the transactions.
+    fn get_chunk_with_outputs(
+        &self,
+        known_version: u64,
+        limit: u64,
+        target_version: u64,
+    ) -> Result<TransactionOutputListWithProof>;
+
+    /// Get the transaction at `version` with its events, proven against the ledger info at the
+    /// same version.
+    fn get_transaction_with_events(&self, version: u64) -> Result<TransactionListWithProof>;
+
     /// Get the epoch change ledger info for epoch so that we can move to next epoch.
     fn get_epoch_proof(&self, epoch: u64) -> Result<LedgerInfoWithSignatures>;
 
@@ -54,6 +79,40 @@ pub trait ExecutorProxyTrait: Send {
 
     /// publishes on-chain config updates to subscribed components
     fn publish_on_chain_config_updates(&mut self, events: Vec<ContractEvent>) -> Result<()>;
+
+    /// Gets the epoch change proof from the epoch of `known_version` to the epoch of `target_li`.
+    fn get_epoch_change_proof(
+        &self,
+        known_version: u64,
+        target_li: LedgerInfoWithSignatures,
+    ) -> Result<EpochChangeProof>;
+
+    /// Gets a chunk of accounts of the state at `version` starting right after `known_key`.
+    fn get_account_chunk(
+        &self,
+        version: u64,
+        known_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)>;
+
+    /// Starts restoring the state snapshot at the version of the verified target LI.
+    /// Returns the key of the last account restored by a previous attempt, if any.
+    fn start_state_snapshot(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+    ) -> Result<Option<HashValue>>;
+
+    /// Verifies and saves a chunk of accounts of the state snapshot being restored.
+    fn save_account_chunk(
+        &mut self,
+        accounts: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()>;
+
+    /// Commits the restored state snapshot and publishes its on-chain configs to all the
+    /// subscribed components.
+    fn finalize_state_snapshot(&mut self) -> Result<()>;
 }
 
 pub(crate) struct ExecutorProxy {
@@ -157,6 +216,24 @@ impl ExecutorProxyTrait for ExecutorProxy {
         self.publish_on_chain_config_updates(reconfig_events)
     }
 
+    fn apply_chunk(
+        &mut self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+        intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
+        _synced_trees: &mut ExecutedTrees,
+    ) -> Result<()> {
+        // track chunk application time
+        let timer = counters::APPLY_CHUNK_DURATION.start_timer();
+        let reconfig_events = self.executor.apply_and_commit_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            intermediate_end_of_epoch_li,
+        )?;
+        timer.stop_and_record();
+        self.publish_on_chain_config_updates(reconfig_events)
+    }
+
     fn get_chunk(
         &self,
         known_version: u64,
@@ -167,6 +244,20 @@ impl ExecutorProxyTrait for ExecutorProxy {
             .get_transactions(known_version + 1, limit, target_version, false)
     }
 
+    fn get_chunk_with_outputs(
+        &self,
+        known_version: u64,
+        limit: u64,
+        target_version: u64,
+    ) -> Result<TransactionOutputListWithProof> {
+        self.storage
+            .get_transaction_outputs(known_version + 1, limit, target_version)
+    }
+
+    fn get_transaction_with_events(&self, version: u64) -> Result<TransactionListWithProof> {
+        self.storage.get_transactions(version, 1, version, true)
+    }
+
     fn get_epoch_proof(&self, epoch: u64) -> Result<LedgerInfoWithSignatures> {
         self.storage
             .get_epoch_ending_ledger_infos(epoch, epoch + 1)?
@@ -224,4 +315,53 @@ impl ExecutorProxyTrait for ExecutorProxy {
         self.on_chain_configs = new_configs;
         Ok(())
     }
+
+    fn get_epoch_change_proof(
+        &self,
+        known_version: u64,
+        target_li: LedgerInfoWithSignatures,
+    ) -> Result<EpochChangeProof> {
+        let (epoch_change_proof, _consistency_proof) = self
+            .storage
+            .get_state_proof_with_ledger_info(known_version, target_li)?;
+        Ok(epoch_change_proof)
+    }
+
+    fn get_account_chunk(
+        &self,
+        version: u64,
+        known_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        self.storage
+            .get_account_chunk_with_proof(version, known_key, limit)
+    }
+
+    fn start_state_snapshot(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+    ) -> Result<Option<HashValue>> {
+        self.executor
+            .start_state_snapshot(txn_list_with_proof, verified_target_li)
+    }
+
+    fn save_account_chunk(
+        &mut self,
+        accounts: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        self.executor.save_account_chunk(accounts, proof)
+    }
+
+    fn finalize_state_snapshot(&mut self) -> Result<()> {
+        self.executor.finalize_state_snapshot()?;
+
+        // All the configs are new compared to the ones read from the genesis state on startup.
+        self.on_chain_configs = Self::fetch_all_configs(&*self.storage)?;
+        for subscription in self.reconfig_subscriptions.iter_mut() {
+            subscription.publish(self.on_chain_configs.clone())?;
+        }
+        Ok(())
+    }
 }
@@ -18,6 +18,8 @@ mod counters;
 mod executor_proxy;
 pub mod network;
 mod request_manager;
+mod snapshot_request;
+mod snapshot_response;
 mod synchronizer;
 
 /// The state distinguishes between the following fields:
@@ -3,13 +3,19 @@
 
 //! Interface between StateSynchronizer and Network layers.
 
-use crate::{chunk_request::GetChunkRequest, chunk_response::GetChunkResponse, counters};
+use crate::{
+    chunk_request::GetChunkRequest,
+    chunk_response::GetChunkResponse,
+    counters,
+    snapshot_request::{GetAccountChunkRequest, GetStateSnapshotRequest},
+    snapshot_response::{GetAccountChunkResponse, GetStateSnapshotResponse},
+};
 use channel::message_queues::QueueStyle;
 use libra_metrics::IntCounterVec;
 use libra_types::PeerId;
 use network::{
     error::NetworkError,
-    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    peer_manager::{ConnectionRequestSender, Misbehavior, PeerManagerRequestSender},
     protocols::network::{NetworkEvents, NetworkSender, NewNetworkSender},
     ProtocolId,
 };
@@ -20,6 +26,10 @@ use serde::{Deserialize, Serialize};
 pub enum StateSynchronizerMsg {
     GetChunkRequest(Box<GetChunkRequest>),
     GetChunkResponse(Box<GetChunkResponse>),
+    GetStateSnapshotRequest(Box<GetStateSnapshotRequest>),
+    GetStateSnapshotResponse(Box<GetStateSnapshotResponse>),
+    GetAccountChunkRequest(Box<GetAccountChunkRequest>),
+    GetAccountChunkResponse(Box<GetAccountChunkResponse>),
 }
 
 /// The interface from Network to StateSynchronizer layer.
@@ -81,4 +91,12 @@ impl StateSynchronizerSender {
         let protocol = ProtocolId::StateSynchronizerDirectSend;
         self.inner.send_to(recipient, protocol, message)
     }
+
+    pub fn report_misbehavior(
+        &mut self,
+        peer: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), NetworkError> {
+        self.inner.report_misbehavior(peer, misbehavior)
+    }
 }
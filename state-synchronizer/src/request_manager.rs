@@ -14,6 +14,7 @@ use libra_config::{
 };
 use libra_logger::prelude::*;
 use netcore::transport::ConnectionOrigin;
+use network::peer_manager::Misbehavior;
 use rand::{
     distributions::{Distribution, WeightedIndex},
     thread_rng,
@@ -139,6 +140,16 @@ impl RequestManager {
     }
 
     pub fn update_score(&mut self, peer: &PeerNetworkId, update_type: PeerScoreUpdateType) {
+        // let the network ban the peers which keep misbehaving, timeouts only get counted
+        match update_type {
+            PeerScoreUpdateType::InvalidChunk => {
+                self.report_misbehavior(peer, Misbehavior::InvalidChunk)
+            }
+            PeerScoreUpdateType::TimeOut => {
+                self.report_misbehavior(peer, Misbehavior::RequestTimeout)
+            }
+            _ => {}
+        }
         if let Some(peer_info) = self.peers.get_mut(peer) {
             let old_score = peer_info.score;
             match update_type {
@@ -162,6 +173,17 @@ impl RequestManager {
         }
     }
 
+    fn report_misbehavior(&mut self, peer: &PeerNetworkId, misbehavior: Misbehavior) {
+        if let Some(sender) = self.network_senders.get_mut(&peer.network_id()) {
+            if let Err(e) = sender.report_misbehavior(peer.peer_id(), misbehavior) {
+                error!(
+                    "[state sync] failed to report misbehavior of {:?}: {}",
+                    peer, e
+                );
+            }
+        }
+    }
+
     // Updates the information used to select a peer to send a chunk request to:
     // * eligible_peers
     // * weighted_index: the chance that a peer is selected from `eligible_peers` is weighted by its score
@@ -246,6 +268,29 @@ impl RequestManager {
 
         // actually execute network send
         let msg = StateSynchronizerMsg::GetChunkRequest(Box::new(req));
+        self.send_to_peers(peers, msg)
+    }
+
+    /// Sends a state snapshot or account chunk request to the peers picked the same way as for
+    /// chunk requests. Unlike chunk requests, these are not tracked: the caller is responsible for
+    /// resending them on timeout, see `is_request_timeout`.
+    pub fn send_state_snapshot_request(&mut self, msg: StateSynchronizerMsg) -> Result<()> {
+        let peers = self.pick_peers();
+        if peers.is_empty() {
+            bail!("No peers to send state snapshot request to");
+        }
+        debug!(
+            "[state sync] request state snapshot - {:?} to peers {:?}",
+            msg, peers
+        );
+        self.send_to_peers(peers, msg)
+    }
+
+    fn send_to_peers(
+        &mut self,
+        peers: Vec<PeerNetworkId>,
+        msg: StateSynchronizerMsg,
+    ) -> Result<()> {
         let mut failed_peer_sends = vec![];
         for peer in peers {
             let sender = self
@@ -267,7 +312,7 @@ impl RequestManager {
         if failed_peer_sends.is_empty() {
             Ok(())
         } else {
-            bail!("Failed to send chunk request to: {:?}", failed_peer_sends)
+            bail!("Failed to send request to: {:?}", failed_peer_sends)
         }
     }
 
@@ -421,6 +466,11 @@ impl RequestManager {
         is_timeout
     }
 
+    /// Checks whether a request sent at `last_request_time` timed out
+    pub fn is_request_timeout(&self, last_request_time: SystemTime) -> bool {
+        Self::is_timeout(last_request_time, self.request_timeout)
+    }
+
     fn is_upstream_peer(&self, peer: &PeerNetworkId, origin: ConnectionOrigin) -> bool {
         let is_network_upstream = self
             .upstream_config
@@ -0,0 +1,77 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use libra_crypto::HashValue;
+use libra_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Asks for the state snapshot at the end of the previous epoch of the upstream node, the starting
+/// point of fast sync.
+pub struct GetStateSnapshotRequest {
+    /// The epoch change proof in the response starts at the epoch of `known_version`.
+    pub known_version: Version,
+}
+
+impl GetStateSnapshotRequest {
+    pub fn new(known_version: Version) -> Self {
+        Self { known_version }
+    }
+}
+
+impl fmt::Debug for GetStateSnapshotRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetStateSnapshotRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[StateSnapshotRequest: known version: {}]",
+            self.known_version
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct GetAccountChunkRequest {
+    /// Version of the state snapshot.
+    pub version: Version,
+    /// The response should start with the account right after `known_key`, or with the leftmost
+    /// account if `None`.
+    pub known_key: Option<HashValue>,
+    /// Max number of accounts in an account chunk response.
+    pub limit: u64,
+}
+
+impl GetAccountChunkRequest {
+    pub fn new(version: Version, known_key: Option<HashValue>, limit: u64) -> Self {
+        Self {
+            version,
+            known_key,
+            limit,
+        }
+    }
+}
+
+impl fmt::Debug for GetAccountChunkRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetAccountChunkRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[AccountChunkRequest: version: {}, known key: {}, limit: {}]",
+            self.version,
+            self.known_key
+                .map_or_else(|| String::from("None"), |key| key.to_hex()),
+            self.limit,
+        )
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use libra_crypto::HashValue;
+use libra_types::{
+    account_state_blob::AccountStateBlob,
+    epoch_change::EpochChangeProof,
+    proof::SparseMerkleRangeProof,
+    transaction::{TransactionListWithProof, Version},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The state snapshot is taken at the version of the last LedgerInfo in the epoch change proof,
+/// which always ends an epoch.
+pub struct GetStateSnapshotResponse {
+    /// Epoch change proof starting at the epoch of the known version of the request.
+    /// Empty if the upstream node has no state snapshot ahead of the known version.
+    pub epoch_change_proof: EpochChangeProof,
+    /// The transaction at the version of the state snapshot, with the proof relative to the last
+    /// LedgerInfo in `epoch_change_proof`.
+    pub txn_list_with_proof: TransactionListWithProof,
+}
+
+impl GetStateSnapshotResponse {
+    pub fn new(
+        epoch_change_proof: EpochChangeProof,
+        txn_list_with_proof: TransactionListWithProof,
+    ) -> Self {
+        Self {
+            epoch_change_proof,
+            txn_list_with_proof,
+        }
+    }
+}
+
+impl fmt::Debug for GetStateSnapshotResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetStateSnapshotResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let snapshot_li_repr = self
+            .epoch_change_proof
+            .ledger_info_with_sigs
+            .last()
+            .map_or("None".to_string(), |li| li.ledger_info().to_string());
+        write!(
+            f,
+            "[StateSnapshotResponse: snapshot LI: {}, epoch change LIs: {}]",
+            snapshot_li_repr,
+            self.epoch_change_proof.ledger_info_with_sigs.len(),
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct GetAccountChunkResponse {
+    /// Version of the state snapshot.
+    pub version: Version,
+    /// Accounts in the increasing order of their keys.
+    pub accounts: Vec<(HashValue, AccountStateBlob)>,
+    /// Proof of the range of accounts ending with the last one in `accounts`.
+    pub proof: SparseMerkleRangeProof,
+}
+
+impl GetAccountChunkResponse {
+    pub fn new(
+        version: Version,
+        accounts: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Self {
+        Self {
+            version,
+            accounts,
+            proof,
+        }
+    }
+}
+
+impl fmt::Debug for GetAccountChunkResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for GetAccountChunkResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let accounts_repr = match (self.accounts.first(), self.accounts.last()) {
+            (Some((first_key, _)), Some((last_key, _))) => format!(
+                "keys [{} - {}]",
+                first_key.short_str(),
+                last_key.short_str()
+            ),
+            _ => "empty".to_string(),
+        };
+        write!(
+            f,
+            "[AccountChunkResponse: version: {}, accounts: {}]",
+            self.version, accounts_repr,
+        )
+    }
+}
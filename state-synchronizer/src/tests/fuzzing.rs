@@ -3,7 +3,7 @@
 
 use crate::{
     chunk_request::{GetChunkRequest, TargetType},
-    chunk_response::{GetChunkResponse, ResponseLedgerInfo},
+    chunk_response::{GetChunkResponse, ResponseLedgerInfo, TransactionChunk},
     coordinator::SyncCoordinator,
     network::{StateSynchronizerMsg, StateSynchronizerSender},
     tests::{
@@ -112,10 +112,13 @@ impl Arbitrary for GetChunkRequest {
             any::<u64>(),
             any::<u64>(),
             any::<TargetType>(),
+            any::<bool>(),
         )
-            .prop_map(|(known_version, current_epoch, limit, target)| {
-                GetChunkRequest::new(known_version, current_epoch, limit, target)
-            })
+            .prop_map(
+                |(known_version, current_epoch, limit, target, fetch_outputs)| {
+                    GetChunkRequest::new(known_version, current_epoch, limit, target, fetch_outputs)
+                },
+            )
             .boxed()
     }
 
@@ -154,7 +157,10 @@ impl Arbitrary for GetChunkResponse {
             any::<TransactionListWithProof>(),
         )
             .prop_map(|(response_li, txn_list_with_proof)| {
-                GetChunkResponse::new(response_li, txn_list_with_proof)
+                GetChunkResponse::new(
+                    response_li,
+                    TransactionChunk::Transactions(txn_list_with_proof),
+                )
             })
             .boxed()
     }
@@ -4,9 +4,11 @@
 use crate::{
     executor_proxy::ExecutorProxyTrait, tests::mock_storage::MockStorage, SynchronizerState,
 };
-use anyhow::Result;
+use anyhow::{ensure, format_err, Result};
 use executor_types::ExecutedTrees;
-use libra_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, test_utils::TEST_SEED, x25519, Uniform};
+use libra_crypto::{
+    hash::ACCUMULATOR_PLACEHOLDER_HASH, test_utils::TEST_SEED, x25519, HashValue, Uniform,
+};
 use libra_network_address::{
     encrypted::{
         RawEncNetworkAddress, TEST_SHARED_VAL_NETADDR_KEY, TEST_SHARED_VAL_NETADDR_KEY_VERSION,
@@ -14,14 +16,26 @@ use libra_network_address::{
     NetworkAddress, RawNetworkAddress,
 };
 use libra_types::{
-    contract_event::ContractEvent, ledger_info::LedgerInfoWithSignatures,
-    on_chain_config::ValidatorSet, proof::TransactionListProof,
-    transaction::TransactionListWithProof, validator_config::ValidatorConfig,
-    validator_info::ValidatorInfo, validator_signer::ValidatorSigner,
+    account_state_blob::AccountStateBlob,
+    contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::ValidatorSet,
+    proof::{SparseMerkleRangeProof, TransactionListProof},
+    transaction::{
+        TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
+        TransactionStatus,
+    },
+    validator_config::ValidatorConfig,
+    validator_info::ValidatorInfo,
+    validator_signer::ValidatorSigner,
     validator_verifier::random_validator_verifier,
+    vm_status::KeptVMStatus,
+    write_set::WriteSet,
 };
 use rand::{rngs::StdRng, SeedableRng};
 use std::{
+    collections::BTreeMap,
     convert::TryFrom,
     str::FromStr,
     sync::{Arc, RwLock},
@@ -94,11 +108,20 @@ pub(crate) type MockRpcHandler = Box<
 pub(crate) struct MockExecutorProxy {
     handler: MockRpcHandler,
     storage: Arc<RwLock<MockStorage>>,
+    // LI and accounts received so far of the state snapshot being restored
+    state_snapshot: Option<(
+        LedgerInfoWithSignatures,
+        BTreeMap<HashValue, AccountStateBlob>,
+    )>,
 }
 
 impl MockExecutorProxy {
     pub(crate) fn new(handler: MockRpcHandler, storage: Arc<RwLock<MockStorage>>) -> Self {
-        Self { handler, storage }
+        Self {
+            handler,
+            storage,
+            state_snapshot: None,
+        }
     }
 }
 
@@ -122,6 +145,25 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         Ok(())
     }
 
+    fn apply_chunk(
+        &mut self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        intermediate_end_of_epoch_li: Option<LedgerInfoWithSignatures>,
+        _synced_trees: &mut ExecutedTrees,
+    ) -> Result<()> {
+        self.storage.write().unwrap().add_txns_with_li(
+            txn_output_list_with_proof
+                .transactions_and_outputs
+                .into_iter()
+                .map(|(txn, _)| txn)
+                .collect(),
+            ledger_info_with_sigs,
+            intermediate_end_of_epoch_li,
+        );
+        Ok(())
+    }
+
     fn get_chunk(
         &self,
         known_version: u64,
@@ -143,6 +185,39 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         (self.handler)(txns_with_proof)
     }
 
+    fn get_chunk_with_outputs(
+        &self,
+        known_version: u64,
+        limit: u64,
+        target_version: u64,
+    ) -> Result<TransactionOutputListWithProof> {
+        let txns_with_proof = self.get_chunk(known_version, limit, target_version)?;
+        let txns_and_outputs = txns_with_proof
+            .transactions
+            .into_iter()
+            .map(|txn| {
+                let output = TransactionOutput::new(
+                    WriteSet::default(),
+                    vec![],
+                    0,
+                    TransactionStatus::Keep(KeptVMStatus::Executed),
+                );
+                (txn, output)
+            })
+            .collect();
+        Ok(TransactionOutputListWithProof::new(
+            txns_and_outputs,
+            txns_with_proof.first_transaction_version,
+            txns_with_proof.proof,
+        ))
+    }
+
+    fn get_transaction_with_events(&self, version: u64) -> Result<TransactionListWithProof> {
+        let mut txn_with_proof = self.get_chunk(version - 1, 1, version)?;
+        txn_with_proof.events = Some(vec![vec![]; txn_with_proof.transactions.len()]);
+        Ok(txn_with_proof)
+    }
+
     fn get_epoch_proof(&self, epoch: u64) -> Result<LedgerInfoWithSignatures> {
         self.storage.read().unwrap().get_epoch_changes(epoch)
     }
@@ -161,4 +236,66 @@ impl ExecutorProxyTrait for MockExecutorProxy {
     fn publish_on_chain_config_updates(&mut self, _events: Vec<ContractEvent>) -> Result<()> {
         Ok(())
     }
+
+    fn get_epoch_change_proof(
+        &self,
+        known_version: u64,
+        target_li: LedgerInfoWithSignatures,
+    ) -> Result<EpochChangeProof> {
+        Ok(self
+            .storage
+            .read()
+            .unwrap()
+            .get_epoch_change_proof(known_version, &target_li))
+    }
+
+    fn get_account_chunk(
+        &self,
+        _version: u64,
+        known_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        self.storage
+            .read()
+            .unwrap()
+            .get_account_chunk(known_key, limit)
+    }
+
+    fn start_state_snapshot(
+        &mut self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: LedgerInfoWithSignatures,
+    ) -> Result<Option<HashValue>> {
+        ensure!(
+            txn_list_with_proof.transactions.len() == 1,
+            "[mock executor] expecting the transaction at the snapshot version"
+        );
+        self.state_snapshot = Some((verified_target_li, BTreeMap::new()));
+        Ok(None)
+    }
+
+    fn save_account_chunk(
+        &mut self,
+        accounts: Vec<(HashValue, AccountStateBlob)>,
+        _proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        self.state_snapshot
+            .as_mut()
+            .ok_or_else(|| format_err!("[mock executor] no state snapshot being restored"))?
+            .1
+            .extend(accounts);
+        Ok(())
+    }
+
+    fn finalize_state_snapshot(&mut self) -> Result<()> {
+        let (ledger_info_with_sigs, accounts) = self
+            .state_snapshot
+            .take()
+            .ok_or_else(|| format_err!("[mock executor] no state snapshot being restored"))?;
+        self.storage
+            .write()
+            .unwrap()
+            .restore_state_snapshot(accounts, ledger_info_with_sigs);
+        Ok(())
+    }
 }
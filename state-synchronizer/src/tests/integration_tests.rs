@@ -135,6 +135,7 @@ impl SynchronizerEnv {
             120_000,
             mock_network,
             upstream_networks,
+            false,
         );
     }
 
@@ -147,6 +148,7 @@ impl SynchronizerEnv {
         multicast_timeout_ms: u64,
         mock_network: bool,
         upstream_networks: Option<Vec<NetworkId>>,
+        enable_fast_sync: bool,
     ) {
         let new_peer_idx = self.synchronizers.len();
         let seed_pubkeys: HashMap<_, _> = self
@@ -166,6 +168,7 @@ impl SynchronizerEnv {
         config.base.role = role;
         config.state_sync.sync_request_timeout_ms = timeout_ms;
         config.state_sync.multicast_timeout_ms = multicast_timeout_ms;
+        config.state_sync.enable_fast_sync = enable_fast_sync;
 
         let network = config.validator_network.unwrap();
         let network_id = if role.is_validator() {
@@ -563,6 +566,7 @@ fn test_request_timeout() {
         300,
         false,
         None,
+        false,
     );
     env.commit(0, 1);
     env.sync_to(1, env.latest_li(0));
@@ -672,6 +676,51 @@ fn catch_up_through_epochs_full_node() {
     assert_eq!(env.latest_li(2).ledger_info().epoch(), 10);
 }
 
+#[test]
+fn fast_sync_full_node() {
+    let mut env = SynchronizerEnv::new(2);
+    env.start_next_synchronizer(
+        SynchronizerEnv::default_handler(),
+        RoleType::Validator,
+        Waypoint::default(),
+        false,
+        None,
+    );
+    for epoch in 1..5 {
+        env.commit(0, epoch * 100);
+        env.move_to_next_epoch();
+    }
+    // At this point peer 0 is at version 400, which ends epoch 4
+
+    env.setup_next_synchronizer(
+        SynchronizerEnv::default_handler(),
+        RoleType::FullNode,
+        Waypoint::default(),
+        60_000,
+        120_000,
+        false,
+        None,
+        true,
+    );
+    assert!(env.wait_for_version(1, 400, None));
+    // Peer 1 restored the state snapshot at the end of epoch 4 instead of executing all the
+    // transactions before it
+    assert_eq!(
+        env.storage_proxies[1].read().unwrap().snapshot_version(),
+        400
+    );
+    assert_eq!(env.latest_li(1), env.latest_li(0));
+
+    // Once the state snapshot is restored peer 1 continues with the regular state sync
+    env.commit(0, 450);
+    assert!(env.wait_for_version(1, 450, None));
+    assert_eq!(env.latest_li(1).ledger_info().epoch(), 5);
+    assert_eq!(
+        env.storage_proxies[1].read().unwrap().accounts(),
+        env.storage_proxies[0].read().unwrap().accounts()
+    );
+}
+
 #[test]
 fn catch_up_with_waypoints() {
     let mut env = SynchronizerEnv::new(3);
@@ -813,6 +862,7 @@ fn test_fn_failover() {
         60_000,
         true,
         Some(vec![NetworkId::vfn_network(), NetworkId::Public]),
+        false,
     );
 
     // start up 3 publicly available VFN
@@ -1060,6 +1110,7 @@ fn test_multicast_failover() {
             NetworkId::Private("second".to_string()),
             NetworkId::Public,
         ]),
+        false,
     );
 
     // setup the other FN upstream peer
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::SynchronizerState;
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use executor_types::ExecutedTrees;
-use libra_crypto::HashValue;
+use libra_crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
 #[cfg(test)]
 use libra_types::{
     account_address::AccountAddress,
@@ -16,12 +16,11 @@ use libra_types::{
     transaction::{authenticator::AuthenticationKey, SignedTransaction},
 };
 use libra_types::{
-    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::Transaction,
+    account_state_blob::AccountStateBlob, epoch_change::EpochChangeProof, epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures, proof::SparseMerkleRangeProof, transaction::Transaction,
     validator_signer::ValidatorSigner,
 };
-#[cfg(test)]
-use std::collections::BTreeMap;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 #[cfg(test)]
 use transaction_builder::encode_peer_to_peer_with_metadata_script;
 #[cfg(test)]
@@ -29,8 +28,12 @@ use vm_genesis::GENESIS_KEYPAIR;
 
 #[derive(Clone)]
 pub struct MockStorage {
-    // some mock transactions in the storage
+    // some mock transactions in the storage, after the state snapshot version
     transactions: Vec<Transaction>,
+    // version of the state snapshot the storage was restored from, 0 if it was not restored
+    snapshot_version: u64,
+    // latest state of the accounts that sent the txns, by hash of the account address
+    accounts: BTreeMap<HashValue, AccountStateBlob>,
     // the executed trees after applying the txns above.
     synced_trees: ExecutedTrees,
     // latest ledger info per epoch
@@ -52,6 +55,8 @@ impl MockStorage {
         ledger_infos.insert(0, genesis_li);
         Self {
             transactions: vec![],
+            snapshot_version: 0,
+            accounts: BTreeMap::new(),
             synced_trees: ExecutedTrees::new_empty(),
             ledger_infos,
             epoch_num,
@@ -61,18 +66,37 @@ impl MockStorage {
     }
 
     fn add_txns(&mut self, txns: &mut Vec<Transaction>) {
+        for txn in txns.iter() {
+            if let Transaction::UserTransaction(signed_txn) = txn {
+                let sender = signed_txn.sender();
+                self.accounts.insert(
+                    HashValue::sha3_256_of(sender.as_ref()),
+                    AccountStateBlob::from(sender.to_vec()),
+                );
+            }
+        }
         self.transactions.append(txns);
-        let num_leaves = self.transactions.len() + 1;
+        let num_leaves = self.version() + 1;
         let frozen_subtree_roots = vec![HashValue::zero(); num_leaves.count_ones() as usize];
         self.synced_trees = ExecutedTrees::new(
             HashValue::zero(), /* dummy_state_root */
             frozen_subtree_roots,
-            num_leaves as u64,
+            num_leaves,
         );
     }
 
     pub fn version(&self) -> u64 {
-        self.transactions.len() as u64
+        self.snapshot_version + self.transactions.len() as u64
+    }
+
+    #[cfg(test)]
+    pub fn snapshot_version(&self) -> u64 {
+        self.snapshot_version
+    }
+
+    #[cfg(test)]
+    pub fn accounts(&self) -> &BTreeMap<HashValue, AccountStateBlob> {
+        &self.accounts
     }
 
     pub fn synced_trees(&self) -> &ExecutedTrees {
@@ -115,18 +139,85 @@ impl MockStorage {
         target_version: u64,
     ) -> Vec<Transaction> {
         let mut res = vec![];
-        if target_version < start_version || start_version == 0 {
+        // the transactions up to the state snapshot version are not available
+        if target_version < start_version || start_version <= self.snapshot_version {
             return res;
         }
         let mut version = start_version;
         let limit = std::cmp::min(limit, target_version - start_version + 1);
-        while version - 1 < self.transactions.len() as u64 && version - start_version < limit {
-            res.push(self.transactions[(version - 1) as usize].clone());
+        while version <= self.version() && version - start_version < limit {
+            res.push(self.transactions[(version - self.snapshot_version - 1) as usize].clone());
             version += 1;
         }
         res
     }
 
+    // Returns the epoch ending LIs after `known_version` up to the epoch of `target_li`
+    pub fn get_epoch_change_proof(
+        &self,
+        known_version: u64,
+        target_li: &LedgerInfoWithSignatures,
+    ) -> EpochChangeProof {
+        let mut ledger_infos: Vec<_> = self
+            .ledger_infos
+            .values()
+            .filter(|li| {
+                li.ledger_info().ends_epoch()
+                    && li.ledger_info().version() > known_version
+                    && li.ledger_info().epoch() <= target_li.ledger_info().epoch()
+            })
+            .cloned()
+            .collect();
+        ledger_infos.sort_by_key(|li| li.ledger_info().epoch());
+        EpochChangeProof::new(ledger_infos, /* more = */ false)
+    }
+
+    // Returns up to `limit` accounts after `known_key`. The mock storage only keeps the latest
+    // state and the mock proof only tells whether there are accounts after the chunk: its right
+    // sibling is a placeholder for the last chunk.
+    pub fn get_account_chunk(
+        &self,
+        known_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        let mut accounts = self
+            .accounts
+            .iter()
+            .filter(|(key, _)| known_key.map_or(true, |known_key| **key > known_key))
+            .map(|(key, blob)| (*key, blob.clone()));
+        let chunk: Vec<_> = accounts.by_ref().take(limit as usize).collect();
+        ensure!(
+            !chunk.is_empty(),
+            "[mock storage] no accounts after {:?}",
+            known_key
+        );
+        let right_sibling = if accounts.next().is_some() {
+            HashValue::zero()
+        } else {
+            *SPARSE_MERKLE_PLACEHOLDER_HASH
+        };
+        Ok((chunk, SparseMerkleRangeProof::new(vec![right_sibling])))
+    }
+
+    // Replaces the storage with the state snapshot at the version of the epoch ending LI
+    pub fn restore_state_snapshot(
+        &mut self,
+        accounts: BTreeMap<HashValue, AccountStateBlob>,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+    ) {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        self.snapshot_version = ledger_info.version();
+        self.transactions.clear();
+        self.accounts = accounts;
+        self.add_txns(&mut vec![]);
+        if let Some(next_epoch_state) = ledger_info.next_epoch_state() {
+            self.epoch_num = next_epoch_state.epoch;
+            self.epoch_state = next_epoch_state.clone();
+        }
+        self.ledger_infos
+            .insert(ledger_info.epoch(), ledger_info_with_sigs.clone());
+    }
+
     pub fn add_txns_with_li(
         &mut self,
         mut transactions: Vec<Transaction>,
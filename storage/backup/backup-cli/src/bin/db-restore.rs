@@ -62,6 +62,8 @@ async fn main() -> Result<()> {
             &opt.global.db_dir,
             false, /* read_only */
             None,  /* pruner */
+            None,  /* ledger pruner */
+            None,  /* write set window */
         )
         .expect("Failed opening DB."),
     );
@@ -38,6 +38,13 @@ enum Command {
     },
     #[structopt(name = "list-accounts")]
     ListAccounts,
+    /// Builds the transaction hash index of DBs created before the index existed. Opens the DB
+    /// for writing, so the node must be stopped.
+    #[structopt(name = "rebuild-txn-hash-index")]
+    RebuildTxnHashIndex {
+        #[structopt(long, default_value = "10000")]
+        batch_size: usize,
+    },
 }
 
 /// Print out latest information stored in the DB.
@@ -150,6 +157,15 @@ fn list_accounts(db: &LibraDB) {
     info!("Total Accounts: {}", num_account);
 }
 
+fn rebuild_txn_hash_index(db: &LibraDB, batch_size: usize) {
+    let num_indexed = db
+        .rebuild_transaction_by_hash_index(batch_size, |version| {
+            info!("Indexed transactions up to version {}", version)
+        })
+        .expect("Unable to rebuild transaction hash index");
+    info!("Total transactions indexed: {}", num_indexed);
+}
+
 fn main() {
     ::libra_logger::Logger::new().init();
 
@@ -165,8 +181,14 @@ fn main() {
     let log_dir = tempfile::tempdir().expect("Unable to get temp dir");
     info!("Opening DB at: {:?}, log at {:?}", p, log_dir.path());
 
-    let db =
-        LibraDB::open(p, true /* readonly */, None /* pruner */).expect("Unable to open LibraDB");
+    // Migrations need to write to the DB, everything else only reads from it.
+    let readonly = !matches!(opt.cmd, Some(Command::RebuildTxnHashIndex { .. }));
+    let db = LibraDB::open(
+        p, readonly, None, /* pruner */
+        None, /* ledger pruner */
+        None, /* write set window */
+    )
+    .expect("Unable to open LibraDB");
     info!("DB opened successfully.");
 
     if let Some(cmd) = opt.cmd {
@@ -183,6 +205,9 @@ fn main() {
             Command::ListAccounts => {
                 list_accounts(&db);
             }
+            Command::RebuildTxnHashIndex { batch_size } => {
+                rebuild_txn_hash_index(&db, batch_size);
+            }
         }
     } else {
         print_head(&db).expect("Unable to read information from DB");
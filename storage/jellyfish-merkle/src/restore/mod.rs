@@ -26,6 +26,7 @@ use libra_types::{
     transaction::Version,
 };
 use mirai_annotations::*;
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum ChildInfo {
@@ -97,9 +98,9 @@ impl InternalInfo {
     }
 }
 
-pub struct JellyfishMerkleRestore<'a, S> {
+pub struct JellyfishMerkleRestore<S> {
     /// The underlying storage.
-    store: &'a S,
+    store: Arc<S>,
 
     /// The version of the tree we are restoring.
     version: Version,
@@ -150,23 +151,25 @@ pub struct JellyfishMerkleRestore<'a, S> {
     expected_root_hash: HashValue,
 }
 
-impl<'a, S> JellyfishMerkleRestore<'a, S>
+impl<S> JellyfishMerkleRestore<S>
 where
-    S: 'a + TreeReader + TreeWriter,
+    S: TreeReader + TreeWriter,
 {
-    pub fn new(store: &'a S, version: Version, expected_root_hash: HashValue) -> Result<Self> {
+    pub fn new(store: Arc<S>, version: Version, expected_root_hash: HashValue) -> Result<Self> {
         let (partial_nodes, previous_leaf) = match store.get_rightmost_leaf()? {
-            Some((node_key, leaf_node)) => {
+            // Leaves of other versions belong to trees that already exist in storage, e.g. the
+            // genesis state a node is bootstrapped with before it restores a snapshot on top of it.
+            Some((node_key, leaf_node)) if node_key.version() == version => {
                 // If the system crashed in the middle of the previous restoration attempt, we need
                 // to recover the partial nodes to the state right before the crash.
                 (
-                    Self::recover_partial_nodes(store, version, node_key)?,
+                    Self::recover_partial_nodes(&*store, version, node_key)?,
                     Some(leaf_node),
                 )
             }
-            None => {
-                // If no rightmost leaf exists, it means this is the first time we start and
-                // storage is still empty. We use a single root node in this case.
+            _ => {
+                // If no rightmost leaf of this version exists, it means this is the first time we
+                // start and storage is still empty. We use a single root node in this case.
                 (
                     vec![InternalInfo::new_empty(NodeKey::new_empty_path(version))],
                     None,
@@ -188,7 +191,7 @@ where
     /// Recovers partial nodes from storage. We do this by looking at all the ancestors of the
     /// rightmost leaf. The ones do not exist in storage are the partial nodes.
     fn recover_partial_nodes(
-        store: &'a S,
+        store: &S,
         version: Version,
         rightmost_leaf_node_key: NodeKey,
     ) -> Result<Vec<InternalInfo>> {
@@ -252,6 +255,12 @@ where
         Ok(partial_nodes)
     }
 
+    /// Returns the key of the most recently added account, including the ones recovered from
+    /// storage after a restart. The next chunk has to start right after it.
+    pub fn previous_key(&self) -> Option<HashValue> {
+        self.previous_leaf.as_ref().map(LeafNode::account_key)
+    }
+
     /// Restores a chunk of accounts. This function will verify that the given chunk is correct
     /// using the proof and root hash, then write things to storage. If the chunk is invalid, an
     /// error will be returned and nothing will be written to storage.
@@ -8,7 +8,7 @@ use crate::{
 use libra_crypto::HashValue;
 use libra_types::{account_state_blob::AccountStateBlob, transaction::Version};
 use proptest::{collection::btree_map, prelude::*};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -22,9 +22,9 @@ proptest! {
         let expected_root_hash = tree.get_root_hash(version).unwrap();
 
         // For this test, restore everything without interruption.
-        let restore_db = MockTreeStore::default();
+        let restore_db = Arc::new(MockTreeStore::default());
         let mut restore =
-            JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
+            JellyfishMerkleRestore::new(Arc::clone(&restore_db), version, expected_root_hash).unwrap();
         for (key, value) in &btree {
             let proof = tree.get_range_proof(*key, version).unwrap();
             restore
@@ -49,10 +49,10 @@ proptest! {
         let expected_root_hash = tree.get_root_hash(version).unwrap();
         let batch1: Vec<_> = all.clone().into_iter().take(batch1_size).collect();
 
-        let restore_db = MockTreeStore::default();
+        let restore_db = Arc::new(MockTreeStore::default());
         {
             let mut restore =
-                JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
+                JellyfishMerkleRestore::new(Arc::clone(&restore_db), version, expected_root_hash).unwrap();
             let proof = tree
                 .get_range_proof(batch1.last().map(|(key, _value)| *key).unwrap(), version)
                 .unwrap();
@@ -75,7 +75,7 @@ proptest! {
                 .collect();
 
             let mut restore =
-                JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
+                JellyfishMerkleRestore::new(Arc::clone(&restore_db), version, expected_root_hash).unwrap();
             let proof = tree
                 .get_range_proof(
                     remaining_accounts.last().map(|(key, _value)| *key).unwrap(),
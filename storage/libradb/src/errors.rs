@@ -14,4 +14,10 @@ pub enum LibraDbError {
     /// Requested too many items.
     #[error("Too many items requested: at least {0} requested, max is {1}")]
     TooManyRequested(u64, u64),
+    /// A requested item has been removed by the pruner.
+    #[error("{0} has been pruned.")]
+    Pruned(String),
+    /// A requested item is not kept by this DB.
+    #[error("{0} is not available.")]
+    NotAvailable(String),
 }
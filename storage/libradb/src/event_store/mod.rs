@@ -169,6 +169,15 @@ impl EventStore {
             if path != *event_key || ver > ledger_version {
                 break;
             }
+            if result.is_empty() && seq > start_seq_num {
+                // Sequence numbers start from 0 without gaps, so missing older ones have been
+                // removed by the ledger pruner.
+                return Err(LibraDbError::Pruned(format!(
+                    "Event {} of key {}",
+                    start_seq_num, event_key
+                ))
+                .into());
+            }
             ensure!(
                 seq == cur_seq,
                 "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
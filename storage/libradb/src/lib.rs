@@ -49,13 +49,14 @@ use crate::{
     },
     pruner::Pruner,
     schema::*,
-    state_store::StateStore,
+    state_store::{StateSnapshotRestore, StateStore},
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Result};
 use itertools::{izip, zip_eq};
 use libra_crypto::hash::{CryptoHash, HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use libra_jellyfish_merkle::iterator::JellyfishMerkleIterator;
 use libra_logger::prelude::*;
 use libra_metrics::OpMetrics;
 use libra_types::{
@@ -66,18 +67,19 @@ use libra_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        AccountStateProof, AccumulatorConsistencyProof, EventProof, SparseMerkleProof,
-        TransactionListProof,
+        position::FrozenSubTreeIterator, AccountStateProof, AccumulatorConsistencyProof,
+        EventProof, SparseMerkleProof, SparseMerkleRangeProof, TransactionListProof,
     },
     transaction::{
-        TransactionInfo, TransactionListWithProof, TransactionToCommit, TransactionWithProof,
-        Version, PRE_GENESIS_VERSION,
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, TransactionStatus, TransactionToCommit,
+        TransactionWithProof, Version, PRE_GENESIS_VERSION,
     },
 };
 use once_cell::sync::Lazy;
 use schemadb::{ColumnFamilyName, DB, DEFAULT_CF_NAME};
 use std::{iter::Iterator, path::Path, sync::Arc, time::Instant};
-use storage_interface::{DbReader, DbWriter, Order, StartupInfo, TreeState};
+use storage_interface::{DbReader, DbWriter, Order, StartupInfo, StateSnapshotReceiver, TreeState};
 
 static OP_COUNTER: Lazy<OpMetrics> = Lazy::new(|| OpMetrics::new_and_registered("storage"));
 
@@ -106,6 +108,7 @@ pub struct LibraDB {
     event_store: EventStore,
     system_store: SystemStore,
     pruner: Option<Pruner>,
+    write_set_window: Option<u64>,
 }
 
 impl LibraDB {
@@ -122,12 +125,28 @@ impl LibraDB {
             TRANSACTION_CF_NAME,
             TRANSACTION_ACCUMULATOR_CF_NAME,
             TRANSACTION_BY_ACCOUNT_CF_NAME,
+            TRANSACTION_BY_HASH_CF_NAME,
             TRANSACTION_INFO_CF_NAME,
+            WRITE_SET_CF_NAME,
         ]
     }
 
-    fn new_with_db(db: DB, prune_window: Option<u64>) -> Self {
+    fn new_with_db(
+        db: DB,
+        prune_window: Option<u64>,
+        ledger_prune_window: Option<u64>,
+        write_set_window: Option<u64>,
+    ) -> Self {
         let db = Arc::new(db);
+        let pruner = if prune_window.is_some() || ledger_prune_window.is_some() {
+            Some(Pruner::new(
+                Arc::clone(&db),
+                prune_window,
+                ledger_prune_window,
+            ))
+        } else {
+            None
+        };
 
         LibraDB {
             db: Arc::clone(&db),
@@ -136,7 +155,8 @@ impl LibraDB {
             state_store: Arc::new(StateStore::new(Arc::clone(&db))),
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&db))),
             system_store: SystemStore::new(Arc::clone(&db)),
-            pruner: prune_window.map(|n| Pruner::new(Arc::clone(&db), n)),
+            pruner,
+            write_set_window,
         }
     }
 
@@ -144,11 +164,17 @@ impl LibraDB {
         db_root_path: P,
         readonly: bool,
         prune_window: Option<u64>,
+        ledger_prune_window: Option<u64>,
+        write_set_window: Option<u64>,
     ) -> Result<Self> {
         ensure!(
             prune_window.is_none() || !readonly,
             "Do not set prune_window when opening readonly.",
         );
+        ensure!(
+            ledger_prune_window.is_none() || !readonly,
+            "Do not set ledger_prune_window when opening readonly.",
+        );
 
         let path = db_root_path.as_ref().join("libradb");
         let instant = Instant::now();
@@ -165,7 +191,12 @@ impl LibraDB {
             instant.elapsed().as_millis()
         );
 
-        Ok(Self::new_with_db(db, prune_window))
+        Ok(Self::new_with_db(
+            db,
+            prune_window,
+            ledger_prune_window,
+            write_set_window,
+        ))
     }
 
     pub fn open_as_secondary<P: AsRef<Path> + Clone>(
@@ -183,16 +214,20 @@ impl LibraDB {
                 Self::column_families(),
             )?,
             None, // prune_window
+            None, // ledger_prune_window
+            None, // write_set_window
         ))
     }
 
-    /// This opens db in non-readonly mode, without the pruner.
+    /// This opens db in non-readonly mode, without the pruner and keeping all write sets.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_for_test<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
         Self::open(
             db_root_path,
-            false, /* readonly */
-            None,  /* pruner */
+            false,                  /* readonly */
+            None,                   /* pruner */
+            None,                   /* ledger pruner */
+            Some(u64::max_value()), /* write set window */
         )
         .expect("Unable to open LibraDB")
     }
@@ -267,6 +302,8 @@ impl LibraDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionWithProof> {
+        self.error_if_ledger_pruned("Transaction", version)?;
+
         let proof = self
             .ledger_store
             .get_transaction_info_with_proof(version, ledger_version)?;
@@ -287,6 +324,44 @@ impl LibraDB {
         })
     }
 
+    // ================================== Migration APIs ================================
+
+    /// Builds the transaction hash index for all transactions in the DB, which is necessary for
+    /// DBs created before the index was introduced. Entries are written in batches of
+    /// `batch_size` transactions, `progress` is called with the version indexed up to after each
+    /// batch. Returns the total number of transactions indexed, which doesn't include pruned ones.
+    pub fn rebuild_transaction_by_hash_index(
+        &self,
+        batch_size: usize,
+        mut progress: impl FnMut(Version),
+    ) -> Result<u64> {
+        ensure!(batch_size > 0, "batch_size should > 0, got {}", batch_size);
+        let num_transactions = match self.ledger_store.get_latest_transaction_info_option()? {
+            Some((latest_version, _)) => latest_version + 1,
+            None => return Ok(0),
+        };
+
+        let first_version = self.transaction_store.get_first_version()?.unwrap_or(0);
+        let mut next_version = first_version;
+        while next_version < num_transactions {
+            let num_to_index = std::cmp::min(batch_size as u64, num_transactions - next_version);
+            let num_indexed = self
+                .transaction_store
+                .rebuild_transaction_by_hash_index(next_version, num_to_index as usize)?;
+            ensure!(
+                num_indexed as u64 == num_to_index,
+                "Expected {} transactions from version {}, got {}.",
+                num_to_index,
+                next_version,
+                num_indexed,
+            );
+            next_version += num_to_index;
+            progress(next_version - 1);
+        }
+
+        Ok(num_transactions - first_version)
+    }
+
     // ================================== Backup APIs ===================================
 
     /// Gets an instance of `BackupHandler` for data backup purpose.
@@ -299,6 +374,47 @@ impl LibraDB {
     }
 
     // ================================== Private APIs ==================================
+    /// Returns `LibraDbError::Pruned` if transactions, transaction infos and events of `version`
+    /// have been removed by the ledger pruner.
+    fn error_if_ledger_pruned(&self, data_type: &str, version: Version) -> Result<()> {
+        if let Some(pruner) = self.pruner.as_ref() {
+            let least_readable_version = pruner.least_readable_ledger_version();
+            if version < least_readable_version {
+                return Err(LibraDbError::Pruned(format!(
+                    "{} {} (least readable version is {})",
+                    data_type, version, least_readable_version
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `LibraDbError::NotAvailable` if the write set of `version` is not stored, either
+    /// because storing write sets is disabled or because `version` is out of the write set window.
+    fn error_if_write_set_unavailable(&self, version: Version) -> Result<()> {
+        if self.write_set_window.is_none() {
+            return Err(LibraDbError::NotAvailable(format!(
+                "WriteSet {} (storing write sets is disabled)",
+                version
+            ))
+            .into());
+        }
+        match self.transaction_store.get_first_write_set_version()? {
+            Some(first_version) if version >= first_version => Ok(()),
+            Some(first_version) => Err(LibraDbError::NotAvailable(format!(
+                "WriteSet {} (write sets are kept from version {} on)",
+                version, first_version
+            ))
+            .into()),
+            None => Err(LibraDbError::NotAvailable(format!(
+                "WriteSet {} (no write sets are stored)",
+                version
+            ))
+            .into()),
+        }
+    }
+
     fn get_events_by_event_key(
         &self,
         event_key: &EventKey,
@@ -424,11 +540,29 @@ impl LibraDB {
         // Transaction updates. Gather transaction hashes.
         zip_eq(first_version..=last_version, txns_to_commit)
             .map(|(ver, txn_to_commit)| {
-                self.transaction_store
-                    .put_transaction(ver, txn_to_commit.transaction(), &mut cs)
+                self.transaction_store.put_transaction(
+                    ver,
+                    txn_to_commit.transaction(),
+                    &mut cs,
+                )?;
+                if self.write_set_window.is_some() {
+                    self.transaction_store.put_write_set(
+                        ver,
+                        txn_to_commit.write_set(),
+                        &mut cs,
+                    )?;
+                }
+                Ok(())
             })
             .collect::<Result<()>>()?;
 
+        // Write sets that fall out of the window are deleted in the same batch.
+        if let Some(window) = self.write_set_window {
+            (first_version.saturating_sub(window)..(last_version + 1).saturating_sub(window))
+                .map(|ver| self.transaction_store.delete_write_set(ver, &mut cs))
+                .collect::<Result<()>>()?;
+        }
+
         // Transaction accumulator updates. Get result root hash.
         let txn_infos = izip!(txns_to_commit, state_root_hashes, event_root_hashes)
             .map(|(t, s, e)| {
@@ -542,6 +676,45 @@ impl DbReader for LibraDB {
             .transpose()
     }
 
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        start_seq_num: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Vec<TransactionWithProof>> {
+        let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
+            .with_label_values(&["get_account_transactions"])
+            .start_timer();
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        self.transaction_store
+            .lookup_transactions_by_account(address, start_seq_num, order, limit, ledger_version)?
+            .into_iter()
+            .map(|(_, version)| {
+                self.get_transaction_with_proof(version, ledger_version, fetch_events)
+            })
+            .collect()
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
+            .with_label_values(&["get_transaction_by_hash"])
+            .start_timer();
+
+        self.transaction_store
+            .lookup_transaction_by_hash(&hash, ledger_version)?
+            .map(|version| self.get_transaction_with_proof(version, ledger_version, fetch_events))
+            .transpose()
+    }
+
     // ======================= State Synchronizer Internal APIs ===================================
     /// Gets a batch of transactions for the purpose of synchronizing state to another node.
     ///
@@ -563,6 +736,7 @@ impl DbReader for LibraDB {
             return Ok(TransactionListWithProof::new_empty());
         }
 
+        self.error_if_ledger_pruned("Transaction", start_version)?;
         let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
         let txns = (start_version..start_version + limit)
@@ -597,6 +771,61 @@ impl DbReader for LibraDB {
         ))
     }
 
+    /// Gets a batch of transactions together with their outputs for the purpose of synchronizing
+    /// state to another node that applies the outputs instead of re-executing the transactions.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
+            .with_label_values(&["get_transaction_outputs"])
+            .start_timer();
+
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        if start_version > ledger_version || limit == 0 {
+            return Ok(TransactionOutputListWithProof::new_empty());
+        }
+
+        self.error_if_ledger_pruned("Transaction", start_version)?;
+        self.error_if_write_set_unavailable(start_version)?;
+        let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+
+        let txn_infos = (start_version..start_version + limit)
+            .map(|version| Ok(self.ledger_store.get_transaction_info(version)?))
+            .collect::<Result<Vec<_>>>()?;
+        let txns_and_outputs = zip_eq(start_version..start_version + limit, &txn_infos)
+            .map(|(version, txn_info)| {
+                let txn = self.transaction_store.get_transaction(version)?;
+                let output = TransactionOutput::new(
+                    self.transaction_store.get_write_set(version)?,
+                    self.event_store.get_events_by_version(version)?,
+                    txn_info.gas_used(),
+                    TransactionStatus::Keep(txn_info.status().clone()),
+                );
+                Ok((txn, output))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let proof = TransactionListProof::new(
+            self.ledger_store.get_transaction_range_proof(
+                Some(start_version),
+                limit,
+                ledger_version,
+            )?,
+            txn_infos,
+        );
+
+        Ok(TransactionOutputListWithProof::new(
+            txns_and_outputs,
+            Some(start_version),
+            proof,
+        ))
+    }
+
     fn get_events(
         &self,
         event_key: &EventKey,
@@ -779,6 +1008,43 @@ impl DbReader for LibraDB {
     fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
         self.ledger_store.get_latest_transaction_info_option()
     }
+
+    /// Gets a chunk of accounts of the state tree at `version` with the range proof of the
+    /// chunk, used to serve state snapshots during state synchronization.
+    fn get_account_chunk_with_proof(
+        &self,
+        version: Version,
+        known_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
+            .with_label_values(&["get_account_chunk_with_proof"])
+            .start_timer();
+
+        ensure!(limit > 0, "limit should > 0, got {}", limit);
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        let chunk = JellyfishMerkleIterator::new(
+            Arc::clone(&self.state_store),
+            version,
+            known_key.unwrap_or_else(HashValue::zero),
+        )?
+        .skip_while(|res| match (res, known_key) {
+            (Ok((key, _blob)), Some(known_key)) => *key == known_key,
+            _ => false,
+        })
+        .take(limit as usize)
+        .collect::<Result<Vec<_>>>()?;
+        let rightmost_key = match chunk.last() {
+            Some((key, _blob)) => *key,
+            None => bail!("No accounts after {:?} at version {}.", known_key, version),
+        };
+        let proof = self
+            .state_store
+            .get_account_state_range_proof(rightmost_key, version)?;
+
+        Ok((chunk, proof))
+    }
 }
 
 impl DbWriter for LibraDB {
@@ -868,6 +1134,85 @@ impl DbWriter for LibraDB {
 
         Ok(())
     }
+
+    /// Gets a receiver restoring the state tree at `version` out of the chunks of a state
+    /// snapshot.
+    fn get_state_snapshot_receiver(
+        &self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<Box<dyn StateSnapshotReceiver>> {
+        Ok(Box::new(StateSnapshotRestore::new(
+            Arc::clone(&self.state_store),
+            version,
+            expected_root_hash,
+        )?))
+    }
+
+    /// Once the state tree at the snapshot version is restored, writes what is needed for the DB
+    /// to continue from that version as if it had committed all transactions up to it:
+    /// the transaction, its events and its info at that version, the frozen subtrees of the
+    /// transaction accumulator and the ledger info. Nothing before the snapshot version is
+    /// available afterwards.
+    fn finalize_state_snapshot(
+        &self,
+        txn: &Transaction,
+        events: &[ContractEvent],
+        txn_info: &TransactionInfo,
+        frozen_subtrees: &[HashValue],
+        ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
+            .with_label_values(&["finalize_state_snapshot"])
+            .start_timer();
+
+        let version = ledger_info_with_sigs.ledger_info().version();
+        let expected_root_hash = txn_info.state_root_hash();
+        let root_hash = self.state_store.get_root_hash(version)?;
+        ensure!(
+            root_hash == expected_root_hash,
+            "Restored state root hash doesn't match expected. {:?} vs {:?}",
+            root_hash,
+            expected_root_hash,
+        );
+
+        let mut cs = ChangeSet::new();
+        let positions: Vec<_> = FrozenSubTreeIterator::new(version + 1).collect();
+        ensure!(
+            positions.len() == frozen_subtrees.len(),
+            "Number of frozen subtree roots not expected. Expected: {}, actual: {}",
+            positions.len(),
+            frozen_subtrees.len(),
+        );
+        zip_eq(positions, frozen_subtrees)
+            .map(|(position, hash)| {
+                cs.batch
+                    .put::<TransactionAccumulatorSchema>(&position, hash)
+            })
+            .collect::<Result<()>>()?;
+        self.transaction_store
+            .put_transaction(version, txn, &mut cs)?;
+        let event_root_hash = self.event_store.put_events(version, events, &mut cs)?;
+        ensure!(
+            event_root_hash == txn_info.event_root_hash(),
+            "Event root hash doesn't match expected. {:?} vs {:?}",
+            event_root_hash,
+            txn_info.event_root_hash(),
+        );
+        cs.batch.put::<TransactionInfoSchema>(&version, txn_info)?;
+        self.ledger_store
+            .put_ledger_info(ledger_info_with_sigs, &mut cs)?;
+        self.db.write_schemas(cs.batch)?;
+
+        self.ledger_store
+            .set_latest_ledger_info(ledger_info_with_sigs.clone());
+        LIBRA_STORAGE_LEDGER_VERSION.set(version as i64);
+        LIBRA_STORAGE_NEXT_BLOCK_EPOCH
+            .set(ledger_info_with_sigs.ledger_info().next_block_epoch() as i64);
+        LIBRA_STORAGE_LATEST_TXN_VERSION.set(version as i64);
+
+        Ok(())
+    }
 }
 
 // Convert requested range and order to a range in ascending order.
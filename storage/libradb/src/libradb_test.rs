@@ -4,7 +4,7 @@
 use super::*;
 #[allow(unused_imports)]
 use crate::{
-    schema::jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+    schema::{jellyfish_merkle_node::JellyfishMerkleNodeSchema, write_set::WriteSetSchema},
     test_helper::{arb_blocks_to_commit, arb_mock_genesis},
 };
 use libra_crypto::hash::CryptoHash;
@@ -162,6 +162,126 @@ fn test_sync_transactions_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoW
     }
 }
 
+fn test_state_snapshot_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    // Whether the first block is committed to the restoring DB before the snapshot, like genesis
+    // is when bootstrapping a node.
+    bootstrapped: bool,
+) {
+    let tmp_dir1 = TempPath::new();
+    let db1 = LibraDB::new_for_test(&tmp_dir1);
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db1.save_transactions(&txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let ledger_info_with_sigs = db1.get_latest_ledger_info().unwrap();
+    let version = ledger_info_with_sigs.ledger_info().version();
+    let txn_info = db1.ledger_store.get_transaction_info(version).unwrap();
+
+    // Restore the state at `version` into another DB, a couple of accounts at a time.
+    let tmp_dir2 = TempPath::new();
+    let db2 = LibraDB::new_for_test(&tmp_dir2);
+    if bootstrapped {
+        let (txns_to_commit, ledger_info_with_sigs) = &input[0];
+        db2.save_transactions(&txns_to_commit, 0, Some(ledger_info_with_sigs))
+            .unwrap();
+    }
+    let mut receiver = db2
+        .get_state_snapshot_receiver(version, txn_info.state_root_hash())
+        .unwrap();
+    loop {
+        let (chunk, proof) = db1
+            .get_account_chunk_with_proof(version, receiver.previous_key(), 2 /* limit */)
+            .unwrap();
+        let is_last_chunk = proof
+            .right_siblings()
+            .iter()
+            .all(|hash| *hash == *SPARSE_MERKLE_PLACEHOLDER_HASH);
+        receiver.add_chunk(chunk, proof).unwrap();
+        if is_last_chunk {
+            break;
+        }
+    }
+    receiver.finish().unwrap();
+    let events = db1.event_store.get_events_by_version(version).unwrap();
+    db2.finalize_state_snapshot(
+        &db1.transaction_store.get_transaction(version).unwrap(),
+        &events,
+        &txn_info,
+        &db1.ledger_store
+            .get_frozen_subtree_hashes(version + 1)
+            .unwrap(),
+        &ledger_info_with_sigs,
+    )
+    .unwrap();
+
+    assert_eq!(
+        db2.get_latest_tree_state().unwrap(),
+        db1.get_latest_tree_state().unwrap()
+    );
+    assert_eq!(db2.get_latest_ledger_info().unwrap(), ledger_info_with_sigs);
+    assert_eq!(
+        db2.event_store.get_events_by_version(version).unwrap(),
+        events
+    );
+    assert_eq!(
+        db2.get_transactions(version, 1, version, true).unwrap(),
+        db1.get_transactions(version, 1, version, true).unwrap()
+    );
+}
+
+fn test_write_set_window_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    write_set_window: Option<u64>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::open(
+        &tmp_dir,
+        false, /* readonly */
+        None,  /* pruner */
+        None,  /* ledger pruner */
+        write_set_window,
+    )
+    .unwrap();
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    let latest_version = cur_ver - 1;
+    let txns_to_commit: Vec<_> = input.into_iter().flat_map(|(txns, _)| txns).collect();
+    for (version, txn_to_commit) in txns_to_commit.iter().enumerate() {
+        let version = version as u64;
+        let res = db.get_transaction_outputs(version, 1, latest_version);
+        match write_set_window {
+            Some(window) if version + window > latest_version => {
+                let (_, output) = &res.unwrap().transactions_and_outputs[0];
+                assert_eq!(output.write_set(), txn_to_commit.write_set());
+            }
+            _ => {
+                let err = res.unwrap_err();
+                assert!(matches!(
+                    err.downcast_ref::<LibraDbError>(),
+                    Some(LibraDbError::NotAvailable(_))
+                ));
+            }
+        }
+        assert_eq!(
+            db.db.get::<WriteSetSchema>(&version).unwrap().is_some(),
+            write_set_window.map_or(false, |window| version + window > latest_version)
+        );
+    }
+}
+
 fn get_events_by_event_key(
     db: &LibraDB,
     ledger_info: &LedgerInfo,
@@ -355,6 +475,19 @@ fn verify_committed_transactions(
             .verify(ledger_info, Some(cur_ver))
             .unwrap();
 
+        let txn_output_list_with_proof = db
+            .get_transaction_outputs(cur_ver, 1, ledger_version)
+            .unwrap();
+        txn_output_list_with_proof
+            .verify(ledger_info, Some(cur_ver))
+            .unwrap();
+        assert_eq!(
+            txn_output_list_with_proof.transactions_and_outputs[0]
+                .1
+                .write_set(),
+            txn_to_commit.write_set()
+        );
+
         // Fetch and verify account states.
         for (addr, expected_blob) in txn_to_commit.account_states() {
             let account_state_with_proof = db
@@ -391,6 +524,29 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input);
     }
+
+    #[test]
+    fn test_state_snapshot(input in arb_blocks_to_commit()) {
+        test_state_snapshot_impl(input, false /* bootstrapped */);
+    }
+
+    #[test]
+    fn test_state_snapshot_bootstrapped(
+        input in arb_blocks_to_commit()
+            .prop_filter("snapshot after the first block", |blocks| blocks.len() > 1)
+    ) {
+        test_state_snapshot_impl(input, true /* bootstrapped */);
+    }
+
+    #[test]
+    fn test_write_set_window(input in arb_blocks_to_commit(), window in 1..10u64) {
+        test_write_set_window_impl(input, Some(window));
+    }
+
+    #[test]
+    fn test_write_sets_disabled(input in arb_blocks_to_commit()) {
+        test_write_set_window_impl(input, None);
+    }
 }
 
 #[test]
@@ -71,6 +71,14 @@ pub static LIBRA_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION: Lazy<IntGauge> = L
     .unwrap()
 });
 
+pub static LIBRA_STORAGE_PRUNER_LEAST_READABLE_LEDGER_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_storage_pruner_least_readable_ledger_version",
+        "Libra storage pruner least readable ledger version"
+    )
+    .unwrap()
+});
+
 pub static LIBRA_STORAGE_API_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         // metric name
@@ -3,24 +3,39 @@
 
 //! This module provides `Pruner` which manages a thread pruning old data in the background and is
 //! meant to be triggered by other threads as they commit new data to the DB.
+//!
+//! Two kinds of data are pruned, each with its own window: the state (stale Jellyfish Merkle nodes)
+//! and the ledger (transactions, transaction infos, events and their indices).
 
 use crate::{
     metrics::{
-        LIBRA_STORAGE_OTHER_TIMERS_SECONDS, LIBRA_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION,
+        LIBRA_STORAGE_OTHER_TIMERS_SECONDS, LIBRA_STORAGE_PRUNER_LEAST_READABLE_LEDGER_VERSION,
+        LIBRA_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION,
     },
     schema::{
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
+        event::EventSchema, event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema, jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        stale_node_index::StaleNodeIndexSchema, transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema, transaction_info::TransactionInfoSchema,
+        write_set::WriteSetSchema,
     },
+    transaction_store::TransactionStore,
     OP_COUNTER,
 };
 use anyhow::Result;
+use libra_crypto::hash::CryptoHash;
 use libra_jellyfish_merkle::StaleNodeIndex;
 use libra_logger::prelude::*;
-use libra_types::transaction::Version;
+use libra_types::{
+    proof::position::Position,
+    transaction::{Transaction, Version},
+};
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
 #[cfg(test)]
 use std::thread::sleep;
 use std::{
+    cmp::min,
     iter::Peekable,
     sync::{
         atomic::{AtomicU64, Ordering},
@@ -38,9 +53,12 @@ use std::{
 /// quits the worker thread eagerly without waiting for all pending work to be done.
 #[derive(Debug)]
 pub(crate) struct Pruner {
-    /// Other than the latest version, how many historical versions to keep being readable. For
-    /// example, this being 0 means keep only the latest version.
-    historical_versions_to_keep: u64,
+    /// Other than the latest version, how many historical versions of the state to keep being
+    /// readable. For example, this being 0 means keep only the latest version. `None` disables
+    /// state pruning.
+    historical_versions_to_keep: Option<u64>,
+    /// Same as the above, for the ledger (transactions, transaction infos and events).
+    ledger_historical_versions_to_keep: Option<u64>,
     /// The worker thread handle, created upon Pruner instance construction and joined upon its
     /// destruction. It only becomes `None` after joined in `drop()`.
     worker_thread: Option<JoinHandle<()>>,
@@ -50,66 +68,134 @@ pub(crate) struct Pruner {
     /// sets this atomic value to `V`, all versions before `V` can no longer be accessed.
     #[allow(dead_code)]
     worker_progress: Arc<AtomicU64>,
+    /// The ledger pruning progress reported by the worker thread. If this is `V`, transactions,
+    /// transaction infos and events of versions before `V` can no longer be accessed.
+    ledger_progress: Arc<AtomicU64>,
 }
 
 impl Pruner {
     /// Creates a worker thread that waits on a channel for pruning commands.
-    pub fn new(db: Arc<DB>, historical_versions_to_keep: u64) -> Self {
+    pub fn new(
+        db: Arc<DB>,
+        historical_versions_to_keep: Option<u64>,
+        ledger_historical_versions_to_keep: Option<u64>,
+    ) -> Self {
         let (command_sender, command_receiver) = channel();
         let worker_progress = Arc::new(AtomicU64::new(0));
         let worker_progress_clone = Arc::clone(&worker_progress);
+        // The ledger is pruned from the oldest end, so after a restart the progress is simply where
+        // the remaining transactions start.
+        let least_readable_ledger_version = if ledger_historical_versions_to_keep.is_some() {
+            TransactionStore::new(Arc::clone(&db))
+                .get_first_version()
+                .expect("Reading the first transaction version should succeed.")
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let ledger_progress = Arc::new(AtomicU64::new(least_readable_ledger_version));
+        let ledger_progress_clone = Arc::clone(&ledger_progress);
 
         let worker_thread = std::thread::Builder::new()
             .name("libradb_pruner".into())
-            .spawn(move || Worker::new(db, command_receiver, worker_progress_clone).work_loop())
+            .spawn(move || {
+                Worker::new(
+                    db,
+                    command_receiver,
+                    worker_progress_clone,
+                    ledger_progress_clone,
+                )
+                .work_loop()
+            })
             .expect("Creating pruner thread should succeed.");
 
         Self {
             historical_versions_to_keep,
+            ledger_historical_versions_to_keep,
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
             worker_progress,
+            ledger_progress,
         }
     }
 
-    /// Sends pruning command to the worker thread when necessary.
+    /// Returns the least version of which transactions, transaction infos and events are still
+    /// readable.
+    pub fn least_readable_ledger_version(&self) -> Version {
+        self.ledger_progress.load(Ordering::Relaxed)
+    }
+
+    /// Sends pruning commands to the worker thread when necessary.
     pub fn wake(&self, latest_version: Version) {
-        if latest_version > self.historical_versions_to_keep {
-            let least_readable_version = latest_version - self.historical_versions_to_keep;
-            self.command_sender
-                .lock()
-                .expect("command_sender to pruner thread should lock.")
-                .send(Command::Prune {
-                    least_readable_version,
-                })
-                .expect("Receiver should not destruct prematurely.");
+        if let Some(least_readable_version) =
+            least_readable_version(latest_version, self.historical_versions_to_keep)
+        {
+            self.send(Command::Prune {
+                least_readable_version,
+            });
+        }
+        if let Some(least_readable_version) =
+            least_readable_version(latest_version, self.ledger_historical_versions_to_keep)
+        {
+            self.send(Command::PruneLedger {
+                least_readable_version,
+            });
         }
     }
 
+    fn send(&self, command: Command) {
+        self.command_sender
+            .lock()
+            .expect("command_sender to pruner thread should lock.")
+            .send(command)
+            .expect("Receiver should not destruct prematurely.");
+    }
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
-    /// an internal counter.
+    /// internal counters.
     #[cfg(test)]
     pub fn wake_and_wait(&self, latest_version: Version) -> Result<()> {
         self.wake(latest_version);
 
-        if latest_version > self.historical_versions_to_keep {
-            let least_readable_version = latest_version - self.historical_versions_to_keep;
-            // Assuming no big pruning chunks will be issued by a test.
-            const TIMEOUT: Duration = Duration::from_secs(10);
-            let end = Instant::now() + TIMEOUT;
-
-            while Instant::now() < end {
-                if self.worker_progress.load(Ordering::Relaxed) >= least_readable_version {
-                    return Ok(());
+        let targets = [
+            (
+                &self.worker_progress,
+                least_readable_version(latest_version, self.historical_versions_to_keep),
+            ),
+            (
+                &self.ledger_progress,
+                least_readable_version(latest_version, self.ledger_historical_versions_to_keep),
+            ),
+        ];
+        // Assuming no big pruning chunks will be issued by a test.
+        const TIMEOUT: Duration = Duration::from_secs(10);
+        let end = Instant::now() + TIMEOUT;
+
+        for (progress, target) in targets.iter() {
+            if let Some(least_readable_version) = target {
+                while progress.load(Ordering::Relaxed) < *least_readable_version {
+                    if Instant::now() >= end {
+                        anyhow::bail!("Timeout waiting for pruner worker.");
+                    }
+                    sleep(Duration::from_millis(1));
                 }
-                sleep(Duration::from_millis(1));
             }
-            anyhow::bail!("Timeout waiting for pruner worker.");
         }
         Ok(())
     }
 }
 
+/// Returns the least version to keep readable given the latest version and the window, or `None`
+/// if nothing needs to be pruned.
+fn least_readable_version(
+    latest_version: Version,
+    historical_versions_to_keep: Option<u64>,
+) -> Option<Version> {
+    historical_versions_to_keep
+        .filter(|n| latest_version > *n)
+        .map(|n| latest_version - n)
+}
+
 impl Drop for Pruner {
     fn drop(&mut self) {
         self.command_sender
@@ -128,6 +214,7 @@ impl Drop for Pruner {
 enum Command {
     Quit,
     Prune { least_readable_version: Version },
+    PruneLedger { least_readable_version: Version },
 }
 
 struct Worker {
@@ -138,6 +225,9 @@ struct Worker {
     /// smaller than `V` are no longer readable.
     /// This being an atomic value is to communicate the info with the Pruner thread (for tests).
     least_readable_version: Arc<AtomicU64>,
+    /// Same as the above two, for the ledger.
+    target_least_readable_ledger_version: Version,
+    least_readable_ledger_version: Arc<AtomicU64>,
     /// Indicates if there's NOT any pending work to do currently, to hint
     /// `Self::receive_commands()` to `recv()` blocking-ly.
     blocking_recv: bool,
@@ -152,12 +242,15 @@ impl Worker {
         db: Arc<DB>,
         command_receiver: Receiver<Command>,
         least_readable_version: Arc<AtomicU64>,
+        least_readable_ledger_version: Arc<AtomicU64>,
     ) -> Self {
         Self {
             db,
             command_receiver,
             least_readable_version,
             target_least_readable_version: 0,
+            least_readable_ledger_version,
+            target_least_readable_ledger_version: 0,
             blocking_recv: true,
             index_min_nonpurged_version: 0,
             index_purged_at: Instant::now(),
@@ -168,37 +261,83 @@ impl Worker {
         while self.receive_commands() {
             // Process a reasonably small batch of work before trying to receive commands again,
             // in case `Command::Quit` is received (that's when we should quit.)
-            match prune_state(
-                Arc::clone(&self.db),
-                self.least_readable_version.load(Ordering::Relaxed),
-                self.target_least_readable_version,
-                Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
-            ) {
-                Ok(least_readable_version) => {
-                    // Make next recv() blocking if all done.
-                    self.blocking_recv =
-                        least_readable_version == self.target_least_readable_version;
-
-                    // Log the progress.
-                    self.least_readable_version
-                        .store(least_readable_version, Ordering::Relaxed);
-                    OP_COUNTER.set(
-                        "pruner.least_readable_state_version",
-                        least_readable_version as usize,
-                    );
-                    LIBRA_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION
-                        .set(least_readable_version as i64);
-
-                    // Try to purge the log.
-                    if let Err(e) = self.maybe_purge_index() {
-                        crit!("Failed purging state state node index, ignored. Err: {}", e);
-                    }
-                }
-                Err(e) => {
-                    crit!("Error pruning stale state nodes. {:?}", e);
-                    // On error, stop retrying vigorously by making next recv() blocking.
-                    self.blocking_recv = true;
+            let state_done = self.prune_state_batch();
+            let ledger_done = self.prune_ledger_batch();
+            // Make next recv() blocking if all done.
+            self.blocking_recv = state_done && ledger_done;
+        }
+    }
+
+    /// Prunes a batch of stale state nodes, returns `true` if there's no more work to do for now.
+    fn prune_state_batch(&mut self) -> bool {
+        // No state pruning command received yet.
+        if self.target_least_readable_version == 0 {
+            return true;
+        }
+
+        match prune_state(
+            Arc::clone(&self.db),
+            self.least_readable_version.load(Ordering::Relaxed),
+            self.target_least_readable_version,
+            Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
+        ) {
+            Ok(least_readable_version) => {
+                // Log the progress.
+                self.least_readable_version
+                    .store(least_readable_version, Ordering::Relaxed);
+                OP_COUNTER.set(
+                    "pruner.least_readable_state_version",
+                    least_readable_version as usize,
+                );
+                LIBRA_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION
+                    .set(least_readable_version as i64);
+
+                // Try to purge the log.
+                if let Err(e) = self.maybe_purge_index() {
+                    crit!("Failed purging state state node index, ignored. Err: {}", e);
                 }
+
+                least_readable_version == self.target_least_readable_version
+            }
+            Err(e) => {
+                crit!("Error pruning stale state nodes. {:?}", e);
+                // On error, stop retrying vigorously.
+                true
+            }
+        }
+    }
+
+    /// Prunes a batch of transactions and the data associated to them, returns `true` if there's
+    /// no more work to do for now.
+    fn prune_ledger_batch(&mut self) -> bool {
+        let least_readable_version = self.least_readable_ledger_version.load(Ordering::Relaxed);
+        if least_readable_version >= self.target_least_readable_ledger_version {
+            return true;
+        }
+
+        match prune_ledger(
+            Arc::clone(&self.db),
+            least_readable_version,
+            self.target_least_readable_ledger_version,
+            Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
+        ) {
+            Ok(least_readable_version) => {
+                // Log the progress.
+                self.least_readable_ledger_version
+                    .store(least_readable_version, Ordering::Relaxed);
+                OP_COUNTER.set(
+                    "pruner.least_readable_ledger_version",
+                    least_readable_version as usize,
+                );
+                LIBRA_STORAGE_PRUNER_LEAST_READABLE_LEDGER_VERSION
+                    .set(least_readable_version as i64);
+
+                least_readable_version == self.target_least_readable_ledger_version
+            }
+            Err(e) => {
+                crit!("Error pruning ledger. {:?}", e);
+                // On error, stop retrying vigorously.
+                true
             }
         }
     }
@@ -238,6 +377,14 @@ impl Worker {
                         self.blocking_recv = false;
                     }
                 }
+                Command::PruneLedger {
+                    least_readable_version,
+                } => {
+                    if least_readable_version > self.target_least_readable_ledger_version {
+                        self.target_least_readable_ledger_version = least_readable_version;
+                        self.blocking_recv = false;
+                    }
+                }
             }
         }
     }
@@ -362,5 +509,74 @@ pub fn prune_state(
     }
 }
 
+/// Prunes transactions of versions in [`least_readable_version`, `target_least_readable_version`),
+/// at most `max_versions` of them, together with their transaction infos, events, event
+/// accumulators and the index entries pointing to them. Returns the new least readable version.
+///
+/// The transaction accumulator is kept intact, since proving a recent transaction against the
+/// latest ledger info involves frozen subtrees covering old versions.
+pub fn prune_ledger(
+    db: Arc<DB>,
+    least_readable_version: Version,
+    target_least_readable_version: Version,
+    max_versions: usize,
+) -> Result<Version> {
+    let end_version = min(
+        target_least_readable_version,
+        least_readable_version.saturating_add(max_versions as u64),
+    );
+    if least_readable_version >= end_version {
+        return Ok(least_readable_version);
+    }
+
+    let _timer = LIBRA_STORAGE_OTHER_TIMERS_SECONDS
+        .with_label_values(&["ledger_pruner_commit"])
+        .start_timer();
+    let mut batch = SchemaBatch::new();
+
+    let mut iter = db.iter::<TransactionSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for res in iter {
+        let (version, txn) = res?;
+        if version >= end_version {
+            break;
+        }
+        if let Transaction::UserTransaction(signed_txn) = &txn {
+            batch.delete::<TransactionByAccountSchema>(&(
+                signed_txn.sender(),
+                signed_txn.sequence_number(),
+            ))?;
+        }
+        batch.delete::<TransactionByHashSchema>(&txn.hash())?;
+        batch.delete::<TransactionSchema>(&version)?;
+        batch.delete::<TransactionInfoSchema>(&version)?;
+        batch.delete::<WriteSetSchema>(&version)?;
+    }
+
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for res in iter {
+        let ((version, index), event) = res?;
+        if version >= end_version {
+            break;
+        }
+        batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+        batch.delete::<EventSchema>(&(version, index))?;
+    }
+
+    let mut iter = db.iter::<EventAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek(&(least_readable_version, Position::from_inorder_index(0)))?;
+    for res in iter {
+        let ((version, position), _) = res?;
+        if version >= end_version {
+            break;
+        }
+        batch.delete::<EventAccumulatorSchema>(&(version, position))?;
+    }
+
+    db.write_schemas(batch)?;
+    Ok(end_version)
+}
+
 #[cfg(test)]
 mod test;
@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::{change_set::ChangeSet, state_store::StateStore, LibraDB};
+use crate::{
+    change_set::ChangeSet, errors::LibraDbError, state_store::StateStore,
+    test_helper::arb_blocks_to_commit, LibraDB,
+};
 use libra_crypto::HashValue;
 use libra_temppath::TempPath;
 use libra_types::{account_address::AccountAddress, account_state_blob::AccountStateBlob};
+use proptest::prelude::*;
 use std::collections::HashMap;
+use storage_interface::{DbReader, DbWriter};
 
 fn put_account_state_set(
     db: &DB,
@@ -49,7 +54,11 @@ fn test_pruner() {
     let tmp_dir = TempPath::new();
     let db = LibraDB::new_for_test(&tmp_dir).db;
     let state_store = &StateStore::new(Arc::clone(&db));
-    let pruner = Pruner::new(Arc::clone(&db), 0 /* historical_versions_to_keep */);
+    let pruner = Pruner::new(
+        Arc::clone(&db),
+        Some(0), /* historical_versions_to_keep */
+        None,    /* ledger_historical_versions_to_keep */
+    );
 
     let _root0 = put_account_state_set(
         &db,
@@ -136,6 +145,7 @@ fn test_worker_quit_eagerly() {
             Arc::clone(&db),
             command_receiver,
             Arc::new(AtomicU64::new(0)), /* progress */
+            Arc::new(AtomicU64::new(0)), /* ledger progress */
         );
         command_sender
             .send(Command::Prune {
@@ -155,3 +165,100 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_ledger_pruner(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::open(
+            &tmp_dir,
+            false,                  /* readonly */
+            None,                   /* pruner */
+            Some(1),                /* ledger pruner */
+            Some(u64::max_value()), /* write set window */
+        )
+        .unwrap();
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as u64;
+        }
+        let latest_version = cur_ver - 1;
+        let pruner = db.pruner.as_ref().unwrap();
+        pruner.wake_and_wait(latest_version).unwrap();
+        let least_readable_version = pruner.least_readable_ledger_version();
+        prop_assert_eq!(least_readable_version, latest_version.saturating_sub(1));
+
+        let txns_to_commit = input.iter().flat_map(|(txns, _)| txns.iter());
+        for (version, txn_to_commit) in txns_to_commit.enumerate() {
+            let version = version as Version;
+            let pruned = version < least_readable_version;
+            let txn = txn_to_commit.transaction();
+
+            prop_assert_eq!(
+                db.db.get::<TransactionSchema>(&version).unwrap().is_none(),
+                pruned
+            );
+            prop_assert_eq!(
+                db.db.get::<TransactionInfoSchema>(&version).unwrap().is_none(),
+                pruned
+            );
+            prop_assert_eq!(
+                db.db.get::<WriteSetSchema>(&version).unwrap().is_none(),
+                pruned
+            );
+            prop_assert_eq!(
+                db.db.get::<TransactionByHashSchema>(&txn.hash()).unwrap().is_none(),
+                pruned
+            );
+            if let Transaction::UserTransaction(signed_txn) = txn {
+                prop_assert_eq!(
+                    db.db
+                        .get::<TransactionByAccountSchema>(&(
+                            signed_txn.sender(),
+                            signed_txn.sequence_number()
+                        ))
+                        .unwrap()
+                        .is_none(),
+                    pruned
+                );
+            }
+            for (idx, event) in txn_to_commit.events().iter().enumerate() {
+                prop_assert_eq!(
+                    db.db.get::<EventSchema>(&(version, idx as u64)).unwrap().is_none(),
+                    pruned
+                );
+                prop_assert_eq!(
+                    db.db
+                        .get::<EventByKeySchema>(&(*event.key(), event.sequence_number()))
+                        .unwrap()
+                        .is_none(),
+                    pruned
+                );
+            }
+
+            // Reads below the window fail with a clear error.
+            let res = db.get_transactions(version, 1, latest_version, true);
+            if pruned {
+                let err = res.unwrap_err();
+                prop_assert!(matches!(
+                    err.downcast_ref::<LibraDbError>(),
+                    Some(LibraDbError::Pruned(_))
+                ));
+            } else {
+                prop_assert_eq!(res.unwrap().transactions, vec![txn.clone()]);
+            }
+        }
+
+        // Event accumulators of pruned versions are gone as well.
+        let mut iter = db.db.iter::<EventAccumulatorSchema>(ReadOptions::default()).unwrap();
+        iter.seek_to_first();
+        if let Some(((version, _), _)) = iter.next().transpose().unwrap() {
+            prop_assert!(version >= least_readable_version);
+        }
+    }
+}
@@ -17,7 +17,9 @@ pub(crate) mod stale_node_index;
 pub(crate) mod transaction;
 pub(crate) mod transaction_accumulator;
 pub(crate) mod transaction_by_account;
+pub(crate) mod transaction_by_hash;
 pub(crate) mod transaction_info;
+pub(crate) mod write_set;
 
 use anyhow::{ensure, Result};
 use schemadb::ColumnFamilyName;
@@ -32,7 +34,9 @@ pub(super) const STALE_NODE_INDEX_CF_NAME: ColumnFamilyName = "stale_node_index"
 pub(super) const TRANSACTION_CF_NAME: ColumnFamilyName = "transaction";
 pub(super) const TRANSACTION_ACCUMULATOR_CF_NAME: ColumnFamilyName = "transaction_accumulator";
 pub(super) const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
+pub(super) const TRANSACTION_BY_HASH_CF_NAME: ColumnFamilyName = "transaction_by_hash";
 pub(super) const TRANSACTION_INFO_CF_NAME: ColumnFamilyName = "transaction_info";
+pub(super) const WRITE_SET_CF_NAME: ColumnFamilyName = "write_set";
 
 fn ensure_slice_len_eq(data: &[u8], len: usize) -> Result<()> {
     ensure!(
@@ -88,7 +92,9 @@ pub mod fuzzing {
                 super::transaction_by_account::TransactionByAccountSchema,
                 data
             );
+            decode_key_value!(super::transaction_by_hash::TransactionByHashSchema, data);
             decode_key_value!(super::transaction_info::TransactionInfoSchema, data);
+            decode_key_value!(super::write_set::WriteSetSchema, data);
         }
     }
 }
@@ -0,0 +1,14 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use libra_types::{transaction::Version, write_set::WriteSet};
+use proptest::prelude::*;
+use schemadb::schema::assert_encode_decode;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(version in any::<Version>(), write_set in any::<WriteSet>()) {
+        assert_encode_decode::<WriteSetSchema>(&version, &write_set);
+    }
+}
@@ -17,6 +17,7 @@ use anyhow::Result;
 use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_jellyfish_merkle::{
     node_type::{LeafNode, Node, NodeKey},
+    restore::JellyfishMerkleRestore,
     JellyfishMerkleTree, NodeBatch, TreeReader, TreeWriter, ROOT_NIBBLE_HEIGHT,
 };
 use libra_types::{
@@ -27,6 +28,7 @@ use libra_types::{
 };
 use schemadb::{SchemaBatch, DB};
 use std::{collections::HashMap, sync::Arc};
+use storage_interface::StateSnapshotReceiver;
 
 #[derive(Debug)]
 pub(crate) struct StateStore {
@@ -145,12 +147,13 @@ impl TreeReader for StateStore {
     }
 
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>> {
-        // Since everything has the same version during restore, we seek to the first node and get
-        // its version.
+        // Since everything being restored has the same version, which is higher than that of any
+        // tree already in the DB (e.g. genesis when restoring a state snapshot during state sync),
+        // we seek to the last node and get its version.
         let mut iter = self
             .db
             .iter::<JellyfishMerkleNodeSchema>(Default::default())?;
-        iter.seek_to_first();
+        iter.seek_to_last();
         let version = match iter.next().transpose()? {
             Some((node_key, _node)) => node_key.version(),
             None => return Ok(None),
@@ -221,3 +224,39 @@ fn add_node_batch(batch: &mut SchemaBatch, node_batch: &NodeBatch) -> Result<()>
         .collect::<Result<Vec<_>>>()?;
     Ok(())
 }
+
+/// Restores the state tree at a given version out of the chunks of a state snapshot received
+/// during state synchronization.
+pub(crate) struct StateSnapshotRestore {
+    restore: JellyfishMerkleRestore<StateStore>,
+}
+
+impl StateSnapshotRestore {
+    pub fn new(
+        store: Arc<StateStore>,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<Self> {
+        Ok(Self {
+            restore: JellyfishMerkleRestore::new(store, version, expected_root_hash)?,
+        })
+    }
+}
+
+impl StateSnapshotReceiver for StateSnapshotRestore {
+    fn add_chunk(
+        &mut self,
+        chunk: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        self.restore.add_chunk(chunk, proof)
+    }
+
+    fn previous_key(&self) -> Option<HashValue> {
+        self.restore.previous_key()
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        self.restore.finish()
+    }
+}
@@ -6,16 +6,22 @@
 use crate::{
     change_set::ChangeSet,
     errors::LibraDbError,
-    schema::{transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema},
+    schema::{
+        transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema,
+    },
 };
 use anyhow::{ensure, format_err, Result};
+use libra_crypto::{hash::CryptoHash, HashValue};
 use libra_types::{
     account_address::AccountAddress,
     block_metadata::BlockMetadata,
     transaction::{Transaction, Version},
+    write_set::WriteSet,
 };
-use schemadb::{SchemaIterator, DB};
+use schemadb::{SchemaBatch, SchemaIterator, DB};
 use std::sync::Arc;
+use storage_interface::Order;
 
 #[derive(Debug)]
 pub(crate) struct TransactionStore {
@@ -46,6 +52,90 @@ impl TransactionStore {
         Ok(None)
     }
 
+    /// Gets the version of a transaction by the hash of the transaction.
+    pub fn lookup_transaction_by_hash(
+        &self,
+        hash: &HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<TransactionByHashSchema>(hash)?
+            .filter(|version| *version <= ledger_version))
+    }
+
+    /// Given `address` and `start_seq_num`, returns the sequence numbers and versions of up to
+    /// `limit` transactions sent by the account, walking the index in `order`. Result won't
+    /// contain records with a version > `ledger_version` and is in the requested order.
+    pub fn lookup_transactions_by_account(
+        &self,
+        address: AccountAddress,
+        start_seq_num: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, Version)>> {
+        let mut iter = match order {
+            Order::Ascending => {
+                let mut iter = self
+                    .db
+                    .iter::<TransactionByAccountSchema>(Default::default())?;
+                iter.seek(&(address, start_seq_num))?;
+                iter
+            }
+            Order::Descending => {
+                let mut iter = self
+                    .db
+                    .rev_iter::<TransactionByAccountSchema>(Default::default())?;
+                iter.seek_for_prev(&(address, start_seq_num))?;
+                iter
+            }
+        };
+
+        let mut result: Vec<(u64, Version)> = Vec::new();
+        while (result.len() as u64) < limit {
+            let ((addr, seq_num), version) = match iter.next().transpose()? {
+                Some(entry) => entry,
+                None => break,
+            };
+            if addr != address {
+                break;
+            }
+            if version > ledger_version {
+                match order {
+                    Order::Ascending => break,
+                    // Transactions committed after `ledger_version` sit at the newest end of the
+                    // index, skip them when walking backwards.
+                    Order::Descending => continue,
+                }
+            }
+            if result.is_empty() && order == Order::Ascending && seq_num > start_seq_num {
+                // Sequence numbers start from 0 without gaps, so missing older ones have been
+                // removed by the ledger pruner.
+                return Err(LibraDbError::Pruned(format!(
+                    "Txn {} of account {}",
+                    start_seq_num, address
+                ))
+                .into());
+            }
+            if let Some((prev_seq_num, _)) = result.last() {
+                let expected = match order {
+                    Order::Ascending => prev_seq_num + 1,
+                    Order::Descending => prev_seq_num - 1,
+                };
+                ensure!(
+                    seq_num == expected,
+                    "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
+                    expected,
+                    seq_num
+                );
+            }
+            result.push((seq_num, version));
+        }
+
+        Ok(result)
+    }
+
     /// Get signed transaction given `version`
     pub fn get_transaction(&self, version: Version) -> Result<Transaction> {
         self.db
@@ -53,6 +143,14 @@ impl TransactionStore {
             .ok_or_else(|| LibraDbError::NotFound(format!("Txn {}", version)).into())
     }
 
+    /// Returns the version of the oldest transaction in the DB, which is not 0 if the ledger has
+    /// been pruned. Returns `None` if there's no transaction at all.
+    pub fn get_first_version(&self) -> Result<Option<Version>> {
+        let mut iter = self.db.iter::<TransactionSchema>(Default::default())?;
+        iter.seek_to_first();
+        Ok(iter.next().transpose()?.map(|(version, _)| version))
+    }
+
     /// Gets an iterator that yields `num_transactions` transactions starting from `start_version`.
     pub fn get_transaction_iter(
         &self,
@@ -108,10 +206,63 @@ impl TransactionStore {
                 &version,
             )?;
         }
+        cs.batch
+            .put::<TransactionByHashSchema>(&transaction.hash(), &version)?;
         cs.batch.put::<TransactionSchema>(&version, &transaction)?;
 
         Ok(())
     }
+
+    /// Get write set given `version`
+    pub fn get_write_set(&self, version: Version) -> Result<WriteSet> {
+        self.db
+            .get::<WriteSetSchema>(&version)?
+            .ok_or_else(|| LibraDbError::NotFound(format!("WriteSet {}", version)).into())
+    }
+
+    /// Save write set at `version`
+    pub fn put_write_set(
+        &self,
+        version: Version,
+        write_set: &WriteSet,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        cs.batch.put::<WriteSetSchema>(&version, write_set)
+    }
+
+    /// Deletes the write set at `version`.
+    pub fn delete_write_set(&self, version: Version, cs: &mut ChangeSet) -> Result<()> {
+        cs.batch.delete::<WriteSetSchema>(&version)
+    }
+
+    /// Get the first version that has a write set stored, if any.
+    pub fn get_first_write_set_version(&self) -> Result<Option<Version>> {
+        let mut iter = self.db.iter::<WriteSetSchema>(Default::default())?;
+        iter.seek_to_first();
+        Ok(iter.next().transpose()?.map(|(version, _)| version))
+    }
+
+    /// Rebuilds the transaction hash index entries of up to `num_transactions` transactions
+    /// starting from `start_version`, for DBs created before the index existed. Returns the number
+    /// of transactions indexed.
+    pub fn rebuild_transaction_by_hash_index(
+        &self,
+        start_version: Version,
+        num_transactions: usize,
+    ) -> Result<usize> {
+        let mut batch = SchemaBatch::new();
+        let mut num_indexed = 0;
+        for (offset, txn) in self
+            .get_transaction_iter(start_version, num_transactions)?
+            .enumerate()
+        {
+            batch.put::<TransactionByHashSchema>(&txn?.hash(), &(start_version + offset as u64))?;
+            num_indexed += 1;
+        }
+        self.db.write_schemas(batch)?;
+
+        Ok(num_indexed)
+    }
 }
 
 pub struct TransactionIter<'a> {
@@ -11,6 +11,7 @@ use libra_types::{
     transaction::{SignedTransaction, Transaction},
 };
 use proptest::{collection::vec, prelude::*};
+use std::collections::HashMap;
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -44,11 +45,151 @@ proptest! {
                     .unwrap(),
                 Some(ver as Version)
             );
+            prop_assert_eq!(
+                store
+                    .lookup_transaction_by_hash(&txn.hash(), ledger_version)
+                    .unwrap(),
+                Some(ver as Version)
+            );
         }
 
         prop_assert!(store.get_transaction(ledger_version + 1).is_err());
     }
 
+    #[test]
+    fn test_rebuild_transaction_by_hash_index(
+        universe in any_with::<AccountInfoUniverse>(3),
+        gens in vec(
+            (any::<Index>(), any::<SignatureCheckedTransactionGen>()),
+            1..10
+        ),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+        let txns = init_store(universe, gens, &store);
+        let ledger_version = txns.len() as Version - 1;
+
+        // drop the index as if the DB was created before it existed
+        let mut batch = SchemaBatch::new();
+        for txn in &txns {
+            batch.delete::<TransactionByHashSchema>(&txn.hash()).unwrap();
+        }
+        store.db.write_schemas(batch).unwrap();
+        prop_assert_eq!(
+            store.lookup_transaction_by_hash(&txns[0].hash(), ledger_version).unwrap(),
+            None
+        );
+
+        prop_assert_eq!(
+            store.rebuild_transaction_by_hash_index(0, txns.len() + 1).unwrap(),
+            txns.len()
+        );
+        for (ver, txn) in txns.iter().enumerate() {
+            prop_assert_eq!(
+                store
+                    .lookup_transaction_by_hash(&txn.hash(), ledger_version)
+                    .unwrap(),
+                Some(ver as Version)
+            );
+            // not visible before it's committed
+            if ver > 0 {
+                prop_assert_eq!(
+                    store
+                        .lookup_transaction_by_hash(&txn.hash(), ver as Version - 1)
+                        .unwrap(),
+                    None
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_transactions_by_account(
+        universe in any_with::<AccountInfoUniverse>(3),
+        gens in vec(
+            (any::<Index>(), any::<SignatureCheckedTransactionGen>()),
+            1..10
+        ),
+        limit in 1..5u64,
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = LibraDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+        let txns = init_store(universe, gens, &store);
+
+        let ledger_version = txns.len() as Version - 1;
+        let mut by_account: HashMap<AccountAddress, Vec<(u64, Version)>> = HashMap::new();
+        for (ver, txn) in txns.iter().enumerate() {
+            let user_txn = txn
+                .as_signed_user_txn()
+                .expect("All should be user transactions here.");
+            by_account
+                .entry(user_txn.sender())
+                .or_default()
+                .push((user_txn.sequence_number(), ver as Version));
+        }
+
+        for (address, expected) in by_account {
+            // walk forward page by page
+            let mut actual = vec![];
+            let mut cursor = 0;
+            loop {
+                let page = store
+                    .lookup_transactions_by_account(
+                        address,
+                        cursor,
+                        Order::Ascending,
+                        limit,
+                        ledger_version,
+                    )
+                    .unwrap();
+                prop_assert!(page.len() as u64 <= limit);
+                match page.last() {
+                    Some((seq_num, _)) => cursor = seq_num + 1,
+                    None => break,
+                }
+                actual.extend(page);
+            }
+            prop_assert_eq!(&actual, &expected);
+
+            // walk backward from the latest
+            let mut actual = vec![];
+            let mut cursor = u64::max_value();
+            loop {
+                let page = store
+                    .lookup_transactions_by_account(
+                        address,
+                        cursor,
+                        Order::Descending,
+                        limit,
+                        ledger_version,
+                    )
+                    .unwrap();
+                actual.extend(page.iter().cloned());
+                match page.last() {
+                    Some((seq_num, _)) if *seq_num > 0 => cursor = seq_num - 1,
+                    _ => break,
+                }
+            }
+            actual.reverse();
+            prop_assert_eq!(&actual, &expected);
+
+            // transactions beyond the ledger version are not visible
+            let (_, first_version) = expected[0];
+            let visible = store
+                .lookup_transactions_by_account(
+                    address,
+                    u64::max_value(),
+                    Order::Descending,
+                    u64::max_value(),
+                    first_version,
+                )
+                .unwrap();
+            prop_assert_eq!(visible, vec![expected[0]]);
+        }
+    }
+
     #[test]
     fn test_get_transaction_iter(
         universe in any_with::<AccountInfoUniverse>(3),
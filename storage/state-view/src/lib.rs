@@ -37,6 +37,8 @@ pub enum StateViewId {
     BlockExecution { block_id: HashValue },
     /// VmValidator verifying incoming transaction.
     TransactionValidation { base_version: Version },
+    /// JSON-RPC simulating a transaction without committing it.
+    TransactionSimulation { base_version: Version },
     /// For test, db-bootstrapper, etc. Usually not aimed to pass to VM.
     Miscellaneous,
 }
@@ -15,10 +15,13 @@ use libra_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     move_resource::MoveStorage,
-    proof::{definition::LeafCount, AccumulatorConsistencyProof, SparseMerkleProof},
+    proof::{
+        definition::LeafCount, AccumulatorConsistencyProof, SparseMerkleProof,
+        SparseMerkleRangeProof,
+    },
     transaction::{
-        TransactionInfo, TransactionListWithProof, TransactionToCommit, TransactionWithProof,
-        Version,
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        TransactionToCommit, TransactionWithProof, Version,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -196,6 +199,21 @@ pub trait DbReader: Send + Sync {
         fetch_events: bool,
     ) -> Result<TransactionListWithProof>;
 
+    /// See [`LibraDB::get_transaction_outputs`].
+    ///
+    /// [`LibraDB::get_transaction_outputs`]:
+    /// ../libradb/struct.LibraDB.html#method.get_transaction_outputs
+    fn get_transaction_outputs(
+        &self,
+        _start_version: Version,
+        _limit: u64,
+        _ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        Err(format_err!(
+            "get_transaction_outputs is not supported by this db"
+        ))
+    }
+
     /// Returns events by given event key
     fn get_events(
         &self,
@@ -248,6 +266,40 @@ pub trait DbReader: Send + Sync {
         fetch_events: bool,
     ) -> Result<Option<TransactionWithProof>>;
 
+    /// Returns the transaction with the given hash, if it's committed at or before
+    /// `ledger_version`.
+    /// See [`LibraDB::get_transaction_by_hash`].
+    ///
+    /// [`LibraDB::get_transaction_by_hash`]:
+    /// ../libradb/struct.LibraDB.html#method.get_transaction_by_hash
+    fn get_transaction_by_hash(
+        &self,
+        _hash: HashValue,
+        _ledger_version: Version,
+        _fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        unimplemented!()
+    }
+
+    /// Returns up to `limit` transactions sent by `address`, starting at sequence number
+    /// `start_seq_num` and walking in `order`. With `Order::Descending`, `u64::max_value()` as
+    /// `start_seq_num` starts at the latest transaction of the account.
+    /// See [`LibraDB::get_account_transactions`].
+    ///
+    /// [`LibraDB::get_account_transactions`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_transactions
+    fn get_account_transactions(
+        &self,
+        _address: AccountAddress,
+        _start_seq_num: u64,
+        _order: Order,
+        _limit: u64,
+        _ledger_version: Version,
+        _fetch_events: bool,
+    ) -> Result<Vec<TransactionWithProof>> {
+        unimplemented!()
+    }
+
     /// Returns proof of new state for a given ledger info with signatures relative to version known
     /// to client
     fn get_state_proof_with_ledger_info(
@@ -308,6 +360,24 @@ pub trait DbReader: Send + Sync {
     fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
         unimplemented!()
     }
+
+    /// Returns up to `limit` accounts of the state tree at `version` whose keys come right after
+    /// `known_key` (or from the leftmost account if `None`), together with the proof of the range
+    /// ending at the last returned account.
+    /// See [`LibraDB::get_account_chunk_with_proof`].
+    ///
+    /// [`LibraDB::get_account_chunk_with_proof`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_chunk_with_proof
+    fn get_account_chunk_with_proof(
+        &self,
+        _version: Version,
+        _known_key: Option<HashValue>,
+        _limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        Err(format_err!(
+            "get_account_chunk_with_proof is not supported by this db"
+        ))
+    }
 }
 
 impl MoveStorage for &dyn DbReader {
@@ -372,6 +442,60 @@ pub trait DbWriter: Send + Sync {
         first_version: Version,
         ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()>;
+
+    /// Returns the receiver of the chunks of the state snapshot at `version`, whose root hash is
+    /// expected to be `expected_root_hash`. A receiver picks up from where a previous one of the
+    /// same snapshot stopped, e.g. before a restart.
+    /// See [`LibraDB::get_state_snapshot_receiver`].
+    ///
+    /// [`LibraDB::get_state_snapshot_receiver`]:
+    /// ../libradb/struct.LibraDB.html#method.get_state_snapshot_receiver
+    fn get_state_snapshot_receiver(
+        &self,
+        _version: Version,
+        _expected_root_hash: HashValue,
+    ) -> Result<Box<dyn StateSnapshotReceiver>> {
+        Err(format_err!(
+            "get_state_snapshot_receiver is not supported by this db"
+        ))
+    }
+
+    /// Finishes the restore of the state snapshot at the version of `ledger_info_with_sigs`:
+    /// persists the transaction at that version, its events, its info and the frozen subtrees of
+    /// the transaction accumulator that ends with it, and commits the ledger info.
+    /// See [`LibraDB::finalize_state_snapshot`].
+    ///
+    /// [`LibraDB::finalize_state_snapshot`]:
+    /// ../libradb/struct.LibraDB.html#method.finalize_state_snapshot
+    fn finalize_state_snapshot(
+        &self,
+        _txn: &Transaction,
+        _events: &[ContractEvent],
+        _txn_info: &TransactionInfo,
+        _frozen_subtrees: &[HashValue],
+        _ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        Err(format_err!(
+            "finalize_state_snapshot is not supported by this db"
+        ))
+    }
+}
+
+/// Receives the accounts of a state snapshot chunk by chunk, in the increasing order of their keys.
+pub trait StateSnapshotReceiver: Send {
+    /// Verifies a chunk of accounts with the proof of the range ending at its last account and
+    /// persists it.
+    fn add_chunk(
+        &mut self,
+        chunk: Vec<(HashValue, AccountStateBlob)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()>;
+
+    /// Returns the key of the last account received so far, `None` if nothing has been received.
+    fn previous_key(&self) -> Option<HashValue>;
+
+    /// Persists what is left of the state tree once all accounts have been received.
+    fn finish(self: Box<Self>) -> Result<()>;
 }
 
 #[derive(Clone)]
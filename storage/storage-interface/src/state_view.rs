@@ -201,3 +201,60 @@ impl<'a> StateView for VerifiedStateView<'a> {
         self.latest_persistent_version.is_none()
     }
 }
+
+/// `DbStateView` is a read-only view of the committed global state as of a given version,
+/// reading account states straight from storage without verifying proofs. It is meant for
+/// serving historical state and simulating transactions against it, not for executing
+/// transactions to commit.
+pub struct DbStateView {
+    id: StateViewId,
+    reader: Arc<dyn DbReader>,
+    version: Version,
+    account_to_state_cache: RefCell<HashMap<AccountAddress, AccountState>>,
+}
+
+impl DbStateView {
+    pub fn new(id: StateViewId, reader: Arc<dyn DbReader>, version: Version) -> Self {
+        Self {
+            id,
+            reader,
+            version,
+            account_to_state_cache: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl StateView for DbStateView {
+    fn id(&self) -> StateViewId {
+        self.id
+    }
+
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        let address = access_path.address;
+        match self.account_to_state_cache.borrow_mut().entry(address) {
+            Entry::Occupied(occupied) => Ok(occupied.get().get(&access_path.path).cloned()),
+            Entry::Vacant(vacant) => {
+                let (blob, _proof) = self
+                    .reader
+                    .get_account_state_with_proof_by_version(address, self.version)?;
+                Ok(vacant
+                    .insert(
+                        blob.as_ref()
+                            .map(TryInto::try_into)
+                            .transpose()?
+                            .unwrap_or_default(),
+                    )
+                    .get(&access_path.path)
+                    .cloned())
+            }
+        }
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths.iter().map(|path| self.get(path)).collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
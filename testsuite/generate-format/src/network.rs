@@ -50,6 +50,7 @@ pub fn get_registry() -> Result<Registry> {
     tracer.trace_type::<messaging::v1::ErrorCode>(&samples)?;
     tracer.trace_type::<messaging::v1::ParsingErrorType>(&samples)?;
     tracer.trace_type::<messaging::v1::NotSupportedType>(&samples)?;
+    tracer.trace_type::<messaging::v1::RateLimitedType>(&samples)?;
     tracer.trace_type::<handshake::v1::ProtocolId>(&samples)?;
     tracer.trace_type::<address::Protocol>(&samples)?;
     tracer.trace_type::<libra_config::network_id::NetworkId>(&samples)?;
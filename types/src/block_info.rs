@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{epoch_state::EpochState, on_chain_config::ValidatorSet, transaction::Version};
-use libra_crypto::hash::HashValue;
 #[cfg(any(test, feature = "fuzzing"))]
 use libra_crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
+use libra_crypto::hash::{HashValue, ORDERED_ONLY_STATE_ID};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,10 @@ pub const GENESIS_ROUND: Round = 0;
 pub const GENESIS_VERSION: Version = 0;
 pub const GENESIS_TIMESTAMP_USECS: u64 = 0;
 
+/// Version of the `BlockInfo` of a block that is ordered but not executed yet. No executed block
+/// reaches it, so an ordered-only `BlockInfo` can't be mistaken for an execution result.
+pub const ORDERED_ONLY_VERSION: Version = Version::max_value();
+
 /// This structure contains all the information needed for tracking a block
 /// without having access to the block or its execution output state. It
 /// assumes that the block is the last block executed within the ledger.
@@ -121,6 +125,38 @@ impl BlockInfo {
         Self::genesis(*ACCUMULATOR_PLACEHOLDER_HASH, validator_set)
     }
 
+    /// Create a `BlockInfo` that only carries the ordering of a block. It is used in decoupled
+    /// execution mode, where validators vote on the order of blocks before they are executed.
+    /// The execution result is marked with the reserved `ORDERED_ONLY_STATE_ID` and
+    /// `ORDERED_ONLY_VERSION`, which no executed block carries.
+    pub fn new_ordered_only(epoch: u64, round: Round, id: HashValue, timestamp_usecs: u64) -> Self {
+        Self {
+            epoch,
+            round,
+            id,
+            executed_state_id: *ORDERED_ONLY_STATE_ID,
+            version: ORDERED_ONLY_VERSION,
+            timestamp_usecs,
+            next_epoch_state: None,
+        }
+    }
+
+    /// Whether this `BlockInfo` carries the ordering of a block without its execution result.
+    pub fn is_ordered_only(&self) -> bool {
+        self.executed_state_id == *ORDERED_ONLY_STATE_ID
+            && self.version == ORDERED_ONLY_VERSION
+            && self.next_epoch_state.is_none()
+    }
+
+    /// Whether the ordering part of this `BlockInfo` (epoch, round, id and timestamp) matches
+    /// the given executed `BlockInfo`.
+    pub fn match_ordered_only(&self, executed_block_info: &BlockInfo) -> bool {
+        self.epoch == executed_block_info.epoch
+            && self.round == executed_block_info.round
+            && self.id == executed_block_info.id
+            && self.timestamp_usecs == executed_block_info.timestamp_usecs
+    }
+
     /// The epoch after this block committed
     pub fn next_block_epoch(&self) -> u64 {
         self.next_epoch_state().map_or(self.epoch(), |e| e.epoch)
@@ -130,4 +130,8 @@ impl NewBlockEvent {
     pub fn votes(&self) -> Vec<AccountAddress> {
         self.votes.clone()
     }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
 }
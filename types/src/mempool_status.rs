@@ -53,6 +53,10 @@ pub enum MempoolStatusCode {
     // transaction didn't pass vm_validation
     VmError = 5,
     UnknownStatus = 6,
+    // Transaction was evicted from full Mempool by transactions paying a higher gas price
+    Evicted = 7,
+    // Account reached max bytes of transactions per account
+    TooManyBytes = 8,
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -67,6 +71,8 @@ impl TryFrom<u64> for MempoolStatusCode {
             4 => Ok(MempoolStatusCode::InvalidUpdate),
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
+            7 => Ok(MempoolStatusCode::Evicted),
+            8 => Ok(MempoolStatusCode::TooManyBytes),
             _ => Err("invalid StatusCode"),
         }
     }
@@ -0,0 +1,41 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+
+/// Defines the consensus parameters that all the validators must agree on.
+/// Validators read the config as of the reconfiguration that starts an epoch, so an update
+/// (which triggers a reconfiguration) is picked up by all of them at the same epoch boundary.
+/// It is published at genesis and stored on-chain as LCS serialized bytes.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct OnChainConsensusConfig {
+    /// Parameters of the reputation based proposer election, validators fall back to their
+    /// local configuration as long as they are not set.
+    pub leader_reputation: Option<LeaderReputationParams>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LeaderReputationParams {
+    /// Number of committed blocks the election looks back at, 0 means the validator set size.
+    pub window_size: u64,
+    pub active_weight: u64,
+    pub inactive_weight: u64,
+    pub failed_weight: u64,
+    pub failure_threshold_percent: u64,
+}
+
+impl OnChainConfig for OnChainConsensusConfig {
+    const IDENTIFIER: &'static str = "LibraConsensusConfig";
+
+    fn deserialize_into_config(bytes: &[u8]) -> Result<Self> {
+        let raw_bytes: Vec<u8> = lcs::from_bytes(&bytes).map_err(|e| {
+            format_err!(
+                "Failed first round of deserialization for OnChainConsensusConfig: {}",
+                e
+            )
+        })?;
+        Self::deserialize_default_impl(&raw_bytes)
+    }
+}
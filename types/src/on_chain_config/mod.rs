@@ -16,6 +16,7 @@ use move_core_types::{
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 
+mod consensus_config;
 mod libra_version;
 mod registered_currencies;
 mod validator_set;
@@ -23,8 +24,12 @@ mod vm_config;
 mod vm_publishing_option;
 
 pub use self::{
-    libra_version::LibraVersion, registered_currencies::RegisteredCurrencies,
-    validator_set::ValidatorSet, vm_config::VMConfig, vm_publishing_option::VMPublishingOption,
+    consensus_config::{LeaderReputationParams, OnChainConsensusConfig},
+    libra_version::LibraVersion,
+    registered_currencies::RegisteredCurrencies,
+    validator_set::ValidatorSet,
+    vm_config::VMConfig,
+    vm_publishing_option::VMPublishingOption,
 };
 
 /// To register an on-chain config in Rust:
@@ -56,6 +61,7 @@ pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     LibraVersion::CONFIG_ID,
     ValidatorSet::CONFIG_ID,
     RegisteredCurrencies::CONFIG_ID,
+    OnChainConsensusConfig::CONFIG_ID,
 ];
 
 #[derive(Clone, Debug, PartialEq)]
@@ -792,4 +792,12 @@ impl<H: CryptoHasher> AccumulatorExtensionProof<H> {
 
         Ok(original_tree.append(self.leaves.as_slice()))
     }
+
+    /// Returns the accumulator extended with the new leaves, without checking the root of the
+    /// original accumulator. Only for proofs vouched for by other means, e.g. a signature.
+    pub fn extend_unverified(&self) -> anyhow::Result<InMemoryAccumulator<H>> {
+        let original_tree =
+            InMemoryAccumulator::<H>::new(self.frozen_subtree_roots.clone(), self.num_leaves)?;
+        Ok(original_tree.append(self.leaves.as_slice()))
+    }
 }
@@ -601,7 +601,7 @@ impl From<VMStatus> for TransactionStatus {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
 pub enum GovernanceRole {
     LibraRoot,
     TreasuryCompliance,
@@ -694,7 +694,7 @@ impl VMValidatorResult {
 }
 
 /// The output of executing a transaction.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct TransactionOutput {
     /// The list of writes this transaction intends to do.
     write_set: WriteSet,
@@ -825,6 +825,7 @@ impl Display for TransactionInfo {
 pub struct TransactionToCommit {
     transaction: Transaction,
     account_states: HashMap<AccountAddress, AccountStateBlob>,
+    write_set: WriteSet,
     events: Vec<ContractEvent>,
     gas_used: u64,
     status: KeptVMStatus,
@@ -834,6 +835,7 @@ impl TransactionToCommit {
     pub fn new(
         transaction: Transaction,
         account_states: HashMap<AccountAddress, AccountStateBlob>,
+        write_set: WriteSet,
         events: Vec<ContractEvent>,
         gas_used: u64,
         status: KeptVMStatus,
@@ -841,6 +843,7 @@ impl TransactionToCommit {
         TransactionToCommit {
             transaction,
             account_states,
+            write_set,
             events,
             gas_used,
             status,
@@ -855,6 +858,10 @@ impl TransactionToCommit {
         &self.account_states
     }
 
+    pub fn write_set(&self) -> &WriteSet {
+        &self.write_set
+    }
+
     pub fn events(&self) -> &[ContractEvent] {
         &self.events
     }
@@ -968,6 +975,106 @@ impl TransactionListWithProof {
     }
 }
 
+/// A list of transactions along with their outputs, proved the same way as
+/// `TransactionListWithProof`. It allows a node that trusts the quorum-signed transaction infos to
+/// apply the outputs directly instead of re-executing the transactions.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TransactionOutputListWithProof {
+    pub transactions_and_outputs: Vec<(Transaction, TransactionOutput)>,
+    pub first_transaction_output_version: Option<Version>,
+    pub proof: TransactionListProof,
+}
+
+impl TransactionOutputListWithProof {
+    /// Constructor.
+    pub fn new(
+        transactions_and_outputs: Vec<(Transaction, TransactionOutput)>,
+        first_transaction_output_version: Option<Version>,
+        proof: TransactionListProof,
+    ) -> Self {
+        Self {
+            transactions_and_outputs,
+            first_transaction_output_version,
+            proof,
+        }
+    }
+
+    /// Creates an empty transaction output list.
+    pub fn new_empty() -> Self {
+        Self::new(vec![], None, TransactionListProof::new_empty())
+    }
+
+    /// Verifies the transaction output list with the proofs, both carried on `self`.
+    ///
+    /// On top of what `TransactionListWithProof::verify` ensures, the events, gas used and status
+    /// of every output are checked against the corresponding transaction info. The write sets
+    /// can only be verified by applying them and comparing the resulting state root hash.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        first_transaction_output_version: Option<Version>,
+    ) -> Result<()> {
+        ensure!(
+            self.first_transaction_output_version == first_transaction_output_version,
+            "First transaction output version ({}) not expected ({}).",
+            TransactionListWithProof::display_option_version(self.first_transaction_output_version),
+            TransactionListWithProof::display_option_version(first_transaction_output_version),
+        );
+
+        let txn_hashes: Vec<_> = self
+            .transactions_and_outputs
+            .iter()
+            .map(|(txn, _)| txn.hash())
+            .collect();
+        self.proof.verify(
+            ledger_info,
+            self.first_transaction_output_version,
+            &txn_hashes,
+        )?;
+
+        itertools::zip_eq(
+            &self.transactions_and_outputs,
+            self.proof.transaction_infos(),
+        )
+        .map(|((_, output), txn_info)| {
+            let event_hashes: Vec<_> = output.events().iter().map(ContractEvent::hash).collect();
+            let event_root_hash =
+                InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes)
+                    .root_hash();
+            ensure!(
+                event_root_hash == txn_info.event_root_hash(),
+                "Some event root hash calculated doesn't match that carried on the \
+                 transaction info.",
+            );
+            ensure!(
+                output.gas_used() == txn_info.gas_used(),
+                "Gas used ({}) doesn't match that carried on the transaction info ({}).",
+                output.gas_used(),
+                txn_info.gas_used(),
+            );
+            match output.status() {
+                TransactionStatus::Keep(status) if status == txn_info.status() => Ok(()),
+                status => Err(format_err!(
+                    "Status ({:?}) doesn't match that carried on the transaction info ({:?}).",
+                    status,
+                    txn_info.status(),
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions_and_outputs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions_and_outputs.len()
+    }
+}
+
 /// `Transaction` will be the transaction type used internally in the libra node to represent the
 /// transaction to be processed and persisted.
 ///